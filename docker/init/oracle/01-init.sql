@@ -0,0 +1,42 @@
+-- Oracle init script for Upsert integration tests
+-- Runs as SYSDBA on first start of gvenzl/oracle-free; APP_USER already exists.
+
+ALTER SESSION SET CONTAINER = FREEPDB1;
+ALTER SESSION SET CURRENT_SCHEMA = UPSERT;
+
+CREATE TABLE customers (
+    id NUMBER(10) GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    first_name VARCHAR2(100) NOT NULL,
+    last_name VARCHAR2(100) NOT NULL,
+    email VARCHAR2(255) UNIQUE,
+    phone VARCHAR2(20),
+    balance NUMBER(18,2) DEFAULT 0.00,
+    is_active NUMBER(1) DEFAULT 1,
+    created_at TIMESTAMP DEFAULT SYSTIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT SYSTIMESTAMP,
+    notes CLOB,
+    profile_image BLOB
+);
+
+CREATE TABLE orders (
+    id NUMBER(19) GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    customer_id NUMBER(10) NOT NULL,
+    order_date DATE DEFAULT SYSDATE NOT NULL,
+    total_amount NUMBER(19,4) NOT NULL,
+    tax_amount NUMBER(10,4),
+    status NUMBER(5) DEFAULT 0,
+    CONSTRAINT fk_orders_customer FOREIGN KEY (customer_id) REFERENCES customers(id),
+    CONSTRAINT chk_orders_total CHECK (total_amount >= 0)
+);
+
+CREATE INDEX idx_orders_customer ON orders (customer_id, order_date);
+
+INSERT INTO customers (first_name, last_name, email, balance)
+VALUES ('Ada', 'Lovelace', 'ada@example.com', 1250.75);
+INSERT INTO customers (first_name, last_name, email, balance)
+VALUES ('Alan', 'Turing', 'alan@example.com', -12.50);
+
+INSERT INTO orders (customer_id, total_amount, tax_amount)
+VALUES (1, 99.9900, 8.2500);
+
+COMMIT;
@@ -74,3 +74,79 @@ CREATE TABLE IF NOT EXISTS context_chunks (
 );
 CREATE INDEX IF NOT EXISTS idx_context_chunks_connection ON context_chunks(connection_id);
 CREATE INDEX IF NOT EXISTS idx_context_chunks_type ON context_chunks(chunk_type);
+
+-- Resumable migration runs: the request and the schema fingerprints taken
+-- when the run started
+CREATE TABLE IF NOT EXISTS migration_runs (
+  id TEXT PRIMARY KEY,
+  request_json TEXT NOT NULL,
+  source_fingerprint TEXT NOT NULL,
+  target_fingerprint TEXT NOT NULL,
+  status TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Per-table progress of a migration run, written after each batch
+CREATE TABLE IF NOT EXISTS migration_checkpoints (
+  migration_id TEXT NOT NULL,
+  source_table TEXT NOT NULL,
+  target_table TEXT NOT NULL,
+  phase TEXT NOT NULL,
+  first_key TEXT,
+  last_key TEXT,
+  rows_inserted INTEGER DEFAULT 0,
+  rows_updated INTEGER DEFAULT 0,
+  rows_deleted INTEGER DEFAULT 0,
+  rows_skipped INTEGER DEFAULT 0,
+  error_count INTEGER DEFAULT 0,
+  completed INTEGER DEFAULT 0,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  PRIMARY KEY (migration_id, source_table, target_table)
+);
+
+-- Pre-migration snapshots: one shadow table in the target per migrated table
+CREATE TABLE IF NOT EXISTS migration_backups (
+  migration_id TEXT NOT NULL,
+  target_connection_id TEXT NOT NULL,
+  target_table TEXT NOT NULL,
+  backup_table TEXT NOT NULL,
+  row_count INTEGER,
+  status TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  PRIMARY KEY (migration_id, target_table)
+);
+
+-- Undo log: one row per write of a migration, in the order it was applied.
+-- Inserts keep the row written, updates and deletes the before-image.
+CREATE TABLE IF NOT EXISTS migration_undo_log (
+  seq INTEGER PRIMARY KEY AUTOINCREMENT,
+  migration_id TEXT NOT NULL,
+  target_table TEXT NOT NULL,
+  operation TEXT NOT NULL,
+  key_json TEXT NOT NULL,
+  before_json TEXT,
+  after_json TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_migration_undo_log_migration ON migration_undo_log(migration_id);
+
+-- Incremental sync: highest watermark value synced per job and table pair
+CREATE TABLE IF NOT EXISTS sync_watermarks (
+  scope TEXT NOT NULL,
+  source_table TEXT NOT NULL,
+  target_table TEXT NOT NULL,
+  watermark_column TEXT NOT NULL,
+  watermark_json TEXT NOT NULL,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  PRIMARY KEY (scope, source_table, target_table)
+);
+
+-- Change data capture: last source log position applied per stream
+CREATE TABLE IF NOT EXISTS cdc_positions (
+  stream_id TEXT PRIMARY KEY,
+  source_connection_id TEXT NOT NULL,
+  target_connection_id TEXT NOT NULL,
+  position TEXT NOT NULL,
+  events_applied INTEGER NOT NULL DEFAULT 0,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
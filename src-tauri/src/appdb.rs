@@ -52,6 +52,98 @@ pub struct MigrationHistoryRow {
     pub duration_ms: i64,
 }
 
+/// A migration run that can be resumed: the request it was started with and
+/// the source/target schema fingerprints (JSON objects of table -> hash).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRunRow {
+    pub id: String,
+    pub request_json: String,
+    pub source_fingerprint: String,
+    pub target_fingerprint: String,
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// One write of a migration in the undo log. Rows are JSON objects:
+/// `key_json` holds the key columns, `before_json` the target row before an
+/// update or delete, `after_json` the values an insert or update wrote.
+/// `seq` is assigned on save and orders the entries as applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoLogRow {
+    pub seq: i64,
+    pub migration_id: String,
+    pub target_table: String,
+    pub operation: String,
+    pub key_json: String,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+}
+
+/// Highest watermark synced for a table pair of a job. `scope` is the job
+/// ID, or the connection pair for ad-hoc runs; the value is JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWatermarkRow {
+    pub scope: String,
+    pub source_table: String,
+    pub target_table: String,
+    pub watermark_column: String,
+    pub watermark_json: String,
+    pub updated_at: String,
+}
+
+/// Last applied position of a change data capture stream. `position` is
+/// the reader's opaque text form: an LSN, a binlog file and offset, or a
+/// resume token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdcPositionRow {
+    pub stream_id: String,
+    pub source_connection_id: String,
+    pub target_connection_id: String,
+    pub position: String,
+    pub events_applied: i64,
+    pub updated_at: String,
+}
+
+/// Snapshot of one target table taken before a migration wrote to it.
+/// `status` is "available", "restored" or "expired" (shadow table dropped
+/// by retention).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationBackupRow {
+    pub migration_id: String,
+    pub target_connection_id: String,
+    pub target_table: String,
+    pub backup_table: String,
+    pub row_count: Option<i64>,
+    pub status: String,
+    pub created_at: String,
+}
+
+/// Committed progress of one table in a migration run. Keys are JSON arrays
+/// of key column values; `phase` is "insert", "update" or "delete".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationCheckpointRow {
+    pub migration_id: String,
+    pub source_table: String,
+    pub target_table: String,
+    pub phase: String,
+    pub first_key: Option<String>,
+    pub last_key: Option<String>,
+    pub rows_inserted: i64,
+    pub rows_updated: i64,
+    pub rows_deleted: i64,
+    pub rows_skipped: i64,
+    pub error_count: i64,
+    pub completed: bool,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatMessageRow {
@@ -375,6 +467,483 @@ impl AppDatabase {
         .context("spawn_blocking join error")?
     }
 
+    // ── Migration Checkpoints ───────────────────────────────────────────
+
+    pub async fn save_migration_run(&self, run: MigrationRunRow) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "INSERT OR REPLACE INTO migration_runs \
+                 (id, request_json, source_fingerprint, target_fingerprint, status, created_at, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    run.id,
+                    run.request_json,
+                    run.source_fingerprint,
+                    run.target_fingerprint,
+                    run.status,
+                    run.created_at,
+                    run.updated_at,
+                ],
+            )
+            .context("Failed to save migration run")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn set_migration_run_status(
+        &self,
+        id: String,
+        status: String,
+        updated_at: String,
+    ) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "UPDATE migration_runs SET status = ?2, updated_at = ?3 WHERE id = ?1",
+                rusqlite::params![id, status, updated_at],
+            )
+            .context("Failed to update migration run")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn get_migration_run(&self, id: String) -> anyhow::Result<Option<MigrationRunRow>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let mut stmt = c
+                .prepare(
+                    "SELECT id, request_json, source_fingerprint, target_fingerprint, status, \
+                     created_at, updated_at FROM migration_runs WHERE id = ?1",
+                )
+                .context("Failed to prepare migration run query")?;
+            let mut rows = stmt
+                .query_map(rusqlite::params![id], |row| {
+                    Ok(MigrationRunRow {
+                        id: row.get(0)?,
+                        request_json: row.get(1)?,
+                        source_fingerprint: row.get(2)?,
+                        target_fingerprint: row.get(3)?,
+                        status: row.get(4)?,
+                        created_at: row.get(5)?,
+                        updated_at: row.get(6)?,
+                    })
+                })
+                .context("Failed to query migration run")?;
+            match rows.next() {
+                Some(row) => Ok(Some(row.context("Failed to read migration run")?)),
+                None => Ok(None),
+            }
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn save_checkpoint(&self, checkpoint: MigrationCheckpointRow) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "INSERT OR REPLACE INTO migration_checkpoints \
+                 (migration_id, source_table, target_table, phase, first_key, last_key, \
+                  rows_inserted, rows_updated, rows_deleted, rows_skipped, error_count, completed, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                rusqlite::params![
+                    checkpoint.migration_id,
+                    checkpoint.source_table,
+                    checkpoint.target_table,
+                    checkpoint.phase,
+                    checkpoint.first_key,
+                    checkpoint.last_key,
+                    checkpoint.rows_inserted,
+                    checkpoint.rows_updated,
+                    checkpoint.rows_deleted,
+                    checkpoint.rows_skipped,
+                    checkpoint.error_count,
+                    checkpoint.completed,
+                    checkpoint.updated_at,
+                ],
+            )
+            .context("Failed to save migration checkpoint")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn load_checkpoints(
+        &self,
+        migration_id: String,
+    ) -> anyhow::Result<Vec<MigrationCheckpointRow>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let mut stmt = c
+                .prepare(
+                    "SELECT migration_id, source_table, target_table, phase, first_key, last_key, \
+                     rows_inserted, rows_updated, rows_deleted, rows_skipped, error_count, completed, updated_at \
+                     FROM migration_checkpoints WHERE migration_id = ?1",
+                )
+                .context("Failed to prepare checkpoint query")?;
+
+            let rows = stmt
+                .query_map(rusqlite::params![migration_id], |row| {
+                    Ok(MigrationCheckpointRow {
+                        migration_id: row.get(0)?,
+                        source_table: row.get(1)?,
+                        target_table: row.get(2)?,
+                        phase: row.get(3)?,
+                        first_key: row.get(4)?,
+                        last_key: row.get(5)?,
+                        rows_inserted: row.get(6)?,
+                        rows_updated: row.get(7)?,
+                        rows_deleted: row.get(8)?,
+                        rows_skipped: row.get(9)?,
+                        error_count: row.get(10)?,
+                        completed: row.get(11)?,
+                        updated_at: row.get(12)?,
+                    })
+                })
+                .context("Failed to query checkpoints")?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(rows)
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    // ── Migration Backups ───────────────────────────────────────────────
+
+    pub async fn save_backup(&self, backup: MigrationBackupRow) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "INSERT OR REPLACE INTO migration_backups \
+                 (migration_id, target_connection_id, target_table, backup_table, row_count, status, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    backup.migration_id,
+                    backup.target_connection_id,
+                    backup.target_table,
+                    backup.backup_table,
+                    backup.row_count,
+                    backup.status,
+                    backup.created_at,
+                ],
+            )
+            .context("Failed to save migration backup")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    /// Backups of one migration, or with `None` of every migration, newest
+    /// first.
+    pub async fn load_backups(
+        &self,
+        migration_id: Option<String>,
+    ) -> anyhow::Result<Vec<MigrationBackupRow>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let mut stmt = c
+                .prepare(
+                    "SELECT migration_id, target_connection_id, target_table, backup_table, \
+                     row_count, status, created_at FROM migration_backups \
+                     WHERE ?1 IS NULL OR migration_id = ?1 \
+                     ORDER BY created_at DESC, target_table",
+                )
+                .context("Failed to prepare backup query")?;
+
+            let rows = stmt
+                .query_map(rusqlite::params![migration_id], |row| {
+                    Ok(MigrationBackupRow {
+                        migration_id: row.get(0)?,
+                        target_connection_id: row.get(1)?,
+                        target_table: row.get(2)?,
+                        backup_table: row.get(3)?,
+                        row_count: row.get(4)?,
+                        status: row.get(5)?,
+                        created_at: row.get(6)?,
+                    })
+                })
+                .context("Failed to query backups")?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(rows)
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn set_backup_status(
+        &self,
+        migration_id: String,
+        status: String,
+    ) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "UPDATE migration_backups SET status = ?2 WHERE migration_id = ?1",
+                rusqlite::params![migration_id, status],
+            )
+            .context("Failed to update migration backup")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    // ── Undo Log ────────────────────────────────────────────────────────
+
+    pub async fn save_undo_entries(&self, entries: Vec<UndoLogRow>) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let tx = c.unchecked_transaction()
+                .context("Failed to begin transaction")?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO migration_undo_log \
+                     (migration_id, target_table, operation, key_json, before_json, after_json) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                ).context("Failed to prepare insert")?;
+                for entry in &entries {
+                    stmt.execute(rusqlite::params![
+                        entry.migration_id,
+                        entry.target_table,
+                        entry.operation,
+                        entry.key_json,
+                        entry.before_json,
+                        entry.after_json,
+                    ]).context("Failed to insert undo entry")?;
+                }
+            }
+            tx.commit().context("Failed to commit transaction")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    /// Undo entries of a migration in the order they were applied.
+    pub async fn load_undo_entries(
+        &self,
+        migration_id: String,
+    ) -> anyhow::Result<Vec<UndoLogRow>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let mut stmt = c
+                .prepare(
+                    "SELECT seq, migration_id, target_table, operation, key_json, before_json, after_json \
+                     FROM migration_undo_log WHERE migration_id = ?1 ORDER BY seq",
+                )
+                .context("Failed to prepare undo log query")?;
+
+            let rows = stmt
+                .query_map(rusqlite::params![migration_id], |row| {
+                    Ok(UndoLogRow {
+                        seq: row.get(0)?,
+                        migration_id: row.get(1)?,
+                        target_table: row.get(2)?,
+                        operation: row.get(3)?,
+                        key_json: row.get(4)?,
+                        before_json: row.get(5)?,
+                        after_json: row.get(6)?,
+                    })
+                })
+                .context("Failed to query undo log")?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(rows)
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn clear_undo_entries(&self, migration_id: String) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "DELETE FROM migration_undo_log WHERE migration_id = ?1",
+                rusqlite::params![migration_id],
+            )
+            .context("Failed to clear undo log")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    // ── Sync Watermarks ─────────────────────────────────────────────────
+
+    pub async fn save_watermark(&self, watermark: SyncWatermarkRow) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "INSERT OR REPLACE INTO sync_watermarks \
+                 (scope, source_table, target_table, watermark_column, watermark_json, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    watermark.scope,
+                    watermark.source_table,
+                    watermark.target_table,
+                    watermark.watermark_column,
+                    watermark.watermark_json,
+                    watermark.updated_at,
+                ],
+            )
+            .context("Failed to save sync watermark")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    pub async fn load_watermarks(&self, scope: String) -> anyhow::Result<Vec<SyncWatermarkRow>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let mut stmt = c
+                .prepare(
+                    "SELECT scope, source_table, target_table, watermark_column, watermark_json, updated_at \
+                     FROM sync_watermarks WHERE scope = ?1 ORDER BY source_table, target_table",
+                )
+                .context("Failed to prepare watermark query")?;
+
+            let rows = stmt
+                .query_map(rusqlite::params![scope], |row| {
+                    Ok(SyncWatermarkRow {
+                        scope: row.get(0)?,
+                        source_table: row.get(1)?,
+                        target_table: row.get(2)?,
+                        watermark_column: row.get(3)?,
+                        watermark_json: row.get(4)?,
+                        updated_at: row.get(5)?,
+                    })
+                })
+                .context("Failed to query watermarks")?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(rows)
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    /// Forget the watermarks of a scope so its next sync reads everything.
+    pub async fn clear_watermarks(&self, scope: String) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "DELETE FROM sync_watermarks WHERE scope = ?1",
+                rusqlite::params![scope],
+            )
+            .context("Failed to clear sync watermarks")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    // ── CDC Positions ───────────────────────────────────────────────────
+
+    pub async fn save_cdc_position(&self, position: CdcPositionRow) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "INSERT OR REPLACE INTO cdc_positions \
+                 (stream_id, source_connection_id, target_connection_id, position, events_applied, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    position.stream_id,
+                    position.source_connection_id,
+                    position.target_connection_id,
+                    position.position,
+                    position.events_applied,
+                    position.updated_at,
+                ],
+            )
+            .context("Failed to save CDC position")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    /// Positions of every stream, or only `stream_id`'s when given.
+    pub async fn load_cdc_positions(
+        &self,
+        stream_id: Option<String>,
+    ) -> anyhow::Result<Vec<CdcPositionRow>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            let mut stmt = c
+                .prepare(
+                    "SELECT stream_id, source_connection_id, target_connection_id, position, events_applied, updated_at \
+                     FROM cdc_positions WHERE ?1 IS NULL OR stream_id = ?1 ORDER BY stream_id",
+                )
+                .context("Failed to prepare CDC position query")?;
+
+            let rows = stmt
+                .query_map(rusqlite::params![stream_id], |row| {
+                    Ok(CdcPositionRow {
+                        stream_id: row.get(0)?,
+                        source_connection_id: row.get(1)?,
+                        target_connection_id: row.get(2)?,
+                        position: row.get(3)?,
+                        events_applied: row.get(4)?,
+                        updated_at: row.get(5)?,
+                    })
+                })
+                .context("Failed to query CDC positions")?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            Ok(rows)
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
+    /// Forget a stream's position so it starts again from the current end
+    /// of the source log.
+    pub async fn delete_cdc_position(&self, stream_id: String) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
+            c.execute(
+                "DELETE FROM cdc_positions WHERE stream_id = ?1",
+                rusqlite::params![stream_id],
+            )
+            .context("Failed to delete CDC position")?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking join error")?
+    }
+
     // ── Chat Messages ───────────────────────────────────────────────────
 
     pub async fn save_chat_message(&self, msg: ChatMessageRow) -> anyhow::Result<()> {
@@ -668,6 +1237,170 @@ mod tests {
         assert_eq!(conns.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_migration_run_and_checkpoints() {
+        let db = temp_db();
+        assert!(db.get_migration_run("m1".into()).await.unwrap().is_none());
+
+        db.save_migration_run(MigrationRunRow {
+            id: "m1".into(),
+            request_json: "{}".into(),
+            source_fingerprint: r#"{"users":"abc"}"#.into(),
+            target_fingerprint: r#"{"users":"def"}"#.into(),
+            status: "running".into(),
+            created_at: "2025-01-01T00:00:00".into(),
+            updated_at: "2025-01-01T00:00:00".into(),
+        })
+        .await
+        .unwrap();
+        db.set_migration_run_status("m1".into(), "failed".into(), "2025-01-01T00:05:00".into())
+            .await
+            .unwrap();
+        let run = db.get_migration_run("m1".into()).await.unwrap().unwrap();
+        assert_eq!(run.status, "failed");
+        assert_eq!(run.updated_at, "2025-01-01T00:05:00");
+
+        let mut checkpoint = MigrationCheckpointRow {
+            migration_id: "m1".into(),
+            source_table: "users".into(),
+            target_table: "users".into(),
+            phase: "insert".into(),
+            first_key: Some("[1]".into()),
+            last_key: Some("[100]".into()),
+            rows_inserted: 100,
+            rows_updated: 0,
+            rows_deleted: 0,
+            rows_skipped: 0,
+            error_count: 0,
+            completed: false,
+            updated_at: "2025-01-01T00:01:00".into(),
+        };
+        db.save_checkpoint(checkpoint.clone()).await.unwrap();
+        checkpoint.last_key = Some("[200]".into());
+        checkpoint.rows_inserted = 200;
+        checkpoint.completed = true;
+        db.save_checkpoint(checkpoint).await.unwrap();
+
+        let saved = db.load_checkpoints("m1".into()).await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].last_key.as_deref(), Some("[200]"));
+        assert_eq!(saved[0].rows_inserted, 200);
+        assert!(saved[0].completed);
+        assert!(db.load_checkpoints("other".into()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_migration_backups() {
+        let db = temp_db();
+        for (id, table, at) in [
+            ("m1", "orders", "2025-01-01T00:00:00"),
+            ("m1", "customers", "2025-01-01T00:00:00"),
+            ("m2", "orders", "2025-02-01T00:00:00"),
+        ] {
+            db.save_backup(MigrationBackupRow {
+                migration_id: id.into(),
+                target_connection_id: "tgt".into(),
+                target_table: table.into(),
+                backup_table: format!("upsert_bak_{}_{}", id, table),
+                row_count: Some(10),
+                status: "available".into(),
+                created_at: at.into(),
+            })
+            .await
+            .unwrap();
+        }
+
+        let all = db.load_backups(None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].migration_id, "m2");
+
+        db.set_backup_status("m1".into(), "restored".into()).await.unwrap();
+        let m1 = db.load_backups(Some("m1".into())).await.unwrap();
+        assert_eq!(m1.len(), 2);
+        assert!(m1.iter().all(|b| b.status == "restored"));
+        assert_eq!(m1[0].target_table, "customers");
+    }
+
+    #[tokio::test]
+    async fn test_undo_log_roundtrip() {
+        let db = temp_db();
+        let entry = |migration: &str, op: &str, key: &str| UndoLogRow {
+            seq: 0,
+            migration_id: migration.into(),
+            target_table: "orders".into(),
+            operation: op.into(),
+            key_json: key.into(),
+            before_json: None,
+            after_json: Some(key.into()),
+        };
+        db.save_undo_entries(vec![
+            entry("m1", "insert", r#"{"id":1}"#),
+            entry("m1", "delete", r#"{"id":2}"#),
+        ])
+        .await
+        .unwrap();
+        db.save_undo_entries(vec![entry("m2", "update", r#"{"id":3}"#)])
+            .await
+            .unwrap();
+
+        let m1 = db.load_undo_entries("m1".into()).await.unwrap();
+        assert_eq!(m1.len(), 2);
+        assert_eq!(m1[0].operation, "insert");
+        assert!(m1[0].seq < m1[1].seq);
+
+        db.clear_undo_entries("m1".into()).await.unwrap();
+        assert!(db.load_undo_entries("m1".into()).await.unwrap().is_empty());
+        assert_eq!(db.load_undo_entries("m2".into()).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_watermarks() {
+        let db = temp_db();
+        let watermark = |value: &str| SyncWatermarkRow {
+            scope: "nightly".into(),
+            source_table: "orders".into(),
+            target_table: "orders_copy".into(),
+            watermark_column: "updated_at".into(),
+            watermark_json: value.into(),
+            updated_at: "2025-01-01T00:00:00".into(),
+        };
+        db.save_watermark(watermark("1")).await.unwrap();
+        db.save_watermark(watermark("42")).await.unwrap();
+
+        let saved = db.load_watermarks("nightly".into()).await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].watermark_json, "42");
+        assert!(db.load_watermarks("other".into()).await.unwrap().is_empty());
+
+        db.clear_watermarks("nightly".into()).await.unwrap();
+        assert!(db.load_watermarks("nightly".into()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cdc_positions() {
+        let db = temp_db();
+        let position = |stream: &str, lsn: &str, applied: i64| CdcPositionRow {
+            stream_id: stream.into(),
+            source_connection_id: "pg".into(),
+            target_connection_id: "mssql".into(),
+            position: lsn.into(),
+            events_applied: applied,
+            updated_at: "2025-01-01T00:00:00".into(),
+        };
+        db.save_cdc_position(position("orders", "0/16B3748", 10)).await.unwrap();
+        db.save_cdc_position(position("orders", "0/16B3F00", 25)).await.unwrap();
+        db.save_cdc_position(position("users", "0/1000000", 1)).await.unwrap();
+
+        let saved = db.load_cdc_positions(Some("orders".into())).await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].position, "0/16B3F00");
+        assert_eq!(saved[0].events_applied, 25);
+        assert_eq!(db.load_cdc_positions(None).await.unwrap().len(), 2);
+
+        db.delete_cdc_position("orders".into()).await.unwrap();
+        assert!(db.load_cdc_positions(Some("orders".into())).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_chat_messages_roundtrip() {
         let db = temp_db();
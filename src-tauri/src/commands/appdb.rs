@@ -77,6 +77,38 @@ pub async fn get_migration_history(
     db.load_migrations().await.map_err(|e| e.to_string())
 }
 
+/// Pre-migration backups, newest first; all of them unless `migration_id`
+/// is given.
+#[tauri::command]
+pub async fn get_migration_backups(
+    state: State<'_, AppDbState>,
+    migration_id: Option<String>,
+) -> Result<Vec<crate::appdb::MigrationBackupRow>, String> {
+    let db = state.lock().await;
+    db.load_backups(migration_id).await.map_err(|e| e.to_string())
+}
+
+/// Incremental-sync watermarks of a scope: a job ID, or
+/// "{source}->{target}" for runs outside a job.
+#[tauri::command]
+pub async fn get_sync_watermarks(
+    state: State<'_, AppDbState>,
+    scope: String,
+) -> Result<Vec<crate::appdb::SyncWatermarkRow>, String> {
+    let db = state.lock().await;
+    db.load_watermarks(scope).await.map_err(|e| e.to_string())
+}
+
+/// Forget a scope's watermarks so its next incremental sync reads every row.
+#[tauri::command]
+pub async fn reset_sync_watermarks(
+    state: State<'_, AppDbState>,
+    scope: String,
+) -> Result<(), String> {
+    let db = state.lock().await;
+    db.clear_watermarks(scope).await.map_err(|e| e.to_string())
+}
+
 // ── Chat Messages ───────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Mutex;
+
+use super::migration::TableMappingDto;
+use crate::appdb::{AppDatabase, CdcPositionRow};
+use crate::db::cdc::{apply_statements, convert_event, open_reader, ChangeEvent, ChangeReader};
+use crate::db::connectors::DatabaseEngine;
+use crate::db::migrator::CancellationToken;
+use crate::db::registry::{ConnectionRegistry, MigrationState, SharedPool};
+use crate::db::sql_generator::SqlGenerator;
+use crate::db::type_mapper::CanonicalType;
+use crate::db::value_converter::column_type_map;
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdcSyncRequest {
+    /// Names the stream. Its stored position, PostgreSQL replication slot
+    /// and MySQL replica ID all derive from it.
+    pub stream_id: String,
+    pub source_connection_id: String,
+    pub target_connection_id: String,
+    pub tables: Vec<TableMappingDto>,
+    /// Events applied per target transaction.
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// Wait between reads once the source has no new changes.
+    #[serde(default)]
+    pub poll_interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CdcProgressEvent {
+    pub stream_id: String,
+    /// "running", "stopped" or "failed".
+    pub status: String,
+    pub events_applied: i64,
+    pub position: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Start applying the source's change log to the target until stopped.
+/// The stream resumes after its stored position, or starts at the current
+/// end of the log the first time. Progress is reported through
+/// `cdc:progress` events. Only SQL targets can take the changes.
+#[tauri::command]
+pub async fn start_cdc_sync(
+    request: CdcSyncRequest,
+    app_handle: AppHandle,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<(), String> {
+    let (src_pool, tgt_pool) = {
+        let reg = registry.lock().await;
+        let source = reg
+            .get(&request.source_connection_id)
+            .ok_or("Source connection not found")?;
+        let target = reg
+            .get(&request.target_connection_id)
+            .ok_or("Target connection not found")?;
+        (source, target)
+    };
+    if tgt_pool.config().read_only {
+        return Err(format!(
+            "Target {} connection is read-only. Disable read-only mode on it before syncing.",
+            tgt_pool.engine()
+        ));
+    }
+    if matches!(tgt_pool.engine(), DatabaseEngine::MongoDb | DatabaseEngine::CosmosDb) {
+        return Err(format!(
+            "CDC applies changes as SQL statements, which a {} target does not take. \
+             Sync to a SQL database, or run an incremental migration instead.",
+            tgt_pool.engine()
+        ));
+    }
+    if migration_state.lock().await.contains(&request.stream_id) {
+        return Err(format!(
+            "CDC stream {} is already running",
+            request.stream_id
+        ));
+    }
+
+    let stored = app_db
+        .lock()
+        .await
+        .load_cdc_positions(Some(request.stream_id.clone()))
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next();
+    let tables = request
+        .tables
+        .iter()
+        .map(|t| t.source_table.clone())
+        .collect();
+    let reader = open_reader(
+        src_pool,
+        &request.stream_id,
+        tables,
+        stored.as_ref().map(|s| s.position.clone()),
+    )
+    .await
+    .map_err(|e| format!("Failed to open the change log: {:#}", e))?;
+    let column_types = target_column_types(&tgt_pool, &request.tables).await?;
+
+    let token = CancellationToken::new();
+    migration_state
+        .lock()
+        .await
+        .insert(request.stream_id.clone(), token.clone());
+
+    let sync = CdcSync {
+        position: stored.as_ref().map(|s| s.position.clone()),
+        applied: stored.map_or(0, |s| s.events_applied),
+        column_types,
+        request,
+        tgt_pool,
+        app_handle,
+        app_db: app_db.inner().clone(),
+    };
+    let migration_state = migration_state.inner().clone();
+    tokio::spawn(async move {
+        let stream_id = sync.request.stream_id.clone();
+        sync.run(reader, token).await;
+        migration_state.lock().await.remove(&stream_id);
+    });
+    Ok(())
+}
+
+/// Stop a running CDC stream after the batch it is applying.
+#[tauri::command]
+pub async fn stop_cdc_sync(
+    stream_id: String,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+) -> Result<bool, String> {
+    Ok(migration_state.lock().await.cancel(&stream_id))
+}
+
+/// Stored positions of every CDC stream.
+#[tauri::command]
+pub async fn get_cdc_positions(
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<Vec<CdcPositionRow>, String> {
+    let db = app_db.lock().await;
+    db.load_cdc_positions(None).await.map_err(|e| e.to_string())
+}
+
+/// Forget a stopped stream's position so it next starts at the current end
+/// of the source log.
+#[tauri::command]
+pub async fn reset_cdc_position(
+    stream_id: String,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<(), String> {
+    if migration_state.lock().await.contains(&stream_id) {
+        return Err(format!("Stop CDC stream {} before resetting it", stream_id));
+    }
+    let db = app_db.lock().await;
+    db.delete_cdc_position(stream_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Canonical column types per target table, to convert events with. A
+/// table whose info cannot be read gets no conversion.
+async fn target_column_types(
+    tgt_pool: &SharedPool,
+    tables: &[TableMappingDto],
+) -> Result<HashMap<String, HashMap<String, CanonicalType>>, String> {
+    let target = tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+    let mut types = HashMap::new();
+    for table in tables {
+        if let Ok(info) = target.get_table_info(&table.target_table).await {
+            types.insert(
+                table.target_table.clone(),
+                column_type_map(&target.engine(), &info.columns),
+            );
+        }
+    }
+    Ok(types)
+}
+
+/// State of one running stream.
+struct CdcSync {
+    request: CdcSyncRequest,
+    /// Column types per target table.
+    column_types: HashMap<String, HashMap<String, CanonicalType>>,
+    tgt_pool: SharedPool,
+    app_handle: AppHandle,
+    app_db: Arc<Mutex<AppDatabase>>,
+    position: Option<String>,
+    applied: i64,
+}
+
+impl CdcSync {
+    async fn run(mut self, mut reader: Box<dyn ChangeReader>, token: CancellationToken) {
+        let batch_size = self.request.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+        let poll = Duration::from_millis(
+            self.request
+                .poll_interval_ms
+                .unwrap_or(DEFAULT_POLL_INTERVAL_MS),
+        );
+        self.emit("running", None);
+
+        let outcome = loop {
+            if token.is_cancelled() {
+                break Ok(());
+            }
+            let batch = match reader.read_changes(batch_size).await {
+                Ok(batch) => batch,
+                Err(e) => break Err(format!("Failed to read changes: {:#}", e)),
+            };
+            if !batch.events.is_empty() {
+                if let Err(e) = self.apply(&batch.events).await {
+                    break Err(e);
+                }
+                self.applied += batch.events.len() as i64;
+            }
+            if let Some(position) = batch.position {
+                if self.position.as_ref() != Some(&position) {
+                    if let Err(e) = self.save_position(&position).await {
+                        break Err(e);
+                    }
+                    if let Err(e) = reader.acknowledge(&position).await {
+                        break Err(format!("Failed to release the change log: {:#}", e));
+                    }
+                    self.position = Some(position);
+                    self.emit("running", None);
+                }
+            }
+            if batch.events.is_empty() {
+                tokio::time::sleep(poll).await;
+            }
+        };
+
+        match outcome {
+            Ok(()) => self.emit("stopped", None),
+            Err(e) => {
+                log::error!("CDC stream {} failed: {}", self.request.stream_id, e);
+                self.emit("failed", Some(e));
+            }
+        }
+    }
+
+    /// Apply a batch in one target transaction, so a stored position never
+    /// runs ahead of what the target holds.
+    async fn apply(&self, events: &[ChangeEvent]) -> Result<(), String> {
+        let mappings: HashMap<&str, &TableMappingDto> = self
+            .request
+            .tables
+            .iter()
+            .map(|t| (t.source_table.as_str(), t))
+            .collect();
+        let sql_gen = SqlGenerator::new(self.tgt_pool.engine());
+        let no_types = HashMap::new();
+
+        let mut target = self.tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+        target
+            .begin_transaction()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {:#}", e))?;
+        let mut result = Ok(());
+        for event in events {
+            let Some(mapping) = mappings.get(event.table.as_str()) else {
+                continue;
+            };
+            let key_columns = if mapping.key_columns.is_empty() {
+                &event.key_columns
+            } else {
+                &mapping.key_columns
+            };
+            let column_types = self.column_types.get(&mapping.target_table).unwrap_or(&no_types);
+            let statements = convert_event(event, column_types).and_then(|event| {
+                apply_statements(&sql_gen, &event, &mapping.target_table, key_columns)
+            });
+            let statements = match statements {
+                Ok(statements) => statements,
+                Err(e) => {
+                    result = Err(format!("{:#}", e));
+                    break;
+                }
+            };
+            for sql in statements {
+                if let Err(e) = target.execute_query(&sql).await {
+                    result = Err(format!(
+                        "Failed to apply {} on {}: {:#}",
+                        event.op.as_str(),
+                        mapping.target_table,
+                        e
+                    ));
+                    break;
+                }
+            }
+            if result.is_err() {
+                break;
+            }
+        }
+
+        match result {
+            Ok(()) => target
+                .commit_transaction()
+                .await
+                .map_err(|e| format!("Failed to commit changes: {:#}", e)),
+            Err(e) => {
+                let _ = target.rollback_transaction().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn save_position(&self, position: &str) -> Result<(), String> {
+        let row = CdcPositionRow {
+            stream_id: self.request.stream_id.clone(),
+            source_connection_id: self.request.source_connection_id.clone(),
+            target_connection_id: self.request.target_connection_id.clone(),
+            position: position.to_string(),
+            events_applied: self.applied,
+            updated_at: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        };
+        self.app_db
+            .lock()
+            .await
+            .save_cdc_position(row)
+            .await
+            .map_err(|e| format!("Failed to store the CDC position: {:#}", e))
+    }
+
+    fn emit(&self, status: &str, error: Option<String>) {
+        let _ = self.app_handle.emit(
+            "cdc:progress",
+            CdcProgressEvent {
+                stream_id: self.request.stream_id.clone(),
+                status: status.to_string(),
+                events_applied: self.applied,
+                position: self.position.clone(),
+                error,
+            },
+        );
+    }
+}
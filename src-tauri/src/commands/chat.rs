@@ -125,14 +125,14 @@ pub async fn index_connection_context(
         .map_err(|e| e.to_string())?;
 
     // Fetch tables from the connection
-    let connector = {
+    let pool = {
         let reg = registry.lock().await;
         reg.get(&connection_id)
             .ok_or_else(|| format!("Connection {} not found in registry", connection_id))?
     };
 
     let table_names = {
-        let conn = connector.lock().await;
+        let conn = pool.acquire().await.map_err(|e| e.to_string())?;
         conn.get_tables().await.map_err(|e| e.to_string())?
     };
 
@@ -162,7 +162,7 @@ pub async fn index_connection_context(
     let limit = table_names.len().min(50);
     for table_name in &table_names[..limit] {
         let info = {
-            let conn = connector.lock().await;
+            let conn = pool.acquire().await.map_err(|e| e.to_string())?;
             match conn.get_table_info(table_name).await {
                 Ok(info) => info,
                 Err(_) => continue,
@@ -2,8 +2,10 @@ use std::sync::Arc;
 use tauri::State;
 use tokio::sync::Mutex;
 
-use crate::db::connectors::{ConnectionConfig, DatabaseEngine};
+use crate::db::connectors::{create_connector, ConnectionConfig, DatabaseEngine};
+use crate::db::pool::{ConnectionPool, PoolConfig, PoolStatus};
 use crate::db::registry::ConnectionRegistry;
+use crate::db::schema::SchemaFilter;
 
 /// DTO that the frontend sends (camelCase fields).
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -18,6 +20,12 @@ pub struct ConnectionConfigDto {
     pub connection_string: Option<String>,
     pub file_path: Option<String>,
     pub read_only: bool,
+    #[serde(default)]
+    pub pool: Option<PoolConfig>,
+    #[serde(default)]
+    pub schema_sample_size: Option<u32>,
+    #[serde(default)]
+    pub schema_filter: Option<SchemaFilter>,
 }
 
 impl From<ConnectionConfigDto> for ConnectionConfig {
@@ -33,6 +41,9 @@ impl From<ConnectionConfigDto> for ConnectionConfig {
             file_path: dto.file_path,
             read_only: dto.read_only,
             connection_timeout_secs: 30,
+            pool: dto.pool.unwrap_or_default(),
+            schema_sample_size: dto.schema_sample_size,
+            schema_filter: dto.schema_filter.unwrap_or_default(),
         }
     }
 }
@@ -40,25 +51,14 @@ impl From<ConnectionConfigDto> for ConnectionConfig {
 /// Test a database connection without persisting it.
 #[tauri::command]
 pub async fn test_connection(config: ConnectionConfigDto) -> Result<bool, String> {
-    use crate::db::connectors::*;
-
-    let cfg: ConnectionConfig = config.into();
-    let mut connector: Box<dyn DatabaseConnector> = match cfg.engine {
-        DatabaseEngine::SqlServer => Box::new(sqlserver::SqlServerConnector::new(cfg.clone())),
-        DatabaseEngine::PostgreSql => Box::new(postgres::PostgresConnector::new(cfg.clone())),
-        DatabaseEngine::MySql => Box::new(mysql::MySqlConnector::new(cfg.clone())),
-        DatabaseEngine::Sqlite => Box::new(sqlite::SqliteConnector::new(cfg.clone())),
-        DatabaseEngine::MongoDb => Box::new(mongodb_connector::MongoDbConnector::new(cfg.clone())),
-        DatabaseEngine::Oracle => Box::new(oracle::OracleConnector::new(cfg.clone())),
-        DatabaseEngine::CosmosDb => Box::new(cosmosdb::CosmosDbConnector::new(cfg.clone())),
-    };
+    let mut connector = create_connector(config.into());
 
     connector.connect().await.map_err(|e| e.to_string())?;
     connector.disconnect().await.map_err(|e| e.to_string())?;
     Ok(true)
 }
 
-/// Connect and register a database in the connection registry.
+/// Open a connection pool and register it in the connection registry.
 #[tauri::command]
 pub async fn connect_database(
     id: String,
@@ -66,8 +66,13 @@ pub async fn connect_database(
     registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
 ) -> Result<(), String> {
     let cfg: ConnectionConfig = config.into();
-    let mut reg = registry.lock().await;
-    reg.connect(id, cfg).await.map_err(|e| e.to_string())
+    // Connect outside the registry lock so other connections stay usable
+    let pool = ConnectionPool::open(cfg).await.map_err(|e| e.to_string())?;
+    let replaced = registry.lock().await.insert(id, pool);
+    if let Some(old) = replaced {
+        old.close().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 /// Disconnect a database and remove it from the registry.
@@ -76,6 +81,22 @@ pub async fn disconnect_database(
     id: String,
     registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
 ) -> Result<(), String> {
-    let mut reg = registry.lock().await;
-    reg.disconnect(&id).await.map_err(|e| e.to_string())
+    let pool = registry.lock().await.remove(&id);
+    match pool {
+        Some(pool) => pool.close().await.map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Report how many pooled connections a registered database has open.
+#[tauri::command]
+pub async fn connection_pool_status(
+    id: String,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+) -> Result<PoolStatus, String> {
+    let reg = registry.lock().await;
+    let pool = reg
+        .get(&id)
+        .ok_or_else(|| format!("Connection '{}' not found", id))?;
+    Ok(pool.status())
 }
@@ -1,40 +1,162 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+
+use futures_util::future::BoxFuture;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 
-use crate::db::data_comparator::{compare_data, DataCompareConfig, MatchStrategy};
+use crate::appdb::{
+    AppDatabase, MigrationBackupRow, MigrationCheckpointRow, MigrationHistoryRow, MigrationRunRow,
+    SyncWatermarkRow, UndoLogRow,
+};
+use crate::db::backup::{backup_table_name, expired_migrations, DEFAULT_RETENTION};
+use crate::db::checkpoint::{compare_keys, row_key, schema_fingerprint, sort_by_key, Phase, ResumePoint};
+use crate::db::connectors::{DatabaseConnector, DatabaseEngine};
+use crate::db::data_comparator::{compare_data, row_match_key, DataCompareConfig, MatchStrategy};
+use crate::db::document::{
+    key_filter, partial_update, DocumentQuery, DocumentWrite, DocumentWriteError,
+    DocumentWriteOptions,
+};
+use crate::db::embedding::{child_keys, embed, resolve_join, EmbedKind, Join};
+use crate::db::flattener::flatten_documents;
+use crate::db::incremental::{max_watermark, rows_since, split_soft_deleted};
 use crate::db::migrator::{
-    plan_migration, CancellationToken, MigrationConfig, MigrationMode,
+    effective_key_columns, plan_migration, CancellationToken, MigrationConfig, MigrationMode,
 };
-use crate::db::registry::{ConnectionRegistry, MigrationState};
-use crate::db::schema::{ColumnInfo, ConstraintType, Row};
+use crate::db::registry::{ConnectionRegistry, MigrationState, SharedPool};
+use crate::db::scheduler::{MigrationUnit, Schedule};
+use crate::db::schema::{qualified_table_name, ColumnInfo, ConstraintInfo, ConstraintType, Row};
 use crate::db::sql_generator::SqlGenerator;
+use crate::db::type_mapper::{map_type_with_overrides, native_type_with_params, TypeOverrideRegistry};
+use crate::db::typed_value::TypedValue;
+use crate::db::undo::{find_conflicts, undo_order, UndoConflict, UndoEntry, UndoOp};
+use crate::db::value_converter::{column_type_map, convert_row, ConversionError};
 
 // ── DTOs ──────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TableMappingDto {
     pub source_table: String,
     pub target_table: String,
     pub key_columns: Vec<String>,
+    /// Column that grows with every change (`updated_at`, rowversion). An
+    /// incremental sync only reads rows past its last synced value.
+    #[serde(default)]
+    pub watermark_column: Option<String>,
+    /// Source flag column marking rows deleted; flagged rows delete their
+    /// target row instead of being written.
+    #[serde(default)]
+    pub soft_delete_column: Option<String>,
+    /// Document-source filter (a MongoDB `find` filter) selecting the
+    /// documents to migrate. Target documents it leaves out are never
+    /// deleted.
+    #[serde(default)]
+    pub source_filter: Option<serde_json::Value>,
+    /// Document-source projection limiting the fields migrated.
+    #[serde(default)]
+    pub source_projection: Option<serde_json::Value>,
+    /// Related source tables embedded in each document written; needs a
+    /// document-store target. An incremental sync re-embeds only the
+    /// documents whose own row changed.
+    #[serde(default)]
+    pub embeddings: Vec<EmbeddingDto>,
+    /// Write documents as relational rows, nested fields as columns named
+    /// by their paths (`address_city`).
+    #[serde(default)]
+    pub flatten: bool,
+    /// Dotted path of the array whose elements are the rows written, keyed
+    /// by their document's `_id` and ordinal. Implies `flatten`. Elements
+    /// dropped from a document are only removed by a full sync.
+    #[serde(default)]
+    pub flatten_array: Option<String>,
+}
+
+/// Source table embedded in the documents of a mapping, joined to it on a
+/// foreign key between the two.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingDto {
+    pub source_table: String,
+    /// Document field holding the embedded rows.
+    pub field: String,
+    /// "Array" (default) for every related row, "Object" for the one
+    /// related row as a sub-document.
+    #[serde(default)]
+    pub embed_as: Option<String>,
+    /// Columns of the embedding and embedded table joined pairwise; read
+    /// from the source foreign keys between them when empty.
+    #[serde(default)]
+    pub parent_columns: Vec<String>,
+    #[serde(default)]
+    pub child_columns: Vec<String>,
+    /// Tables embedded in turn in each embedded row.
+    #[serde(default)]
+    pub embeddings: Vec<EmbeddingDto>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MigrationConfigDto {
     pub mode: String,
     pub conflict_resolution: String,
     pub batch_size: usize,
+    /// Tables migrated at once; defaults to the target pool's `maxSize`.
+    #[serde(default)]
+    pub max_parallel_tables: Option<usize>,
+    /// Foreign key handling while loading: "Auto" (default), "Defer" or
+    /// "Disable". See [`ConstraintMode`].
+    #[serde(default)]
+    pub constraint_mode: Option<String>,
+    /// Snapshot the target tables before writing; defaults to true.
+    #[serde(default)]
+    pub backup_before_migrate: Option<bool>,
+    /// Migrations per target connection whose backups are kept; older
+    /// ones are dropped after each new backup. Defaults to 5.
+    #[serde(default)]
+    pub backup_retention: Option<usize>,
+    /// Read only source rows at or past each table's stored watermark. Target
+    /// rows missing from the source are then never deleted.
+    #[serde(default)]
+    pub incremental: bool,
+    /// On document-store targets, stop each bulk write at its first failed
+    /// document instead of writing the rest.
+    #[serde(default)]
+    pub ordered_writes: bool,
+    /// Target schema for each source schema (`dbo` → `public`). A target
+    /// table named in a mapped schema, or unqualified in the source
+    /// engine's default schema, moves to the schema mapped to.
+    #[serde(default)]
+    pub schema_map: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DryRunRequest {
     pub source_connection_id: String,
     pub target_connection_id: String,
     pub tables: Vec<TableMappingDto>,
     pub config: MigrationConfigDto,
+    /// Optional project file with `typeOverrides`; these take precedence
+    /// over overrides stored in settings.
+    #[serde(default)]
+    pub type_override_file: Option<String>,
+    /// Scheduled job the run belongs to; incremental watermarks are kept
+    /// per job.
+    #[serde(default)]
+    pub job_id: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnTypeMappingDto {
+    pub column: String,
+    pub source_type: String,
+    pub target_type: String,
+    pub applied_override: Option<String>,
+    pub warnings: Vec<String>,
+    pub is_lossy: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -49,6 +171,7 @@ pub struct DryRunTableResult {
     pub deletes: usize,
     pub skips: usize,
     pub warnings: Vec<String>,
+    pub type_mappings: Vec<ColumnTypeMappingDto>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -66,6 +189,16 @@ pub struct MigrationProgressEvent {
     pub status: String,
 }
 
+/// Rows left violating a foreign key after a load with relaxed constraints.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintViolationDto {
+    pub table: String,
+    pub constraint: String,
+    pub referenced_table: String,
+    pub violating_rows: u64,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MigrationResultDto {
@@ -76,10 +209,104 @@ pub struct MigrationResultDto {
     pub error_count: usize,
     pub duration_ms: u64,
     pub status: String,
+    /// Tables loaded with relaxed foreign keys and anything that went
+    /// wrong relaxing, restoring or validating them.
+    pub warnings: Vec<String>,
+    /// Foreign keys found violated when they were re-validated.
+    pub constraint_violations: Vec<ConstraintViolationDto>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoResultDto {
+    /// False when conflicts blocked the undo; the target is then untouched.
+    pub applied: bool,
+    pub rows_reverted: usize,
+    pub conflicts: Vec<UndoConflict>,
+    /// Caveats of an applied undo, such as running without a transaction.
+    pub warnings: Vec<String>,
 }
 
 // ── Helpers ──────────────────────────────────────────────────────────
 
+/// Settings key holding a JSON array of `TypeOverride`s.
+const TYPE_OVERRIDES_SETTING: &str = "typeOverrides";
+
+/// Build the override registry: project file entries first, then the ones
+/// saved in settings.
+async fn load_type_overrides(
+    app_db: &Mutex<AppDatabase>,
+    project_file: Option<&str>,
+) -> Result<TypeOverrideRegistry, String> {
+    let mut registry = match project_file {
+        Some(path) => TypeOverrideRegistry::load_file(std::path::Path::new(path))
+            .map_err(|e| format!("{:#}", e))?,
+        None => TypeOverrideRegistry::default(),
+    };
+    let saved = app_db
+        .lock()
+        .await
+        .get_setting(TYPE_OVERRIDES_SETTING.to_string())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(json) = saved.filter(|j| !j.trim().is_empty()) {
+        let from_settings = TypeOverrideRegistry::from_json(&json)
+            .map_err(|e| format!("Invalid '{}' setting: {:#}", TYPE_OVERRIDES_SETTING, e))?;
+        registry.extend(from_settings);
+    }
+    Ok(registry)
+}
+
+/// Look up the source and target pools. The registry lock is released
+/// before any connection is checked out.
+async fn resolve_pools(
+    registry: &Mutex<ConnectionRegistry>,
+    request: &DryRunRequest,
+) -> Result<(SharedPool, SharedPool), String> {
+    let reg = registry.lock().await;
+    let source = reg
+        .get(&request.source_connection_id)
+        .ok_or("Source connection not found")?;
+    let target = reg
+        .get(&request.target_connection_id)
+        .ok_or("Target connection not found")?;
+    Ok((source, target))
+}
+
+/// `request` with `config.schema_map` applied to its target tables.
+fn with_schema_map(
+    request: &DryRunRequest,
+    source: &DatabaseEngine,
+    target: &DatabaseEngine,
+) -> DryRunRequest {
+    let mut request = request.clone();
+    let schema_map = std::mem::take(&mut request.config.schema_map);
+    for table in &mut request.tables {
+        table.target_table = remap_table_schema(&table.target_table, &schema_map, source, target);
+    }
+    request
+}
+
+/// `table` moved to the target schema `schema_map` gives its schema, named
+/// the way the target engine lists it. Unmapped tables keep their name.
+fn remap_table_schema(
+    table: &str,
+    schema_map: &HashMap<String, String>,
+    source: &DatabaseEngine,
+    target: &DatabaseEngine,
+) -> String {
+    let qualified = table.split_once('.').filter(|_| source.qualifies_table_names());
+    let (schema, name) = match (qualified, source.default_schema()) {
+        (Some(parts), _) => parts,
+        (None, Some(default)) => (default, table),
+        (None, None) => return table.to_string(),
+    };
+    match schema_map.get(schema) {
+        Some(mapped) => qualified_table_name(mapped, name, target.default_schema().unwrap_or("")),
+        None => table.to_string(),
+    }
+}
+
 fn parse_mode(s: &str) -> MigrationMode {
     match s {
         "Mirror" => MigrationMode::Mirror,
@@ -90,6 +317,47 @@ fn parse_mode(s: &str) -> MigrationMode {
     }
 }
 
+/// How foreign keys are handled while loading the target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConstraintMode {
+    /// Enforce them; only tables in an FK cycle, a self-referencing table
+    /// included, are loaded with checks off.
+    Auto,
+    /// Load each scheduled unit in one transaction with checks deferred to
+    /// commit. Engines that cannot defer fall back to `Disable`.
+    Defer,
+    /// Switch checks off while loading, then re-enable and validate them.
+    Disable,
+}
+
+/// The mode a scheduled unit is loaded in: `Auto` enforces foreign keys
+/// unless the unit is an FK cycle (a self-referencing table included),
+/// where a child row can precede its parent and checks are disabled.
+fn unit_constraint_mode(requested: ConstraintMode, unit: &MigrationUnit) -> ConstraintMode {
+    if requested == ConstraintMode::Auto && unit.cyclic {
+        ConstraintMode::Disable
+    } else {
+        requested
+    }
+}
+
+fn parse_constraint_mode(s: Option<&str>) -> ConstraintMode {
+    match s {
+        Some("Defer") => ConstraintMode::Defer,
+        Some("Disable") => ConstraintMode::Disable,
+        _ => ConstraintMode::Auto,
+    }
+}
+
+/// Read a `COUNT(*)` result, whatever numeric representation the engine uses.
+fn first_count(rows: &[Row]) -> Option<u64> {
+    match rows.first()?.values().next()? {
+        serde_json::Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f as u64)),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        other => TypedValue::from_json(other).and_then(|t| t.to_text().parse().ok()),
+    }
+}
+
 /// Filter a row to only include columns present in the target table.
 fn filter_row_to_target(row: &Row, target_columns: &std::collections::HashSet<String>) -> Row {
     row.iter()
@@ -103,92 +371,505 @@ fn build_migration_config(dto: &MigrationConfigDto, key_columns: &[String]) -> M
         mode: parse_mode(&dto.mode),
         batch_size: dto.batch_size.max(1),
         key_columns: key_columns.to_vec(),
+        backup_before_migrate: dto.backup_before_migrate.unwrap_or(true),
         ..Default::default()
     }
 }
 
-/// Sort table mappings so parent tables (FK targets) are processed before
-/// children. Uses Kahn's algorithm for topological sort. Tables involved
-/// in circular FK references are appended at the end in their original order.
-fn sort_tables_by_fk(
+/// Target-side facts gathered once before planning or migrating.
+#[derive(Default)]
+struct TargetTables {
+    columns: HashMap<String, Vec<ColumnInfo>>,
+    /// FK parent tables per table, including the table itself when it
+    /// references itself.
+    fk_parents: HashMap<String, Vec<String>>,
+    /// FK constraints per table, to disable by name and to validate.
+    foreign_keys: HashMap<String, Vec<ConstraintInfo>>,
+}
+
+/// Read the target schema of every mapped table. Tables whose info cannot
+/// be read are left out and migrate without schema validation.
+async fn prefetch_target_tables(
+    tgt_pool: &SharedPool,
     tables: &[TableMappingDto],
-    fk_deps: &std::collections::HashMap<String, Vec<String>>,
-) -> Vec<TableMappingDto> {
-    let table_set: std::collections::HashSet<&str> =
-        tables.iter().map(|t| t.target_table.as_str()).collect();
+) -> Result<TargetTables, String> {
+    let mut targets = TargetTables::default();
+    let guard = tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+    for table in tables {
+        if let Ok(info) = guard.get_table_info(&table.target_table).await {
+            let fks: Vec<ConstraintInfo> = info
+                .constraints
+                .into_iter()
+                .filter(|c| c.constraint_type == ConstraintType::ForeignKey)
+                .collect();
+            let parents: Vec<String> = fks
+                .iter()
+                .filter_map(|c| c.referenced_table.clone())
+                .collect();
+            targets
+                .fk_parents
+                .insert(table.target_table.clone(), parents);
+            targets.foreign_keys.insert(table.target_table.clone(), fks);
+            targets
+                .columns
+                .insert(table.target_table.clone(), info.columns);
+        }
+    }
+    Ok(targets)
+}
 
-    // Map target_table name -> index in input slice
-    let idx_map: std::collections::HashMap<&str, usize> = tables
+/// Schema fingerprint per table, keyed by table name. Tables whose info
+/// cannot be read fingerprint as having no columns.
+async fn source_fingerprints(
+    src_pool: &SharedPool,
+    tables: &[TableMappingDto],
+) -> Result<BTreeMap<String, String>, String> {
+    let guard = src_pool.acquire().await.map_err(|e| e.to_string())?;
+    let mut fingerprints = BTreeMap::new();
+    for table in tables {
+        // A schema inferred from a random sample of documents varies
+        // between reads, so it cannot tell whether the source changed
+        if guard.engine() == DatabaseEngine::MongoDb {
+            fingerprints.insert(table.source_table.clone(), schema_fingerprint(&[]));
+            continue;
+        }
+        let columns = guard
+            .get_table_info(&table.source_table)
+            .await
+            .map(|info| info.columns)
+            .unwrap_or_default();
+        fingerprints.insert(table.source_table.clone(), schema_fingerprint(&columns));
+    }
+    Ok(fingerprints)
+}
+
+fn target_fingerprints(
+    targets: &TargetTables,
+    tables: &[TableMappingDto],
+) -> BTreeMap<String, String> {
+    tables
         .iter()
-        .enumerate()
-        .map(|(i, t)| (t.target_table.as_str(), i))
-        .collect();
+        .map(|t| {
+            let columns = targets.columns.get(&t.target_table).cloned().unwrap_or_default();
+            (t.target_table.clone(), schema_fingerprint(&columns))
+        })
+        .collect()
+}
 
-    // Build in-degree counts (only for tables in our working set)
-    let mut in_degree: std::collections::HashMap<&str, usize> = tables
+/// Tables whose fingerprint differs from the one stored when the run started.
+fn changed_tables(stored_json: &str, current: &BTreeMap<String, String>) -> Vec<String> {
+    let stored: BTreeMap<String, String> = serde_json::from_str(stored_json).unwrap_or_default();
+    let mut changed: Vec<String> = current
         .iter()
-        .map(|t| (t.target_table.as_str(), 0usize))
+        .filter(|(table, hash)| stored.get(*table) != Some(*hash))
+        .map(|(table, _)| table.clone())
         .collect();
+    changed.extend(stored.keys().filter(|t| !current.contains_key(*t)).cloned());
+    changed
+}
+
+fn timestamp_now() -> String {
+    chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// Scope incremental watermarks are stored under: the job, or the
+/// connection pair for runs outside a job.
+fn sync_scope(request: &DryRunRequest) -> String {
+    request.job_id.clone().unwrap_or_else(|| {
+        format!("{}->{}", request.source_connection_id, request.target_connection_id)
+    })
+}
+
+/// Stored watermarks of the request's tables. A watermark recorded for a
+/// different column is ignored, so changing the column starts over.
+async fn load_watermarks(
+    request: &DryRunRequest,
+    app_db: &Mutex<AppDatabase>,
+) -> Result<HashMap<(String, String), serde_json::Value>, String> {
+    let stored = app_db
+        .lock()
+        .await
+        .load_watermarks(sync_scope(request))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(stored
+        .into_iter()
+        .filter(|w| {
+            request.tables.iter().any(|t| {
+                t.source_table == w.source_table
+                    && t.target_table == w.target_table
+                    && t.watermark_column.as_deref() == Some(w.watermark_column.as_str())
+            })
+        })
+        .filter_map(|w| {
+            let value = serde_json::from_str(&w.watermark_json).ok()?;
+            Some(((w.source_table, w.target_table), value))
+        })
+        .collect())
+}
+
+/// Source rows of a mapping: the whole table, or the documents matching its
+/// filter and projection.
+async fn fetch_source_rows(
+    conn: &dyn DatabaseConnector,
+    table: &TableMappingDto,
+) -> anyhow::Result<Vec<Row>> {
+    if table.source_filter.is_none() && table.source_projection.is_none() {
+        return conn.get_rows(&table.source_table, None, None).await;
+    }
+    let query = DocumentQuery {
+        filter: table.source_filter.clone(),
+        projection: table.source_projection.clone(),
+        ..Default::default()
+    };
+    conn.find_documents(&table.source_table, &query).await
+}
+
+/// Rows of a flattened mapping: its documents' own columns, or the
+/// elements of its array.
+fn flatten_source(table: &TableMappingDto, rows: Vec<Row>) -> Vec<Row> {
+    if !table.flatten && table.flatten_array.is_none() {
+        return rows;
+    }
+    flatten_documents(&rows, &table.source_table, table.flatten_array.as_deref())
+}
+
+/// Embedded rows are only written to document stores.
+fn check_embedding_target(table: &TableMappingDto, engine: &DatabaseEngine) -> Result<(), String> {
+    if table.embeddings.is_empty() || *engine == DatabaseEngine::MongoDb {
+        return Ok(());
+    }
+    Err(format!(
+        "{} embeds related tables, which needs a document-store target, not {}",
+        table.source_table, engine
+    ))
+}
+
+/// Columns joining `table` to an embedded table: the ones named on the
+/// embedding, or those of a source foreign key between the two.
+async fn embedding_join(
+    conn: &dyn DatabaseConnector,
+    table: &str,
+    embedding: &EmbeddingDto,
+) -> anyhow::Result<Join> {
+    if !embedding.parent_columns.is_empty() || !embedding.child_columns.is_empty() {
+        if embedding.parent_columns.len() != embedding.child_columns.len() {
+            anyhow::bail!(
+                "Embedding {} in {} joins {} parent columns to {} child columns",
+                embedding.source_table,
+                table,
+                embedding.parent_columns.len(),
+                embedding.child_columns.len()
+            );
+        }
+        return Ok(Join {
+            parent_columns: embedding.parent_columns.clone(),
+            child_columns: embedding.child_columns.clone(),
+        });
+    }
+    let parent = conn.get_table_info(table).await?.constraints;
+    let child = conn.get_table_info(&embedding.source_table).await?.constraints;
+    resolve_join(table, &embedding.source_table, &parent, &child).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No foreign key joins {} and {}; name the join columns on the embedding",
+            table,
+            embedding.source_table
+        )
+    })
+}
 
-    for t in tables {
-        if let Some(deps) = fk_deps.get(&t.target_table) {
-            for dep in deps {
-                if table_set.contains(dep.as_str()) && dep != &t.target_table {
-                    *in_degree.entry(t.target_table.as_str()).or_insert(0) += 1;
+/// Embed the tables of `embeddings` in `rows` of `table`, nested ones
+/// first. With `chunk`, only the rows related to `rows` are read, that
+/// many keys at a time; otherwise related tables are read whole. Returns
+/// warnings about sub-documents that matched more than one row.
+fn embed_related<'a>(
+    conn: &'a dyn DatabaseConnector,
+    table: &'a str,
+    rows: &'a mut [Row],
+    embeddings: &'a [EmbeddingDto],
+    chunk: Option<usize>,
+) -> BoxFuture<'a, anyhow::Result<Vec<String>>> {
+    Box::pin(async move {
+        let mut warnings = Vec::new();
+        for embedding in embeddings {
+            let join = embedding_join(conn, table, embedding).await?;
+            let mut children = match chunk {
+                Some(chunk) => {
+                    let keys = child_keys(rows, &join);
+                    if keys.is_empty() {
+                        Vec::new()
+                    } else {
+                        let child = &embedding.source_table;
+                        fetch_rows_by_key(conn, child, &join.child_columns, &keys, chunk).await?
+                    }
                 }
+                None => conn.get_rows(&embedding.source_table, None, None).await?,
+            };
+            let nested = &embedding.embeddings;
+            let child = embedding.source_table.as_str();
+            warnings.extend(embed_related(conn, child, &mut children, nested, chunk).await?);
+
+            let kind = EmbedKind::parse(embedding.embed_as.as_deref().unwrap_or_default());
+            let ambiguous = embed(rows, children, &embedding.field, kind, &join);
+            if ambiguous > 0 {
+                warnings.push(format!(
+                    "{} row(s) of {} match more than one {} row; '{}' holds the first",
+                    ambiguous, table, embedding.source_table, embedding.field
+                ));
             }
         }
+        Ok(warnings)
+    })
+}
+
+/// Rows of `table` whose `column` is at or past `since`, filtered by the
+/// database when it speaks SQL and after a full read otherwise.
+async fn fetch_rows_since(
+    conn: &dyn DatabaseConnector,
+    table: &str,
+    column: &str,
+    since: &serde_json::Value,
+) -> anyhow::Result<Vec<Row>> {
+    match SqlGenerator::new(conn.engine()).select_rows_since(table, column, since) {
+        Some(sql) => conn.execute_query(&sql).await,
+        None => Ok(rows_since(conn.get_rows(table, None, None).await?, column, since)),
+    }
+}
+
+/// Rows of `table` matching the keys of `rows`, queried `chunk` keys at a
+/// time when the database speaks SQL and filtered from a full read
+/// otherwise.
+async fn fetch_rows_by_key(
+    conn: &dyn DatabaseConnector,
+    table: &str,
+    key_columns: &[String],
+    rows: &[Row],
+    chunk: usize,
+) -> anyhow::Result<Vec<Row>> {
+    let sql_gen = SqlGenerator::new(conn.engine());
+    let mut found = Vec::new();
+    for keys in rows.chunks(chunk.max(1)) {
+        let Some(sql) = sql_gen.select_by_keys(table, key_columns, keys) else {
+            let wanted: HashSet<String> =
+                rows.iter().map(|r| row_match_key(r, key_columns)).collect();
+            let all = conn.get_rows(table, None, None).await?;
+            return Ok(all
+                .into_iter()
+                .filter(|r| wanted.contains(&row_match_key(r, key_columns)))
+                .collect());
+        };
+        found.extend(conn.execute_query(&sql).await?);
     }
+    Ok(found)
+}
+
+fn undo_log_row(migration_id: &str, entry: &UndoEntry) -> UndoLogRow {
+    let json = |row: &Row| serde_json::to_string(row).unwrap_or_default();
+    UndoLogRow {
+        seq: 0,
+        migration_id: migration_id.to_string(),
+        target_table: entry.table.clone(),
+        operation: entry.op.as_str().to_string(),
+        key_json: json(&entry.key),
+        before_json: entry.before.as_ref().map(json),
+        after_json: entry.after.as_ref().map(json),
+    }
+}
+
+fn undo_entry(row: &UndoLogRow) -> Option<UndoEntry> {
+    let parse = |text: &str| serde_json::from_str::<Row>(text).ok();
+    Some(UndoEntry {
+        table: row.target_table.clone(),
+        op: UndoOp::parse(&row.operation)?,
+        key: parse(&row.key_json)?,
+        before: row.before_json.as_deref().and_then(parse),
+        after: row.after_json.as_deref().and_then(parse),
+    })
+}
+
+fn target_table_names(tables: &[TableMappingDto]) -> Vec<String> {
+    tables.iter().map(|t| t.target_table.clone()).collect()
+}
 
-    // Kahn's algorithm
-    let mut queue: std::collections::VecDeque<&str> = in_degree
+/// Mappings that write to `target_table`, in request order.
+fn mappings_for<'a>(
+    tables: &'a [TableMappingDto],
+    target_table: &'a str,
+) -> impl Iterator<Item = &'a TableMappingDto> {
+    tables
         .iter()
-        .filter(|(_, &deg)| deg == 0)
-        .map(|(&name, _)| name)
-        .collect();
+        .filter(move |t| t.target_table == target_table)
+}
 
-    // Sort the initial queue for deterministic order
-    let mut q_vec: Vec<&str> = queue.drain(..).collect();
-    q_vec.sort();
-    queue.extend(q_vec);
+/// Sort table mappings so parent tables (FK targets) are processed before
+/// children. Tables in a circular FK reference stay together in request order.
+fn sort_tables_by_fk(
+    tables: &[TableMappingDto],
+    fk_deps: &HashMap<String, Vec<String>>,
+) -> Vec<TableMappingDto> {
+    let schedule = Schedule::new(&target_table_names(tables), fk_deps);
+    for unit in schedule.units().iter().filter(|u| u.tables.len() > 1) {
+        log::warn!(
+            "Tables {} have circular FK dependencies; processing in original order",
+            unit.tables.join(", ")
+        );
+    }
+    schedule
+        .linear_order()
+        .iter()
+        .flat_map(|name| mappings_for(tables, name).cloned())
+        .collect()
+}
 
-    let mut sorted: Vec<usize> = Vec::with_capacity(tables.len());
-    let mut visited: std::collections::HashSet<&str> = std::collections::HashSet::new();
+fn log_conversion_errors(table: &str, operation: &str, errors: &[ConversionError]) {
+    for e in errors {
+        log::warn!(
+            "Skipping {} on {}: column '{}': {}",
+            operation,
+            table,
+            e.column.as_deref().unwrap_or("?"),
+            e
+        );
+    }
+}
 
-    while let Some(current) = queue.pop_front() {
-        if let Some(&idx) = idx_map.get(current) {
-            sorted.push(idx);
-            visited.insert(current);
+/// Snapshot every existing target table of the request into a shadow
+/// table before anything is written, recording each in the app DB. Returns
+/// a warning instead when the target engine has no SQL tables to copy.
+async fn create_backups(
+    request: &DryRunRequest,
+    migration_id: &str,
+    tgt_pool: &SharedPool,
+    targets: &TargetTables,
+    app_db: &Mutex<AppDatabase>,
+) -> Result<Vec<String>, String> {
+    let guard = tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+    let sql_gen = SqlGenerator::new(guard.engine());
+    let mut seen = HashSet::new();
+    for table in target_table_names(&request.tables) {
+        if !seen.insert(table.clone()) || !targets.columns.contains_key(&table) {
+            continue;
         }
-        // Decrease in-degree for tables that depend on `current`
-        for t in tables {
-            if visited.contains(t.target_table.as_str()) {
-                continue;
-            }
-            if let Some(deps) = fk_deps.get(&t.target_table) {
-                if deps.iter().any(|d| d == current) {
-                    let deg = in_degree.entry(t.target_table.as_str()).or_insert(1);
-                    *deg = deg.saturating_sub(1);
-                    if *deg == 0 {
-                        queue.push_back(t.target_table.as_str());
-                    }
-                }
-            }
+        let backup_table = backup_table_name(migration_id, &table);
+        let Some(sql) = sql_gen.create_snapshot(&table, &backup_table) else {
+            return Ok(vec![format!(
+                "{} targets cannot be snapshotted; the migration runs without a backup",
+                guard.engine()
+            )]);
+        };
+        guard
+            .execute_query(&sql)
+            .await
+            .map_err(|e| format!("Failed to back up {} to {}: {:#}", table, backup_table, e))?;
+        let row_count = guard
+            .execute_query(&sql_gen.count_rows(&backup_table))
+            .await
+            .ok()
+            .and_then(|rows| first_count(&rows))
+            .map(|n| n as i64);
+        app_db
+            .lock()
+            .await
+            .save_backup(MigrationBackupRow {
+                migration_id: migration_id.to_string(),
+                target_connection_id: request.target_connection_id.clone(),
+                target_table: table,
+                backup_table,
+                row_count,
+                status: "available".to_string(),
+                created_at: timestamp_now(),
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(Vec::new())
+}
+
+/// Drop the shadow tables of migrations on this target that fall outside
+/// the retention policy. Failures only produce warnings.
+async fn prune_backups(
+    target_connection_id: &str,
+    keep: usize,
+    tgt_pool: &SharedPool,
+    app_db: &Mutex<AppDatabase>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let backups = match app_db.lock().await.load_backups(None).await {
+        Ok(backups) => backups,
+        Err(e) => return vec![format!("Could not read backups for retention: {:#}", e)],
+    };
+    let live: Vec<&MigrationBackupRow> = backups
+        .iter()
+        .filter(|b| b.target_connection_id == target_connection_id && b.status != "expired")
+        .collect();
+    let mut runs: Vec<(String, String)> = Vec::new();
+    for backup in &live {
+        if !runs.iter().any(|(id, _)| id == &backup.migration_id) {
+            runs.push((backup.migration_id.clone(), backup.created_at.clone()));
         }
     }
+    let expired = expired_migrations(&runs, keep);
+    if expired.is_empty() {
+        return warnings;
+    }
 
-    // Append any remaining tables (circular deps) in original order
-    for (i, t) in tables.iter().enumerate() {
-        if !visited.contains(t.target_table.as_str()) {
-            sorted.push(i);
-            log::warn!(
-                "Table '{}' has circular FK dependencies; processing in original order",
-                t.target_table
-            );
+    let guard = match tgt_pool.acquire().await {
+        Ok(guard) => guard,
+        Err(e) => return vec![format!("Could not connect to drop expired backups: {:#}", e)],
+    };
+    let sql_gen = SqlGenerator::new(guard.engine());
+    for migration_id in expired {
+        for backup in live.iter().filter(|b| b.migration_id == migration_id) {
+            if let Err(e) = guard.execute_query(&sql_gen.drop_table(&backup.backup_table)).await {
+                warnings.push(format!(
+                    "Could not drop expired backup {}: {:#}",
+                    backup.backup_table, e
+                ));
+            }
+        }
+        if let Err(e) = app_db
+            .lock()
+            .await
+            .set_backup_status(migration_id.clone(), "expired".to_string())
+            .await
+        {
+            warnings.push(format!("Could not expire backups of {}: {:#}", migration_id, e));
         }
     }
+    warnings
+}
 
-    sorted.iter().map(|&i| tables[i].clone()).collect()
+/// Record the outcome of a run in the migration history.
+async fn record_history(
+    app_db: &Mutex<AppDatabase>,
+    request: &DryRunRequest,
+    migration_id: &str,
+    started_at: String,
+    outcome: &Result<MigrationResultDto, String>,
+) {
+    let result = outcome.as_ref().ok();
+    let count = |f: fn(&MigrationResultDto) -> usize| result.map_or(0, |r| f(r) as i64);
+    let entry = MigrationHistoryRow {
+        id: migration_id.to_string(),
+        source_connection_id: Some(request.source_connection_id.clone()),
+        target_connection_id: Some(request.target_connection_id.clone()),
+        mode: request.config.mode.clone(),
+        status: result.map_or("failed".to_string(), |r| r.status.clone()),
+        config_json: serde_json::to_string(&request.config).ok(),
+        result_json: result.and_then(|r| serde_json::to_string(r).ok()),
+        error: outcome.as_ref().err().cloned(),
+        started_at,
+        completed_at: Some(timestamp_now()),
+        rows_inserted: count(|r| r.rows_inserted),
+        rows_updated: count(|r| r.rows_updated),
+        rows_deleted: count(|r| r.rows_deleted),
+        rows_skipped: count(|r| r.rows_skipped),
+        error_count: count(|r| r.error_count),
+        duration_ms: result.map_or(0, |r| r.duration_ms as i64),
+    };
+    if let Err(e) = app_db.lock().await.save_migration(entry).await {
+        log::warn!("Failed to record migration {} in history: {:#}", migration_id, e);
+    }
 }
 
 // ── Commands ─────────────────────────────────────────────────────────
@@ -199,57 +880,45 @@ fn sort_tables_by_fk(
 pub async fn dry_run(
     request: DryRunRequest,
     registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
 ) -> Result<Vec<DryRunTableResult>, String> {
+    let type_overrides =
+        load_type_overrides(&app_db, request.type_override_file.as_deref()).await?;
+    let (src_pool, tgt_pool) = resolve_pools(&registry, &request).await?;
+    let request = with_schema_map(&request, &src_pool.engine(), &tgt_pool.engine());
+
     // Pre-fetch target schemas for validation and FK ordering
-    let mut table_schemas: std::collections::HashMap<String, Vec<ColumnInfo>> =
-        std::collections::HashMap::new();
-    let mut fk_deps: std::collections::HashMap<String, Vec<String>> =
-        std::collections::HashMap::new();
-    {
-        let reg = registry.lock().await;
-        let tgt_conn = reg
-            .get(&request.target_connection_id)
-            .ok_or("Target connection not found")?;
-        let guard = tgt_conn.lock().await;
-        for table in &request.tables {
-            if let Ok(info) = guard.get_table_info(&table.target_table).await {
-                table_schemas.insert(table.target_table.clone(), info.columns.clone());
-                let deps: Vec<String> = info
-                    .constraints
-                    .iter()
-                    .filter(|c| c.constraint_type == ConstraintType::ForeignKey)
-                    .filter_map(|c| c.referenced_table.clone())
-                    .filter(|rt| rt != &table.target_table)
-                    .collect();
-                fk_deps.insert(table.target_table.clone(), deps);
-            }
-        }
-    }
+    let targets = prefetch_target_tables(&tgt_pool, &request.tables).await?;
 
-    let sorted_tables = sort_tables_by_fk(&request.tables, &fk_deps);
+    let sorted_tables = sort_tables_by_fk(&request.tables, &targets.fk_parents);
     let mut results = Vec::new();
 
     for table in &sorted_tables {
-        // Fetch rows from source
-        let source_rows = {
-            let reg = registry.lock().await;
-            let src_conn = reg
-                .get(&request.source_connection_id)
-                .ok_or("Source connection not found")?;
-            let guard = src_conn.lock().await;
-            guard
-                .get_rows(&table.source_table, None, None)
+        // Fetch rows and column types from source
+        check_embedding_target(table, &tgt_pool.engine())?;
+        let (source_rows, source_engine, source_columns, embedding_warnings) = {
+            let guard = src_pool.acquire().await.map_err(|e| e.to_string())?;
+            let mut rows = fetch_source_rows(&*guard, table)
                 .await
-                .map_err(|e| format!("Source fetch error ({}): {}", table.source_table, e))?
+                .map_err(|e| format!("Source fetch error ({}): {}", table.source_table, e))?;
+            rows = flatten_source(table, rows);
+            // A filtered read only needs the rows related to it
+            let chunk = table.source_filter.as_ref().map(|_| request.config.batch_size);
+            let warnings =
+                embed_related(&*guard, &table.source_table, &mut rows, &table.embeddings, chunk)
+                    .await
+                    .map_err(|e| format!("Embedding error ({}): {:#}", table.source_table, e))?;
+            let columns = guard
+                .get_table_info(&table.source_table)
+                .await
+                .map(|info| info.columns)
+                .unwrap_or_default();
+            (rows, guard.engine(), columns, warnings)
         };
 
         // Fetch rows from target
         let target_rows = {
-            let reg = registry.lock().await;
-            let tgt_conn = reg
-                .get(&request.target_connection_id)
-                .ok_or("Target connection not found")?;
-            let guard = tgt_conn.lock().await;
+            let guard = tgt_pool.acquire().await.map_err(|e| e.to_string())?;
             guard
                 .get_rows(&table.target_table, None, None)
                 .await
@@ -257,7 +926,8 @@ pub async fn dry_run(
         };
 
         // Use pre-fetched schema; fall back to row keys
-        let schema = table_schemas
+        let schema = targets
+            .columns
             .get(&table.target_table)
             .cloned()
             .unwrap_or_default();
@@ -280,10 +950,14 @@ pub async fn dry_run(
         };
 
         let mig_config = build_migration_config(&request.config, &table.key_columns);
-        let plan = plan_migration(&filtered_source, &target_rows, &mig_config);
+        let mut plan = plan_migration(&filtered_source, &target_rows, &mig_config);
+        // Documents the source filter leaves out are not gone
+        if table.source_filter.is_some() {
+            plan.rows_to_delete.clear();
+        }
 
         // Check for schema incompatibilities and count rows that would be skipped
-        let mut warnings = Vec::new();
+        let mut warnings = embedding_warnings;
         let source_col_names: std::collections::HashSet<&str> = filtered_source
             .first()
             .map(|r| r.keys().map(|k| k.as_str()).collect())
@@ -301,24 +975,68 @@ pub async fn dry_run(
         }
 
         // Count rows that would be skipped by prepare_row_for_insert
-        let sql_gen = SqlGenerator::new({
-            let reg = registry.lock().await;
-            let tgt_conn = reg
-                .get(&request.target_connection_id)
-                .ok_or("Target connection not found")?;
-            let guard = tgt_conn.lock().await;
-            guard.engine()
-        });
+        let target_engine = tgt_pool.engine();
+        let column_types = column_type_map(&target_engine, &schema);
+
+        // Report the type each source column maps to and which override fired
+        let type_mappings: Vec<ColumnTypeMappingDto> = source_columns
+            .iter()
+            .map(|col| {
+                let source_type = native_type_with_params(col);
+                let mapped = map_type_with_overrides(
+                    &source_engine,
+                    &target_engine,
+                    &source_type,
+                    &type_overrides,
+                    Some(&table.source_table),
+                    Some(&col.name),
+                );
+                ColumnTypeMappingDto {
+                    column: col.name.clone(),
+                    source_type,
+                    target_type: mapped.target_type,
+                    applied_override: mapped.applied_override,
+                    warnings: mapped.warnings,
+                    is_lossy: mapped.is_lossy,
+                }
+            })
+            .collect();
+
+        let sql_gen = SqlGenerator::new(target_engine);
         let mut valid_inserts = 0usize;
         let mut skipped_inserts = 0usize;
+        let mut conversion_failures = 0usize;
+        let mut first_conversion_error: Option<ConversionError> = None;
         for row in &plan.rows_to_insert {
             let (prepared, _) = sql_gen.prepare_row_for_insert(row, &schema);
-            if prepared.is_some() {
-                valid_inserts += 1;
-            } else {
-                skipped_inserts += 1;
+            match prepared.map(|r| convert_row(&r, &column_types)) {
+                Some(Ok(_)) => valid_inserts += 1,
+                Some(Err(errs)) => {
+                    skipped_inserts += 1;
+                    conversion_failures += 1;
+                    if first_conversion_error.is_none() {
+                        first_conversion_error = errs.into_iter().next();
+                    }
+                }
+                None => skipped_inserts += 1,
             }
         }
+        for row in &plan.rows_to_update {
+            if let Err(errs) = convert_row(row, &column_types) {
+                conversion_failures += 1;
+                if first_conversion_error.is_none() {
+                    first_conversion_error = errs.into_iter().next();
+                }
+            }
+        }
+        if let Some(err) = first_conversion_error {
+            warnings.push(format!(
+                "{} row(s) have values that cannot be converted to the target column types - e.g. column '{}': {}",
+                conversion_failures,
+                err.column.as_deref().unwrap_or("?"),
+                err
+            ));
+        }
 
         results.push(DryRunTableResult {
             source_table: table.source_table.clone(),
@@ -330,154 +1048,760 @@ pub async fn dry_run(
             deletes: plan.rows_to_delete.len(),
             skips: plan.rows_to_review.len() + skipped_inserts,
             warnings,
+            type_mappings,
         });
     }
 
     Ok(results)
 }
 
-/// Execute a real migration: fetch data, compute plan, generate SQL,
-/// execute on target, emit progress events.
-#[tauri::command]
-pub async fn execute_migration(
-    request: DryRunRequest,
-    migration_id: String,
-    app_handle: AppHandle,
-    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
-    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
-) -> Result<MigrationResultDto, String> {
-    // Set up cancellation token
-    let cancel_token = CancellationToken::new();
-    {
-        let mut ms = migration_state.lock().await;
-        ms.insert(migration_id.clone(), cancel_token.clone());
+/// Running counts for a migration, summed over tables.
+#[derive(Debug, Clone, Copy, Default)]
+struct MigrationTotals {
+    inserted: usize,
+    updated: usize,
+    deleted: usize,
+    skipped: usize,
+    errors: usize,
+}
+
+impl MigrationTotals {
+    fn add(&mut self, other: &MigrationTotals) {
+        self.inserted += other.inserted;
+        self.updated += other.updated;
+        self.deleted += other.deleted;
+        self.skipped += other.skipped;
+        self.errors += other.errors;
     }
+}
 
-    let start = std::time::Instant::now();
-    let mut total_inserted = 0usize;
-    let mut total_updated = 0usize;
-    let mut total_deleted = 0usize;
-    let mut total_skipped = 0usize;
-    let mut total_errors = 0usize;
-    let mut final_status = "completed".to_string();
+/// Writes done on one table since its last saved checkpoint.
+struct TableProgress {
+    phase: Phase,
+    first_key: Option<Vec<serde_json::Value>>,
+    last_key: Option<Vec<serde_json::Value>>,
+    unsaved: usize,
+    /// Undo entries of the writes since the last checkpoint.
+    undo: Vec<UndoEntry>,
+    /// Watermark to store once the table completes without errors.
+    watermark: Option<serde_json::Value>,
+}
 
-    // ── Pre-fetch target schemas and FK dependencies for all tables ──
-    let mut table_schemas: std::collections::HashMap<String, Vec<ColumnInfo>> =
-        std::collections::HashMap::new();
-    let mut fk_deps: std::collections::HashMap<String, Vec<String>> =
-        std::collections::HashMap::new();
-    {
-        let reg = registry.lock().await;
-        let tgt_conn = reg
-            .get(&request.target_connection_id)
-            .ok_or("Target connection not found")?;
-        let guard = tgt_conn.lock().await;
-        for table in &request.tables {
-            if let Ok(info) = guard.get_table_info(&table.target_table).await {
-                table_schemas.insert(table.target_table.clone(), info.columns.clone());
-                let deps: Vec<String> = info
-                    .constraints
-                    .iter()
-                    .filter(|c| c.constraint_type == ConstraintType::ForeignKey)
-                    .filter_map(|c| c.referenced_table.clone())
-                    .filter(|rt| rt != &table.target_table)
-                    .collect();
-                fk_deps.insert(table.target_table.clone(), deps);
-            }
+impl TableProgress {
+    /// Progress of a table whose writes are all done.
+    fn finished() -> Self {
+        Self {
+            phase: Phase::Delete,
+            first_key: None,
+            last_key: None,
+            unsaved: 0,
+            undo: Vec::new(),
+            watermark: None,
         }
     }
+}
 
-    // ── Sort tables by FK dependency order (parents first) ──
-    let sorted_tables = sort_tables_by_fk(&request.tables, &fk_deps);
+/// Writes queued for one bulk call on a document-store target, each with
+/// the undo entry to log once it applies.
+#[derive(Default)]
+struct PendingWrites {
+    writes: Vec<DocumentWrite>,
+    undo: Vec<UndoEntry>,
+}
 
-    for table in &sorted_tables {
-        if cancel_token.is_cancelled() {
-            final_status = "cancelled".to_string();
-            break;
-        }
+impl PendingWrites {
+    fn push(&mut self, write: DocumentWrite, undo: UndoEntry) {
+        self.writes.push(write);
+        self.undo.push(undo);
+    }
+}
 
-        // Emit table-start event
-        let _ = app_handle.emit(
+fn key_json(key: &Option<Vec<serde_json::Value>>) -> Option<String> {
+    key.as_ref().map(|k| serde_json::Value::Array(k.clone()).to_string())
+}
+
+/// State shared by the tables of one `execute_migration` run. Tables run
+/// concurrently on the command's task, so the counters only need a plain
+/// mutex that is never held across an await.
+struct MigrationRun<'a> {
+    request: &'a DryRunRequest,
+    migration_id: &'a str,
+    app_handle: &'a AppHandle,
+    src_pool: &'a SharedPool,
+    tgt_pool: &'a SharedPool,
+    cancel_token: &'a CancellationToken,
+    app_db: &'a Mutex<AppDatabase>,
+    constraint_mode: ConstraintMode,
+    targets: TargetTables,
+    /// Checkpoints of an interrupted run, keyed by (source, target) table.
+    checkpoints: HashMap<(String, String), MigrationCheckpointRow>,
+    totals: std::sync::Mutex<MigrationTotals>,
+    warnings: std::sync::Mutex<Vec<String>>,
+    violations: std::sync::Mutex<Vec<ConstraintViolationDto>>,
+    /// Stored watermarks by (source, target) table, for incremental syncs.
+    watermarks: HashMap<(String, String), serde_json::Value>,
+    /// Progress of tables in a deferred unit by (source, target) table,
+    /// held until the unit's transaction commits.
+    deferred: std::sync::Mutex<HashMap<(String, String), TableProgress>>,
+}
+
+impl MigrationRun<'_> {
+    fn emit_progress(&self, table: &str, processed_rows: usize, total_rows: usize, status: &str) {
+        let totals = *self.totals.lock().unwrap();
+        let _ = self.app_handle.emit(
             "migration:progress",
             MigrationProgressEvent {
-                migration_id: migration_id.clone(),
-                table: table.source_table.clone(),
-                processed_rows: 0,
-                total_rows: 0,
-                inserted: total_inserted,
-                updated: total_updated,
-                deleted: total_deleted,
-                skipped: total_skipped,
-                errors: total_errors,
-                status: "running".to_string(),
+                migration_id: self.migration_id.to_string(),
+                table: table.to_string(),
+                processed_rows,
+                total_rows,
+                inserted: totals.inserted,
+                updated: totals.updated,
+                deleted: totals.deleted,
+                skipped: totals.skipped,
+                errors: totals.errors,
+                status: status.to_string(),
             },
         );
+    }
 
-        // Fetch source rows
-        let source_rows = {
-            let reg = registry.lock().await;
-            let src_conn = reg
-                .get(&request.source_connection_id)
-                .ok_or("Source connection not found")?;
-            let guard = src_conn.lock().await;
-            guard
-                .get_rows(&table.source_table, None, None)
-                .await
-                .map_err(|e| format!("Source fetch error: {}", e))?
-        };
+    fn warn(&self, message: String) {
+        log::warn!("{}", message);
+        self.warnings.lock().unwrap().push(message);
+    }
 
-        // Fetch target rows
-        let target_rows = {
-            let reg = registry.lock().await;
-            let tgt_conn = reg
-                .get(&request.target_connection_id)
-                .ok_or("Target connection not found")?;
-            let guard = tgt_conn.lock().await;
-            guard
-                .get_rows(&table.target_table, None, None)
-                .await
-                .map_err(|e| format!("Target fetch error: {}", e))?
-        };
+    /// Migrate every table of a scheduled unit on one target connection,
+    /// relaxing foreign keys for it as the constraint mode asks. Tables in an
+    /// FK cycle are always loaded with constraints deferred or disabled.
+    async fn migrate_unit(&self, unit: &MigrationUnit) -> Result<(), String> {
+        let mut target = self.tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+        let mappings: Vec<&TableMappingDto> = unit
+            .tables
+            .iter()
+            .flat_map(|name| mappings_for(&self.request.tables, name))
+            .collect();
+        let tables = unit.tables.join(", ");
+        let sql_gen = SqlGenerator::new(target.engine());
 
-        // Determine target engine and target columns for SQL generation
-        let (target_engine, target_columns, target_schema) = {
-            let reg = registry.lock().await;
-            let tgt_conn = reg
-                .get(&request.target_connection_id)
-                .ok_or("Target connection not found")?;
-            let guard = tgt_conn.lock().await;
-            let engine = guard.engine();
-            // Use pre-fetched schema; fall back to row keys
-            let schema = table_schemas
-                .get(&table.target_table)
-                .cloned()
-                .unwrap_or_default();
-            let cols: std::collections::HashSet<String> = if !schema.is_empty() {
-                schema.iter().map(|c| c.name.clone()).collect()
-            } else if let Some(first) = target_rows.first() {
-                first.keys().cloned().collect()
+        let mut mode = unit_constraint_mode(self.constraint_mode, unit);
+        if mode == ConstraintMode::Auto {
+            for mapping in mappings {
+                self.migrate_table(mapping, &*target, true).await?;
+            }
+            return Ok(());
+        }
+        if self.constraint_mode == ConstraintMode::Auto {
+            let cycle = if unit.tables.len() == 1 {
+                format!("Table {} references itself", tables)
             } else {
-                std::collections::HashSet::new()
+                format!("Tables {} reference each other in a foreign key cycle", tables)
             };
-            (engine, cols, schema)
-        };
-
-        let has_column_filter = !target_columns.is_empty();
+            self.warn(format!(
+                "{}; loading with foreign key checks disabled on {}",
+                cycle,
+                target.engine()
+            ));
+        }
+        if mode == ConstraintMode::Defer && sql_gen.defer_constraints().is_none() {
+            self.warn(format!(
+                "{} cannot defer foreign key checks; disabling them while loading {} instead",
+                target.engine(),
+                tables
+            ));
+            mode = ConstraintMode::Disable;
+        }
+        if mode == ConstraintMode::Defer {
+            if let Some(reason) = self.undeferrable_keys(&*target, unit, &sql_gen).await {
+                self.warn(format!(
+                    "{}; disabling foreign keys while loading {} instead",
+                    reason, tables
+                ));
+                mode = ConstraintMode::Disable;
+            }
+        }
 
-        // Filter source rows to only target-compatible columns before planning
-        let filtered_source: Vec<Row> = if has_column_filter {
-            source_rows.iter().map(|r| filter_row_to_target(r, &target_columns)).collect()
+        if mode == ConstraintMode::Defer {
+            self.load_deferred(&mut *target, unit, &mappings, &sql_gen)
+                .await
         } else {
-            source_rows.clone()
-        };
+            self.load_with_fks_disabled(&*target, unit, &mappings, &sql_gen)
+                .await
+        }
+    }
 
-        // Use compare_data directly to get changed_columns for partial updates
-        let compare_config = DataCompareConfig {
-            match_strategy: if table.key_columns.is_empty() {
-                MatchStrategy::PrimaryKey
-            } else {
-                MatchStrategy::CompositeKey(table.key_columns.clone())
-            },
+    /// Why deferring the unit's foreign keys would not help: some are not
+    /// declared `DEFERRABLE`, so they would still be checked per statement.
+    /// `None` when all of them can be deferred.
+    async fn undeferrable_keys(
+        &self,
+        target: &dyn DatabaseConnector,
+        unit: &MigrationUnit,
+        sql_gen: &SqlGenerator,
+    ) -> Option<String> {
+        let sql = sql_gen.non_deferrable_foreign_keys(&unit.tables)?;
+        match target.execute_query(&sql).await {
+            Ok(rows) => {
+                let names: Vec<&str> = rows
+                    .iter()
+                    .filter_map(|r| r.get("name").and_then(|v| v.as_str()))
+                    .collect();
+                (!names.is_empty()).then(|| {
+                    format!(
+                        "Foreign keys {} on {} are not DEFERRABLE",
+                        names.join(", "),
+                        unit.tables.join(", ")
+                    )
+                })
+            }
+            Err(e) => Some(format!("Could not check which foreign keys are deferrable: {:#}", e)),
+        }
+    }
+
+    /// Load a unit with FK checks switched off, then switch them back on and
+    /// count the rows that violate them.
+    async fn load_with_fks_disabled(
+        &self,
+        target: &dyn DatabaseConnector,
+        unit: &MigrationUnit,
+        mappings: &[&TableMappingDto],
+        sql_gen: &SqlGenerator,
+    ) -> Result<(), String> {
+        let tables = unit.tables.join(", ");
+        let fk_tables: Vec<(String, Vec<String>)> = unit
+            .tables
+            .iter()
+            .map(|t| {
+                let names = self
+                    .foreign_keys_of(t)
+                    .iter()
+                    .map(|c| c.name.clone())
+                    .collect();
+                (t.clone(), names)
+            })
+            .collect();
+
+        for sql in sql_gen.disable_foreign_keys(&fk_tables) {
+            if let Err(e) = target.execute_query(&sql).await {
+                self.warn(format!(
+                    "Could not disable foreign keys for {} ({}): {:#}. Rows referencing rows \
+                     that are not loaded yet will fail.",
+                    tables, sql, e
+                ));
+            }
+        }
+
+        let mut result = Ok(());
+        for mapping in mappings {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+            if let Err(e) = self.migrate_table(mapping, target, true).await {
+                result = Err(e);
+                break;
+            }
+        }
+
+        // Validate before re-enabling: SQL Server and Oracle refuse to
+        // re-enable a violated constraint, and the count says why
+        if result.is_ok() {
+            self.validate_foreign_keys(target, unit, sql_gen).await;
+        }
+
+        // Always restore, so the connection goes back to the pool enforcing FKs
+        for sql in sql_gen.enable_foreign_keys(&fk_tables) {
+            if let Err(e) = target.execute_query(&sql).await {
+                self.warn(format!(
+                    "Re-enabling foreign keys for {} failed ({}): {:#}",
+                    tables, sql, e
+                ));
+            }
+        }
+        result
+    }
+
+    /// Load a unit in one transaction with FK checks deferred to commit. The
+    /// unit is all or nothing: a failed statement or a violated key rolls
+    /// back every table in it.
+    async fn load_deferred(
+        &self,
+        target: &mut dyn DatabaseConnector,
+        unit: &MigrationUnit,
+        mappings: &[&TableMappingDto],
+        sql_gen: &SqlGenerator,
+    ) -> Result<(), String> {
+        let tables = unit.tables.join(", ");
+        target
+            .begin_transaction()
+            .await
+            .map_err(|e| format!("Failed to start transaction for {}: {:#}", tables, e))?;
+        if let Some(sql) = sql_gen.defer_constraints() {
+            if let Err(e) = target.execute_query(&sql).await {
+                self.warn(format!(
+                    "Could not defer foreign keys for {} ({}): {:#}",
+                    tables, sql, e
+                ));
+            }
+        }
+
+        // Checkpoints are only saved once the transaction commits
+        let mut loaded = Vec::new();
+        let mut outcome = Ok(MigrationTotals::default());
+        for mapping in mappings {
+            if self.cancel_token.is_cancelled() {
+                break;
+            }
+            match self.migrate_table(mapping, &*target, false).await {
+                Ok(counts) => {
+                    if let Ok(total) = outcome.as_mut() {
+                        total.add(&counts);
+                    }
+                    loaded.push((*mapping, counts));
+                }
+                Err(e) => {
+                    outcome = Err(e);
+                    break;
+                }
+            }
+        }
+
+        let failure = match outcome {
+            Err(e) => Some(e),
+            Ok(counts) if counts.errors > 0 => Some(format!(
+                "{} statement(s) failed while loading {} with deferred constraints",
+                counts.errors, tables
+            )),
+            Ok(_) => {
+                let violations = self.validate_foreign_keys(&*target, unit, sql_gen).await;
+                (!violations.is_empty()).then(|| {
+                    let details: Vec<String> = violations
+                        .iter()
+                        .map(|v| {
+                            format!("{}.{}: {} row(s)", v.table, v.constraint, v.violating_rows)
+                        })
+                        .collect();
+                    format!(
+                        "Deferred foreign keys on {} are violated ({})",
+                        tables,
+                        details.join(", ")
+                    )
+                })
+            }
+        };
+
+        match failure {
+            None => {
+                target
+                    .commit_transaction()
+                    .await
+                    .map_err(|e| format!("Failed to commit {}: {:#}", tables, e))?;
+                let completed = !self.cancel_token.is_cancelled();
+                for (mapping, counts) in &loaded {
+                    let mut progress = self.take_deferred(mapping);
+                    if completed {
+                        self.save_checkpoint(mapping, &mut progress, counts, true).await;
+                    } else {
+                        self.save_undo(progress.undo).await;
+                    }
+                }
+                Ok(())
+            }
+            Some(e) => {
+                for mapping in mappings {
+                    self.take_deferred(mapping);
+                }
+                if let Err(rb) = target.rollback_transaction().await {
+                    log::warn!("Rollback of {} failed: {:#}", tables, rb);
+                }
+                Err(format!("{}; the transaction was rolled back", e))
+            }
+        }
+    }
+
+    /// FK constraints of a target table, as read before the run.
+    fn foreign_keys_of(&self, table: &str) -> &[ConstraintInfo] {
+        self.targets
+            .foreign_keys
+            .get(table)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Count rows violating each foreign key of the unit's tables. Violations
+    /// are recorded for the result and also returned.
+    async fn validate_foreign_keys(
+        &self,
+        target: &dyn DatabaseConnector,
+        unit: &MigrationUnit,
+        sql_gen: &SqlGenerator,
+    ) -> Vec<ConstraintViolationDto> {
+        let mut found = Vec::new();
+        for table in &unit.tables {
+            for fk in self.foreign_keys_of(table) {
+                let Some(sql) = sql_gen.count_fk_violations(table, fk) else {
+                    self.warn(format!(
+                        "Cannot validate foreign key {} on {}: referenced columns are unknown",
+                        fk.name, table
+                    ));
+                    continue;
+                };
+                let count = match target.execute_query(&sql).await {
+                    Ok(rows) => first_count(&rows),
+                    Err(e) => {
+                        self.warn(format!(
+                            "Validating foreign key {} on {} failed: {:#}",
+                            fk.name, table, e
+                        ));
+                        continue;
+                    }
+                };
+                match count {
+                    Some(0) => {}
+                    Some(n) => {
+                        self.warn(format!(
+                            "{} row(s) in {} violate foreign key {} to {}",
+                            n,
+                            table,
+                            fk.name,
+                            fk.referenced_table.as_deref().unwrap_or("?")
+                        ));
+                        found.push(ConstraintViolationDto {
+                            table: table.clone(),
+                            constraint: fk.name.clone(),
+                            referenced_table: fk.referenced_table.clone().unwrap_or_default(),
+                            violating_rows: n,
+                        });
+                    }
+                    None => self.warn(format!(
+                        "Validating foreign key {} on {} returned no count",
+                        fk.name, table
+                    )),
+                }
+            }
+        }
+        self.violations
+            .lock()
+            .unwrap()
+            .extend(found.iter().cloned());
+        found
+    }
+
+    fn take_deferred(&self, table: &TableMappingDto) -> TableProgress {
+        let pair = (table.source_table.clone(), table.target_table.clone());
+        self.deferred
+            .lock()
+            .unwrap()
+            .remove(&pair)
+            .unwrap_or_else(TableProgress::finished)
+    }
+
+    /// Append entries to the migration's undo log.
+    async fn save_undo(&self, entries: Vec<UndoEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+        let rows: Vec<UndoLogRow> = entries
+            .iter()
+            .map(|e| undo_log_row(self.migration_id, e))
+            .collect();
+        if let Err(e) = self.app_db.lock().await.save_undo_entries(rows).await {
+            self.warn(format!(
+                "Failed to record {} undo entries; those writes cannot be undone: {:#}",
+                entries.len(),
+                e
+            ));
+        }
+    }
+
+    /// Save a checkpoint for `table` with its cumulative counts, after the
+    /// undo entries of the writes it covers. A table completed with every
+    /// row applied also advances its incremental watermark; one with errors
+    /// or skipped rows keeps the old one so the next sync reads them again.
+    async fn save_checkpoint(
+        &self,
+        table: &TableMappingDto,
+        progress: &mut TableProgress,
+        counts: &MigrationTotals,
+        completed: bool,
+    ) {
+        self.save_undo(std::mem::take(&mut progress.undo)).await;
+        if completed && counts.errors == 0 && counts.skipped == 0 {
+            if let (Some(column), Some(value)) = (&table.watermark_column, progress.watermark.take())
+            {
+                let watermark = SyncWatermarkRow {
+                    scope: sync_scope(self.request),
+                    source_table: table.source_table.clone(),
+                    target_table: table.target_table.clone(),
+                    watermark_column: column.clone(),
+                    watermark_json: value.to_string(),
+                    updated_at: timestamp_now(),
+                };
+                if let Err(e) = self.app_db.lock().await.save_watermark(watermark).await {
+                    self.warn(format!(
+                        "Failed to store the watermark of {}; its next sync rereads these rows: {:#}",
+                        table.source_table, e
+                    ));
+                }
+            }
+        }
+        let checkpoint = MigrationCheckpointRow {
+            migration_id: self.migration_id.to_string(),
+            source_table: table.source_table.clone(),
+            target_table: table.target_table.clone(),
+            phase: progress.phase.as_str().to_string(),
+            first_key: key_json(&progress.first_key),
+            last_key: key_json(&progress.last_key),
+            rows_inserted: counts.inserted as i64,
+            rows_updated: counts.updated as i64,
+            rows_deleted: counts.deleted as i64,
+            rows_skipped: counts.skipped as i64,
+            error_count: counts.errors as i64,
+            completed,
+            updated_at: timestamp_now(),
+        };
+        if let Err(e) = self.app_db.lock().await.save_checkpoint(checkpoint).await {
+            log::warn!("Failed to save checkpoint for {}: {:#}", table.target_table, e);
+        }
+    }
+
+    /// Note that the write on `key` in `phase` is about to run, saving a
+    /// checkpoint first when a batch is full or the phase changes.
+    async fn advance(
+        &self,
+        table: &TableMappingDto,
+        progress: &mut TableProgress,
+        counts: &MigrationTotals,
+        phase: Phase,
+        key: Vec<serde_json::Value>,
+    ) {
+        let batch_full = progress.unsaved >= self.request.config.batch_size.max(1);
+        if progress.unsaved > 0 && (batch_full || phase != progress.phase) {
+            self.save_checkpoint(table, progress, counts, false).await;
+            progress.unsaved = 0;
+            progress.first_key = None;
+        }
+        progress.phase = phase;
+        if progress.first_key.is_none() {
+            progress.first_key = Some(key.clone());
+        }
+        progress.last_key = Some(key);
+        progress.unsaved += 1;
+    }
+
+    /// Send the queued document writes, counting each one that applied and
+    /// reporting each one that failed.
+    async fn flush_documents(
+        &self,
+        table: &TableMappingDto,
+        target: &dyn DatabaseConnector,
+        pending: &mut PendingWrites,
+        progress: &mut TableProgress,
+        counts: &mut MigrationTotals,
+    ) {
+        if pending.writes.is_empty() {
+            return;
+        }
+        let writes = std::mem::take(&mut pending.writes);
+        let undo = std::mem::take(&mut pending.undo);
+        let options = DocumentWriteOptions {
+            batch_size: self.request.config.batch_size.max(1),
+            ordered: self.request.config.ordered_writes,
+        };
+        let mut failed: HashMap<usize, DocumentWriteError> = match target
+            .write_documents(&table.target_table, &writes, &options)
+            .await
+        {
+            Ok(result) => result.errors.into_iter().map(|e| (e.index, e)).collect(),
+            Err(e) => (0..writes.len())
+                .map(|index| {
+                    let error = DocumentWriteError {
+                        index,
+                        code: None,
+                        message: format!("{:#}", e),
+                    };
+                    (index, error)
+                })
+                .collect(),
+        };
+        for (index, (write, entry)) in writes.iter().zip(undo).enumerate() {
+            match failed.remove(&index) {
+                Some(error) => {
+                    counts.errors += 1;
+                    log::warn!(
+                        "{} error on {} for document {}: {}{}",
+                        write.kind(),
+                        table.target_table,
+                        serde_json::to_string(&entry.key).unwrap_or_default(),
+                        error.message,
+                        error.code.map(|c| format!(" (code {})", c)).unwrap_or_default()
+                    );
+                }
+                None => {
+                    match write {
+                        DocumentWrite::Insert { .. } => counts.inserted += 1,
+                        DocumentWrite::Delete { .. } => counts.deleted += 1,
+                        _ => counts.updated += 1,
+                    }
+                    progress.undo.push(entry);
+                }
+            }
+        }
+    }
+
+    /// Fetch, plan and write one table on the given target connection.
+    /// With `checkpoints`, progress is saved after every batch so an
+    /// interrupted run can resume; a table already completed by an earlier
+    /// attempt is skipped. Document-store targets are written in bulk, one
+    /// batch per checkpoint.
+    async fn migrate_table(
+        &self,
+        table: &TableMappingDto,
+        target: &dyn DatabaseConnector,
+        checkpoints: bool,
+    ) -> Result<MigrationTotals, String> {
+        let request = self.request;
+        let cancel_token = self.cancel_token;
+        let previous = self
+            .checkpoints
+            .get(&(table.source_table.clone(), table.target_table.clone()));
+        let mut counts = previous
+            .map(|c| MigrationTotals {
+                inserted: c.rows_inserted as usize,
+                updated: c.rows_updated as usize,
+                deleted: c.rows_deleted as usize,
+                skipped: c.rows_skipped as usize,
+                errors: c.error_count as usize,
+            })
+            .unwrap_or_default();
+
+        if previous.is_some_and(|c| c.completed) {
+            self.totals.lock().unwrap().add(&counts);
+            self.emit_progress(&table.source_table, 0, 0, "completed");
+            return Ok(counts);
+        }
+        let resume = previous.and_then(|c| {
+            let phase = Phase::parse(&c.phase)?;
+            let last_key = serde_json::from_str(c.last_key.as_deref()?).ok()?;
+            Some(ResumePoint { phase, last_key })
+        });
+        let mut progress = TableProgress {
+            phase: resume.as_ref().map_or(Phase::Insert, |r| r.phase),
+            first_key: None,
+            last_key: resume.as_ref().map(|r| r.last_key.clone()),
+            unsaved: 0,
+            undo: Vec::new(),
+            watermark: None,
+        };
+        let done = |phase: Phase, key: &[serde_json::Value]| {
+            resume.as_ref().is_some_and(|r| r.covers(phase, key))
+        };
+
+        check_embedding_target(table, &target.engine())?;
+
+        // Emit table-start event
+        self.emit_progress(&table.source_table, 0, 0, "running");
+
+        // Fetch source rows; an incremental sync only reads those past the
+        // table's stored watermark
+        let watermark_column = table
+            .watermark_column
+            .as_deref()
+            .filter(|_| request.config.incremental);
+        let since = watermark_column.and_then(|_| {
+            self.watermarks
+                .get(&(table.source_table.clone(), table.target_table.clone()))
+        });
+        let source_rows = {
+            let guard = self.src_pool.acquire().await.map_err(|e| e.to_string())?;
+            let subset = table.source_filter.is_some() || table.source_projection.is_some();
+            match (watermark_column, since) {
+                (Some(column), Some(after)) if !subset => {
+                    fetch_rows_since(&*guard, &table.source_table, column, after).await
+                }
+                (Some(column), Some(after)) => fetch_source_rows(&*guard, table)
+                    .await
+                    .map(|rows| rows_since(rows, column, after)),
+                _ => fetch_source_rows(&*guard, table).await,
+            }
+            .map_err(|e| format!("Source fetch error: {}", e))?
+        };
+        progress.watermark = watermark_column
+            .and_then(|column| max_watermark(&source_rows, column))
+            .or_else(|| since.cloned());
+        let (mut source_rows, soft_deleted) = match table.soft_delete_column.as_deref() {
+            Some(flag) => split_soft_deleted(source_rows, flag),
+            None => (source_rows, Vec::new()),
+        };
+        source_rows = flatten_source(table, source_rows);
+        let soft_deleted = flatten_source(table, soft_deleted);
+
+        // A partial read only needs the target rows it can touch, and only
+        // the related rows its documents embed
+        let partial = since.is_some() || table.source_filter.is_some();
+        if !table.embeddings.is_empty() {
+            let guard = self.src_pool.acquire().await.map_err(|e| e.to_string())?;
+            let chunk = partial.then_some(request.config.batch_size);
+            let warnings = embed_related(
+                &*guard,
+                &table.source_table,
+                &mut source_rows,
+                &table.embeddings,
+                chunk,
+            )
+            .await
+            .map_err(|e| format!("Embedding error: {:#}", e))?;
+            for warning in warnings {
+                self.warn(warning);
+            }
+        }
+        let mig_config = build_migration_config(&request.config, &table.key_columns);
+        let target_rows = if partial {
+            let changed: Vec<Row> = source_rows.iter().chain(&soft_deleted).cloned().collect();
+            let key = effective_key_columns(&mig_config, &changed);
+            let chunk = request.config.batch_size;
+            fetch_rows_by_key(target, &table.target_table, &key, &changed, chunk).await
+        } else {
+            target.get_rows(&table.target_table, None, None).await
+        }
+        .map_err(|e| format!("Target fetch error: {}", e))?;
+
+        // Determine target engine and target columns for SQL generation
+        let (target_engine, target_columns, target_schema) = {
+            let engine = target.engine();
+            // Use pre-fetched schema; fall back to row keys
+            let schema = self
+                .targets
+                .columns
+                .get(&table.target_table)
+                .cloned()
+                .unwrap_or_default();
+            let cols: std::collections::HashSet<String> = if !schema.is_empty() {
+                schema.iter().map(|c| c.name.clone()).collect()
+            } else if let Some(first) = target_rows.first() {
+                first.keys().cloned().collect()
+            } else {
+                std::collections::HashSet::new()
+            };
+            (engine, cols, schema)
+        };
+
+        // Collections take any field, so only SQL targets drop columns
+        let has_column_filter =
+            !target_columns.is_empty() && target_engine != DatabaseEngine::MongoDb;
+
+        // Filter source rows to only target-compatible columns before planning
+        let filtered_source: Vec<Row> = if has_column_filter {
+            source_rows.iter().map(|r| filter_row_to_target(r, &target_columns)).collect()
+        } else {
+            source_rows.clone()
+        };
+
+        // Use compare_data directly to get changed_columns for partial updates
+        let compare_config = DataCompareConfig {
+            match_strategy: if table.key_columns.is_empty() {
+                MatchStrategy::PrimaryKey
+            } else {
+                MatchStrategy::CompositeKey(table.key_columns.clone())
+            },
             ignore_columns: Vec::new(),
             normalize_whitespace: false,
             case_insensitive: false,
@@ -486,20 +1810,67 @@ pub async fn execute_migration(
             use_hash_mode: false,
             batch_size: request.config.batch_size.max(1),
         };
-        let diff = compare_data(&filtered_source, &target_rows, &compare_config);
+        let mut diff = compare_data(&filtered_source, &target_rows, &compare_config);
 
         // Also build a plan for insert/delete decisions based on mode
-        let mig_config = build_migration_config(&request.config, &table.key_columns);
-        let plan = plan_migration(&filtered_source, &target_rows, &mig_config);
+        let mut plan = plan_migration(&filtered_source, &target_rows, &mig_config);
 
+        // Rows missing from a partial read are unchanged, not gone
+        if partial {
+            plan.rows_to_delete.clear();
+        }
+        // Source rows flagged deleted remove their target row in every mode
+        if !soft_deleted.is_empty() {
+            let key = effective_key_columns(&mig_config, &soft_deleted);
+            let flagged: HashSet<String> =
+                soft_deleted.iter().map(|r| row_match_key(r, &key)).collect();
+            let planned: HashSet<String> =
+                plan.rows_to_delete.iter().map(|r| row_match_key(r, &key)).collect();
+            plan.rows_to_delete.extend(
+                target_rows
+                    .iter()
+                    .filter(|r| {
+                        let k = row_match_key(r, &key);
+                        flagged.contains(&k) && !planned.contains(&k)
+                    })
+                    .cloned(),
+            );
+        }
+
+        // Write in key order so a checkpoint's last key bounds what is done
+        let order_key = effective_key_columns(&mig_config, &filtered_source);
+        sort_by_key(&mut plan.rows_to_insert, &order_key);
+        sort_by_key(&mut plan.rows_to_delete, &order_key);
+        diff.updated_rows.sort_by(|a, b| {
+            compare_keys(&row_key(&a.source_row, &order_key), &row_key(&b.source_row, &order_key))
+        });
+
+        let column_types = column_type_map(&target_engine, &target_schema);
+        let documents = target_engine == DatabaseEngine::MongoDb;
         let sql_gen = SqlGenerator::new(target_engine);
         let key_cols = &table.key_columns;
+        let doc_key = if key_cols.is_empty() { &order_key } else { key_cols };
+        let batch_size = request.config.batch_size.max(1);
+        let mut pending = PendingWrites::default();
 
         // Execute inserts (with schema-aware validation)
         for row in &plan.rows_to_insert {
             if cancel_token.is_cancelled() {
                 break;
             }
+            let key = row_key(row, &order_key);
+            if done(Phase::Insert, &key) {
+                continue;
+            }
+            // A checkpoint only covers writes already sent
+            if pending.writes.len() >= batch_size || (checkpoints && progress.unsaved >= batch_size)
+            {
+                self.flush_documents(table, target, &mut pending, &mut progress, &mut counts)
+                    .await;
+            }
+            if checkpoints {
+                self.advance(table, &mut progress, &counts, Phase::Insert, key).await;
+            }
             // Validate row against target schema: truncate oversized strings,
             // skip rows missing required NOT NULL columns.
             let (prepared, prep_warnings) =
@@ -510,7 +1881,7 @@ pub async fn execute_migration(
             let insert_row = match prepared {
                 Some(r) => r,
                 None => {
-                    total_skipped += 1;
+                    counts.skipped += 1;
                     log::warn!(
                         "Skipping insert on {}: row failed NOT NULL validation",
                         table.target_table
@@ -518,124 +1889,503 @@ pub async fn execute_migration(
                     continue;
                 }
             };
-            let sql = sql_gen.generate_insert(&table.target_table, &insert_row);
-            let exec_result = {
-                let reg = registry.lock().await;
-                let tgt_conn = reg
-                    .get(&request.target_connection_id)
-                    .ok_or("Target connection not found")?;
-                let guard = tgt_conn.lock().await;
-                guard.execute_query(&sql).await
+            // Coerce values to the target column types (dates, decimals, GUIDs, ...)
+            let insert_row = match convert_row(&insert_row, &column_types) {
+                Ok(r) => r,
+                Err(errs) => {
+                    counts.errors += 1;
+                    log_conversion_errors(&table.target_table, "insert", &errs);
+                    continue;
+                }
             };
+            if documents {
+                let entry = UndoEntry::inserted(&table.target_table, &insert_row, &order_key);
+                pending.push(DocumentWrite::Insert { document: insert_row }, entry);
+                continue;
+            }
+            let sql = sql_gen.generate_insert(&table.target_table, &insert_row);
+            let exec_result = target.execute_query(&sql).await;
             match exec_result {
-                Ok(_) => total_inserted += 1,
+                Ok(_) => {
+                    counts.inserted += 1;
+                    let entry = UndoEntry::inserted(&table.target_table, &insert_row, &order_key);
+                    progress.undo.push(entry);
+                }
                 Err(e) => {
-                    total_errors += 1;
+                    counts.errors += 1;
                     log::warn!("Insert error on {}: {:#}\nSQL: {}", table.target_table, e, sql);
                 }
             }
         }
+        self.flush_documents(table, target, &mut pending, &mut progress, &mut counts)
+            .await;
 
         // Execute updates using partial SET (only changed columns)
         for row_diff in &diff.updated_rows {
             if cancel_token.is_cancelled() {
                 break;
             }
+            let key = row_key(&row_diff.source_row, &order_key);
+            if done(Phase::Update, &key) {
+                continue;
+            }
+            if pending.writes.len() >= batch_size || (checkpoints && progress.unsaved >= batch_size)
+            {
+                self.flush_documents(table, target, &mut pending, &mut progress, &mut counts)
+                    .await;
+            }
+            if checkpoints {
+                self.advance(table, &mut progress, &counts, Phase::Update, key).await;
+            }
+            let update_row = match convert_row(&row_diff.source_row, &column_types) {
+                Ok(r) => r,
+                Err(errs) => {
+                    counts.errors += 1;
+                    log_conversion_errors(&table.target_table, "update", &errs);
+                    continue;
+                }
+            };
+            if documents {
+                // Mirror makes the document match the source whole
+                let write = if mig_config.mode == MigrationMode::Mirror {
+                    Some(DocumentWrite::Replace {
+                        filter: key_filter(&update_row, doc_key),
+                        document: update_row.clone(),
+                    })
+                } else {
+                    partial_update(&update_row, &row_diff.changed_columns, doc_key)
+                };
+                let Some(write) = write else {
+                    counts.skipped += 1;
+                    continue;
+                };
+                let entry = UndoEntry::updated(
+                    &table.target_table,
+                    &row_diff.target_row,
+                    &update_row,
+                    &row_diff.changed_columns,
+                    &order_key,
+                );
+                pending.push(write, entry);
+                continue;
+            }
             let sql = sql_gen.generate_partial_update(
                 &table.target_table,
-                &row_diff.source_row,
+                &update_row,
                 &row_diff.changed_columns,
                 key_cols,
             );
             if sql.is_empty() {
-                total_skipped += 1;
+                counts.skipped += 1;
                 continue;
             }
-            let exec_result = {
-                let reg = registry.lock().await;
-                let tgt_conn = reg
-                    .get(&request.target_connection_id)
-                    .ok_or("Target connection not found")?;
-                let guard = tgt_conn.lock().await;
-                guard.execute_query(&sql).await
-            };
+            let exec_result = target.execute_query(&sql).await;
             match exec_result {
-                Ok(_) => total_updated += 1,
+                Ok(_) => {
+                    counts.updated += 1;
+                    progress.undo.push(UndoEntry::updated(
+                        &table.target_table,
+                        &row_diff.target_row,
+                        &update_row,
+                        &row_diff.changed_columns,
+                        &order_key,
+                    ));
+                }
                 Err(e) => {
-                    total_errors += 1;
+                    counts.errors += 1;
                     log::warn!("Update error on {}: {:#}\nSQL: {}", table.target_table, e, sql);
                 }
             }
         }
+        self.flush_documents(table, target, &mut pending, &mut progress, &mut counts)
+            .await;
 
         // Execute deletes
         for row in &plan.rows_to_delete {
             if cancel_token.is_cancelled() {
                 break;
             }
+            let key = row_key(row, &order_key);
+            if done(Phase::Delete, &key) {
+                continue;
+            }
+            if pending.writes.len() >= batch_size || (checkpoints && progress.unsaved >= batch_size)
+            {
+                self.flush_documents(table, target, &mut pending, &mut progress, &mut counts)
+                    .await;
+            }
+            if checkpoints {
+                self.advance(table, &mut progress, &counts, Phase::Delete, key).await;
+            }
+            if documents {
+                let entry = UndoEntry::deleted(&table.target_table, row, &order_key);
+                pending.push(DocumentWrite::Delete { filter: key_filter(row, doc_key) }, entry);
+                continue;
+            }
             let sql = sql_gen.generate_delete(&table.target_table, row, key_cols);
-            let exec_result = {
-                let reg = registry.lock().await;
-                let tgt_conn = reg
-                    .get(&request.target_connection_id)
-                    .ok_or("Target connection not found")?;
-                let guard = tgt_conn.lock().await;
-                guard.execute_query(&sql).await
-            };
+            let exec_result = target.execute_query(&sql).await;
             match exec_result {
-                Ok(_) => total_deleted += 1,
+                Ok(_) => {
+                    counts.deleted += 1;
+                    progress.undo.push(UndoEntry::deleted(&table.target_table, row, &order_key));
+                }
                 Err(e) => {
-                    total_errors += 1;
+                    counts.errors += 1;
                     log::warn!("Delete error on {}: {}", table.target_table, e);
                 }
             }
         }
+        self.flush_documents(table, target, &mut pending, &mut progress, &mut counts)
+            .await;
 
-        total_skipped += plan.rows_to_review.len();
+        counts.skipped += plan.rows_to_review.len();
+        if checkpoints {
+            let completed = !cancel_token.is_cancelled();
+            self.save_checkpoint(table, &mut progress, &counts, completed).await;
+        } else {
+            let pair = (table.source_table.clone(), table.target_table.clone());
+            self.deferred.lock().unwrap().insert(pair, progress);
+        }
+        self.totals.lock().unwrap().add(&counts);
 
         // Emit table-done event
-        let _ = app_handle.emit(
-            "migration:progress",
-            MigrationProgressEvent {
-                migration_id: migration_id.clone(),
-                table: table.source_table.clone(),
-                processed_rows: plan.rows_to_insert.len()
-                    + plan.rows_to_update.len()
-                    + plan.rows_to_delete.len(),
-                total_rows: source_rows.len(),
-                inserted: total_inserted,
-                updated: total_updated,
-                deleted: total_deleted,
-                skipped: total_skipped,
-                errors: total_errors,
-                status: "completed".to_string(),
-            },
+        self.emit_progress(
+            &table.source_table,
+            plan.rows_to_insert.len() + plan.rows_to_update.len() + plan.rows_to_delete.len(),
+            source_rows.len(),
+            "completed",
         );
+        Ok(counts)
+    }
+}
+
+/// Execute a real migration: fetch data, compute plan, generate SQL,
+/// execute on target, emit progress events. Tables run in parallel up to
+/// `maxParallelTables`, children only after their FK parents finish.
+/// Progress is checkpointed per batch so `resume_migration` can continue
+/// the run after a failure or cancellation.
+#[tauri::command]
+pub async fn execute_migration(
+    request: DryRunRequest,
+    migration_id: String,
+    app_handle: AppHandle,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<MigrationResultDto, String> {
+    run_migration(
+        &request,
+        &migration_id,
+        None,
+        &app_handle,
+        &registry,
+        &migration_state,
+        &app_db,
+    )
+    .await
+}
+
+/// Continue an interrupted migration from its last checkpoints. Tables that
+/// finished are skipped and the rest pick up after their last committed
+/// key; re-planning against the target makes replayed rows no-ops in
+/// Upsert and Merge modes. Refuses if a source or target table's schema
+/// changed since the run started.
+#[tauri::command]
+pub async fn resume_migration(
+    migration_id: String,
+    app_handle: AppHandle,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<MigrationResultDto, String> {
+    if migration_state.lock().await.contains(&migration_id) {
+        return Err(format!("Migration {} is already running", migration_id));
     }
+    let (stored, checkpoints) = {
+        let db = app_db.lock().await;
+        let stored = db
+            .get_migration_run(migration_id.clone())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No migration run found with id {}", migration_id))?;
+        let checkpoints = db
+            .load_checkpoints(migration_id.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        (stored, checkpoints)
+    };
+    if stored.status == "undone" {
+        return Err(format!("Migration {} was undone; start a new migration", migration_id));
+    }
+    let request: DryRunRequest = serde_json::from_str(&stored.request_json)
+        .map_err(|e| format!("Stored migration request is invalid: {}", e))?;
+
+    run_migration(
+        &request,
+        &migration_id,
+        Some((stored, checkpoints)),
+        &app_handle,
+        &registry,
+        &migration_state,
+        &app_db,
+    )
+    .await
+}
+
+/// Shared body of `execute_migration` and `resume_migration`. `resume`
+/// carries the stored run and its checkpoints when continuing one. Every
+/// attempt, failed or not, is recorded in the migration history.
+async fn run_migration(
+    request: &DryRunRequest,
+    migration_id: &str,
+    resume: Option<(MigrationRunRow, Vec<MigrationCheckpointRow>)>,
+    app_handle: &AppHandle,
+    registry: &Mutex<ConnectionRegistry>,
+    migration_state: &Mutex<MigrationState>,
+    app_db: &Mutex<AppDatabase>,
+) -> Result<MigrationResultDto, String> {
+    let started_at = timestamp_now();
+    let outcome = migrate_tables(
+        request,
+        migration_id,
+        resume,
+        app_handle,
+        registry,
+        migration_state,
+        app_db,
+    )
+    .await;
+    record_history(app_db, request, migration_id, started_at, &outcome).await;
+    outcome
+}
 
-    if cancel_token.is_cancelled() {
-        final_status = "cancelled".to_string();
+async fn migrate_tables(
+    request: &DryRunRequest,
+    migration_id: &str,
+    resume: Option<(MigrationRunRow, Vec<MigrationCheckpointRow>)>,
+    app_handle: &AppHandle,
+    registry: &Mutex<ConnectionRegistry>,
+    migration_state: &Mutex<MigrationState>,
+    app_db: &Mutex<AppDatabase>,
+) -> Result<MigrationResultDto, String> {
+    let (src_pool, tgt_pool) = resolve_pools(registry, request).await?;
+    let request = &with_schema_map(request, &src_pool.engine(), &tgt_pool.engine());
+
+    // Refuse up front rather than failing every batch against a read-only target
+    if tgt_pool.config().read_only {
+        return Err(format!(
+            "Target {} connection is read-only. Disable read-only mode on it before migrating.",
+            tgt_pool.engine()
+        ));
+    }
+
+    // Set up cancellation token
+    let cancel_token = CancellationToken::new();
+    {
+        let mut ms = migration_state.lock().await;
+        ms.insert(migration_id.to_string(), cancel_token.clone());
     }
 
+    let start = std::time::Instant::now();
+    let resuming = resume.is_some();
+
+    let prepared = prepare_run(request, migration_id, resume, &src_pool, &tgt_pool, app_db).await;
+    let (targets, checkpoints) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            migration_state.lock().await.remove(migration_id);
+            return Err(e);
+        }
+    };
+
+    let watermarks = if request.config.incremental {
+        match load_watermarks(request, app_db).await {
+            Ok(watermarks) => watermarks,
+            Err(e) => {
+                migration_state.lock().await.remove(migration_id);
+                return Err(e);
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    // ── Snapshot the target before the first write; a resumed run already has one ──
+    let mut warnings = Vec::new();
+    if !resuming && build_migration_config(&request.config, &[]).backup_before_migrate {
+        match create_backups(request, migration_id, &tgt_pool, &targets, app_db).await {
+            Ok(found) => warnings.extend(found),
+            Err(e) => {
+                migration_state.lock().await.remove(migration_id);
+                let _ = app_db
+                    .lock()
+                    .await
+                    .set_migration_run_status(
+                        migration_id.to_string(),
+                        "failed".to_string(),
+                        timestamp_now(),
+                    )
+                    .await;
+                return Err(format!("{}; nothing was migrated", e));
+            }
+        }
+        // The backup just taken is always kept
+        let keep = request.config.backup_retention.unwrap_or(DEFAULT_RETENTION).max(1);
+        warnings.extend(prune_backups(&request.target_connection_id, keep, &tgt_pool, app_db).await);
+    }
+
+    // ── Schedule tables by FK dependency (parents first, cycles together) ──
+    let mut schedule = Schedule::new(&target_table_names(&request.tables), &targets.fk_parents);
+    let max_parallel = request
+        .config
+        .max_parallel_tables
+        .unwrap_or(tgt_pool.pool_config().max_size)
+        .max(1);
+
+    let run = MigrationRun {
+        request,
+        migration_id,
+        app_handle,
+        src_pool: &src_pool,
+        tgt_pool: &tgt_pool,
+        cancel_token: &cancel_token,
+        app_db,
+        constraint_mode: parse_constraint_mode(request.config.constraint_mode.as_deref()),
+        targets,
+        checkpoints,
+        totals: std::sync::Mutex::new(MigrationTotals::default()),
+        warnings: std::sync::Mutex::new(warnings),
+        violations: std::sync::Mutex::new(Vec::new()),
+        watermarks,
+        deferred: std::sync::Mutex::new(HashMap::new()),
+    };
+    let run = &run;
+
+    let mut running = FuturesUnordered::new();
+    let mut failure: Option<String> = None;
+    loop {
+        // Start every ready unit there is room for; stop starting new ones
+        // once a table fails or the migration is cancelled
+        while failure.is_none() && !cancel_token.is_cancelled() && running.len() < max_parallel {
+            let Some(index) = schedule.next_ready() else {
+                break;
+            };
+            let unit = schedule.unit(index).clone();
+            running.push(async move { (index, run.migrate_unit(&unit).await) });
+        }
+        let Some((index, result)) = running.next().await else {
+            break;
+        };
+        schedule.complete(index);
+        if let Err(e) = result {
+            failure.get_or_insert(e);
+        }
+    }
+    drop(running);
+
+    let final_status = if cancel_token.is_cancelled() || !schedule.is_finished() {
+        "cancelled".to_string()
+    } else {
+        "completed".to_string()
+    };
+
     // Cleanup
     {
         let mut ms = migration_state.lock().await;
-        ms.remove(&migration_id);
+        ms.remove(migration_id);
+    }
+
+    let run_status = if failure.is_some() { "failed" } else { final_status.as_str() };
+    if let Err(e) = app_db
+        .lock()
+        .await
+        .set_migration_run_status(migration_id.to_string(), run_status.to_string(), timestamp_now())
+        .await
+    {
+        log::warn!("Failed to record status of migration {}: {:#}", migration_id, e);
+    }
+
+    if let Some(e) = failure {
+        return Err(e);
     }
 
     let duration_ms = start.elapsed().as_millis() as u64;
+    let totals = *run.totals.lock().unwrap();
+    let warnings = std::mem::take(&mut *run.warnings.lock().unwrap());
+    let constraint_violations = std::mem::take(&mut *run.violations.lock().unwrap());
 
     Ok(MigrationResultDto {
-        rows_inserted: total_inserted,
-        rows_updated: total_updated,
-        rows_deleted: total_deleted,
-        rows_skipped: total_skipped,
-        error_count: total_errors,
+        rows_inserted: totals.inserted,
+        rows_updated: totals.updated,
+        rows_deleted: totals.deleted,
+        rows_skipped: totals.skipped,
+        error_count: totals.errors,
         duration_ms,
         status: final_status,
+        warnings,
+        constraint_violations,
     })
 }
 
+/// Read the target schemas and record the run in the app DB. A fresh run
+/// stores the request and schema fingerprints; a resumed one checks the
+/// fingerprints still match and returns its checkpoints by table pair.
+async fn prepare_run(
+    request: &DryRunRequest,
+    migration_id: &str,
+    resume: Option<(MigrationRunRow, Vec<MigrationCheckpointRow>)>,
+    src_pool: &SharedPool,
+    tgt_pool: &SharedPool,
+    app_db: &Mutex<AppDatabase>,
+) -> Result<(TargetTables, HashMap<(String, String), MigrationCheckpointRow>), String> {
+    // ── Pre-fetch target schemas and FK dependencies for all tables ──
+    let targets = prefetch_target_tables(tgt_pool, &request.tables).await?;
+    let source_fp = source_fingerprints(src_pool, &request.tables).await?;
+    let target_fp = target_fingerprints(&targets, &request.tables);
+    let now = timestamp_now();
+    let db = app_db.lock().await;
+
+    let Some((stored, checkpoints)) = resume else {
+        let request_json = serde_json::to_string(request).map_err(|e| e.to_string())?;
+        db.save_migration_run(MigrationRunRow {
+            id: migration_id.to_string(),
+            request_json,
+            source_fingerprint: serde_json::to_string(&source_fp).map_err(|e| e.to_string())?,
+            target_fingerprint: serde_json::to_string(&target_fp).map_err(|e| e.to_string())?,
+            status: "running".to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        return Ok((targets, HashMap::new()));
+    };
+
+    let changed = changed_tables(&stored.source_fingerprint, &source_fp);
+    if !changed.is_empty() {
+        return Err(format!(
+            "Source schema changed since the migration started (tables: {}); start a new migration",
+            changed.join(", ")
+        ));
+    }
+    let changed = changed_tables(&stored.target_fingerprint, &target_fp);
+    if !changed.is_empty() {
+        return Err(format!(
+            "Target schema changed since the migration started (tables: {}); start a new migration",
+            changed.join(", ")
+        ));
+    }
+    db.set_migration_run_status(migration_id.to_string(), "running".to_string(), now)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let checkpoints = checkpoints
+        .into_iter()
+        .map(|c| ((c.source_table.clone(), c.target_table.clone()), c))
+        .collect();
+    Ok((targets, checkpoints))
+}
+
 /// Cancel a running migration.
 #[tauri::command]
 pub async fn cancel_migration(
@@ -645,3 +2395,393 @@ pub async fn cancel_migration(
     let ms = migration_state.lock().await;
     Ok(ms.cancel(&migration_id))
 }
+
+/// Roll the target tables of a migration back to the snapshot taken before
+/// it ran. Each table is emptied and refilled from its shadow table in one
+/// transaction with foreign keys disabled. Returns the restored backups.
+#[tauri::command]
+pub async fn restore_backup(
+    migration_id: String,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<Vec<MigrationBackupRow>, String> {
+    if migration_state.lock().await.contains(&migration_id) {
+        return Err(format!(
+            "Migration {} is still running; cancel it before restoring",
+            migration_id
+        ));
+    }
+    let backups = app_db
+        .lock()
+        .await
+        .load_backups(Some(migration_id.clone()))
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some(first) = backups.first() else {
+        return Err(format!("Migration {} has no backup", migration_id));
+    };
+    if backups.iter().any(|b| b.status == "expired") {
+        return Err(format!(
+            "The backup of migration {} expired under the retention policy",
+            migration_id
+        ));
+    }
+
+    let tgt_pool = registry
+        .lock()
+        .await
+        .get(&first.target_connection_id)
+        .ok_or("Target connection not found; connect to it before restoring")?;
+    if tgt_pool.config().read_only {
+        return Err(format!(
+            "Target {} connection is read-only. Disable read-only mode on it before restoring.",
+            tgt_pool.engine()
+        ));
+    }
+    let mut target = tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+    let sql_gen = SqlGenerator::new(target.engine());
+
+    // Column lists come from the shadow tables; FK names from the live ones
+    let mut statements = Vec::new();
+    let mut fk_tables = Vec::new();
+    for backup in &backups {
+        let columns: Vec<String> = target
+            .get_table_info(&backup.backup_table)
+            .await
+            .map_err(|e| format!("Backup table {} is unreadable: {:#}", backup.backup_table, e))?
+            .columns
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        let fk_names = target
+            .get_table_info(&backup.target_table)
+            .await
+            .map(|info| {
+                info.constraints
+                    .into_iter()
+                    .filter(|c| c.constraint_type == ConstraintType::ForeignKey)
+                    .map(|c| c.name)
+                    .collect()
+            })
+            .unwrap_or_default();
+        fk_tables.push((backup.target_table.clone(), fk_names));
+        statements.extend(sql_gen.restore_snapshot(
+            &backup.target_table,
+            &backup.backup_table,
+            &columns,
+        ));
+    }
+
+    // SQLite ignores the foreign_keys pragma inside a transaction, so
+    // disable before beginning and re-enable after the end
+    for sql in sql_gen.disable_foreign_keys(&fk_tables) {
+        if let Err(e) = target.execute_query(&sql).await {
+            log::warn!("Could not disable foreign keys for restore ({}): {:#}", sql, e);
+        }
+    }
+    let mut restored = target
+        .begin_transaction()
+        .await
+        .map_err(|e| format!("Failed to start restore transaction: {:#}", e));
+    if restored.is_ok() {
+        for sql in &statements {
+            if let Err(e) = target.execute_query(sql).await {
+                restored = Err(format!("Restore failed ({}): {:#}", sql, e));
+                break;
+            }
+        }
+        restored = match restored {
+            Ok(()) => target
+                .commit_transaction()
+                .await
+                .map_err(|e| format!("Failed to commit restore: {:#}", e)),
+            Err(e) => {
+                if let Err(rb) = target.rollback_transaction().await {
+                    log::warn!("Rollback of restore failed: {:#}", rb);
+                }
+                Err(format!("{}; the target was left unchanged", e))
+            }
+        };
+    }
+    for sql in sql_gen.enable_foreign_keys(&fk_tables) {
+        if let Err(e) = target.execute_query(&sql).await {
+            log::warn!("Re-enabling foreign keys after restore failed ({}): {:#}", sql, e);
+        }
+    }
+    restored?;
+
+    let db = app_db.lock().await;
+    db.set_backup_status(migration_id.clone(), "restored".to_string())
+        .await
+        .map_err(|e| e.to_string())?;
+    db.load_backups(Some(migration_id)).await.map_err(|e| e.to_string())
+}
+
+/// Reverse a migration from its undo log: re-insert deleted rows, set
+/// updated columns back and delete the rows it inserted, in FK order, in
+/// one transaction. MongoDB targets get the inverse document writes, and a
+/// standalone server without transactions gets them applied without one,
+/// with a warning. If any logged row changed on the target since, nothing
+/// is applied and the conflicts are returned instead.
+#[tauri::command]
+pub async fn undo_migration(
+    migration_id: String,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+    migration_state: State<'_, Arc<Mutex<MigrationState>>>,
+    app_db: State<'_, Arc<Mutex<AppDatabase>>>,
+) -> Result<UndoResultDto, String> {
+    if migration_state.lock().await.contains(&migration_id) {
+        return Err(format!(
+            "Migration {} is still running; cancel it before undoing",
+            migration_id
+        ));
+    }
+    let (stored, log) = {
+        let db = app_db.lock().await;
+        let stored = db
+            .get_migration_run(migration_id.clone())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("No migration run found with id {}", migration_id))?;
+        let log = db
+            .load_undo_entries(migration_id.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        (stored, log)
+    };
+    if log.is_empty() {
+        return Err(format!("Migration {} has nothing to undo", migration_id));
+    }
+    let entries: Vec<UndoEntry> = log.iter().filter_map(undo_entry).collect();
+    if entries.len() != log.len() {
+        return Err(format!("The undo log of migration {} is corrupt", migration_id));
+    }
+    let request: DryRunRequest = serde_json::from_str(&stored.request_json)
+        .map_err(|e| format!("Stored migration request is invalid: {}", e))?;
+
+    let tgt_pool = registry
+        .lock()
+        .await
+        .get(&request.target_connection_id)
+        .ok_or("Target connection not found; connect to it before undoing")?;
+    if tgt_pool.config().read_only {
+        return Err(format!(
+            "Target {} connection is read-only. Disable read-only mode on it before undoing.",
+            tgt_pool.engine()
+        ));
+    }
+    if tgt_pool.engine() == DatabaseEngine::CosmosDb {
+        return Err(format!(
+            "Migration {} cannot be undone: Cosmos DB targets take neither SQL statements nor \
+             transactional document writes. Restore a backup of the target instead.",
+            migration_id
+        ));
+    }
+    let targets = prefetch_target_tables(&tgt_pool, &request.tables).await?;
+    let table_order =
+        Schedule::new(&target_table_names(&request.tables), &targets.fk_parents).linear_order();
+    let mut target = tgt_pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let mut by_table: BTreeMap<&str, Vec<&UndoEntry>> = BTreeMap::new();
+    for entry in &entries {
+        by_table.entry(entry.table.as_str()).or_default().push(entry);
+    }
+    let mut conflicts = Vec::new();
+    for (table, table_entries) in &by_table {
+        let current = target
+            .get_rows(table, None, None)
+            .await
+            .map_err(|e| format!("Target fetch error ({}): {:#}", table, e))?;
+        conflicts.extend(find_conflicts(table_entries, &current));
+    }
+    if !conflicts.is_empty() {
+        return Ok(UndoResultDto {
+            applied: false,
+            rows_reverted: 0,
+            conflicts,
+            warnings: Vec::new(),
+        });
+    }
+
+    let ordered = undo_order(&entries, &table_order);
+    let transactional = target
+        .supports_transactions()
+        .await
+        .map_err(|e| format!("Failed to check transaction support: {:#}", e))?;
+    let mut warnings = Vec::new();
+    if transactional {
+        target
+            .begin_transaction()
+            .await
+            .map_err(|e| format!("Failed to start undo transaction: {:#}", e))?;
+    } else {
+        let warning = format!(
+            "The {} target has no transactions, so the undo was applied without one",
+            target.engine()
+        );
+        log::warn!("{}", warning);
+        warnings.push(warning);
+    }
+    let reverted = match apply_inverses(&*target, &ordered).await {
+        Ok(reverted) => reverted,
+        Err(e) if transactional => {
+            if let Err(rb) = target.rollback_transaction().await {
+                log::warn!("Rollback of undo failed: {:#}", rb);
+            }
+            return Err(format!("Undo failed ({}); the target was left unchanged", e));
+        }
+        Err(e) => {
+            return Err(format!(
+                "Undo failed ({}); without a transaction, writes before the failure were kept",
+                e
+            ));
+        }
+    };
+    if transactional {
+        target
+            .commit_transaction()
+            .await
+            .map_err(|e| format!("Failed to commit undo: {:#}", e))?;
+    }
+
+    let db = app_db.lock().await;
+    db.clear_undo_entries(migration_id.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Err(e) = db
+        .set_migration_run_status(migration_id.clone(), "undone".to_string(), timestamp_now())
+        .await
+    {
+        log::warn!("Failed to record undo of migration {}: {:#}", migration_id, e);
+    }
+    Ok(UndoResultDto {
+        applied: true,
+        rows_reverted: reverted,
+        conflicts: Vec::new(),
+        warnings,
+    })
+}
+
+/// Replay the inverses of `entries` in order inside the open transaction,
+/// if there is one, as document writes on MongoDB and SQL statements elsewhere. Returns how
+/// many were applied; stops at the first failure.
+async fn apply_inverses(
+    target: &dyn DatabaseConnector,
+    entries: &[&UndoEntry],
+) -> Result<usize, String> {
+    if target.engine() == DatabaseEngine::MongoDb {
+        let options = DocumentWriteOptions {
+            batch_size: 1000,
+            ordered: true,
+        };
+        let mut reverted = 0;
+        // One call per run of consecutive entries on the same collection
+        for run in entries.chunk_by(|a, b| a.table == b.table) {
+            let writes: Vec<DocumentWrite> =
+                run.iter().filter_map(|e| e.inverse_document_write()).collect();
+            let table = &run[0].table;
+            let result = target
+                .write_documents(table, &writes, &options)
+                .await
+                .map_err(|e| format!("{}: {:#}", table, e))?;
+            if let Some(error) = result.errors.first() {
+                return Err(format!("{} document {}: {}", table, error.index, error.message));
+            }
+            reverted += writes.len();
+        }
+        return Ok(reverted);
+    }
+    let sql_gen = SqlGenerator::new(target.engine());
+    let statements: Vec<String> = entries.iter().filter_map(|e| e.inverse_sql(&sql_gen)).collect();
+    for sql in &statements {
+        target
+            .execute_query(sql)
+            .await
+            .map_err(|e| format!("{}: {:#}", sql, e))?;
+    }
+    Ok(statements.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connectors::ConnectionConfig;
+    use crate::db::pool::ConnectionPool;
+
+    fn mapping(table: &str) -> TableMappingDto {
+        serde_json::from_value(serde_json::json!({
+            "sourceTable": table,
+            "targetTable": table,
+            "keyColumns": ["id"],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_remap_table_schema() {
+        let map = HashMap::from([
+            ("dbo".to_string(), "sales".to_string()),
+            ("main".to_string(), "archive".to_string()),
+        ]);
+        let (sqlserver, postgres, sqlite) = (
+            DatabaseEngine::SqlServer,
+            DatabaseEngine::PostgreSql,
+            DatabaseEngine::Sqlite,
+        );
+        assert_eq!(remap_table_schema("orders", &map, &sqlserver, &postgres), "sales.orders");
+        assert_eq!(remap_table_schema("hr.staff", &map, &sqlserver, &postgres), "hr.staff");
+        // A dot in a SQLite table name does not name a schema
+        assert_eq!(
+            remap_table_schema("events.v2", &map, &sqlite, &postgres),
+            "archive.events.v2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_self_referencing_table_loads_with_checks_disabled_in_auto_mode() {
+        let pool = ConnectionPool::open(ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let conn = pool.acquire().await.unwrap();
+        for ddl in [
+            "CREATE TABLE employees (id INTEGER PRIMARY KEY, \
+             manager_id INTEGER REFERENCES employees(id))",
+            "CREATE TABLE badges (id INTEGER PRIMARY KEY, \
+             employee_id INTEGER REFERENCES employees(id))",
+        ] {
+            conn.execute_query(ddl).await.unwrap();
+        }
+        drop(conn);
+
+        let tables = vec![mapping("badges"), mapping("employees")];
+        let targets = prefetch_target_tables(&pool, &tables).await.unwrap();
+        let schedule = Schedule::new(&target_table_names(&tables), &targets.fk_parents);
+        let modes: Vec<(&str, ConstraintMode)> = schedule
+            .units()
+            .iter()
+            .map(|u| {
+                (
+                    u.tables[0].as_str(),
+                    unit_constraint_mode(ConstraintMode::Auto, u),
+                )
+            })
+            .collect();
+        assert_eq!(
+            modes,
+            vec![
+                ("employees", ConstraintMode::Disable),
+                ("badges", ConstraintMode::Auto)
+            ]
+        );
+        let employees = &schedule.units()[0];
+        assert_eq!(
+            unit_constraint_mode(ConstraintMode::Defer, employees),
+            ConstraintMode::Defer
+        );
+    }
+}
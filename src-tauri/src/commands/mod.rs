@@ -1,4 +1,5 @@
 pub mod appdb;
+pub mod cdc;
 pub mod chat;
 pub mod connection;
 pub mod migration;
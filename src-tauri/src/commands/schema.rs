@@ -2,8 +2,25 @@ use std::sync::Arc;
 use tauri::State;
 use tokio::sync::Mutex;
 
-use crate::db::registry::ConnectionRegistry;
+use crate::db::comparator::{compare_schemas_with_mode, ComparisonMode, SchemaDiffResult};
+use crate::db::connectors::DatabaseEngine;
+use crate::db::flattener::{plan_flattening, FlatTable};
+use crate::db::registry::{ConnectionRegistry, SharedPool};
 use crate::db::schema::TableInfo;
+use crate::db::type_report::{ReportFormat, TypeCompatibilityReport};
+
+/// Documents sampled when planning a flattening without a sample size.
+const DEFAULT_FLATTEN_SAMPLE: u64 = 1000;
+
+/// How `compare_schemas` compares column types, defaults and index kinds.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub enum SchemaCompareMode {
+    /// As declared, ignoring case
+    Native,
+    /// Through the canonical type system, keeping only differences that
+    /// survive a migration between the two engines
+    CrossEngine,
+}
 
 /// Return the list of table names for a connection.
 #[tauri::command]
@@ -11,11 +28,12 @@ pub async fn get_tables(
     connection_id: String,
     registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
 ) -> Result<Vec<String>, String> {
-    let reg = registry.lock().await;
-    let conn = reg
+    let pool = registry
+        .lock()
+        .await
         .get(&connection_id)
         .ok_or_else(|| format!("Connection '{}' not found", connection_id))?;
-    let guard = conn.lock().await;
+    let guard = pool.acquire().await.map_err(|e| e.to_string())?;
     guard.get_tables().await.map_err(|e| e.to_string())
 }
 
@@ -26,11 +44,12 @@ pub async fn get_table_info(
     table_name: String,
     registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
 ) -> Result<TableInfo, String> {
-    let reg = registry.lock().await;
-    let conn = reg
+    let pool = registry
+        .lock()
+        .await
         .get(&connection_id)
         .ok_or_else(|| format!("Connection '{}' not found", connection_id))?;
-    let guard = conn.lock().await;
+    let guard = pool.acquire().await.map_err(|e| e.to_string())?;
     guard
         .get_table_info(&table_name)
         .await
@@ -44,13 +63,252 @@ pub async fn get_row_count(
     table_name: String,
     registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
 ) -> Result<i64, String> {
-    let reg = registry.lock().await;
-    let conn = reg
+    let pool = registry
+        .lock()
+        .await
         .get(&connection_id)
         .ok_or_else(|| format!("Connection '{}' not found", connection_id))?;
-    let guard = conn.lock().await;
+    let guard = pool.acquire().await.map_err(|e| e.to_string())?;
     guard
         .get_row_count(&table_name)
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Run every column of the source schema (or the given tables) through the
+/// type mapper against `target_engine`.
+#[tauri::command]
+pub async fn type_compatibility_report(
+    connection_id: String,
+    target_engine: DatabaseEngine,
+    tables: Option<Vec<String>>,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+) -> Result<TypeCompatibilityReport, String> {
+    let pool = registry
+        .lock()
+        .await
+        .get(&connection_id)
+        .ok_or_else(|| format!("Connection '{}' not found", connection_id))?;
+    let guard = pool.acquire().await.map_err(|e| e.to_string())?;
+    let table_names = match tables {
+        Some(t) => t,
+        None => guard.get_tables().await.map_err(|e| e.to_string())?,
+    };
+    let mut infos = Vec::with_capacity(table_names.len());
+    for name in &table_names {
+        infos.push(
+            guard
+                .get_table_info(name)
+                .await
+                .map_err(|e| format!("Failed to read schema of '{}': {}", name, e))?,
+        );
+    }
+    Ok(TypeCompatibilityReport::build(
+        &guard.engine(),
+        &target_engine,
+        &infos,
+    ))
+}
+
+/// Plan the relational tables a document collection flattens into on
+/// `target_engine`: the collection's own table (named `root_table`, the
+/// collection by default) and one child table per array found in the
+/// first `sample_size` documents, each with its DDL.
+#[tauri::command]
+pub async fn plan_document_flattening(
+    connection_id: String,
+    collection: String,
+    target_engine: DatabaseEngine,
+    root_table: Option<String>,
+    sample_size: Option<u64>,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+) -> Result<Vec<FlatTable>, String> {
+    let pool = registry
+        .lock()
+        .await
+        .get(&connection_id)
+        .ok_or_else(|| format!("Connection '{}' not found", connection_id))?;
+    let guard = pool.acquire().await.map_err(|e| e.to_string())?;
+    let info = guard
+        .get_table_info(&collection)
+        .await
+        .map_err(|e| format!("Failed to read schema of '{}': {}", collection, e))?;
+    let sample = guard
+        .get_rows(&collection, Some(sample_size.unwrap_or(DEFAULT_FLATTEN_SAMPLE)), None)
+        .await
+        .map_err(|e| format!("Failed to sample '{}': {}", collection, e))?;
+    let root_table = root_table.unwrap_or_else(|| collection.clone());
+    Ok(plan_flattening(
+        &collection,
+        &root_table,
+        &info.columns,
+        &sample,
+        &target_engine,
+    ))
+}
+
+/// Compare the schemas of two connections. Without a `mode`, connections
+/// on different engines are compared cross-engine and others natively.
+#[tauri::command]
+pub async fn compare_schemas(
+    source_connection_id: String,
+    target_connection_id: String,
+    mode: Option<SchemaCompareMode>,
+    registry: State<'_, Arc<Mutex<ConnectionRegistry>>>,
+) -> Result<SchemaDiffResult, String> {
+    compare_connection_schemas(&registry, &source_connection_id, &target_connection_id, mode).await
+}
+
+async fn compare_connection_schemas(
+    registry: &Mutex<ConnectionRegistry>,
+    source_connection_id: &str,
+    target_connection_id: &str,
+    mode: Option<SchemaCompareMode>,
+) -> Result<SchemaDiffResult, String> {
+    let (source_pool, target_pool) = {
+        let reg = registry.lock().await;
+        let get = |id: &str| -> Result<SharedPool, String> {
+            reg.get(id)
+                .ok_or_else(|| format!("Connection '{}' not found", id))
+        };
+        (get(source_connection_id)?, get(target_connection_id)?)
+    };
+    let (source_engine, target_engine) = (source_pool.engine(), target_pool.engine());
+    let mode = match mode {
+        Some(mode) => mode,
+        None if source_engine == target_engine => SchemaCompareMode::Native,
+        None => SchemaCompareMode::CrossEngine,
+    };
+    let comparison = match mode {
+        SchemaCompareMode::Native => ComparisonMode::Native,
+        SchemaCompareMode::CrossEngine => ComparisonMode::CrossEngine {
+            source: source_engine,
+            target: target_engine,
+        },
+    };
+
+    let source = source_pool
+        .acquire()
+        .await
+        .map_err(|e| e.to_string())?
+        .get_schema()
+        .await
+        .map_err(|e| format!("Failed to read source schema: {}", e))?;
+    let target = target_pool
+        .acquire()
+        .await
+        .map_err(|e| e.to_string())?
+        .get_schema()
+        .await
+        .map_err(|e| format!("Failed to read target schema: {}", e))?;
+    Ok(compare_schemas_with_mode(&source, &target, &comparison))
+}
+
+/// Write a compatibility report to `path` as CSV, Markdown or JSON.
+#[tauri::command]
+pub async fn export_type_compatibility_report(
+    report: TypeCompatibilityReport,
+    format: ReportFormat,
+    path: String,
+) -> Result<(), String> {
+    let content = report.render(format).map_err(|e| e.to_string())?;
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| format!("Failed to write report to {}: {}", path, e))
+}
+
+/// Accept the rename detected to `object_name` in a schema diff, returning
+/// the updated diff.
+#[tauri::command]
+pub async fn confirm_schema_rename(
+    mut diff: SchemaDiffResult,
+    object_name: String,
+) -> Result<SchemaDiffResult, String> {
+    if !diff.confirm_rename(&object_name) {
+        return Err(format!("No rename to '{}' was detected", object_name));
+    }
+    Ok(diff)
+}
+
+/// Turn the rename detected to `object_name` back into a removal and an
+/// addition, returning the updated diff.
+#[tauri::command]
+pub async fn reject_schema_rename(
+    mut diff: SchemaDiffResult,
+    object_name: String,
+) -> Result<SchemaDiffResult, String> {
+    if !diff.reject_rename(&object_name) {
+        return Err(format!("No rename to '{}' was detected", object_name));
+    }
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::comparator::ChangeType;
+    use crate::db::connectors::ConnectionConfig;
+
+    async fn registry_with(tables: &[(&str, &str)]) -> Mutex<ConnectionRegistry> {
+        let mut registry = ConnectionRegistry::new();
+        for (id, ddl) in tables {
+            registry
+                .connect(
+                    id.to_string(),
+                    ConnectionConfig {
+                        engine: DatabaseEngine::Sqlite,
+                        read_only: false,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            let pool = registry.get(id).unwrap();
+            pool.acquire().await.unwrap().execute_query(ddl).await.unwrap();
+        }
+        Mutex::new(registry)
+    }
+
+    #[tokio::test]
+    async fn test_compare_schemas_mode() {
+        let registry = registry_with(&[
+            ("src", "CREATE TABLE users (id INT, name TEXT)"),
+            ("tgt", "CREATE TABLE users (id INTEGER, name TEXT)"),
+        ])
+        .await;
+        let modified = |diff: &SchemaDiffResult| {
+            diff.changes
+                .iter()
+                .filter(|c| c.change_type == ChangeType::Modified)
+                .map(|c| c.object_name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // Same engine defaults to comparing declared types
+        let native = compare_connection_schemas(&registry, "src", "tgt", None)
+            .await
+            .unwrap();
+        assert_eq!(modified(&native), vec!["users.id"]);
+
+        let cross = compare_connection_schemas(
+            &registry,
+            "src",
+            "tgt",
+            Some(SchemaCompareMode::CrossEngine),
+        )
+        .await
+        .unwrap();
+        assert!(modified(&cross).is_empty());
+
+        let err = compare_connection_schemas(&registry, "src", "missing", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Connection 'missing' not found");
+    }
+
+    #[test]
+    fn test_compare_mode_deserializes() {
+        let mode: SchemaCompareMode = serde_json::from_str("\"CrossEngine\"").unwrap();
+        assert_eq!(mode, SchemaCompareMode::CrossEngine);
+    }
+}
@@ -0,0 +1,85 @@
+//! Pre-migration snapshots of target tables.
+//!
+//! Before a migration writes, every affected target table is copied to a
+//! shadow table in the target database. Restoring empties the table and
+//! copies the shadow back. Shadows of older migrations are dropped under a
+//! keep-the-last-N retention policy.
+
+/// Prefix of every shadow table, so they are easy to spot and clean up.
+pub const BACKUP_PREFIX: &str = "upsert_bak_";
+
+/// Migrations per target connection whose backups are kept by default.
+pub const DEFAULT_RETENTION: usize = 5;
+
+/// Identifier limit of the strictest supported engine (PostgreSQL).
+const MAX_NAME_LEN: usize = 63;
+
+/// Name of the shadow table holding `table`'s snapshot for a migration:
+/// the prefix, eight characters of the migration ID and the table name with
+/// anything but letters, digits and underscores replaced.
+pub fn backup_table_name(migration_id: &str, table: &str) -> String {
+    let tag: String = migration_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(8)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let base: String = table
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut name = format!("{}{}_{}", BACKUP_PREFIX, tag, base);
+    name.truncate(MAX_NAME_LEN);
+    name
+}
+
+/// Migrations whose backups fall outside the retention policy, given as
+/// (migration ID, created_at) pairs. The `keep` most recent are retained;
+/// timestamps are ISO 8601 so they order as text.
+pub fn expired_migrations(migrations: &[(String, String)], keep: usize) -> Vec<String> {
+    let mut by_age: Vec<&(String, String)> = migrations.iter().collect();
+    by_age.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    by_age
+        .into_iter()
+        .skip(keep)
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_table_name() {
+        assert_eq!(
+            backup_table_name("3f2a9c1e-77aa-4bcd-9e00-123456789abc", "orders"),
+            "upsert_bak_3f2a9c1e_orders"
+        );
+        assert_eq!(
+            backup_table_name("AB-CD-1234", "dbo.Order Items"),
+            "upsert_bak_abcd1234_dbo_Order_Items"
+        );
+        let long = backup_table_name("m1", &"x".repeat(100));
+        assert_eq!(long.len(), MAX_NAME_LEN);
+        assert!(long.starts_with("upsert_bak_m1_x"));
+    }
+
+    #[test]
+    fn test_expired_migrations() {
+        let runs = vec![
+            ("a".to_string(), "2024-01-01T00:00:00".to_string()),
+            ("c".to_string(), "2024-03-01T00:00:00".to_string()),
+            ("b".to_string(), "2024-02-01T00:00:00".to_string()),
+        ];
+        assert_eq!(expired_migrations(&runs, 2), vec!["a".to_string()]);
+        assert_eq!(expired_migrations(&runs, 0).len(), 3);
+        assert!(expired_migrations(&runs, 5).is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+//! Introspection of the schema objects other than tables: views, routines,
+//! triggers, sequences and user-defined types.
+//!
+//! Each engine supplies one catalog query per kind of object it supports.
+//! The queries alias their columns to the field names of the matching
+//! `schema` struct, so every row deserializes straight into it. A query
+//! the server cannot run (an older version lacking a catalog view or
+//! function, missing permissions) leaves that kind of object empty with a
+//! warning rather than failing the whole schema read.
+
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+
+use super::connectors::DatabaseConnector;
+use super::schema::{Row, SchemaFilter, SchemaInfo};
+
+/// Catalog queries of one engine; `None` where the engine has no such objects.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CatalogQueries {
+    pub views: Option<&'static str>,
+    pub routines: Option<&'static str>,
+    pub triggers: Option<&'static str>,
+    pub sequences: Option<&'static str>,
+    pub user_types: Option<&'static str>,
+}
+
+/// Fill the views, routines, triggers, sequences and user types of `schema`
+/// from the engine's catalog, keeping those in schemas `filter` includes.
+/// Triggers also follow the filter's table patterns.
+pub async fn read_schema_objects(
+    conn: &dyn DatabaseConnector,
+    queries: &CatalogQueries,
+    filter: &SchemaFilter,
+    schema: &mut SchemaInfo,
+) {
+    schema.views = or_empty(read_objects(conn, queries.views).await, "views");
+    schema.routines = or_empty(read_objects(conn, queries.routines).await, "routines");
+    schema.triggers = or_empty(read_objects(conn, queries.triggers).await, "triggers");
+    schema.sequences = or_empty(read_objects(conn, queries.sequences).await, "sequences");
+    schema.user_types = or_empty(
+        read_objects(conn, queries.user_types).await,
+        "user-defined types",
+    );
+
+    schema.views.retain(|v| filter.includes_schema(&v.schema_name));
+    schema.routines.retain(|r| filter.includes_schema(&r.schema_name));
+    schema
+        .triggers
+        .retain(|t| filter.includes_table(&t.schema_name, &t.table_name));
+    schema.sequences.retain(|s| filter.includes_schema(&s.schema_name));
+    schema.user_types.retain(|t| filter.includes_schema(&t.schema_name));
+}
+
+/// The objects read, or none with a warning when the query failed.
+fn or_empty<T>(result: anyhow::Result<Vec<T>>, what: &str) -> Vec<T> {
+    result.unwrap_or_else(|e| {
+        log::warn!("Failed to read {}; leaving them out of the schema: {:#}", what, e);
+        Vec::new()
+    })
+}
+
+async fn read_objects<T: DeserializeOwned>(
+    conn: &dyn DatabaseConnector,
+    query: Option<&str>,
+) -> anyhow::Result<Vec<T>> {
+    let Some(query) = query else {
+        return Ok(Vec::new());
+    };
+    conn.execute_query(query)
+        .await?
+        .into_iter()
+        .map(from_row)
+        .collect()
+}
+
+/// Deserialize a catalog row, leaving the fields of NULL columns at their
+/// defaults.
+fn from_row<T: DeserializeOwned>(row: Row) -> anyhow::Result<T> {
+    let object: serde_json::Map<String, serde_json::Value> =
+        row.into_iter().filter(|(_, v)| !v.is_null()).collect();
+    serde_json::from_value(serde_json::Value::Object(object))
+        .context("Unexpected catalog row")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::{RoutineInfo, SequenceInfo};
+    use serde_json::json;
+
+    #[test]
+    fn test_from_row_skips_nulls() {
+        let row: Row = [
+            ("schema_name".to_string(), json!("public")),
+            ("name".to_string(), json!("touch")),
+            ("routine_type".to_string(), json!("PROCEDURE")),
+            ("signature".to_string(), json!("id integer")),
+            ("return_type".to_string(), json!(null)),
+            ("body".to_string(), json!(null)),
+        ]
+        .into_iter()
+        .collect();
+        let routine: RoutineInfo = from_row(row).unwrap();
+        assert_eq!(routine.name, "touch");
+        assert_eq!(routine.return_type, None);
+        assert_eq!(routine.body, "");
+
+        let row: Row = [
+            ("name".to_string(), json!("order_seq")),
+            ("cycle".to_string(), json!(true)),
+        ]
+        .into_iter()
+        .collect();
+        let sequence: SequenceInfo = from_row(row).unwrap();
+        assert!(sequence.cycle);
+        assert_eq!(sequence.increment, "");
+    }
+
+    #[test]
+    fn test_failed_query_leaves_objects_out() {
+        let failed: anyhow::Result<Vec<SequenceInfo>> =
+            Err(anyhow::anyhow!("'STRING_AGG' is not a recognized built-in function name"));
+        assert!(or_empty(failed, "sequences").is_empty());
+        assert_eq!(or_empty(Ok(vec![1, 2]), "views"), vec![1, 2]);
+    }
+}
@@ -0,0 +1,287 @@
+//! MySQL binlog row events.
+//!
+//! The reader registers as a replica (`binlog_format = ROW` is required)
+//! and streams from the stored file and offset in non-blocking mode, so a
+//! read ends once it has caught up. Positions only advance at transaction
+//! commits. Column names come from `information_schema` unless the server
+//! logs them (`binlog_row_metadata = FULL`).
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mysql_async::binlog::events::{EventData, RowsEventData};
+use mysql_async::binlog::row::BinlogRow;
+use mysql_async::binlog::value::BinlogValue;
+use mysql_async::{BinlogStreamRequest, Conn, Opts};
+
+use super::{ChangeBatch, ChangeEvent, ChangeOp, ChangeReader};
+use crate::db::connectors::mysql::{value_to_json, MySqlConnector};
+use crate::db::registry::SharedPool;
+use crate::db::schema::Row;
+
+/// Binlog file and the offset of the next event to read in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinlogPosition {
+    pub file: String,
+    pub offset: u64,
+}
+
+impl BinlogPosition {
+    /// Parse the `file:offset` form positions are stored in.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (file, offset) = text.rsplit_once(':')?;
+        Some(Self {
+            file: file.to_string(),
+            offset: offset.parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for BinlogPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.offset)
+    }
+}
+
+/// Column names of a table in ordinal order, and its primary key.
+struct TableColumns {
+    names: Vec<String>,
+    key: Vec<String>,
+}
+
+pub struct BinlogReader {
+    pool: SharedPool,
+    opts: Opts,
+    server_id: u32,
+    database: String,
+    tables: HashSet<String>,
+    position: BinlogPosition,
+    columns: HashMap<String, TableColumns>,
+}
+
+impl BinlogReader {
+    pub async fn open(
+        pool: SharedPool,
+        server_id: u32,
+        tables: Vec<String>,
+        position: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let opts = MySqlConnector::new(pool.config().clone()).build_opts()?;
+        let (database, position) = {
+            let conn = pool.acquire().await?;
+            let database = conn
+                .execute_query("SELECT DATABASE() AS db")
+                .await?
+                .first()
+                .and_then(|r| r.get("db")?.as_str().map(str::to_string))
+                .ok_or_else(|| anyhow!("The MySQL connection has no default database"))?;
+            let position = match position {
+                Some(text) => BinlogPosition::parse(&text)
+                    .ok_or_else(|| anyhow!("Invalid binlog position '{}'", text))?,
+                None => {
+                    // SHOW MASTER STATUS was renamed in MySQL 8.4
+                    let status = match conn.execute_query("SHOW BINARY LOG STATUS").await {
+                        Ok(rows) => rows,
+                        Err(_) => conn.execute_query("SHOW MASTER STATUS").await?,
+                    };
+                    let row = status
+                        .first()
+                        .ok_or_else(|| anyhow!("Binary logging is disabled on the source"))?;
+                    BinlogPosition {
+                        file: row
+                            .get("File")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string(),
+                        offset: row.get("Position").and_then(|v| v.as_u64()).unwrap_or(4),
+                    }
+                }
+            };
+            (database, position)
+        };
+        Ok(Self {
+            pool,
+            opts,
+            server_id,
+            database,
+            tables: tables.into_iter().collect(),
+            position,
+            columns: HashMap::new(),
+        })
+    }
+
+    async fn load_columns(&mut self, table: &str) -> anyhow::Result<()> {
+        if self.columns.contains_key(table) {
+            return Ok(());
+        }
+        let conn = self.pool.acquire().await?;
+        let rows = conn
+            .execute_query(&format!(
+                "SELECT COLUMN_NAME, COLUMN_KEY FROM information_schema.COLUMNS \
+                 WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = '{}' ORDER BY ORDINAL_POSITION",
+                table.replace('\'', "''")
+            ))
+            .await
+            .with_context(|| format!("Failed to read the columns of {}", table))?;
+        let column = |row: &Row, name: &str| {
+            row.get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let columns = TableColumns {
+            names: rows.iter().map(|r| column(r, "COLUMN_NAME")).collect(),
+            key: rows
+                .iter()
+                .filter(|r| column(r, "COLUMN_KEY") == "PRI")
+                .map(|r| column(r, "COLUMN_NAME"))
+                .collect(),
+        };
+        self.columns.insert(table.to_string(), columns);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChangeReader for BinlogReader {
+    async fn read_changes(&mut self, max_events: usize) -> anyhow::Result<ChangeBatch> {
+        let conn = Conn::new(self.opts.clone())
+            .await
+            .context("Failed to open a MySQL replication connection")?;
+        let file = self.position.file.clone();
+        let request = BinlogStreamRequest::new(self.server_id)
+            .with_filename(file.as_bytes())
+            .with_pos(self.position.offset)
+            .with_non_blocking();
+        let mut stream = conn
+            .get_binlog_stream(request)
+            .await
+            .context("Failed to start the binlog stream")?;
+
+        let mut batch = ChangeBatch::default();
+        let mut current_file = file.clone();
+        let mut open: Vec<ChangeEvent> = Vec::new();
+        while let Some(event) = stream.next().await {
+            let event = event.context("Failed to read a binlog event")?;
+            let end = u64::from(event.header().log_pos());
+            let data = event
+                .read_data()
+                .context("Failed to decode a binlog event")?;
+            let committed = match data {
+                Some(EventData::RotateEvent(rotate)) => {
+                    current_file = rotate.name().into_owned();
+                    false
+                }
+                Some(EventData::XidEvent(_)) => true,
+                Some(EventData::QueryEvent(query)) => query.query().trim() == "COMMIT",
+                Some(EventData::RowsEvent(rows)) => {
+                    let table = match stream.get_tme(rows.table_id()) {
+                        Some(tme) if tme.database_name() == self.database.as_str() => {
+                            tme.table_name().into_owned()
+                        }
+                        _ => continue,
+                    };
+                    if !self.tables.contains(&table) {
+                        continue;
+                    }
+                    self.load_columns(&table).await?;
+                    let tme = stream
+                        .get_tme(rows.table_id())
+                        .ok_or_else(|| anyhow!("Binlog table map for {} went missing", table))?;
+                    let columns = &self.columns[&table];
+                    let op =
+                        match rows {
+                            RowsEventData::WriteRowsEventV1(_)
+                            | RowsEventData::WriteRowsEvent(_) => ChangeOp::Insert,
+                            RowsEventData::DeleteRowsEventV1(_)
+                            | RowsEventData::DeleteRowsEvent(_) => ChangeOp::Delete,
+                            _ => ChangeOp::Update,
+                        };
+                    for images in rows.rows(tme) {
+                        let (before, after) = images.context("Failed to decode a binlog row")?;
+                        open.push(ChangeEvent {
+                            table: table.clone(),
+                            op,
+                            key_columns: columns.key.clone(),
+                            before: before.map(|r| binlog_row(&r, &columns.names)),
+                            after: after.map(|r| binlog_row(&r, &columns.names)),
+                        });
+                    }
+                    false
+                }
+                _ => false,
+            };
+            if committed {
+                batch.events.append(&mut open);
+                batch.position = Some(
+                    BinlogPosition {
+                        file: current_file.clone(),
+                        offset: end,
+                    }
+                    .to_string(),
+                );
+                if batch.events.len() >= max_events {
+                    break;
+                }
+            }
+        }
+        if let Err(e) = stream.close().await {
+            log::warn!("Failed to close the binlog stream: {}", e);
+        }
+        Ok(batch)
+    }
+
+    async fn acknowledge(&mut self, position: &str) -> anyhow::Result<()> {
+        match BinlogPosition::parse(position) {
+            Some(position) => {
+                self.position = position;
+                Ok(())
+            }
+            None => bail!("Invalid binlog position '{}'", position),
+        }
+    }
+}
+
+/// Row of a binlog row image. Columns the image leaves out (with
+/// `binlog_row_image = MINIMAL`) are missing; unlogged names are `@<n>`
+/// and resolved by ordinal position.
+fn binlog_row(row: &BinlogRow, names: &[String]) -> Row {
+    row.columns_ref()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, column)| {
+            let logged = column.name_str();
+            let name = match logged
+                .strip_prefix('@')
+                .and_then(|n| n.parse::<usize>().ok())
+            {
+                Some(ordinal) => names.get(ordinal)?.clone(),
+                None => logged.into_owned(),
+            };
+            let value = match row.as_ref(i)? {
+                BinlogValue::Value(value) => value_to_json(Some(value), Some(column)),
+                BinlogValue::Jsonb(jsonb) => serde_json::Value::try_from(jsonb.clone()).ok()?,
+                // Partial JSON updates only carry a diff
+                BinlogValue::JsonDiff(_) => return None,
+            };
+            Some((name, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_round_trip() {
+        let position = BinlogPosition::parse("binlog.000042:1337").unwrap();
+        assert_eq!(position.file, "binlog.000042");
+        assert_eq!(position.offset, 1337);
+        assert_eq!(position.to_string(), "binlog.000042:1337");
+        assert!(BinlogPosition::parse("binlog.000042").is_none());
+        assert!(BinlogPosition::parse("binlog.000042:x").is_none());
+    }
+}
@@ -0,0 +1,138 @@
+//! MongoDB change streams.
+//!
+//! One database-level stream, filtered to the synced collections, is kept
+//! open between reads. Updates look up the full current document, and the
+//! position is the stream's resume token in relaxed Extended JSON. Change
+//! streams need a replica set or sharded cluster.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken};
+use mongodb::change_stream::ChangeStream;
+use mongodb::options::FullDocumentType;
+use mongodb::Client;
+
+use super::{ChangeBatch, ChangeEvent, ChangeOp, ChangeReader};
+use crate::db::connectors::mongodb_connector::{bson_doc_to_row, MongoDbConnector};
+use crate::db::registry::SharedPool;
+
+pub struct ChangeStreamReader {
+    // Keeps the stream's connection pool alive
+    _client: Client,
+    stream: ChangeStream<ChangeStreamEvent<Document>>,
+}
+
+impl ChangeStreamReader {
+    pub async fn open(
+        pool: SharedPool,
+        tables: Vec<String>,
+        position: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let connector = MongoDbConnector::new(pool.config().clone());
+        let client = Client::with_uri_str(connector.build_uri())
+            .await
+            .context("Failed to create MongoDB client")?;
+        let token = position.as_deref().map(decode_token).transpose()?;
+        let stream = client
+            .database(&connector.database_name())
+            .watch()
+            .pipeline([doc! { "$match": { "ns.coll": { "$in": tables } } }])
+            .full_document(FullDocumentType::UpdateLookup)
+            .resume_after(token)
+            .await
+            .context("Failed to open a change stream; MongoDB needs a replica set for them")?;
+        Ok(Self {
+            _client: client,
+            stream,
+        })
+    }
+}
+
+#[async_trait]
+impl ChangeReader for ChangeStreamReader {
+    async fn read_changes(&mut self, max_events: usize) -> anyhow::Result<ChangeBatch> {
+        let mut batch = ChangeBatch::default();
+        while batch.events.len() < max_events {
+            match self
+                .stream
+                .next_if_any()
+                .await
+                .context("Failed to read from the change stream")?
+            {
+                Some(event) => batch.events.extend(change_event(event)),
+                None => break,
+            }
+        }
+        batch.position = self
+            .stream
+            .resume_token()
+            .map(|token| encode_token(&token))
+            .transpose()?;
+        Ok(batch)
+    }
+
+    async fn acknowledge(&mut self, _position: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn encode_token(token: &ResumeToken) -> anyhow::Result<String> {
+    let bson = mongodb::bson::to_bson(token).context("Failed to encode resume token")?;
+    Ok(bson.into_relaxed_extjson().to_string())
+}
+
+fn decode_token(text: &str) -> anyhow::Result<ResumeToken> {
+    let json: serde_json::Value =
+        serde_json::from_str(text).context("Invalid change stream resume token")?;
+    let bson = Bson::try_from(json).context("Invalid change stream resume token")?;
+    mongodb::bson::from_bson(bson).context("Invalid change stream resume token")
+}
+
+/// Event for a document change; collection-level events (drop, rename,
+/// invalidate) are skipped. An update whose document is gone by lookup
+/// time carries only its key and the fields it set.
+fn change_event(event: ChangeStreamEvent<Document>) -> Option<ChangeEvent> {
+    let table = event.ns?.coll?;
+    let key = event.document_key.as_ref().map(bson_doc_to_row);
+    let document = event.full_document.as_ref().map(bson_doc_to_row);
+    let (op, before, after) = match event.operation_type {
+        OperationType::Insert => (ChangeOp::Insert, None, document),
+        OperationType::Update | OperationType::Replace => {
+            let after = document.or_else(|| {
+                let mut partial = key.clone()?;
+                if let Some(fields) = event.update_description {
+                    partial.extend(bson_doc_to_row(&fields.updated_fields));
+                }
+                Some(partial)
+            });
+            (ChangeOp::Update, key, after)
+        }
+        OperationType::Delete => (ChangeOp::Delete, key, None),
+        _ => return None,
+    };
+    Some(ChangeEvent {
+        table,
+        op,
+        key_columns: vec!["_id".to_string()],
+        before,
+        after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_token_round_trip() {
+        let token: ResumeToken = mongodb::bson::from_bson(Bson::Document(
+            doc! { "_data": "8265A1B2C3000000012B022C0100296E5A1004" },
+        ))
+        .unwrap();
+        let text = encode_token(&token).unwrap();
+        assert!(text.contains("8265A1B2C3"));
+        assert_eq!(decode_token(&text).unwrap(), token);
+        assert!(decode_token("not json").is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Log-based change data capture.
+//!
+//! A [`ChangeReader`] turns a source's change log into insert, update and
+//! delete events: PostgreSQL logical replication (pgoutput), MySQL binlog
+//! row events, SQL Server CDC change tables or MongoDB change streams.
+//! Events are converted to the target column types and applied with
+//! [`SqlGenerator`] statements, the same ones a migration writes, so only
+//! SQL targets are supported. The position after each applied batch
+//! is stored in the app database so a stopped sync resumes where it left
+//! off.
+
+pub mod binlog;
+pub mod change_stream;
+pub mod pgoutput;
+pub mod sqlserver;
+
+use std::collections::HashMap;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use super::connectors::DatabaseEngine;
+use super::data_comparator::row_match_key;
+use super::registry::SharedPool;
+use super::schema::Row;
+use super::sql_generator::SqlGenerator;
+use super::type_mapper::CanonicalType;
+use super::value_converter::convert_row;
+
+/// Kind of row change read from a log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeOp::Insert => "insert",
+            ChangeOp::Update => "update",
+            ChangeOp::Delete => "delete",
+        }
+    }
+}
+
+/// One row change, in the source's table naming (unqualified for the
+/// default schema, `schema.table` otherwise).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub table: String,
+    pub op: ChangeOp,
+    /// Key columns the log identifies rows by (replica identity, `_id`);
+    /// empty when it has none.
+    pub key_columns: Vec<String>,
+    /// Row before an update or delete. May hold only the key columns.
+    pub before: Option<Row>,
+    /// Row after an insert or update. Columns the log left out (unchanged
+    /// TOAST values, say) are missing rather than null.
+    pub after: Option<Row>,
+}
+
+/// Events read in one call, whole transactions only.
+#[derive(Debug, Default)]
+pub struct ChangeBatch {
+    pub events: Vec<ChangeEvent>,
+    /// Position to resume from once the events are applied; `None` when
+    /// the log had nothing new.
+    pub position: Option<String>,
+}
+
+#[async_trait]
+pub trait ChangeReader: Send {
+    /// Read the changes after the current position, stopping at the first
+    /// transaction boundary past `max_events`.
+    async fn read_changes(&mut self, max_events: usize) -> anyhow::Result<ChangeBatch>;
+
+    /// Called once a batch is applied and its position stored, so the
+    /// source can release the log behind it.
+    async fn acknowledge(&mut self, position: &str) -> anyhow::Result<()>;
+}
+
+/// Open a reader for `tables` of the pool's database, resuming after
+/// `position` when given and at the current end of the log otherwise.
+pub async fn open_reader(
+    pool: SharedPool,
+    stream_id: &str,
+    tables: Vec<String>,
+    position: Option<String>,
+) -> anyhow::Result<Box<dyn ChangeReader>> {
+    let reader: Box<dyn ChangeReader> = match pool.engine() {
+        DatabaseEngine::PostgreSql => Box::new(
+            pgoutput::PgOutputReader::open(pool, &slot_name(stream_id), tables, position).await?,
+        ),
+        DatabaseEngine::MySql => Box::new(
+            binlog::BinlogReader::open(pool, server_id(stream_id), tables, position).await?,
+        ),
+        DatabaseEngine::SqlServer => {
+            Box::new(sqlserver::ChangeTableReader::open(pool, tables, position).await?)
+        }
+        DatabaseEngine::MongoDb => {
+            Box::new(change_stream::ChangeStreamReader::open(pool, tables, position).await?)
+        }
+        other => bail!("{} has no change log to read from", other),
+    };
+    Ok(reader)
+}
+
+/// Replication slot and publication name of a stream: lowercase letters,
+/// digits and underscores, within PostgreSQL's 63-character limit.
+pub fn slot_name(stream_id: &str) -> String {
+    let base: String = stream_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut name = format!("upsert_cdc_{}", base);
+    name.truncate(63);
+    name
+}
+
+/// Replica server ID a stream registers with on MySQL, stable per stream
+/// and clear of the low IDs servers are usually given.
+pub fn server_id(stream_id: &str) -> u32 {
+    let digest = Sha256::digest(stream_id.as_bytes());
+    let hash = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    0x4000_0000 | (hash & 0x3fff_ffff)
+}
+
+/// The event with its row images converted to the target's column types
+/// (see [`super::value_converter::column_type_map`]), as a migration
+/// converts rows before writing them. Fails on the first value that does
+/// not fit, naming every failing column.
+pub fn convert_event(
+    event: &ChangeEvent,
+    column_types: &HashMap<String, CanonicalType>,
+) -> anyhow::Result<ChangeEvent> {
+    let convert = |image: &Option<Row>| -> anyhow::Result<Option<Row>> {
+        let Some(row) = image else {
+            return Ok(None);
+        };
+        convert_row(row, column_types).map(Some).map_err(|errors| {
+            let details: Vec<String> = errors
+                .iter()
+                .map(|e| format!("column '{}': {}", e.column.as_deref().unwrap_or("?"), e))
+                .collect();
+            anyhow::anyhow!(
+                "{} event on {} does not fit the target: {}",
+                event.op.as_str(),
+                event.table,
+                details.join("; ")
+            )
+        })
+    };
+    Ok(ChangeEvent {
+        before: convert(&event.before)?,
+        after: convert(&event.after)?,
+        ..event.clone()
+    })
+}
+
+/// Statements applying one event to `table`. An insert first deletes any
+/// row with its key, so a batch replayed after a crash applies cleanly; an
+/// update that changed the key deletes the old row and inserts the new.
+pub fn apply_statements(
+    sql_gen: &SqlGenerator,
+    event: &ChangeEvent,
+    table: &str,
+    key_columns: &[String],
+) -> anyhow::Result<Vec<String>> {
+    if key_columns.is_empty() {
+        bail!(
+            "No key columns for {}; set them on the table mapping",
+            event.table
+        );
+    }
+    let image = |row: &Option<Row>, which: &str| match row {
+        Some(row) if key_columns.iter().all(|k| row.contains_key(k)) => Ok(row.clone()),
+        _ => Err(anyhow::anyhow!(
+            "{} event on {} has no {} image with key columns {}",
+            event.op.as_str(),
+            event.table,
+            which,
+            key_columns.join(", ")
+        )),
+    };
+
+    match event.op {
+        ChangeOp::Insert => {
+            let after = image(&event.after, "after")?;
+            Ok(vec![
+                sql_gen.generate_delete(table, &after, key_columns),
+                sql_gen.generate_insert(table, &after),
+            ])
+        }
+        ChangeOp::Update => {
+            let after = image(&event.after, "after")?;
+            let key_changed = image(&event.before, "before").is_ok_and(|before| {
+                row_match_key(&before, key_columns) != row_match_key(&after, key_columns)
+            });
+            if key_changed {
+                let before = image(&event.before, "before")?;
+                Ok(vec![
+                    sql_gen.generate_delete(table, &before, key_columns),
+                    sql_gen.generate_delete(table, &after, key_columns),
+                    sql_gen.generate_insert(table, &after),
+                ])
+            } else if after.keys().any(|c| !key_columns.contains(c)) {
+                Ok(vec![sql_gen.generate_update(table, &after, key_columns)])
+            } else {
+                Ok(Vec::new())
+            }
+        }
+        ChangeOp::Delete => {
+            let before = image(&event.before, "before")?;
+            Ok(vec![sql_gen.generate_delete(table, &before, key_columns)])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn event(op: ChangeOp, before: Option<Row>, after: Option<Row>) -> ChangeEvent {
+        ChangeEvent {
+            table: "orders".into(),
+            op,
+            key_columns: vec!["id".into()],
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn test_slot_name_and_server_id() {
+        assert_eq!(slot_name("Nightly Orders"), "upsert_cdc_nightly_orders");
+        assert_eq!(slot_name(&"x".repeat(100)).len(), 63);
+        assert_eq!(server_id("a"), server_id("a"));
+        assert_ne!(server_id("a"), server_id("b"));
+        assert!(server_id("a") >= 0x4000_0000);
+    }
+
+    #[test]
+    fn test_apply_statements() {
+        let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        let key = vec!["id".to_string()];
+        let new = row(&[("id", json!(1)), ("qty", json!(5))]);
+
+        let insert = apply_statements(
+            &gen,
+            &event(ChangeOp::Insert, None, Some(new.clone())),
+            "t",
+            &key,
+        )
+        .unwrap();
+        assert_eq!(
+            insert,
+            vec![
+                "DELETE FROM \"t\" WHERE \"id\" = 1;".to_string(),
+                "INSERT INTO \"t\" (\"id\", \"qty\") VALUES (1, 5);".to_string(),
+            ]
+        );
+
+        let update = event(ChangeOp::Update, None, Some(new.clone()));
+        assert_eq!(
+            apply_statements(&gen, &update, "t", &key).unwrap(),
+            vec!["UPDATE \"t\" SET \"qty\" = 5 WHERE \"id\" = 1;".to_string()]
+        );
+
+        let moved = event(
+            ChangeOp::Update,
+            Some(row(&[("id", json!(9))])),
+            Some(new.clone()),
+        );
+        let moved = apply_statements(&gen, &moved, "t", &key).unwrap();
+        assert_eq!(moved.len(), 3);
+        assert_eq!(moved[0], "DELETE FROM \"t\" WHERE \"id\" = 9;");
+
+        let delete = event(ChangeOp::Delete, Some(row(&[("id", json!(1))])), None);
+        assert_eq!(
+            apply_statements(&gen, &delete, "t", &key).unwrap(),
+            vec!["DELETE FROM \"t\" WHERE \"id\" = 1;".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_convert_event() {
+        let types = HashMap::from([
+            ("id".to_string(), CanonicalType::Int),
+            ("paid".to_string(), CanonicalType::Boolean),
+        ]);
+        let change = event(
+            ChangeOp::Update,
+            Some(row(&[("id", json!("7"))])),
+            Some(row(&[("id", json!("7")), ("paid", json!(1)), ("note", json!("x"))])),
+        );
+        let converted = convert_event(&change, &types).unwrap();
+        assert_eq!(converted.before, Some(row(&[("id", json!(7))])));
+        assert_eq!(
+            converted.after,
+            Some(row(&[("id", json!(7)), ("paid", json!(true)), ("note", json!("x"))]))
+        );
+
+        let bad = event(ChangeOp::Insert, None, Some(row(&[("id", json!("seven"))])));
+        let err = convert_event(&bad, &types).unwrap_err().to_string();
+        assert!(err.contains("column 'id'"), "{}", err);
+    }
+
+    #[test]
+    fn test_apply_statements_needs_keys() {
+        let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        let delete = event(ChangeOp::Delete, Some(row(&[("qty", json!(1))])), None);
+        assert!(apply_statements(&gen, &delete, "t", &["id".to_string()]).is_err());
+        assert!(apply_statements(&gen, &delete, "t", &[]).is_err());
+        let only_key = event(ChangeOp::Update, None, Some(row(&[("id", json!(1))])));
+        assert!(apply_statements(&gen, &only_key, "t", &["id".to_string()])
+            .unwrap()
+            .is_empty());
+    }
+}
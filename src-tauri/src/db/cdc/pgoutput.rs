@@ -0,0 +1,601 @@
+//! PostgreSQL logical replication through the pgoutput plugin.
+//!
+//! Changes are peeked from a logical replication slot over a regular
+//! connection with `pg_logical_slot_peek_binary_changes`, and the slot is
+//! only advanced once a batch is applied, so nothing is lost if the sync
+//! stops in between. The slot, and a publication of the synced tables
+//! under the same name, are created on first use; tables added to the sync
+//! later are added to the publication.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{ChangeBatch, ChangeEvent, ChangeOp, ChangeReader};
+use crate::db::registry::SharedPool;
+use crate::db::schema::Row;
+use crate::db::typed_value::{self, TypedValue};
+
+pub struct PgOutputReader {
+    pool: SharedPool,
+    slot: String,
+    tables: HashSet<String>,
+    decoder: Decoder,
+}
+
+impl PgOutputReader {
+    /// Create the publication and slot if missing, add synced tables the
+    /// publication lacks, and move the slot up to `position` if it lags
+    /// behind the stored one. A read-only source cannot change the
+    /// publication, so it must already cover the tables.
+    pub async fn open(
+        pool: SharedPool,
+        slot: &str,
+        tables: Vec<String>,
+        position: Option<String>,
+    ) -> anyhow::Result<Self> {
+        {
+            let conn = pool.acquire().await?;
+            let publication = conn
+                .execute_query(&format!(
+                    "SELECT pubname FROM pg_publication WHERE pubname = {}",
+                    literal(slot)
+                ))
+                .await?;
+            let published = if publication.is_empty() {
+                None
+            } else {
+                let rows = conn
+                    .execute_query(&format!(
+                        "SELECT schemaname, tablename FROM pg_publication_tables \
+                         WHERE pubname = {}",
+                        literal(slot)
+                    ))
+                    .await?;
+                Some(rows.iter().filter_map(published_table).collect::<HashSet<_>>())
+            };
+            if let Some(sql) = publication_statement(slot, &tables, published.as_ref()) {
+                if pool.config().read_only {
+                    bail!(
+                        "The source connection is read-only, so publication {} cannot be set \
+                         up for the synced tables. Run `{}` on the source, or disable \
+                         read-only mode on it.",
+                        slot,
+                        sql
+                    );
+                }
+                conn.execute_query(&sql)
+                    .await
+                    .with_context(|| format!("Failed to set up publication {}", slot))?;
+            }
+
+            let existing = conn
+                .execute_query(&format!(
+                    "SELECT slot_name FROM pg_replication_slots WHERE slot_name = {}",
+                    literal(slot)
+                ))
+                .await?;
+            if existing.is_empty() {
+                conn.execute_query(&format!(
+                    "SELECT pg_create_logical_replication_slot({}, 'pgoutput')",
+                    literal(slot)
+                ))
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to create replication slot {}; the source needs wal_level = logical",
+                        slot
+                    )
+                })?;
+            }
+        }
+
+        let mut reader = Self {
+            pool,
+            slot: slot.to_string(),
+            tables: tables.into_iter().collect(),
+            decoder: Decoder::default(),
+        };
+        if let Some(position) = position {
+            reader.acknowledge(&position).await?;
+        }
+        Ok(reader)
+    }
+}
+
+#[async_trait]
+impl ChangeReader for PgOutputReader {
+    async fn read_changes(&mut self, max_events: usize) -> anyhow::Result<ChangeBatch> {
+        let rows = {
+            let conn = self.pool.acquire().await?;
+            conn.execute_query(&format!(
+                "SELECT data FROM pg_logical_slot_peek_binary_changes({slot}, NULL, {max}, \
+                 'proto_version', '1', 'publication_names', {slot})",
+                slot = literal(&self.slot),
+                max = max_events.max(1)
+            ))
+            .await
+            .context("Failed to read from the replication slot")?
+        };
+
+        let mut batch = ChangeBatch::default();
+        for row in &rows {
+            let data = match row.get("data").and_then(TypedValue::from_json) {
+                Some(TypedValue::Bytes(data)) => data,
+                _ => bail!("Replication slot returned a change without pgoutput data"),
+            };
+            match self.decoder.decode(&data)? {
+                Decoded::Change(event) if self.tables.contains(&event.table) => {
+                    batch.events.push(event)
+                }
+                Decoded::Commit(end_lsn) => batch.position = Some(format_lsn(end_lsn)),
+                _ => {}
+            }
+        }
+        Ok(batch)
+    }
+
+    async fn acknowledge(&mut self, position: &str) -> anyhow::Result<()> {
+        parse_lsn(position).ok_or_else(|| anyhow!("Invalid LSN '{}'", position))?;
+        let conn = self.pool.acquire().await?;
+        conn.execute_query(&format!(
+            "SELECT pg_replication_slot_advance(slot_name, {lsn}::pg_lsn) FROM pg_replication_slots \
+             WHERE slot_name = {slot} AND confirmed_flush_lsn < {lsn}::pg_lsn",
+            slot = literal(&self.slot),
+            lsn = literal(position)
+        ))
+        .await
+        .context("Failed to advance the replication slot")?;
+        Ok(())
+    }
+}
+
+fn literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Statement making publication `name` cover `tables`: a CREATE when it
+/// does not exist (`published` is `None`), an ALTER adding the tables it
+/// lacks, or `None` when it already covers them.
+fn publication_statement(
+    name: &str,
+    tables: &[String],
+    published: Option<&HashSet<String>>,
+) -> Option<String> {
+    let missing: Vec<String> = tables
+        .iter()
+        .filter(|t| published.map_or(true, |p| !p.contains(unqualified(t))))
+        .map(|t| qualified(t))
+        .collect();
+    match published {
+        None => Some(format!("CREATE PUBLICATION \"{}\" FOR TABLE {}", name, missing.join(", "))),
+        Some(_) if missing.is_empty() => None,
+        Some(_) => Some(format!(
+            "ALTER PUBLICATION \"{}\" ADD TABLE {}",
+            name,
+            missing.join(", ")
+        )),
+    }
+}
+
+/// Table of a `pg_publication_tables` row, named as the sync names it.
+fn published_table(row: &Row) -> Option<String> {
+    let schema = row.get("schemaname")?.as_str()?;
+    let table = row.get("tablename")?.as_str()?;
+    Some(if schema == "public" {
+        table.to_string()
+    } else {
+        format!("{}.{}", schema, table)
+    })
+}
+
+/// Table name without the default `public.` schema.
+fn unqualified(table: &str) -> &str {
+    table.strip_prefix("public.").unwrap_or(table)
+}
+
+/// `schema.table` quoted for a publication; unqualified names are public.
+fn qualified(table: &str) -> String {
+    table
+        .split('.')
+        .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// LSN in PostgreSQL's `X/Y` text form.
+pub fn format_lsn(lsn: u64) -> String {
+    format!("{:X}/{:X}", lsn >> 32, lsn & 0xffff_ffff)
+}
+
+pub fn parse_lsn(text: &str) -> Option<u64> {
+    let (high, low) = text.split_once('/')?;
+    let high = u64::from_str_radix(high, 16).ok()?;
+    let low = u64::from_str_radix(low, 16).ok()?;
+    (high <= 0xffff_ffff && low <= 0xffff_ffff).then_some(high << 32 | low)
+}
+
+#[derive(Debug, Clone)]
+struct Relation {
+    table: String,
+    columns: Vec<RelationColumn>,
+}
+
+#[derive(Debug, Clone)]
+struct RelationColumn {
+    name: String,
+    type_oid: u32,
+    key: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum TupleValue {
+    Null,
+    /// An unchanged TOAST value the message leaves out.
+    Unchanged,
+    Text(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Decoded {
+    Change(ChangeEvent),
+    /// End of a transaction, with the LSN just past it.
+    Commit(u64),
+    Skip,
+}
+
+/// Decodes pgoutput protocol version 1 messages, remembering the relation
+/// descriptions that precede a table's first change.
+#[derive(Default)]
+struct Decoder {
+    relations: HashMap<u32, Relation>,
+}
+
+impl Decoder {
+    fn decode(&mut self, data: &[u8]) -> anyhow::Result<Decoded> {
+        let mut buf = Buf { data, pos: 0 };
+        match buf.u8()? {
+            b'C' => {
+                buf.u8()?; // flags
+                buf.u64()?; // commit LSN
+                Ok(Decoded::Commit(buf.u64()?))
+            }
+            b'R' => {
+                let oid = buf.u32()?;
+                let namespace = buf.cstr()?;
+                let name = buf.cstr()?;
+                buf.u8()?; // replica identity setting
+                let count = buf.u16()?;
+                let mut columns = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    let flags = buf.u8()?;
+                    let name = buf.cstr()?;
+                    let type_oid = buf.u32()?;
+                    buf.u32()?; // type modifier
+                    columns.push(RelationColumn {
+                        name,
+                        type_oid,
+                        key: flags & 1 == 1,
+                    });
+                }
+                let table = if namespace == "public" {
+                    name
+                } else {
+                    format!("{}.{}", namespace, name)
+                };
+                self.relations.insert(oid, Relation { table, columns });
+                Ok(Decoded::Skip)
+            }
+            b'I' => {
+                let relation = self.relation(buf.u32()?)?;
+                buf.expect(b'N')?;
+                let after = tuple_row(relation, &buf.tuple()?, false);
+                Ok(change(relation, ChangeOp::Insert, None, Some(after)))
+            }
+            b'U' => {
+                let relation = self.relation(buf.u32()?)?;
+                let mut before = None;
+                let mut kind = buf.u8()?;
+                if kind == b'K' || kind == b'O' {
+                    before = Some(tuple_row(relation, &buf.tuple()?, kind == b'K'));
+                    kind = buf.u8()?;
+                }
+                if kind != b'N' {
+                    bail!("Malformed pgoutput update message");
+                }
+                let after = tuple_row(relation, &buf.tuple()?, false);
+                Ok(change(relation, ChangeOp::Update, before, Some(after)))
+            }
+            b'D' => {
+                let relation = self.relation(buf.u32()?)?;
+                let kind = buf.u8()?;
+                let before = tuple_row(relation, &buf.tuple()?, kind == b'K');
+                Ok(change(relation, ChangeOp::Delete, Some(before), None))
+            }
+            // Begin, origin, type, truncate and logical messages
+            _ => Ok(Decoded::Skip),
+        }
+    }
+
+    fn relation(&self, oid: u32) -> anyhow::Result<&Relation> {
+        self.relations
+            .get(&oid)
+            .ok_or_else(|| anyhow!("pgoutput change for unknown relation {}", oid))
+    }
+}
+
+fn change(relation: &Relation, op: ChangeOp, before: Option<Row>, after: Option<Row>) -> Decoded {
+    Decoded::Change(ChangeEvent {
+        table: relation.table.clone(),
+        op,
+        key_columns: relation
+            .columns
+            .iter()
+            .filter(|c| c.key)
+            .map(|c| c.name.clone())
+            .collect(),
+        before,
+        after,
+    })
+}
+
+/// Row of a tuple. Unchanged TOAST values are left out, as are non-key
+/// columns of a key-only (`K`) tuple, which are sent as nulls.
+fn tuple_row(relation: &Relation, tuple: &[TupleValue], key_only: bool) -> Row {
+    relation
+        .columns
+        .iter()
+        .zip(tuple)
+        .filter(|(column, _)| column.key || !key_only)
+        .filter_map(|(column, value)| match value {
+            TupleValue::Null => Some((column.name.clone(), Value::Null)),
+            TupleValue::Unchanged => None,
+            TupleValue::Text(text) => {
+                Some((column.name.clone(), text_value(column.type_oid, text)))
+            }
+        })
+        .collect()
+}
+
+/// Typed JSON value of a column's text output, by type OID.
+fn text_value(type_oid: u32, text: &str) -> Value {
+    let parsed = match type_oid {
+        16 => Some(Value::Bool(text == "t")),
+        20 | 21 | 23 | 26 => text.parse::<i64>().ok().map(Value::from),
+        700 | 701 => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        1700 => typed_value::is_decimal_text(text).then(|| typed_value::decimal(text)),
+        17 => text
+            .strip_prefix("\\x")
+            .and_then(|h| hex::decode(h).ok())
+            .map(|b| typed_value::bytes(&b)),
+        1082 => chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .map(typed_value::date),
+        1083 => chrono::NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+            .ok()
+            .map(typed_value::time),
+        1114 => chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .map(typed_value::datetime),
+        1184 => chrono::DateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f%#z")
+            .ok()
+            .map(typed_value::timestamp),
+        2950 => uuid::Uuid::parse_str(text).ok().map(typed_value::uuid),
+        114 | 3802 => serde_json::from_str(text).ok(),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+/// Big-endian cursor over a pgoutput message.
+struct Buf<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Buf<'_> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&[u8]> {
+        let end = self.pos + n;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| anyhow!("Truncated pgoutput message"))?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> anyhow::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> anyhow::Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> anyhow::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn expect(&mut self, byte: u8) -> anyhow::Result<()> {
+        match self.u8()? {
+            b if b == byte => Ok(()),
+            b => bail!(
+                "Expected pgoutput tuple '{}', found '{}'",
+                byte as char,
+                b as char
+            ),
+        }
+    }
+
+    fn cstr(&mut self) -> anyhow::Result<String> {
+        let rest = &self.data[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!("Unterminated string in pgoutput message"))?;
+        let text = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(text)
+    }
+
+    fn tuple(&mut self) -> anyhow::Result<Vec<TupleValue>> {
+        let count = self.u16()?;
+        let mut values = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            values.push(match self.u8()? {
+                b'n' => TupleValue::Null,
+                b'u' => TupleValue::Unchanged,
+                b't' => {
+                    let len = self.u32()? as usize;
+                    TupleValue::Text(String::from_utf8_lossy(self.take(len)?).into_owned())
+                }
+                other => bail!("Unsupported pgoutput tuple value '{}'", other as char),
+            });
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn relation_message() -> Vec<u8> {
+        let mut m = vec![b'R'];
+        m.extend(16384u32.to_be_bytes());
+        m.extend(b"sales\0orders\0");
+        m.push(b'd');
+        m.extend(3u16.to_be_bytes());
+        for (flags, name, oid) in [(1u8, "id", 23u32), (0, "total", 1700), (0, "note", 25)] {
+            m.push(flags);
+            m.extend(name.as_bytes());
+            m.push(0);
+            m.extend(oid.to_be_bytes());
+            m.extend((-1i32).to_be_bytes());
+        }
+        m
+    }
+
+    fn tuple(values: &[Option<&str>]) -> Vec<u8> {
+        let mut t = (values.len() as u16).to_be_bytes().to_vec();
+        for value in values {
+            match value {
+                None => t.push(b'n'),
+                Some("~") => t.push(b'u'),
+                Some(text) => {
+                    t.push(b't');
+                    t.extend((text.len() as u32).to_be_bytes());
+                    t.extend(text.as_bytes());
+                }
+            }
+        }
+        t
+    }
+
+    #[test]
+    fn test_publication_statement() {
+        let tables = vec!["orders".to_string(), "sales.items".to_string()];
+        assert_eq!(
+            publication_statement("s", &tables, None).unwrap(),
+            r#"CREATE PUBLICATION "s" FOR TABLE "orders", "sales"."items""#
+        );
+
+        let rows = [
+            Row::from([
+                ("schemaname".to_string(), json!("public")),
+                ("tablename".to_string(), json!("orders")),
+            ]),
+            Row::from([
+                ("schemaname".to_string(), json!("sales")),
+                ("tablename".to_string(), json!("items")),
+            ]),
+        ];
+        let mut published: HashSet<String> = rows.iter().filter_map(published_table).collect();
+        assert_eq!(publication_statement("s", &tables, Some(&published)), None);
+
+        published.remove("sales.items");
+        assert_eq!(
+            publication_statement("s", &tables, Some(&published)).unwrap(),
+            r#"ALTER PUBLICATION "s" ADD TABLE "sales"."items""#
+        );
+    }
+
+    #[test]
+    fn test_lsn_round_trip() {
+        assert_eq!(parse_lsn("16/B374D848"), Some(0x16_B374_D848));
+        assert_eq!(format_lsn(0x16_B374_D848), "16/B374D848");
+        assert_eq!(parse_lsn("nope"), None);
+    }
+
+    #[test]
+    fn test_decode_changes() {
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.decode(&relation_message()).unwrap(), Decoded::Skip);
+
+        let mut insert = vec![b'I'];
+        insert.extend(16384u32.to_be_bytes());
+        insert.push(b'N');
+        insert.extend(tuple(&[Some("7"), Some("12.50"), None]));
+        let Decoded::Change(event) = decoder.decode(&insert).unwrap() else {
+            panic!("expected a change");
+        };
+        assert_eq!(event.table, "sales.orders");
+        assert_eq!(event.op, ChangeOp::Insert);
+        assert_eq!(event.key_columns, vec!["id".to_string()]);
+        let after = event.after.unwrap();
+        assert_eq!(after["id"], json!(7));
+        assert_eq!(after["total"], json!({"$decimal": "12.50"}));
+        assert_eq!(after["note"], Value::Null);
+
+        // Key changed: old key tuple, new tuple with an unchanged TOAST value
+        let mut update = vec![b'U'];
+        update.extend(16384u32.to_be_bytes());
+        update.push(b'K');
+        update.extend(tuple(&[Some("6"), None, None]));
+        update.push(b'N');
+        update.extend(tuple(&[Some("7"), Some("13"), Some("~")]));
+        let Decoded::Change(event) = decoder.decode(&update).unwrap() else {
+            panic!("expected a change");
+        };
+        assert_eq!(event.before.unwrap().len(), 1);
+        assert!(!event.after.unwrap().contains_key("note"));
+
+        let mut commit = vec![b'C', 0];
+        commit.extend(0x10u64.to_be_bytes());
+        commit.extend(0x20u64.to_be_bytes());
+        commit.extend(0u64.to_be_bytes());
+        assert_eq!(decoder.decode(&commit).unwrap(), Decoded::Commit(0x20));
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_relation_and_truncation() {
+        let mut decoder = Decoder::default();
+        let mut delete = vec![b'D'];
+        delete.extend(1u32.to_be_bytes());
+        assert!(decoder.decode(&delete).is_err());
+        assert!(decoder.decode(&relation_message()[..10]).is_err());
+    }
+
+    #[test]
+    fn test_text_values() {
+        assert_eq!(text_value(16, "t"), json!(true));
+        assert_eq!(text_value(17, "\\xdead"), json!({"$binary": "dead"}));
+        assert_eq!(
+            text_value(1114, "2024-01-02 03:04:05.5"),
+            json!({"$datetime": "2024-01-02T03:04:05.500"})
+        );
+        assert_eq!(text_value(3802, "{\"a\": 1}"), json!({"a": 1}));
+        assert_eq!(text_value(25, "plain"), json!("plain"));
+    }
+}
@@ -0,0 +1,279 @@
+//! SQL Server change data capture tables.
+//!
+//! Each synced table needs CDC enabled (`sys.sp_cdc_enable_table`); its
+//! capture instance's change function is queried from just past the last
+//! applied LSN up to the current maximum, so every read ends on a
+//! transaction boundary. SQL Server's cleanup job owns retention, so there
+//! is nothing to release after a batch.
+
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+
+use super::{ChangeBatch, ChangeEvent, ChangeOp, ChangeReader};
+use crate::db::registry::SharedPool;
+use crate::db::schema::Row;
+
+/// Column prefix of CDC metadata in change rows.
+const META_PREFIX: &str = "__$";
+
+struct CaptureInstance {
+    table: String,
+    name: String,
+    key_columns: Vec<String>,
+}
+
+pub struct ChangeTableReader {
+    pool: SharedPool,
+    instances: Vec<CaptureInstance>,
+    /// Last LSN applied, as `0x`-prefixed hex.
+    position: Option<String>,
+}
+
+impl ChangeTableReader {
+    pub async fn open(
+        pool: SharedPool,
+        tables: Vec<String>,
+        position: Option<String>,
+    ) -> anyhow::Result<Self> {
+        if let Some(ref lsn) = position {
+            check_lsn(lsn)?;
+        }
+        let conn = pool.acquire().await?;
+        let mut instances = Vec::with_capacity(tables.len());
+        for table in tables {
+            let rows = conn
+                .execute_query(&format!(
+                    "SELECT TOP 1 capture_instance, object_id FROM cdc.change_tables \
+                     WHERE source_object_id = OBJECT_ID({}) ORDER BY create_date DESC",
+                    literal(&table)
+                ))
+                .await
+                .context("Failed to look up CDC capture instances")?;
+            let Some(row) = rows.first() else {
+                bail!(
+                    "CDC is not enabled for {}; enable it with sys.sp_cdc_enable_table",
+                    table
+                );
+            };
+            let name = text(row, "capture_instance")
+                .ok_or_else(|| anyhow!("Capture instance of {} has no name", table))?;
+            let object_id = row
+                .get("object_id")
+                .and_then(|v| v.as_i64())
+                .ok_or_else(|| anyhow!("Capture instance {} has no object ID", name))?;
+            let key_columns = conn
+                .execute_query(&format!(
+                    "SELECT column_name FROM cdc.index_columns WHERE object_id = {} \
+                     ORDER BY index_ordinal",
+                    object_id
+                ))
+                .await
+                .context("Failed to read CDC index columns")?
+                .iter()
+                .filter_map(|r| text(r, "column_name"))
+                .collect();
+            instances.push(CaptureInstance {
+                table,
+                name,
+                key_columns,
+            });
+        }
+        drop(conn);
+
+        let mut reader = Self {
+            pool,
+            instances,
+            position,
+        };
+        if reader.position.is_none() {
+            reader.position = reader.max_lsn().await?;
+        }
+        Ok(reader)
+    }
+
+    async fn max_lsn(&self) -> anyhow::Result<Option<String>> {
+        let conn = self.pool.acquire().await?;
+        let rows = conn
+            .execute_query("SELECT CONVERT(varchar(24), sys.fn_cdc_get_max_lsn(), 1) AS lsn")
+            .await
+            .context("Failed to read the maximum CDC LSN")?;
+        Ok(rows.first().and_then(|r| text(r, "lsn")))
+    }
+}
+
+#[async_trait]
+impl ChangeReader for ChangeTableReader {
+    async fn read_changes(&mut self, _max_events: usize) -> anyhow::Result<ChangeBatch> {
+        let Some(to) = self.max_lsn().await? else {
+            // The capture job has not run yet
+            return Ok(ChangeBatch::default());
+        };
+        if self.position.as_ref().is_some_and(|p| *p >= to) {
+            return Ok(ChangeBatch::default());
+        }
+
+        let conn = self.pool.acquire().await?;
+        let mut changes = Vec::new();
+        for instance in &self.instances {
+            let next = match self.position {
+                Some(ref p) => format!("sys.fn_cdc_increment_lsn({})", p),
+                None => "NULL".to_string(),
+            };
+            let bounds = conn
+                .execute_query(&format!(
+                    "SELECT CONVERT(varchar(24), {}, 1) AS next_lsn, \
+                     CONVERT(varchar(24), sys.fn_cdc_get_min_lsn({}), 1) AS min_lsn",
+                    next,
+                    literal(&instance.name)
+                ))
+                .await?;
+            let bounds = bounds.first();
+            let from = [
+                bounds.and_then(|r| text(r, "next_lsn")),
+                bounds.and_then(|r| text(r, "min_lsn")),
+            ]
+            .into_iter()
+            .flatten()
+            .max();
+            let Some(from) = from.filter(|from| *from <= to) else {
+                continue;
+            };
+            let rows = conn
+                .execute_query(&format!(
+                    "SELECT CONVERT(varchar(24), __$start_lsn, 1) AS [__$lsn], \
+                     CONVERT(varchar(24), __$seqval, 1) AS [__$seq], * \
+                     FROM cdc.fn_cdc_get_all_changes_{}({}, {}, N'all update old')",
+                    instance.name, from, to
+                ))
+                .await
+                .with_context(|| format!("Failed to read CDC changes of {}", instance.table))?;
+            changes.extend(change_events(&instance.table, &instance.key_columns, rows));
+        }
+
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(ChangeBatch {
+            events: changes.into_iter().map(|(_, event)| event).collect(),
+            position: Some(to),
+        })
+    }
+
+    async fn acknowledge(&mut self, position: &str) -> anyhow::Result<()> {
+        check_lsn(position)?;
+        self.position = Some(position.to_string());
+        Ok(())
+    }
+}
+
+fn literal(text: &str) -> String {
+    format!("N'{}'", text.replace('\'', "''"))
+}
+
+fn text(row: &Row, column: &str) -> Option<String> {
+    row.get(column)?.as_str().map(str::to_string)
+}
+
+/// LSNs are spliced into queries, so only accept their hex literal form.
+fn check_lsn(lsn: &str) -> anyhow::Result<()> {
+    let valid = lsn
+        .strip_prefix("0x")
+        .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    if !valid {
+        bail!("Invalid SQL Server LSN '{}'", lsn);
+    }
+    Ok(())
+}
+
+/// Events of a capture instance's change rows, each with its (LSN,
+/// sequence) order key. Operation 1 is a delete, 2 an insert, 3 and 4 the
+/// before and after images of an update.
+fn change_events(
+    table: &str,
+    key_columns: &[String],
+    rows: Vec<Row>,
+) -> Vec<((String, String), ChangeEvent)> {
+    let mut events = Vec::new();
+    let mut before: Option<((String, String), Row)> = None;
+    for mut row in rows {
+        let order = (
+            text(&row, "__$lsn").unwrap_or_default(),
+            text(&row, "__$seq").unwrap_or_default(),
+        );
+        let operation = row.get("__$operation").and_then(|v| v.as_i64());
+        row.retain(|column, _| !column.starts_with(META_PREFIX));
+        let event = |op, before, after| ChangeEvent {
+            table: table.to_string(),
+            op,
+            key_columns: key_columns.to_vec(),
+            before,
+            after,
+        };
+        match operation {
+            Some(1) => events.push((order, event(ChangeOp::Delete, Some(row), None))),
+            Some(2) => events.push((order, event(ChangeOp::Insert, None, Some(row)))),
+            Some(3) => before = Some((order, row)),
+            Some(4) => {
+                let old = before
+                    .take()
+                    .filter(|(o, _)| *o == order)
+                    .map(|(_, old)| old);
+                events.push((order, event(ChangeOp::Update, old, Some(row))));
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn change(lsn: &str, seq: &str, op: i64, id: i64, qty: i64) -> Row {
+        [
+            ("__$lsn", json!(lsn)),
+            ("__$seq", json!(seq)),
+            ("__$start_lsn", json!({"$binary": "00"})),
+            ("__$operation", json!(op)),
+            ("id", json!(id)),
+            ("qty", json!(qty)),
+        ]
+        .into_iter()
+        .map(|(k, v): (&str, Value)| (k.to_string(), v))
+        .collect()
+    }
+
+    #[test]
+    fn test_change_events() {
+        let rows = vec![
+            change("0x01", "0x01", 2, 1, 5),
+            change("0x02", "0x01", 3, 1, 5),
+            change("0x02", "0x01", 4, 1, 6),
+            change("0x03", "0x01", 1, 1, 6),
+        ];
+        let events = change_events("orders", &["id".to_string()], rows);
+        let ops: Vec<ChangeOp> = events.iter().map(|(_, e)| e.op).collect();
+        assert_eq!(
+            ops,
+            vec![ChangeOp::Insert, ChangeOp::Update, ChangeOp::Delete]
+        );
+
+        let update = &events[1].1;
+        assert_eq!(update.before.as_ref().unwrap()["qty"], json!(5));
+        assert_eq!(update.after.as_ref().unwrap()["qty"], json!(6));
+        assert!(update
+            .after
+            .as_ref()
+            .unwrap()
+            .keys()
+            .all(|k| !k.starts_with(META_PREFIX)));
+        assert_eq!(events[2].0, ("0x03".to_string(), "0x01".to_string()));
+    }
+
+    #[test]
+    fn test_check_lsn() {
+        assert!(check_lsn("0x0000002A000001F40003").is_ok());
+        assert!(check_lsn("0x").is_err());
+        assert!(check_lsn("1; DROP TABLE t").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Checkpoint bookkeeping for resumable migrations.
+//!
+//! A table's writes run in three phases (inserts, updates, deletes), each in
+//! key order, so "everything up to this key in this phase" describes the
+//! committed progress exactly. A resumed run recomputes the plan and skips
+//! whatever lies at or before the checkpoint.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::schema::{ColumnInfo, Row};
+use super::typed_value::TypedValue;
+
+/// Write phase of a table, in execution order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Insert => "insert",
+            Phase::Update => "update",
+            Phase::Delete => "delete",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "insert" => Some(Phase::Insert),
+            "update" => Some(Phase::Update),
+            "delete" => Some(Phase::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// Where a table's previous run stopped: the last key written in a phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumePoint {
+    pub phase: Phase,
+    pub last_key: Vec<Value>,
+}
+
+impl ResumePoint {
+    /// Whether the operation on `key` in `phase` was already done.
+    pub fn covers(&self, phase: Phase, key: &[Value]) -> bool {
+        match phase.cmp(&self.phase) {
+            Ordering::Less => true,
+            Ordering::Equal => compare_keys(key, &self.last_key) != Ordering::Greater,
+            Ordering::Greater => false,
+        }
+    }
+}
+
+/// Values of `key_columns` in `row`; missing columns read as null.
+pub fn row_key(row: &Row, key_columns: &[String]) -> Vec<Value> {
+    key_columns
+        .iter()
+        .map(|c| row.get(c).cloned().unwrap_or(Value::Null))
+        .collect()
+}
+
+/// Sort rows by key so batches cover contiguous key ranges.
+pub fn sort_by_key(rows: &mut [Row], key_columns: &[String]) {
+    rows.sort_by(|a, b| compare_keys(&row_key(a, key_columns), &row_key(b, key_columns)));
+}
+
+/// Total order over keys: column by column, nulls first, numbers by value,
+/// tagged values by their text, then strings. Values of different kinds
+/// order by kind.
+pub fn compare_keys(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| compare_values(x, y))
+        .find(|o| *o != Ordering::Equal)
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => match (x.as_i64(), y.as_i64()) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            _ => {
+                let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+                x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+            }
+        },
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        _ if kind(a) == kind(b) => text(a).cmp(&text(b)),
+        _ => kind(a).cmp(&kind(b)),
+    }
+}
+
+fn kind(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::Object(_) if TypedValue::from_json(value).is_some() => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    }
+}
+
+fn text(value: &Value) -> String {
+    match TypedValue::from_json(value) {
+        Some(typed) => typed.to_text(),
+        None => value.to_string(),
+    }
+}
+
+/// Hash of a table's column names, types and nullability, independent of
+/// column order in the metadata.
+pub fn schema_fingerprint(columns: &[ColumnInfo]) -> String {
+    let mut parts: Vec<String> = columns
+        .iter()
+        .map(|c| {
+            format!(
+                "{}:{}:{}:{:?}:{:?}:{:?}",
+                c.name, c.data_type, c.is_nullable, c.max_length, c.precision, c.scale
+            )
+        })
+        .collect();
+    parts.sort();
+    let mut hasher = Sha256::new();
+    hasher.update(parts.join("\n").as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            is_primary_key: false,
+            max_length: None,
+            precision: None,
+            scale: None,
+            default_value: None,
+            ordinal_position: 0,
+        }
+    }
+
+    #[test]
+    fn test_phase_round_trip() {
+        for phase in [Phase::Insert, Phase::Update, Phase::Delete] {
+            assert_eq!(Phase::parse(phase.as_str()), Some(phase));
+        }
+        assert_eq!(Phase::parse("merge"), None);
+        assert!(Phase::Insert < Phase::Update && Phase::Update < Phase::Delete);
+    }
+
+    #[test]
+    fn test_compare_keys() {
+        assert_eq!(compare_keys(&[json!(2)], &[json!(10)]), Ordering::Less);
+        assert_eq!(compare_keys(&[json!(2.5)], &[json!(2)]), Ordering::Greater);
+        assert_eq!(compare_keys(&[json!("b")], &[json!("a")]), Ordering::Greater);
+        assert_eq!(compare_keys(&[Value::Null], &[json!(0)]), Ordering::Less);
+        assert_eq!(
+            compare_keys(&[json!(1), json!("x")], &[json!(1), json!("y")]),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_keys(
+                &[json!({"$date": "2024-01-02"})],
+                &[json!({"$date": "2024-01-10"})]
+            ),
+            Ordering::Less
+        );
+        assert_eq!(compare_keys(&[json!(7)], &[json!(7)]), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_sort_by_key() {
+        let key = vec!["id".to_string()];
+        let mut rows = vec![
+            row(&[("id", json!(10))]),
+            row(&[("id", json!(2))]),
+            row(&[("id", json!(7))]),
+        ];
+        sort_by_key(&mut rows, &key);
+        let ids: Vec<Value> = rows.iter().map(|r| r["id"].clone()).collect();
+        assert_eq!(ids, vec![json!(2), json!(7), json!(10)]);
+    }
+
+    #[test]
+    fn test_resume_point_covers() {
+        let point = ResumePoint {
+            phase: Phase::Update,
+            last_key: vec![json!(5)],
+        };
+        assert!(point.covers(Phase::Insert, &[json!(100)]));
+        assert!(point.covers(Phase::Update, &[json!(5)]));
+        assert!(point.covers(Phase::Update, &[json!(3)]));
+        assert!(!point.covers(Phase::Update, &[json!(6)]));
+        assert!(!point.covers(Phase::Delete, &[json!(1)]));
+    }
+
+    #[test]
+    fn test_schema_fingerprint() {
+        let a = vec![column("id", "integer"), column("name", "text")];
+        let reordered = vec![column("name", "text"), column("id", "integer")];
+        assert_eq!(schema_fingerprint(&a), schema_fingerprint(&reordered));
+
+        let retyped = vec![column("id", "bigint"), column("name", "text")];
+        assert_ne!(schema_fingerprint(&a), schema_fingerprint(&retyped));
+        let added = vec![column("id", "integer"), column("name", "text"), column("x", "text")];
+        assert_ne!(schema_fingerprint(&a), schema_fingerprint(&added));
+    }
+}
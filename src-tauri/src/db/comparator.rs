@@ -1,10 +1,20 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-
-use super::schema::{ColumnInfo, ConstraintInfo, IndexInfo, TableInfo};
+use std::collections::{BTreeMap, HashMap};
+
+use super::connectors::DatabaseEngine;
+use super::renames::{
+    column_similarity, match_renames, table_similarity, COLUMN_RENAME_THRESHOLD,
+    TABLE_RENAME_THRESHOLD,
+};
+use super::schema::{
+    ColumnInfo, ConstraintInfo, IndexInfo, RoutineInfo, SchemaInfo, SequenceInfo, TableInfo,
+    TriggerInfo, UserTypeInfo, ViewInfo,
+};
+use super::type_mapper::{from_canonical, native_type_with_params, to_canonical};
 
 /// Result of comparing two schemas
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaDiffResult {
     pub source_database: String,
     pub target_database: String,
@@ -12,6 +22,77 @@ pub struct SchemaDiffResult {
     pub summary: DiffSummary,
 }
 
+impl SchemaDiffResult {
+    /// Detected renames the user has not confirmed yet.
+    pub fn pending_renames(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes
+            .iter()
+            .filter(|c| c.rename.as_ref().is_some_and(|r| !r.confirmed))
+    }
+
+    /// Accept the detected rename to `object_name`. False when there is none.
+    pub fn confirm_rename(&mut self, object_name: &str) -> bool {
+        let rename = self
+            .changes
+            .iter_mut()
+            .filter(|c| c.change_type == ChangeType::Renamed && c.object_name == object_name)
+            .find_map(|c| c.rename.as_mut());
+        match rename {
+            Some(rename) => {
+                rename.confirmed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Turn the detected rename to `object_name` back into a removal of the
+    /// old object and an addition of the new one. A table takes the changes
+    /// found inside it along. False when there is no such rename.
+    pub fn reject_rename(&mut self, object_name: &str) -> bool {
+        let Some(index) = self
+            .changes
+            .iter()
+            .position(|c| c.change_type == ChangeType::Renamed && c.object_name == object_name)
+        else {
+            return false;
+        };
+        let change = self.changes.remove(index);
+        let old_name = change.rename.map(|r| r.from).unwrap_or_default();
+        if change.object_type == SchemaObjectType::Table {
+            let prefix = format!("{}.", object_name);
+            self.changes.retain(|c| {
+                let inside = matches!(
+                    c.object_type,
+                    SchemaObjectType::Column
+                        | SchemaObjectType::Index
+                        | SchemaObjectType::Constraint
+                );
+                !(inside && c.object_name.starts_with(&prefix))
+            });
+        }
+        let split = [
+            (old_name, ChangeType::Removed),
+            (object_name.to_string(), ChangeType::Added),
+        ];
+        // Inserted in reverse so the removal ends up first
+        for (name, change_type) in split.into_iter().rev() {
+            self.changes.insert(
+                index,
+                SchemaChange {
+                    object_type: change.object_type.clone(),
+                    object_name: name,
+                    change_type,
+                    details: vec![],
+                    rename: None,
+                },
+            );
+        }
+        self.summary = DiffSummary::tally(&self.changes);
+        true
+    }
+}
+
 /// Summary counts for a diff operation
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DiffSummary {
@@ -19,15 +100,48 @@ pub struct DiffSummary {
     pub removals: usize,
     pub modifications: usize,
     pub unchanged: usize,
+    #[serde(default)]
+    pub renames: usize,
+}
+
+impl DiffSummary {
+    fn tally(changes: &[SchemaChange]) -> Self {
+        let mut summary = Self::default();
+        for change in changes {
+            match change.change_type {
+                ChangeType::Added => summary.additions += 1,
+                ChangeType::Removed => summary.removals += 1,
+                ChangeType::Modified => summary.modifications += 1,
+                ChangeType::Unchanged => summary.unchanged += 1,
+                ChangeType::Renamed => summary.renames += 1,
+            }
+        }
+        summary
+    }
 }
 
 /// A single schema change between source and target
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaChange {
     pub object_type: SchemaObjectType,
     pub object_name: String,
     pub change_type: ChangeType,
     pub details: Vec<ChangeDetail>,
+    /// Set on detected renames; `object_name` is then the new name.
+    #[serde(default)]
+    pub rename: Option<RenameInfo>,
+}
+
+/// A rename proposed from structural similarity. It stays a proposal until
+/// the user confirms it; DDL must not be generated from unconfirmed ones.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RenameInfo {
+    /// Name in the source
+    pub from: String,
+    /// Similarity of the two objects, from the threshold up to 1
+    pub confidence: f64,
+    pub confirmed: bool,
 }
 
 /// Types of schema objects that can differ
@@ -39,7 +153,10 @@ pub enum SchemaObjectType {
     Constraint,
     View,
     StoredProcedure,
+    Function,
     Trigger,
+    Sequence,
+    UserType,
 }
 
 /// Types of changes
@@ -49,62 +166,114 @@ pub enum ChangeType {
     Removed,
     Modified,
     Unchanged,
+    /// Removed under one name and added under another with the same shape
+    Renamed,
 }
 
 /// Detail about what specifically changed
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ChangeDetail {
     pub property: String,
     pub source_value: Option<String>,
     pub target_value: Option<String>,
 }
 
-/// Compare two schemas and produce a diff result
-pub fn compare_schemas(
-    source_tables: &[TableInfo],
-    target_tables: &[TableInfo],
-    source_db: &str,
-    target_db: &str,
+/// How column types, defaults and indexes are compared
+#[derive(Debug, Clone, Default)]
+pub enum ComparisonMode {
+    /// Declared types and defaults are compared as written, ignoring case
+    #[default]
+    Native,
+    /// Both sides are mapped through the canonical type system, so only
+    /// differences that survive a migration between the engines remain
+    CrossEngine {
+        source: DatabaseEngine,
+        target: DatabaseEngine,
+    },
+}
+
+/// Compare two schemas and produce a diff result. Tables and other objects
+/// are matched by schema-qualified name ([`SchemaInfo::qualify`]), so
+/// `sales.orders` and `archive.orders` stay apart while default schemas
+/// match across engines. Views, routines, triggers, sequences and
+/// user-defined types have their definitions compared with whitespace
+/// normalized.
+pub fn compare_schemas(source: &SchemaInfo, target: &SchemaInfo) -> SchemaDiffResult {
+    compare_schemas_with_mode(source, target, &ComparisonMode::Native)
+}
+
+/// [`compare_schemas`] with column types, defaults and index kinds compared
+/// according to `mode`.
+pub fn compare_schemas_with_mode(
+    source: &SchemaInfo,
+    target: &SchemaInfo,
+    mode: &ComparisonMode,
 ) -> SchemaDiffResult {
     let mut changes = Vec::new();
-    let mut summary = DiffSummary::default();
-
-    // Build lookup maps by table name
-    let source_map: HashMap<&str, &TableInfo> =
-        source_tables.iter().map(|t| (t.table_name.as_str(), t)).collect();
-    let target_map: HashMap<&str, &TableInfo> =
-        target_tables.iter().map(|t| (t.table_name.as_str(), t)).collect();
+    let table_key =
+        |schema: &SchemaInfo, t: &TableInfo| schema.qualify(&t.schema_name, &t.table_name);
+
+    // Build lookup maps by qualified table name
+    let source_map: HashMap<String, &TableInfo> =
+        source.tables.iter().map(|t| (table_key(source, t), t)).collect();
+    let target_map: HashMap<String, &TableInfo> =
+        target.tables.iter().map(|t| (table_key(target, t), t)).collect();
+
+    let source_only: Vec<(String, &TableInfo)> = source
+        .tables
+        .iter()
+        .map(|t| (table_key(source, t), t))
+        .filter(|(name, _)| !target_map.contains_key(name))
+        .collect();
+    let target_only: Vec<(String, &TableInfo)> = target
+        .tables
+        .iter()
+        .map(|t| (table_key(target, t), t))
+        .filter(|(name, _)| !source_map.contains_key(name))
+        .collect();
+    let renames = match_renames(&source_only, &target_only, TABLE_RENAME_THRESHOLD, |s, t| {
+        table_similarity(s.1, t.1)
+    });
 
     // Tables in source but not in target => Removed
-    for table in source_tables {
-        if !target_map.contains_key(table.table_name.as_str()) {
+    for (i, (name, _)) in source_only.iter().enumerate() {
+        if !renames.iter().any(|(r, _, _)| *r == i) {
             changes.push(SchemaChange {
                 object_type: SchemaObjectType::Table,
-                object_name: table.table_name.clone(),
+                object_name: name.clone(),
                 change_type: ChangeType::Removed,
                 details: vec![],
+                rename: None,
             });
-            summary.removals += 1;
         }
     }
 
     // Tables in target but not in source => Added
-    for table in target_tables {
-        if !source_map.contains_key(table.table_name.as_str()) {
+    for (j, (name, _)) in target_only.iter().enumerate() {
+        if !renames.iter().any(|(_, a, _)| *a == j) {
             changes.push(SchemaChange {
                 object_type: SchemaObjectType::Table,
-                object_name: table.table_name.clone(),
+                object_name: name.clone(),
                 change_type: ChangeType::Added,
                 details: vec![],
+                rename: None,
             });
-            summary.additions += 1;
         }
     }
 
+    // Structurally alike tables on either side => Renamed, internals compared
+    for (i, j, confidence) in renames {
+        let (old_name, source_table) = &source_only[i];
+        let (new_name, target_table) = &target_only[j];
+        changes.push(renamed(SchemaObjectType::Table, old_name, new_name, confidence));
+        changes.extend(compare_tables(new_name, source_table, target_table, mode));
+    }
+
     // Tables in both => compare internals
     for (name, source_table) in &source_map {
         if let Some(target_table) = target_map.get(name) {
-            let table_changes = compare_tables(source_table, target_table);
+            let table_changes = compare_tables(name, source_table, target_table, mode);
             if table_changes.is_empty() {
                 // Table is unchanged at the detail level
                 changes.push(SchemaChange {
@@ -112,38 +281,224 @@ pub fn compare_schemas(
                     object_name: name.to_string(),
                     change_type: ChangeType::Unchanged,
                     details: vec![],
+                    rename: None,
                 });
-                summary.unchanged += 1;
             } else {
                 // Merge sub-object changes (columns, indexes, constraints) into the top-level list
-                for change in &table_changes {
-                    match change.change_type {
-                        ChangeType::Added => summary.additions += 1,
-                        ChangeType::Removed => summary.removals += 1,
-                        ChangeType::Modified => summary.modifications += 1,
-                        ChangeType::Unchanged => summary.unchanged += 1,
-                    }
-                }
                 changes.extend(table_changes);
             }
         }
     }
 
+    changes.extend(compare_schema_objects(source, target));
+
     SchemaDiffResult {
-        source_database: source_db.to_string(),
-        target_database: target_db.to_string(),
+        source_database: source.database_name.clone(),
+        target_database: target.database_name.clone(),
+        summary: DiffSummary::tally(&changes),
         changes,
-        summary,
     }
 }
 
+/// A detected rename of `old_name` to `new_name`, awaiting confirmation.
+fn renamed(
+    object_type: SchemaObjectType,
+    old_name: &str,
+    new_name: &str,
+    confidence: f64,
+) -> SchemaChange {
+    SchemaChange {
+        object_type,
+        object_name: new_name.to_string(),
+        change_type: ChangeType::Renamed,
+        details: vec![ChangeDetail {
+            property: "name".to_string(),
+            source_value: Some(old_name.to_string()),
+            target_value: Some(new_name.to_string()),
+        }],
+        rename: Some(RenameInfo {
+            from: old_name.to_string(),
+            confidence: (confidence * 100.0).round() / 100.0,
+            confirmed: false,
+        }),
+    }
+}
+
+/// Compare the non-table objects of two schemas
+fn compare_schema_objects(source: &SchemaInfo, target: &SchemaInfo) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+
+    changes.extend(compare_objects(
+        SchemaObjectType::View,
+        (source, &source.views),
+        (target, &target.views),
+        |schema, v: &ViewInfo| schema.qualify(&v.schema_name, &v.name),
+        |v| vec![("definition", Some(normalize_definition(&v.definition)))],
+    ));
+
+    // Overloaded routines are told apart by their signatures
+    for (object_type, routine_type) in [
+        (SchemaObjectType::StoredProcedure, "PROCEDURE"),
+        (SchemaObjectType::Function, "FUNCTION"),
+    ] {
+        let pick = |routines: &[RoutineInfo]| -> Vec<RoutineInfo> {
+            routines
+                .iter()
+                .filter(|r| r.routine_type.eq_ignore_ascii_case(routine_type))
+                .cloned()
+                .collect()
+        };
+        let (src, tgt) = (pick(&source.routines), pick(&target.routines));
+        let overloaded = |name: &str| {
+            src.iter().filter(|r| r.name == name).count() > 1
+                || tgt.iter().filter(|r| r.name == name).count() > 1
+        };
+        changes.extend(compare_objects(
+            object_type,
+            (source, &src),
+            (target, &tgt),
+            |schema, r: &RoutineInfo| {
+                let name = schema.qualify(&r.schema_name, &r.name);
+                if overloaded(&r.name) {
+                    format!("{}({})", name, r.signature)
+                } else {
+                    name
+                }
+            },
+            |r| {
+                vec![
+                    ("signature", Some(normalize_definition(&r.signature))),
+                    ("return_type", r.return_type.as_deref().map(normalize_definition)),
+                    ("body", Some(normalize_definition(&r.body))),
+                ]
+            },
+        ));
+    }
+
+    changes.extend(compare_objects(
+        SchemaObjectType::Trigger,
+        (source, &source.triggers),
+        (target, &target.triggers),
+        |schema, t: &TriggerInfo| {
+            format!("{}.{}", schema.qualify(&t.schema_name, &t.table_name), t.name)
+        },
+        |t| {
+            vec![
+                ("timing", Some(t.timing.to_uppercase())),
+                ("event", Some(t.event.to_uppercase())),
+                ("definition", Some(normalize_definition(&t.definition))),
+            ]
+        },
+    ));
+
+    changes.extend(compare_objects(
+        SchemaObjectType::Sequence,
+        (source, &source.sequences),
+        (target, &target.sequences),
+        |schema, s: &SequenceInfo| schema.qualify(&s.schema_name, &s.name),
+        |s| {
+            vec![
+                ("data_type", Some(normalize_type(&s.data_type))),
+                ("start_value", Some(s.start_value.clone())),
+                ("increment", Some(s.increment.clone())),
+                ("min_value", Some(s.min_value.clone())),
+                ("max_value", Some(s.max_value.clone())),
+                ("cycle", Some(s.cycle.to_string())),
+            ]
+        },
+    ));
+
+    changes.extend(compare_objects(
+        SchemaObjectType::UserType,
+        (source, &source.user_types),
+        (target, &target.user_types),
+        |schema, t: &UserTypeInfo| schema.qualify(&t.schema_name, &t.name),
+        |t| {
+            vec![
+                ("kind", Some(t.kind.clone())),
+                ("definition", Some(normalize_definition(&t.definition))),
+            ]
+        },
+    ));
+
+    changes
+}
+
+/// Compare one kind of object between source and target, matched by `key`
+/// within the schema each side belongs to. `properties` lists the values
+/// compared, already normalized.
+fn compare_objects<T>(
+    object_type: SchemaObjectType,
+    (source_schema, source): (&SchemaInfo, &[T]),
+    (target_schema, target): (&SchemaInfo, &[T]),
+    key: impl Fn(&SchemaInfo, &T) -> String,
+    properties: impl Fn(&T) -> Vec<(&'static str, Option<String>)>,
+) -> Vec<SchemaChange> {
+    let source_map: BTreeMap<String, &T> =
+        source.iter().map(|o| (key(source_schema, o), o)).collect();
+    let target_map: BTreeMap<String, &T> =
+        target.iter().map(|o| (key(target_schema, o), o)).collect();
+    let change = |name: &String, change_type: ChangeType, details: Vec<ChangeDetail>| SchemaChange {
+        object_type: object_type.clone(),
+        object_name: name.clone(),
+        change_type,
+        details,
+        rename: None,
+    };
+    let mut changes = Vec::new();
+
+    // In source, not in target
+    for name in source_map.keys().filter(|k| !target_map.contains_key(*k)) {
+        changes.push(change(name, ChangeType::Removed, vec![]));
+    }
+
+    // In target, not in source
+    for name in target_map.keys().filter(|k| !source_map.contains_key(*k)) {
+        changes.push(change(name, ChangeType::Added, vec![]));
+    }
+
+    for (name, src) in &source_map {
+        let Some(tgt) = target_map.get(name) else {
+            continue;
+        };
+        let details: Vec<ChangeDetail> = properties(src)
+            .into_iter()
+            .zip(properties(tgt))
+            .filter(|((_, s), (_, t))| s != t)
+            .map(|((property, s), (_, t))| ChangeDetail {
+                property: property.to_string(),
+                source_value: s,
+                target_value: t,
+            })
+            .collect();
+        let change_type = if details.is_empty() {
+            ChangeType::Unchanged
+        } else {
+            ChangeType::Modified
+        };
+        changes.push(change(name, change_type, details));
+    }
+
+    changes
+}
+
+/// Definition text with every run of whitespace collapsed to one space,
+/// so reformatting alone is not a change.
+fn normalize_definition(definition: &str) -> String {
+    definition.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 /// Compare two matched tables and return changes for columns, indexes, and constraints
-fn compare_tables(source: &TableInfo, target: &TableInfo) -> Vec<SchemaChange> {
-    let table_name = &source.table_name;
+fn compare_tables(
+    table_name: &str,
+    source: &TableInfo,
+    target: &TableInfo,
+    mode: &ComparisonMode,
+) -> Vec<SchemaChange> {
     let mut changes = Vec::new();
 
-    changes.extend(compare_columns(table_name, &source.columns, &target.columns));
-    changes.extend(compare_indexes(table_name, &source.indexes, &target.indexes));
+    changes.extend(compare_columns(table_name, &source.columns, &target.columns, mode));
+    changes.extend(compare_indexes(table_name, &source.indexes, &target.indexes, mode));
     changes.extend(compare_constraints(
         table_name,
         &source.constraints,
@@ -158,6 +513,7 @@ fn compare_columns(
     table_name: &str,
     source_cols: &[ColumnInfo],
     target_cols: &[ColumnInfo],
+    mode: &ComparisonMode,
 ) -> Vec<SchemaChange> {
     let mut changes = Vec::new();
 
@@ -166,40 +522,68 @@ fn compare_columns(
     let target_map: HashMap<&str, &ColumnInfo> =
         target_cols.iter().map(|c| (c.name.as_str(), c)).collect();
 
+    let source_only: Vec<&ColumnInfo> = source_cols
+        .iter()
+        .filter(|c| !target_map.contains_key(c.name.as_str()))
+        .collect();
+    let target_only: Vec<&ColumnInfo> = target_cols
+        .iter()
+        .filter(|c| !source_map.contains_key(c.name.as_str()))
+        .collect();
+    let renames = match_renames(&source_only, &target_only, COLUMN_RENAME_THRESHOLD, |s, t| {
+        column_similarity(s, t)
+    });
+
     // Removed columns (in source, not in target)
-    for col in source_cols {
-        if !target_map.contains_key(col.name.as_str()) {
+    for (i, col) in source_only.iter().enumerate() {
+        if !renames.iter().any(|(r, _, _)| *r == i) {
             changes.push(SchemaChange {
                 object_type: SchemaObjectType::Column,
                 object_name: format!("{}.{}", table_name, col.name),
                 change_type: ChangeType::Removed,
                 details: vec![],
+                rename: None,
             });
         }
     }
 
     // Added columns (in target, not in source)
-    for col in target_cols {
-        if !source_map.contains_key(col.name.as_str()) {
+    for (j, col) in target_only.iter().enumerate() {
+        if !renames.iter().any(|(_, a, _)| *a == j) {
             changes.push(SchemaChange {
                 object_type: SchemaObjectType::Column,
                 object_name: format!("{}.{}", table_name, col.name),
                 change_type: ChangeType::Added,
                 details: vec![],
+                rename: None,
             });
         }
     }
 
+    // Renamed columns - the rename plus any property changes
+    for (i, j, confidence) in renames {
+        let (src_col, tgt_col) = (source_only[i], target_only[j]);
+        let mut change = renamed(
+            SchemaObjectType::Column,
+            &format!("{}.{}", table_name, src_col.name),
+            &format!("{}.{}", table_name, tgt_col.name),
+            confidence,
+        );
+        change.details.extend(diff_column_properties(src_col, tgt_col, mode));
+        changes.push(change);
+    }
+
     // Matched columns - compare properties
     for (name, src_col) in &source_map {
         if let Some(tgt_col) = target_map.get(name) {
-            let details = diff_column_properties(src_col, tgt_col);
+            let details = diff_column_properties(src_col, tgt_col, mode);
             if details.is_empty() {
                 changes.push(SchemaChange {
                     object_type: SchemaObjectType::Column,
                     object_name: format!("{}.{}", table_name, name),
                     change_type: ChangeType::Unchanged,
                     details: vec![],
+                    rename: None,
                 });
             } else {
                 changes.push(SchemaChange {
@@ -207,6 +591,7 @@ fn compare_columns(
                     object_name: format!("{}.{}", table_name, name),
                     change_type: ChangeType::Modified,
                     details,
+                    rename: None,
                 });
             }
         }
@@ -216,12 +601,27 @@ fn compare_columns(
 }
 
 /// Compare individual column properties and return change details
-fn diff_column_properties(source: &ColumnInfo, target: &ColumnInfo) -> Vec<ChangeDetail> {
+fn diff_column_properties(
+    source: &ColumnInfo,
+    target: &ColumnInfo,
+    mode: &ComparisonMode,
+) -> Vec<ChangeDetail> {
     let mut details = Vec::new();
 
     // Compare data types using canonical normalization
-    let src_canonical = normalize_type(&source.data_type);
-    let tgt_canonical = normalize_type(&target.data_type);
+    let (src_canonical, tgt_canonical) = match mode {
+        ComparisonMode::Native => (
+            normalize_type(&source.data_type),
+            normalize_type(&target.data_type),
+        ),
+        ComparisonMode::CrossEngine {
+            source: source_engine,
+            target: target_engine,
+        } => (
+            canonical_type(source_engine, target_engine, source),
+            canonical_type(target_engine, target_engine, target),
+        ),
+    };
     if src_canonical != tgt_canonical {
         details.push(ChangeDetail {
             property: "data_type".to_string(),
@@ -238,7 +638,11 @@ fn diff_column_properties(source: &ColumnInfo, target: &ColumnInfo) -> Vec<Chang
         });
     }
 
-    if source.max_length != target.max_length {
+    // Across engines sizes are part of the canonical type, and each engine
+    // reports them in its own units
+    let same_engine = matches!(mode, ComparisonMode::Native);
+
+    if same_engine && source.max_length != target.max_length {
         details.push(ChangeDetail {
             property: "max_length".to_string(),
             source_value: source.max_length.map(|v| v.to_string()),
@@ -246,7 +650,7 @@ fn diff_column_properties(source: &ColumnInfo, target: &ColumnInfo) -> Vec<Chang
         });
     }
 
-    if source.precision != target.precision {
+    if same_engine && source.precision != target.precision {
         details.push(ChangeDetail {
             property: "precision".to_string(),
             source_value: source.precision.map(|v| v.to_string()),
@@ -254,7 +658,7 @@ fn diff_column_properties(source: &ColumnInfo, target: &ColumnInfo) -> Vec<Chang
         });
     }
 
-    if source.scale != target.scale {
+    if same_engine && source.scale != target.scale {
         details.push(ChangeDetail {
             property: "scale".to_string(),
             source_value: source.scale.map(|v| v.to_string()),
@@ -262,7 +666,13 @@ fn diff_column_properties(source: &ColumnInfo, target: &ColumnInfo) -> Vec<Chang
         });
     }
 
-    if source.default_value != target.default_value {
+    let defaults_differ = if same_engine {
+        source.default_value != target.default_value
+    } else {
+        source.default_value.as_deref().map(canonical_default)
+            != target.default_value.as_deref().map(canonical_default)
+    };
+    if defaults_differ {
         details.push(ChangeDetail {
             property: "default_value".to_string(),
             source_value: source.default_value.clone(),
@@ -275,17 +685,105 @@ fn diff_column_properties(source: &ColumnInfo, target: &ColumnInfo) -> Vec<Chang
 
 /// Normalize a type string for comparison purposes.
 /// Uses a lowercased, trimmed form so that "INT" == "int" == " int ".
-/// This handles the simple case; for cross-engine comparison,
-/// the caller should use type_mapper::to_canonical.
-fn normalize_type(type_str: &str) -> String {
+/// This handles the simple case; cross-engine comparison goes through
+/// [`canonical_type`] instead (see [`ComparisonMode::CrossEngine`]).
+pub(crate) fn normalize_type(type_str: &str) -> String {
     type_str.trim().to_lowercase()
 }
 
+/// The type `column` of `engine` takes on in `target_engine`, so that
+/// `nvarchar(50)` and `character varying(50)` compare equal.
+fn canonical_type(
+    engine: &DatabaseEngine,
+    target_engine: &DatabaseEngine,
+    column: &ColumnInfo,
+) -> String {
+    let canonical = to_canonical(engine, &native_type_with_params(column));
+    normalize_type(&from_canonical(target_engine, &canonical))
+}
+
+/// A column default with engine spelling removed: redundant parentheses,
+/// casts, `N` prefixes and quotes around numbers, and the engines' names
+/// for the current time, new UUIDs and boolean literals.
+fn canonical_default(default: &str) -> String {
+    let mut value = default.trim();
+    while value.starts_with('(') && value.ends_with(')') && encloses(value) {
+        value = value[1..value.len() - 1].trim();
+    }
+    // PostgreSQL casts of the whole value: 'abc'::character varying
+    let value = match value.rfind("::") {
+        Some(i) if !value[i..].contains('\'') && balanced(&value[..i]) => value[..i].trim(),
+        _ => value,
+    };
+    let value = value
+        .strip_prefix(['N', 'n'])
+        .filter(|v| v.starts_with('\''))
+        .unwrap_or(value);
+    if let Some(unquoted) = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .filter(|v| v.parse::<f64>().is_ok())
+    {
+        return unquoted.to_string();
+    }
+    if value.starts_with('\'') {
+        return value.to_string();
+    }
+
+    let lower = value.to_lowercase();
+    match lower.as_str() {
+        "getdate()" | "sysdatetime()" | "current_timestamp" | "current_timestamp()" | "now()"
+        | "localtimestamp" | "transaction_timestamp()" | "sysdate" | "systimestamp"
+        | "datetime('now')" => "current_timestamp".to_string(),
+        "newid()" | "newsequentialid()" | "gen_random_uuid()" | "uuid_generate_v4()"
+        | "uuid()" => "new_uuid".to_string(),
+        "true" => "1".to_string(),
+        "false" => "0".to_string(),
+        _ => lower,
+    }
+}
+
+fn balanced(value: &str) -> bool {
+    value.matches('(').count() == value.matches(')').count()
+}
+
+/// Whether the opening parenthesis of `value` closes at its very end.
+fn encloses(value: &str) -> bool {
+    let mut depth = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return i == value.len() - 1;
+        }
+    }
+    false
+}
+
+/// The engine-neutral kind of an index: SQL Server's (non)clustered and
+/// Oracle's normal indexes are B-trees like everyone else's.
+fn canonical_index_kind(index_type: &str) -> String {
+    let upper = index_type.trim().to_uppercase();
+    match upper.as_str() {
+        "" | "CLUSTERED" | "NONCLUSTERED" | "NORMAL" | "FUNCTION-BASED NORMAL" | "IOT - TOP"
+        | "INDEX" => "BTREE".to_string(),
+        _ => upper
+            .strip_prefix("NONCLUSTERED ")
+            .or_else(|| upper.strip_prefix("CLUSTERED "))
+            .unwrap_or(&upper)
+            .to_string(),
+    }
+}
+
 /// Compare indexes between source and target tables
 fn compare_indexes(
     table_name: &str,
     source_idxs: &[IndexInfo],
     target_idxs: &[IndexInfo],
+    mode: &ComparisonMode,
 ) -> Vec<SchemaChange> {
     let mut changes = Vec::new();
 
@@ -302,6 +800,7 @@ fn compare_indexes(
                 object_name: format!("{}.{}", table_name, idx.name),
                 change_type: ChangeType::Removed,
                 details: vec![],
+                rename: None,
             });
         }
     }
@@ -314,6 +813,7 @@ fn compare_indexes(
                 object_name: format!("{}.{}", table_name, idx.name),
                 change_type: ChangeType::Added,
                 details: vec![],
+                rename: None,
             });
         }
     }
@@ -321,13 +821,14 @@ fn compare_indexes(
     // Matched indexes - compare properties
     for (name, src_idx) in &source_map {
         if let Some(tgt_idx) = target_map.get(name) {
-            let details = diff_index_properties(src_idx, tgt_idx);
+            let details = diff_index_properties(src_idx, tgt_idx, mode);
             if details.is_empty() {
                 changes.push(SchemaChange {
                     object_type: SchemaObjectType::Index,
                     object_name: format!("{}.{}", table_name, name),
                     change_type: ChangeType::Unchanged,
                     details: vec![],
+                    rename: None,
                 });
             } else {
                 changes.push(SchemaChange {
@@ -335,6 +836,7 @@ fn compare_indexes(
                     object_name: format!("{}.{}", table_name, name),
                     change_type: ChangeType::Modified,
                     details,
+                    rename: None,
                 });
             }
         }
@@ -344,7 +846,11 @@ fn compare_indexes(
 }
 
 /// Compare individual index properties
-fn diff_index_properties(source: &IndexInfo, target: &IndexInfo) -> Vec<ChangeDetail> {
+fn diff_index_properties(
+    source: &IndexInfo,
+    target: &IndexInfo,
+    mode: &ComparisonMode,
+) -> Vec<ChangeDetail> {
     let mut details = Vec::new();
 
     if source.columns != target.columns {
@@ -363,12 +869,28 @@ fn diff_index_properties(source: &IndexInfo, target: &IndexInfo) -> Vec<ChangeDe
         });
     }
 
-    if source.is_clustered != target.is_clustered {
-        details.push(ChangeDetail {
-            property: "is_clustered".to_string(),
-            source_value: Some(source.is_clustered.to_string()),
-            target_value: Some(target.is_clustered.to_string()),
-        });
+    match mode {
+        ComparisonMode::Native => {
+            if source.is_clustered != target.is_clustered {
+                details.push(ChangeDetail {
+                    property: "is_clustered".to_string(),
+                    source_value: Some(source.is_clustered.to_string()),
+                    target_value: Some(target.is_clustered.to_string()),
+                });
+            }
+        }
+        // Clustering is storage layout most engines cannot express; the
+        // kind of index is what carries over
+        ComparisonMode::CrossEngine { .. } => {
+            if canonical_index_kind(&source.index_type) != canonical_index_kind(&target.index_type)
+            {
+                details.push(ChangeDetail {
+                    property: "index_type".to_string(),
+                    source_value: Some(source.index_type.clone()),
+                    target_value: Some(target.index_type.clone()),
+                });
+            }
+        }
     }
 
     details
@@ -395,6 +917,7 @@ fn compare_constraints(
                 object_name: format!("{}.{}", table_name, con.name),
                 change_type: ChangeType::Removed,
                 details: vec![],
+                rename: None,
             });
         }
     }
@@ -407,6 +930,7 @@ fn compare_constraints(
                 object_name: format!("{}.{}", table_name, con.name),
                 change_type: ChangeType::Added,
                 details: vec![],
+                rename: None,
             });
         }
     }
@@ -421,6 +945,7 @@ fn compare_constraints(
                     object_name: format!("{}.{}", table_name, name),
                     change_type: ChangeType::Unchanged,
                     details: vec![],
+                    rename: None,
                 });
             } else {
                 changes.push(SchemaChange {
@@ -428,6 +953,7 @@ fn compare_constraints(
                     object_name: format!("{}.{}", table_name, name),
                     change_type: ChangeType::Modified,
                     details,
+                    rename: None,
                 });
             }
         }
@@ -520,6 +1046,27 @@ mod tests {
         }
     }
 
+    /// Helper to build a SchemaInfo holding only tables, with SQL Server's
+    /// default schema like the `table` and `routine` helpers
+    fn schema(name: &str, tables: &[TableInfo]) -> SchemaInfo {
+        SchemaInfo {
+            default_schema: Some("dbo".to_string()),
+            ..SchemaInfo::with_tables(name.to_string(), tables.to_vec())
+        }
+    }
+
+    /// Helper to build a RoutineInfo
+    fn routine(name: &str, routine_type: &str, signature: &str, body: &str) -> RoutineInfo {
+        RoutineInfo {
+            schema_name: "dbo".to_string(),
+            name: name.to_string(),
+            routine_type: routine_type.to_string(),
+            signature: signature.to_string(),
+            return_type: None,
+            body: body.to_string(),
+        }
+    }
+
     /// Helper to build a TableInfo
     fn table(
         name: &str,
@@ -534,6 +1081,7 @@ mod tests {
             indexes,
             constraints,
             row_count: None,
+            field_stats: Vec::new(),
         }
     }
 
@@ -547,7 +1095,7 @@ mod tests {
         )];
         let target = source.clone();
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         assert_eq!(result.summary.additions, 0);
         assert_eq!(result.summary.removals, 0);
         assert_eq!(result.summary.modifications, 0);
@@ -563,7 +1111,7 @@ mod tests {
             table("orders", vec![col("id", "int", false, true)], vec![], vec![]),
         ];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         assert_eq!(result.summary.additions, 1);
         let added: Vec<_> = result
             .changes
@@ -582,7 +1130,7 @@ mod tests {
         ];
         let target = vec![table("users", vec![col("id", "int", false, true)], vec![], vec![])];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         assert_eq!(result.summary.removals, 1);
         let removed: Vec<_> = result
             .changes
@@ -608,7 +1156,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let added_cols: Vec<_> = result
             .changes
             .iter()
@@ -633,7 +1181,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let removed_cols: Vec<_> = result
             .changes
             .iter()
@@ -658,7 +1206,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -684,7 +1232,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -709,7 +1257,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         // Should be considered unchanged since INT == int after normalization
         let modified: Vec<_> = result
             .changes
@@ -734,7 +1282,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let added: Vec<_> = result
             .changes
             .iter()
@@ -759,7 +1307,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let removed: Vec<_> = result
             .changes
             .iter()
@@ -783,7 +1331,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -808,7 +1356,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -828,7 +1376,7 @@ mod tests {
             vec![constraint("pk_users", ConstraintType::PrimaryKey, &["id"])],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let added: Vec<_> = result
             .changes
             .iter()
@@ -853,7 +1401,7 @@ mod tests {
             vec![constraint("uc_email", ConstraintType::Check, &["email"])],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -885,7 +1433,7 @@ mod tests {
             )],
         )];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -897,7 +1445,7 @@ mod tests {
 
     #[test]
     fn test_empty_schemas() {
-        let result = compare_schemas(&[], &[], "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &[]), &schema("tgt_db", &[]));
         assert_eq!(result.summary.additions, 0);
         assert_eq!(result.summary.removals, 0);
         assert_eq!(result.summary.modifications, 0);
@@ -948,7 +1496,7 @@ mod tests {
             ),
         ];
 
-        let result = compare_schemas(&source, &target, "src_db", "tgt_db");
+        let result = compare_schemas(&schema("src_db", &source), &schema("tgt_db", &target));
 
         // Should have: 1 removed table (legacy), 1 added table (products),
         //   1 removed column (old_field), 1 added column (email), 1 modified column (id type),
@@ -973,7 +1521,7 @@ mod tests {
             vec![],
         )];
 
-        let result = compare_schemas(&source, &target, "src", "tgt");
+        let result = compare_schemas(&schema("src", &source), &schema("tgt", &target));
         // col a: modified (int->bigint), col b: removed, col c: added
         assert_eq!(result.summary.additions, 1);
         assert_eq!(result.summary.removals, 1);
@@ -993,7 +1541,7 @@ mod tests {
         let source = vec![table("orders", vec![], vec![], vec![src_con])];
         let target = vec![table("orders", vec![], vec![], vec![tgt_con])];
 
-        let result = compare_schemas(&source, &target, "src", "tgt");
+        let result = compare_schemas(&schema("src", &source), &schema("tgt", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -1017,7 +1565,7 @@ mod tests {
         let source = vec![table("users", vec![src_col], vec![], vec![])];
         let target = vec![table("users", vec![tgt_col], vec![], vec![])];
 
-        let result = compare_schemas(&source, &target, "src", "tgt");
+        let result = compare_schemas(&schema("src", &source), &schema("tgt", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -1043,7 +1591,7 @@ mod tests {
         let source = vec![table("payments", vec![src_col], vec![], vec![])];
         let target = vec![table("payments", vec![tgt_col], vec![], vec![])];
 
-        let result = compare_schemas(&source, &target, "src", "tgt");
+        let result = compare_schemas(&schema("src", &source), &schema("tgt", &target));
         let modified: Vec<_> = result
             .changes
             .iter()
@@ -1054,4 +1602,372 @@ mod tests {
         assert!(props.contains(&"precision"));
         assert!(props.contains(&"scale"));
     }
+
+    #[test]
+    fn test_tables_matched_by_schema() {
+        let in_schema = |schema_name: &str, name: &str| TableInfo {
+            schema_name: schema_name.to_string(),
+            ..table(name, vec![col("id", "int", false, true)], vec![], vec![])
+        };
+        let source = vec![in_schema("dbo", "customers"), in_schema("sales", "orders")];
+        let target = vec![in_schema("public", "customers"), in_schema("archive", "orders")];
+
+        let postgres = SchemaInfo {
+            default_schema: Some("public".to_string()),
+            ..schema("tgt", &target)
+        };
+        let result = compare_schemas(&schema("src", &source), &postgres);
+        let changes: Vec<_> = result
+            .changes
+            .iter()
+            .map(|c| (c.object_name.as_str(), c.change_type.clone()))
+            .collect();
+        // Default schemas match across engines; other schemas stay apart
+        assert_eq!(
+            changes,
+            vec![
+                ("sales.orders", ChangeType::Removed),
+                ("archive.orders", ChangeType::Added),
+                ("customers.id", ChangeType::Unchanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_default_dbo_schema_stays_apart_on_postgres() {
+        let in_schema = |schema_name: &str| TableInfo {
+            schema_name: schema_name.to_string(),
+            ..table("orders", vec![col("id", "int", false, true)], vec![], vec![])
+        };
+        let postgres = |tables: &[TableInfo]| SchemaInfo {
+            default_schema: Some("public".to_string()),
+            ..schema("shop", tables)
+        };
+        let source = postgres(&[in_schema("dbo"), in_schema("public")]);
+        let target = postgres(&[in_schema("public")]);
+
+        let result = compare_schemas(&source, &target);
+        let changes: Vec<_> = result
+            .changes
+            .iter()
+            .map(|c| (c.object_name.as_str(), c.change_type.clone()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("dbo.orders", ChangeType::Removed),
+                ("orders.id", ChangeType::Unchanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_view_changes() {
+        let view = |name: &str, definition: &str| ViewInfo {
+            schema_name: "dbo".to_string(),
+            name: name.to_string(),
+            definition: definition.to_string(),
+        };
+        let mut source = schema("src", &[]);
+        source.views = vec![
+            view("active_users", "SELECT id FROM users WHERE active = 1"),
+            view("old_report", "SELECT 1"),
+            view("totals", "SELECT SUM(amount) FROM orders"),
+        ];
+        let mut target = schema("tgt", &[]);
+        target.views = vec![
+            view("active_users", "SELECT  id\n  FROM users\n WHERE active = 1"),
+            view("new_report", "SELECT 2"),
+            view("totals", "SELECT SUM(total) FROM orders"),
+        ];
+
+        let result = compare_schemas(&source, &target);
+        let change = |name: &str| {
+            result
+                .changes
+                .iter()
+                .find(|c| c.object_type == SchemaObjectType::View && c.object_name == name)
+                .unwrap()
+        };
+        // Reformatting alone is not a change
+        assert_eq!(change("active_users").change_type, ChangeType::Unchanged);
+        assert_eq!(change("old_report").change_type, ChangeType::Removed);
+        assert_eq!(change("new_report").change_type, ChangeType::Added);
+        let totals = change("totals");
+        assert_eq!(totals.change_type, ChangeType::Modified);
+        assert_eq!(totals.details[0].property, "definition");
+        assert_eq!(result.summary.additions, 1);
+        assert_eq!(result.summary.removals, 1);
+        assert_eq!(result.summary.modifications, 1);
+    }
+
+    #[test]
+    fn test_routine_changes() {
+        let mut source = schema("src", &[]);
+        source.routines = vec![
+            routine("archive", "PROCEDURE", "@days int", "BEGIN\n  DELETE old;\nEND"),
+            routine("price", "FUNCTION", "@id int", "RETURN 1"),
+            routine("price", "FUNCTION", "@id int, @qty int", "RETURN 2"),
+        ];
+        let mut target = schema("tgt", &[]);
+        target.routines = vec![
+            routine("archive", "PROCEDURE", "@days bigint", "BEGIN DELETE old; END"),
+            routine("price", "FUNCTION", "@id int", "RETURN 1"),
+        ];
+
+        let result = compare_schemas(&source, &target);
+        let archive = result
+            .changes
+            .iter()
+            .find(|c| c.object_type == SchemaObjectType::StoredProcedure)
+            .unwrap();
+        assert_eq!(archive.change_type, ChangeType::Modified);
+        let props: Vec<_> = archive.details.iter().map(|d| d.property.as_str()).collect();
+        assert_eq!(props, vec!["signature"]);
+
+        // Overloads are keyed by signature
+        let functions: Vec<_> = result
+            .changes
+            .iter()
+            .filter(|c| c.object_type == SchemaObjectType::Function)
+            .map(|c| (c.object_name.as_str(), c.change_type.clone()))
+            .collect();
+        assert_eq!(
+            functions,
+            vec![
+                ("price(@id int, @qty int)", ChangeType::Removed),
+                ("price(@id int)", ChangeType::Unchanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_definition() {
+        assert_eq!(
+            normalize_definition("  SELECT a,\n\tb  FROM t\r\n"),
+            "SELECT a, b FROM t"
+        );
+    }
+
+    /// Source and target schemas where `customers` became `clients` and
+    /// its `cust_name` column became `client_name`
+    fn renamed_schemas() -> (SchemaInfo, SchemaInfo) {
+        let source = vec![table(
+            "customers",
+            vec![
+                col("id", "int", false, true),
+                col("cust_name", "varchar", true, false),
+                col("email", "varchar", true, false),
+                col("created", "datetime", true, false),
+            ],
+            vec![idx("idx_email", &["email"], true)],
+            vec![],
+        )];
+        let target = vec![table(
+            "clients",
+            vec![
+                col("id", "int", false, true),
+                col("client_name", "varchar", true, false),
+                col("email", "varchar", true, false),
+                col("created", "datetime", true, false),
+            ],
+            vec![idx("idx_email", &["email"], true)],
+            vec![],
+        )];
+        (schema("src", &source), schema("tgt", &target))
+    }
+
+    #[test]
+    fn test_renames_detected() {
+        let (source, target) = renamed_schemas();
+        let result = compare_schemas(&source, &target);
+
+        let table_rename = result
+            .changes
+            .iter()
+            .find(|c| c.object_type == SchemaObjectType::Table)
+            .unwrap();
+        assert_eq!(table_rename.change_type, ChangeType::Renamed);
+        assert_eq!(table_rename.object_name, "clients");
+        let rename = table_rename.rename.as_ref().unwrap();
+        assert_eq!(rename.from, "customers");
+        assert!(rename.confidence >= TABLE_RENAME_THRESHOLD && rename.confidence < 1.0);
+        assert!(!rename.confirmed);
+
+        let column_rename = result
+            .changes
+            .iter()
+            .find(|c| c.object_name == "clients.client_name")
+            .unwrap();
+        assert_eq!(column_rename.change_type, ChangeType::Renamed);
+        assert_eq!(column_rename.rename.as_ref().unwrap().from, "clients.cust_name");
+        assert_eq!(column_rename.details.len(), 1);
+        assert_eq!(column_rename.details[0].property, "name");
+
+        assert_eq!(result.summary.renames, 2);
+        assert_eq!(result.summary.additions, 0);
+        assert_eq!(result.summary.removals, 0);
+        assert_eq!(result.pending_renames().count(), 2);
+    }
+
+    #[test]
+    fn test_confirm_rename() {
+        let (source, target) = renamed_schemas();
+        let mut result = compare_schemas(&source, &target);
+
+        assert!(result.confirm_rename("clients"));
+        assert!(!result.confirm_rename("customers"));
+        let names: Vec<_> = result.pending_renames().map(|c| c.object_name.as_str()).collect();
+        assert_eq!(names, vec!["clients.client_name"]);
+        assert_eq!(result.summary.renames, 2);
+    }
+
+    #[test]
+    fn test_reject_rename() {
+        let (source, target) = renamed_schemas();
+        let mut result = compare_schemas(&source, &target);
+
+        assert!(result.reject_rename("clients"));
+        assert!(!result.reject_rename("clients"));
+        let changes: Vec<_> = result
+            .changes
+            .iter()
+            .map(|c| (c.object_name.as_str(), c.change_type.clone()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("customers", ChangeType::Removed),
+                ("clients", ChangeType::Added),
+            ]
+        );
+        assert_eq!(result.summary.renames, 0);
+        assert_eq!(result.summary.additions, 1);
+        assert_eq!(result.summary.removals, 1);
+    }
+
+    #[test]
+    fn test_reject_column_rename() {
+        let (source, target) = renamed_schemas();
+        let mut result = compare_schemas(&source, &target);
+
+        assert!(result.reject_rename("clients.client_name"));
+        let column_changes: Vec<_> = result
+            .changes
+            .iter()
+            .filter(|c| c.object_type == SchemaObjectType::Column)
+            .filter(|c| c.change_type != ChangeType::Unchanged)
+            .map(|c| (c.object_name.as_str(), c.change_type.clone()))
+            .collect();
+        assert_eq!(
+            column_changes,
+            vec![
+                ("clients.cust_name", ChangeType::Removed),
+                ("clients.client_name", ChangeType::Added),
+            ]
+        );
+        assert_eq!(result.summary.renames, 1);
+    }
+
+    #[test]
+    fn test_diff_round_trips_as_camel_case_json() {
+        let (source, target) = renamed_schemas();
+        let result = compare_schemas(&source, &target);
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["sourceDatabase"], "src");
+        let change = &json["changes"][0];
+        assert_eq!(change["objectName"], "clients");
+        assert_eq!(change["changeType"], "Renamed");
+        assert_eq!(change["rename"]["from"], "customers");
+        assert_eq!(change["details"][0]["sourceValue"], "customers");
+        assert_eq!(json["summary"]["renames"], 2);
+
+        let mut back: SchemaDiffResult = serde_json::from_value(json).unwrap();
+        assert!(back.confirm_rename("clients"));
+    }
+
+    #[test]
+    fn test_cross_engine_comparison() {
+        let column =
+            |name: &str, data_type: &str, max_length: Option<i32>, default: &str| ColumnInfo {
+                max_length,
+                default_value: Some(default.to_string()),
+                ..col(name, data_type, true, false)
+            };
+        let index = |index_type: &str, clustered: bool| IndexInfo {
+            index_type: index_type.to_string(),
+            is_clustered: clustered,
+            ..idx("idx_orders", &["id"], true)
+        };
+        let source = vec![table(
+            "orders",
+            vec![
+                column("id", "int", None, "((0))"),
+                column("name", "nvarchar", Some(50), "N'none'"),
+                column("created", "datetime2", None, "(getdate())"),
+                column("code", "varchar", Some(10), "('x')"),
+            ],
+            vec![index("CLUSTERED", true)],
+            vec![],
+        )];
+        let target = vec![table(
+            "orders",
+            vec![
+                column("id", "integer", None, "'0'::integer"),
+                column("name", "character varying", Some(50), "'none'::character varying"),
+                column("created", "timestamp without time zone", None, "now()"),
+                column("code", "character varying", Some(20), "'y'::character varying"),
+            ],
+            vec![index("BTREE", false)],
+            vec![],
+        )];
+        let (source, target) = (schema("src", &source), schema("tgt", &target));
+
+        let native = compare_schemas(&source, &target);
+        assert!(native.summary.modifications >= 5);
+
+        let mode = ComparisonMode::CrossEngine {
+            source: DatabaseEngine::SqlServer,
+            target: DatabaseEngine::PostgreSql,
+        };
+        let result = compare_schemas_with_mode(&source, &target, &mode);
+        let modified: Vec<_> = result
+            .changes
+            .iter()
+            .filter(|c| c.change_type == ChangeType::Modified)
+            .map(|c| {
+                let props: Vec<_> = c.details.iter().map(|d| d.property.as_str()).collect();
+                (c.object_name.as_str(), props)
+            })
+            .collect();
+        assert_eq!(
+            modified,
+            vec![("orders.code", vec!["data_type", "default_value"])]
+        );
+    }
+
+    #[test]
+    fn test_canonical_default() {
+        assert_eq!(canonical_default("((0))"), "0");
+        assert_eq!(canonical_default("'0'::integer"), "0");
+        assert_eq!(canonical_default("(getdate())"), canonical_default("now()"));
+        assert_eq!(canonical_default("CURRENT_TIMESTAMP"), "current_timestamp");
+        assert_eq!(canonical_default("(newid())"), canonical_default("gen_random_uuid()"));
+        assert_eq!(canonical_default("false"), canonical_default("((0))"));
+        assert_eq!(canonical_default("N'Abc'"), "'Abc'");
+        assert_eq!(
+            canonical_default("nextval('users_id_seq'::regclass)"),
+            "nextval('users_id_seq'::regclass)"
+        );
+        assert_ne!(canonical_default("'true'"), canonical_default("true"));
+    }
+
+    #[test]
+    fn test_canonical_index_kind() {
+        assert_eq!(canonical_index_kind("CLUSTERED"), "BTREE");
+        assert_eq!(canonical_index_kind("NORMAL"), "BTREE");
+        assert_eq!(canonical_index_kind("btree"), "BTREE");
+        assert_eq!(canonical_index_kind("NONCLUSTERED COLUMNSTORE"), "COLUMNSTORE");
+        assert_eq!(canonical_index_kind("HASH"), "HASH");
+    }
 }
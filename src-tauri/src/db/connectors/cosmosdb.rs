@@ -1,85 +1,839 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
-use crate::db::schema::{Row, SchemaInfo, TableInfo};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::schema::{ColumnInfo, IndexInfo, Row, SchemaInfo, TableInfo};
+use crate::db::typed_value;
+use anyhow::{anyhow, bail, Context};
 use async_trait::async_trait;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{Client, Method, Response};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Mutex;
 
-/// CosmosDB connector - STUB IMPLEMENTATION
-/// All database methods have todo!() bodies.
-/// The interface is defined to match the DatabaseConnector trait so the
-/// project compiles and CosmosDB support can be added later.
+const API_VERSION: &str = "2018-12-31";
+const EMULATOR_PORT: u16 = 8081;
+/// Largest page the service will return in one response.
+const MAX_PAGE_SIZE: u64 = 1000;
+/// Properties the service adds to every document; hidden from rows.
+const SYSTEM_PROPERTIES: [&str; 5] = ["_rid", "_self", "_etag", "_attachments", "_ts"];
+
+/// Characters left as-is by JavaScript's `encodeURIComponent`, which is the
+/// encoding the service expects for the authorization token.
+const URI_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// Azure Cosmos DB connector over the SQL (Core) REST API.
+///
+/// Containers are exposed as tables. With a database configured they are
+/// addressed by name; otherwise as `database/container`. Like MongoDB there
+/// is no fixed schema, so columns are inferred from sampled documents, and
+/// `execute_query` takes a JSON command:
+///
+/// - `{"query": "SELECT ...", "container": "orders", "parameters": [...]}`
+/// - `{"upsert": "orders", "documents": [{...}]}`
+/// - `{"delete": "orders", "id": "42", "partitionKey": "eu"}`
 pub struct CosmosDbConnector {
-    #[allow(dead_code)]
     config: ConnectionConfig,
+    client: Option<Client>,
+    endpoint: String,
+    key: Vec<u8>,
+    /// Partition key paths per container, e.g. `["/region"]`
+    partition_keys: Mutex<HashMap<String, Vec<String>>>,
+    /// Continuation token that resumes a container feed at a given offset
+    continuations: Mutex<HashMap<(String, u64), String>>,
 }
 
 impl CosmosDbConnector {
     pub fn new(config: ConnectionConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            client: None,
+            endpoint: String::new(),
+            key: Vec::new(),
+            partition_keys: Mutex::new(HashMap::new()),
+            continuations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolve the account endpoint and base64 master key from config.
+    ///
+    /// Accepts a standard `AccountEndpoint=...;AccountKey=...;` connection
+    /// string, or `host` (URL or host name), `port` and `password` (the key).
+    fn endpoint_and_key(&self) -> anyhow::Result<(String, String)> {
+        if let Some(ref conn_str) = self.config.connection_string {
+            let mut endpoint = None;
+            let mut key = None;
+            for part in conn_str.split(';').filter(|p| !p.trim().is_empty()) {
+                let (name, value) = part
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("Invalid connection string segment '{}'", part))?;
+                match name.trim().to_lowercase().as_str() {
+                    "accountendpoint" => endpoint = Some(value.trim().to_string()),
+                    "accountkey" => key = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+            let endpoint =
+                endpoint.ok_or_else(|| anyhow!("Connection string is missing AccountEndpoint"))?;
+            let key = key
+                .or_else(|| self.config.password.clone())
+                .ok_or_else(|| anyhow!("Connection string is missing AccountKey"))?;
+            return Ok((endpoint.trim_end_matches('/').to_string(), key));
+        }
+
+        let host = self.config.host.as_deref().unwrap_or("localhost");
+        let endpoint = if host.starts_with("http://") || host.starts_with("https://") {
+            host.trim_end_matches('/').to_string()
+        } else {
+            format!(
+                "https://{}:{}",
+                host,
+                self.config.port.unwrap_or(EMULATOR_PORT)
+            )
+        };
+        let key = self
+            .config
+            .password
+            .clone()
+            .ok_or_else(|| anyhow!("A Cosmos DB account key is required"))?;
+        Ok((endpoint, key))
+    }
+
+    /// The emulator serves a self-signed certificate on localhost.
+    fn is_emulator(endpoint: &str) -> bool {
+        let host = endpoint
+            .split("://")
+            .nth(1)
+            .unwrap_or(endpoint)
+            .split([':', '/'])
+            .next()
+            .unwrap_or("");
+        matches!(host, "localhost" | "127.0.0.1")
+    }
+
+    fn client(&self) -> anyhow::Result<&Client> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| anyhow!("Not connected to Cosmos DB"))
+    }
+
+    /// Split a table name into database and container.
+    fn resolve(&self, table_name: &str) -> anyhow::Result<(String, String)> {
+        match table_name.split_once('/') {
+            Some((db, coll)) => Ok((db.to_string(), coll.to_string())),
+            None => {
+                let db = self
+                    .config
+                    .database
+                    .clone()
+                    .ok_or_else(|| anyhow!("No database configured for container '{}'", table_name))?;
+                Ok((db, table_name.to_string()))
+            }
+        }
+    }
+
+    /// Send an authenticated request. `resource_link` is the unencoded id
+    /// path the signature covers; the URL path is derived from it.
+    async fn request(
+        &self,
+        method: Method,
+        url_path: &str,
+        resource_type: &str,
+        resource_link: &str,
+        headers: &[(&str, String)],
+        body: Option<String>,
+    ) -> anyhow::Result<Response> {
+        let client = self.client()?;
+        let date = chrono::Utc::now()
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let auth = auth_token(&self.key, method.as_str(), resource_type, resource_link, &date);
+
+        let mut req = client
+            .request(method, format!("{}/{}", self.endpoint, url_path))
+            .header("authorization", auth)
+            .header("x-ms-date", date)
+            .header("x-ms-version", API_VERSION);
+        for (name, value) in headers {
+            req = req.header(*name, value);
+        }
+        if let Some(body) = body {
+            req = req.body(body);
+        }
+
+        let resp = req.send().await.context("Cosmos DB request failed")?;
+        if resp.status().is_success() {
+            return Ok(resp);
+        }
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<Value>(&text)
+            .ok()
+            .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+            .unwrap_or(text);
+        bail!("Cosmos DB returned {}: {}", status, message)
+    }
+
+    async fn list_databases(&self) -> anyhow::Result<Vec<String>> {
+        let body: Value = self
+            .request(Method::GET, "dbs", "dbs", "", &[], None)
+            .await?
+            .json()
+            .await?;
+        Ok(ids(&body, "Databases"))
+    }
+
+    async fn list_containers(&self, db: &str) -> anyhow::Result<Vec<String>> {
+        let link = format!("dbs/{}", db);
+        let body: Value = self
+            .request(Method::GET, &encode_path(&link), "colls", &link, &[], None)
+            .await?
+            .json()
+            .await?;
+        Ok(ids(&body, "DocumentCollections"))
+    }
+
+    /// Partition key paths of a container, cached after the first lookup.
+    async fn partition_key_paths(&self, table_name: &str) -> anyhow::Result<Vec<String>> {
+        if let Some(paths) = self.partition_keys.lock().unwrap().get(table_name) {
+            return Ok(paths.clone());
+        }
+        let (db, coll) = self.resolve(table_name)?;
+        let link = format!("dbs/{}/colls/{}", db, coll);
+        let body: Value = self
+            .request(Method::GET, &encode_path(&link), "colls", &link, &[], None)
+            .await?
+            .json()
+            .await?;
+        let paths: Vec<String> = body["partitionKey"]["paths"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|p| p.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        self.partition_keys
+            .lock()
+            .unwrap()
+            .insert(table_name.to_string(), paths.clone());
+        Ok(paths)
+    }
+
+    /// Fetch one page of a container's document feed.
+    async fn read_page(
+        &self,
+        table_name: &str,
+        max_items: u64,
+        continuation: Option<&str>,
+    ) -> anyhow::Result<(Vec<Value>, Option<String>)> {
+        let (db, coll) = self.resolve(table_name)?;
+        let link = format!("dbs/{}/colls/{}", db, coll);
+        let mut headers = vec![("x-ms-max-item-count", max_items.to_string())];
+        if let Some(token) = continuation {
+            headers.push(("x-ms-continuation", token.to_string()));
+        }
+        let resp = self
+            .request(
+                Method::GET,
+                &format!("{}/docs", encode_path(&link)),
+                "docs",
+                &link,
+                &headers,
+                None,
+            )
+            .await?;
+        let next = continuation_header(&resp);
+        let body: Value = resp.json().await?;
+        Ok((documents(&body), next))
+    }
+
+    /// Run a SQL query, following continuations until exhausted.
+    async fn query_documents(
+        &self,
+        table_name: &str,
+        query: &str,
+        parameters: Value,
+    ) -> anyhow::Result<Vec<Value>> {
+        let (db, coll) = self.resolve(table_name)?;
+        let link = format!("dbs/{}/colls/{}", db, coll);
+        let body = serde_json::json!({ "query": query, "parameters": parameters }).to_string();
+        let mut docs = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut headers = vec![
+                ("content-type", "application/query+json".to_string()),
+                ("x-ms-documentdb-isquery", "True".to_string()),
+                ("x-ms-documentdb-query-enablecrosspartition", "True".to_string()),
+            ];
+            if let Some(ref token) = continuation {
+                headers.push(("x-ms-continuation", token.clone()));
+            }
+            let resp = self
+                .request(
+                    Method::POST,
+                    &format!("{}/docs", encode_path(&link)),
+                    "docs",
+                    &link,
+                    &headers,
+                    Some(body.clone()),
+                )
+                .await?;
+            continuation = continuation_header(&resp);
+            let page: Value = resp.json().await?;
+            docs.extend(documents(&page));
+            if continuation.is_none() {
+                return Ok(docs);
+            }
+        }
+    }
+
+    /// Upsert documents, routing each one to its logical partition.
+    ///
+    /// Tagged values are written as their text form and `id` is coerced to
+    /// a string, as the service requires.
+    pub async fn upsert_documents(&self, table_name: &str, rows: &[Row]) -> anyhow::Result<usize> {
+        read_only::ensure_writable(&self.config, "an upsert")?;
+        let paths = self.partition_key_paths(table_name).await?;
+        let (db, coll) = self.resolve(table_name)?;
+        let link = format!("dbs/{}/colls/{}", db, coll);
+        for row in rows {
+            let doc = row_to_document(row)?;
+            let headers = [
+                ("content-type", "application/json".to_string()),
+                ("x-ms-documentdb-is-upsert", "True".to_string()),
+                (
+                    "x-ms-documentdb-partitionkey",
+                    partition_key_header(&doc, &paths),
+                ),
+            ];
+            self.request(
+                Method::POST,
+                &format!("{}/docs", encode_path(&link)),
+                "docs",
+                &link,
+                &headers,
+                Some(doc.to_string()),
+            )
+            .await
+            .with_context(|| format!("Failed to upsert document into {}", table_name))?;
+        }
+        self.continuations.lock().unwrap().clear();
+        Ok(rows.len())
+    }
+
+    async fn delete_document(
+        &self,
+        table_name: &str,
+        id: &str,
+        partition_key: &Value,
+    ) -> anyhow::Result<()> {
+        read_only::ensure_writable(&self.config, "a delete")?;
+        let (db, coll) = self.resolve(table_name)?;
+        let link = format!("dbs/{}/colls/{}/docs/{}", db, coll, id);
+        let key = match partition_key {
+            Value::Array(_) => partition_key.to_string(),
+            other => Value::Array(vec![other.clone()]).to_string(),
+        };
+        self.request(
+            Method::DELETE,
+            &encode_path(&link),
+            "docs",
+            &link,
+            &[("x-ms-documentdb-partitionkey", key)],
+            None,
+        )
+        .await?;
+        self.continuations.lock().unwrap().clear();
+        Ok(())
+    }
+
+    /// Create the database and container if they do not exist yet.
+    pub async fn ensure_container(
+        &self,
+        table_name: &str,
+        partition_key_path: &str,
+    ) -> anyhow::Result<()> {
+        read_only::ensure_writable(&self.config, "a container creation")?;
+        let (db, coll) = self.resolve(table_name)?;
+        let json = [("content-type", "application/json".to_string())];
+        if !self.list_databases().await?.contains(&db) {
+            self.request(
+                Method::POST,
+                "dbs",
+                "dbs",
+                "",
+                &json,
+                Some(serde_json::json!({ "id": db }).to_string()),
+            )
+            .await
+            .context("Failed to create database")?;
+        }
+        if !self.list_containers(&db).await?.contains(&coll) {
+            let link = format!("dbs/{}", db);
+            let body = serde_json::json!({
+                "id": coll,
+                "partitionKey": { "paths": [partition_key_path], "kind": "Hash" }
+            });
+            self.request(
+                Method::POST,
+                &format!("{}/colls", encode_path(&link)),
+                "colls",
+                &link,
+                &json,
+                Some(body.to_string()),
+            )
+            .await
+            .context("Failed to create container")?;
+        }
+        Ok(())
     }
 }
 
 #[async_trait]
 impl DatabaseConnector for CosmosDbConnector {
     async fn connect(&mut self) -> anyhow::Result<()> {
-        todo!("CosmosDB connect implementation")
+        let (endpoint, key) = self.endpoint_and_key()?;
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(key.trim())
+            .context("Cosmos DB account key is not valid base64")?;
+
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(
+                self.config.connection_timeout_secs,
+            ))
+            .danger_accept_invalid_certs(Self::is_emulator(&endpoint))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        self.endpoint = endpoint;
+        self.key = key;
+        self.client = Some(client);
+
+        // Listing databases verifies both the endpoint and the key
+        if let Err(e) = self.list_databases().await {
+            self.client = None;
+            return Err(e.context("Cosmos DB connection check failed"));
+        }
+        Ok(())
     }
 
     async fn disconnect(&mut self) -> anyhow::Result<()> {
-        todo!("CosmosDB disconnect implementation")
+        self.client = None;
+        self.partition_keys.lock().unwrap().clear();
+        self.continuations.lock().unwrap().clear();
+        Ok(())
     }
 
     async fn is_connected(&self) -> bool {
-        false
+        self.client.is_some() && self.list_databases().await.is_ok()
     }
 
     async fn get_schema(&self) -> anyhow::Result<SchemaInfo> {
-        todo!("CosmosDB get_schema implementation")
+        let tables = self.get_tables().await?;
+        let mut table_infos = Vec::new();
+
+        for container in &tables {
+            match self.get_table_info(container).await {
+                Ok(info) => table_infos.push(info),
+                Err(e) => {
+                    log::warn!("Failed to infer schema for container {}: {}", container, e);
+                }
+            }
+        }
+
+        Ok(SchemaInfo::with_tables(
+            self.config.database.clone().unwrap_or_default(),
+            table_infos,
+        ))
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
-        todo!("CosmosDB get_tables implementation")
+        let filter = &self.config.schema_filter;
+        let mut names = match self.config.database {
+            Some(ref db) => {
+                let mut containers = self.list_containers(db).await?;
+                containers.retain(|c| filter.includes_table(db, c));
+                containers
+            }
+            None => {
+                let mut all = Vec::new();
+                for db in self.list_databases().await? {
+                    for coll in self.list_containers(&db).await? {
+                        if filter.includes_table(&db, &coll) {
+                            all.push(format!("{}/{}", db, coll));
+                        }
+                    }
+                }
+                all
+            }
+        };
+        names.sort();
+        Ok(names)
     }
 
-    async fn get_table_info(&self, _table_name: &str) -> anyhow::Result<TableInfo> {
-        todo!("CosmosDB get_table_info implementation")
+    async fn get_table_info(&self, table_name: &str) -> anyhow::Result<TableInfo> {
+        let (db, coll) = self.resolve(table_name)?;
+        let paths = self.partition_key_paths(table_name).await?;
+
+        // Sample up to 100 documents to infer schema
+        let (sample, _) = self.read_page(table_name, 100, None).await?;
+        let mut field_types: HashMap<String, String> = HashMap::new();
+        for doc in &sample {
+            if let Some(obj) = doc.as_object() {
+                for (key, value) in obj {
+                    if SYSTEM_PROPERTIES.contains(&key.as_str()) {
+                        continue;
+                    }
+                    field_types
+                        .entry(key.clone())
+                        .or_insert_with(|| json_type_name(value).to_string());
+                }
+            }
+        }
+
+        let mut field_list: Vec<(String, String)> = field_types.into_iter().collect();
+        field_list.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let columns: Vec<ColumnInfo> = field_list
+            .iter()
+            .enumerate()
+            .map(|(i, (name, dtype))| ColumnInfo {
+                name: name.clone(),
+                data_type: dtype.clone(),
+                is_nullable: name != "id",
+                is_primary_key: name == "id",
+                max_length: None,
+                precision: None,
+                scale: None,
+                default_value: None,
+                ordinal_position: (i + 1) as i32,
+            })
+            .collect();
+
+        // Surface the partition key as a hash index over its top-level fields
+        let indexes = if paths.is_empty() {
+            Vec::new()
+        } else {
+            vec![IndexInfo {
+                name: "partitionKey".to_string(),
+                columns: paths
+                    .iter()
+                    .map(|p| p.trim_start_matches('/').replace('/', "."))
+                    .collect(),
+                is_unique: false,
+                is_clustered: false,
+                index_type: "HASH".to_string(),
+            }]
+        };
+
+        let row_count = self.get_row_count(table_name).await.ok();
+
+        Ok(TableInfo {
+            schema_name: db,
+            table_name: if table_name.contains('/') {
+                table_name.to_string()
+            } else {
+                coll
+            },
+            columns,
+            indexes,
+            constraints: Vec::new(),
+            row_count,
+            field_stats: Vec::new(),
+        })
     }
 
     async fn get_rows(
         &self,
-        _table_name: &str,
-        _limit: Option<u64>,
-        _offset: Option<u64>,
+        table_name: &str,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> anyhow::Result<Vec<Row>> {
-        todo!("CosmosDB get_rows implementation")
+        let limit = limit.unwrap_or(100);
+        let offset = offset.unwrap_or(0);
+
+        // Resume from the closest cached continuation at or before `offset`
+        let (mut position, mut token) = {
+            let cache = self.continuations.lock().unwrap();
+            cache
+                .iter()
+                .filter(|((t, o), _)| t == table_name && *o <= offset)
+                .max_by_key(|((_, o), _)| *o)
+                .map(|((_, o), tok)| (*o, Some(tok.clone())))
+                .unwrap_or((0, None))
+        };
+
+        while position < offset {
+            let (docs, next) = self
+                .read_page(table_name, (offset - position).min(MAX_PAGE_SIZE), token.as_deref())
+                .await?;
+            position += docs.len() as u64;
+            token = next;
+            if token.is_none() {
+                return Ok(Vec::new());
+            }
+        }
+
+        let mut rows = Vec::new();
+        while (rows.len() as u64) < limit {
+            let remaining = limit - rows.len() as u64;
+            let (docs, next) = self
+                .read_page(table_name, remaining.min(MAX_PAGE_SIZE), token.as_deref())
+                .await?;
+            rows.extend(docs.iter().map(document_to_row));
+            token = next;
+            if token.is_none() {
+                break;
+            }
+        }
+
+        if let Some(token) = token {
+            let mut cache = self.continuations.lock().unwrap();
+            if cache.len() >= 1024 {
+                cache.clear();
+            }
+            cache.insert((table_name.to_string(), offset + rows.len() as u64), token);
+        }
+        Ok(rows)
     }
 
-    async fn execute_query(&self, _query: &str) -> anyhow::Result<Vec<Row>> {
-        todo!("CosmosDB execute_query implementation")
+    async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
+        let command: Value = serde_json::from_str(query)
+            .map_err(|e| anyhow!("Query must be a valid JSON command: {}", e))?;
+
+        if let Some(sql) = command.get("query").and_then(|q| q.as_str()) {
+            let container = command["container"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Query command needs a 'container'"))?;
+            let parameters = command.get("parameters").cloned().unwrap_or(Value::Array(vec![]));
+            let docs = self.query_documents(container, sql, parameters).await?;
+            return Ok(docs.iter().map(document_to_row).collect());
+        }
+
+        if let Some(container) = command.get("upsert").and_then(|c| c.as_str()) {
+            let rows: Vec<Row> = command["documents"]
+                .as_array()
+                .ok_or_else(|| anyhow!("Upsert command needs a 'documents' array"))?
+                .iter()
+                .map(|d| {
+                    d.as_object()
+                        .map(|o| o.clone().into_iter().collect())
+                        .ok_or_else(|| anyhow!("Each document must be a JSON object"))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            let count = self.upsert_documents(container, &rows).await?;
+            return Ok(vec![Row::from([(
+                "upserted".to_string(),
+                serde_json::json!(count),
+            )])]);
+        }
+
+        if let Some(container) = command.get("delete").and_then(|c| c.as_str()) {
+            let id = match &command["id"] {
+                Value::String(s) => s.clone(),
+                Value::Null => bail!("Delete command needs an 'id'"),
+                other => other.to_string(),
+            };
+            let partition_key = command.get("partitionKey").cloned().unwrap_or(Value::Null);
+            self.delete_document(container, &id, &partition_key).await?;
+            return Ok(Vec::new());
+        }
+
+        bail!("Unsupported Cosmos DB command; expected 'query', 'upsert' or 'delete'")
     }
 
     async fn begin_transaction(&mut self) -> anyhow::Result<()> {
-        todo!("CosmosDB begin_transaction implementation")
+        // The REST API only offers transactional batches within a single
+        // logical partition, which does not fit this interface.
+        log::warn!("Cosmos DB has no cross-partition transactions. Transaction not started.");
+        Ok(())
     }
 
     async fn commit_transaction(&mut self) -> anyhow::Result<()> {
-        todo!("CosmosDB commit_transaction implementation")
+        log::warn!("Cosmos DB has no cross-partition transactions. Commit is a no-op.");
+        Ok(())
     }
 
     async fn rollback_transaction(&mut self) -> anyhow::Result<()> {
-        todo!("CosmosDB rollback_transaction implementation")
+        log::warn!("Cosmos DB has no cross-partition transactions. Rollback is a no-op.");
+        Ok(())
     }
 
     fn engine(&self) -> DatabaseEngine {
         DatabaseEngine::CosmosDb
     }
 
-    async fn get_row_count(&self, _table_name: &str) -> anyhow::Result<i64> {
-        todo!("CosmosDB get_row_count implementation")
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
     }
+
+    async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
+        // Cross-partition COUNT needs a query plan the gateway does not
+        // provide, so read the quota usage reported on the container.
+        let (db, coll) = self.resolve(table_name)?;
+        let link = format!("dbs/{}/colls/{}", db, coll);
+        let resp = self
+            .request(
+                Method::GET,
+                &encode_path(&link),
+                "colls",
+                &link,
+                &[("x-ms-documentdb-populatequotainfo", "True".to_string())],
+                None,
+            )
+            .await?;
+        let usage = resp
+            .headers()
+            .get("x-ms-resource-usage")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        parse_documents_count(usage)
+            .ok_or_else(|| anyhow!("Cosmos DB did not report a document count"))
+    }
+}
+
+/// Build the `authorization` header value for a master-key request.
+fn auth_token(key: &[u8], verb: &str, resource_type: &str, resource_link: &str, date: &str) -> String {
+    let payload = string_to_sign(verb, resource_type, resource_link, date);
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    let signature = base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes());
+    utf8_percent_encode(
+        &format!("type=master&ver=1.0&sig={}", signature),
+        URI_COMPONENT,
+    )
+    .to_string()
+}
+
+fn string_to_sign(verb: &str, resource_type: &str, resource_link: &str, date: &str) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n\n",
+        verb.to_lowercase(),
+        resource_type.to_lowercase(),
+        resource_link,
+        date.to_lowercase()
+    )
+}
+
+/// Percent-encode each segment of a resource link for use in a URL.
+fn encode_path(link: &str) -> String {
+    link.split('/')
+        .map(|seg| utf8_percent_encode(seg, URI_COMPONENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn continuation_header(resp: &Response) -> Option<String> {
+    resp.headers()
+        .get("x-ms-continuation")
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+}
+
+fn ids(body: &Value, list_key: &str) -> Vec<String> {
+    body[list_key]
+        .as_array()
+        .map(|a| a.iter().filter_map(|r| r["id"].as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn documents(body: &Value) -> Vec<Value> {
+    body["Documents"].as_array().cloned().unwrap_or_default()
+}
+
+/// `x-ms-resource-usage` looks like `documentSize=0;documentsCount=42;...`.
+fn parse_documents_count(usage: &str) -> Option<i64> {
+    usage
+        .split(';')
+        .filter_map(|kv| kv.split_once('='))
+        .find(|(k, _)| k.trim() == "documentsCount")
+        .and_then(|(_, v)| v.trim().parse().ok())
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn document_to_row(doc: &Value) -> Row {
+    doc.as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter(|(k, _)| !SYSTEM_PROPERTIES.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn row_to_document(row: &Row) -> anyhow::Result<Value> {
+    let mut doc = serde_json::Map::with_capacity(row.len());
+    for (key, value) in row {
+        let value = if key == "id" {
+            match typed_value::to_plain(value) {
+                Value::String(s) => Value::String(s),
+                Value::Number(n) => Value::String(n.to_string()),
+                other => bail!("Document id must be a string or number, got {}", other),
+            }
+        } else {
+            plain_json(value)
+        };
+        doc.insert(key.clone(), value);
+    }
+    if !doc.contains_key("id") {
+        bail!("Cosmos DB documents need an 'id' field");
+    }
+    Ok(Value::Object(doc))
+}
+
+/// Unwrap tagged values recursively so nested objects are stored as text.
+fn plain_json(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(plain_json).collect()),
+        Value::Object(obj) if typed_value::TypedValue::from_json(value).is_none() => {
+            Value::Object(obj.iter().map(|(k, v)| (k.clone(), plain_json(v))).collect())
+        }
+        other => typed_value::to_plain(other),
+    }
+}
+
+/// Partition key header: a JSON array with one value per key path. A
+/// missing value routes to the "undefined" partition, written as `{}`.
+fn partition_key_header(doc: &Value, paths: &[String]) -> String {
+    let values: Vec<Value> = paths
+        .iter()
+        .map(|path| {
+            path.trim_start_matches('/')
+                .split('/')
+                .try_fold(doc, |node, seg| node.get(seg))
+                .cloned()
+                .unwrap_or_else(|| Value::Object(serde_json::Map::new()))
+        })
+        .collect();
+    Value::Array(values).to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    const EMULATOR_KEY: &str =
+        "C2y6yDjf5/R+ob0N8A7Cgv30VRDJIWEHLM+4QDU9DE2nQ9nDuVTqobD4b8mGGyPMbIZnqyMsEcaGQy67XIw/Jw==";
 
     #[test]
     fn test_new_connector() {
@@ -101,4 +855,175 @@ mod tests {
         let connector = CosmosDbConnector::new(config);
         assert!(!connector.is_connected().await);
     }
+
+    #[test]
+    fn test_endpoint_from_connection_string() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            connection_string: Some(format!(
+                "AccountEndpoint=https://localhost:8081/;AccountKey={};",
+                EMULATOR_KEY
+            )),
+            ..Default::default()
+        };
+        let (endpoint, key) = CosmosDbConnector::new(config).endpoint_and_key().unwrap();
+        assert_eq!(endpoint, "https://localhost:8081");
+        // The trailing '=' padding must survive the split
+        assert_eq!(key, EMULATOR_KEY);
+    }
+
+    #[test]
+    fn test_endpoint_from_host() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            host: Some("localhost".to_string()),
+            password: Some(EMULATOR_KEY.to_string()),
+            ..Default::default()
+        };
+        let (endpoint, _) = CosmosDbConnector::new(config).endpoint_and_key().unwrap();
+        assert_eq!(endpoint, "https://localhost:8081");
+    }
+
+    #[test]
+    fn test_missing_key_is_an_error() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            host: Some("https://acct.documents.azure.com".to_string()),
+            ..Default::default()
+        };
+        assert!(CosmosDbConnector::new(config).endpoint_and_key().is_err());
+    }
+
+    #[test]
+    fn test_is_emulator() {
+        assert!(CosmosDbConnector::is_emulator("https://localhost:8081"));
+        assert!(CosmosDbConnector::is_emulator("https://127.0.0.1:8081/"));
+        assert!(!CosmosDbConnector::is_emulator("https://acct.documents.azure.com:443"));
+    }
+
+    #[test]
+    fn test_string_to_sign() {
+        assert_eq!(
+            string_to_sign("GET", "DOCS", "dbs/ToDoList/colls/Items", "Thu, 27 Apr 2017 00:51:12 GMT"),
+            "get\ndocs\ndbs/ToDoList/colls/Items\nthu, 27 apr 2017 00:51:12 gmt\n\n"
+        );
+    }
+
+    #[test]
+    fn test_auth_token_is_encoded_master_signature() {
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(EMULATOR_KEY)
+            .unwrap();
+        let token = auth_token(&key, "GET", "dbs", "", "Thu, 27 Apr 2017 00:51:12 GMT");
+        assert!(token.starts_with("type%3Dmaster%26ver%3D1.0%26sig%3D"));
+        assert!(!token.contains('+') && !token.contains('/') && !token.contains('='));
+        // Same inputs, same signature
+        assert_eq!(token, auth_token(&key, "GET", "dbs", "", "Thu, 27 Apr 2017 00:51:12 GMT"));
+        assert_ne!(token, auth_token(&key, "GET", "colls", "", "Thu, 27 Apr 2017 00:51:12 GMT"));
+    }
+
+    #[test]
+    fn test_resolve_table_names() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            database: Some("shop".to_string()),
+            ..Default::default()
+        };
+        let connector = CosmosDbConnector::new(config);
+        assert_eq!(
+            connector.resolve("orders").unwrap(),
+            ("shop".to_string(), "orders".to_string())
+        );
+        assert_eq!(
+            connector.resolve("other/items").unwrap(),
+            ("other".to_string(), "items".to_string())
+        );
+
+        let no_db = CosmosDbConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            ..Default::default()
+        });
+        assert!(no_db.resolve("orders").is_err());
+    }
+
+    #[test]
+    fn test_partition_key_header() {
+        let doc = json!({"id": "1", "region": "eu", "address": {"zip": "1011"}});
+        assert_eq!(partition_key_header(&doc, &["/region".to_string()]), r#"["eu"]"#);
+        assert_eq!(
+            partition_key_header(&doc, &["/address/zip".to_string()]),
+            r#"["1011"]"#
+        );
+        assert_eq!(partition_key_header(&doc, &["/missing".to_string()]), "[{}]");
+        assert_eq!(
+            partition_key_header(&doc, &["/region".to_string(), "/id".to_string()]),
+            r#"["eu","1"]"#
+        );
+    }
+
+    #[test]
+    fn test_row_to_document() {
+        let row: Row = [
+            ("id".to_string(), json!(42)),
+            ("price".to_string(), typed_value::decimal("9.99")),
+            ("meta".to_string(), json!({"at": {"$date": "2024-01-02"}})),
+        ]
+        .into_iter()
+        .collect();
+        let doc = row_to_document(&row).unwrap();
+        assert_eq!(doc["id"], json!("42"));
+        assert_eq!(doc["price"], json!("9.99"));
+        assert_eq!(doc["meta"], json!({"at": "2024-01-02"}));
+
+        let no_id: Row = [("a".to_string(), json!(1))].into_iter().collect();
+        assert!(row_to_document(&no_id).is_err());
+    }
+
+    #[test]
+    fn test_document_to_row_hides_system_properties() {
+        let doc = json!({"id": "1", "name": "x", "_rid": "r", "_etag": "e", "_ts": 1});
+        let row = document_to_row(&doc);
+        assert_eq!(row.len(), 2);
+        assert!(!row.contains_key("_rid"));
+    }
+
+    #[test]
+    fn test_parse_documents_count() {
+        assert_eq!(
+            parse_documents_count("documentSize=0;documentsSize=12;documentsCount=42;collectionSize=1"),
+            Some(42)
+        );
+        assert_eq!(parse_documents_count(""), None);
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("dbs/my db/colls/a+b"), "dbs/my%20db/colls/a%2Bb");
+    }
+
+    #[tokio::test]
+    async fn test_execute_query_rejects_non_json() {
+        let connector = CosmosDbConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            ..Default::default()
+        });
+        assert!(connector.execute_query("SELECT * FROM c").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_writes() {
+        let connector = CosmosDbConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::CosmosDb,
+            database: Some("shop".to_string()),
+            ..Default::default()
+        });
+        assert!(connector.is_read_only());
+        let err = connector
+            .execute_query(r#"{"upsert": "orders", "documents": [{"id": "1"}]}"#)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("read-only"), "{}", err);
+        assert!(connector.ensure_container("orders", "/id").await.is_err());
+    }
 }
@@ -1,12 +1,19 @@
 pub mod cosmosdb;
 pub mod mongodb_connector;
+pub mod mongodb_schema;
 pub mod mysql;
+#[cfg_attr(not(feature = "oracle"), path = "oracle_unavailable.rs")]
 pub mod oracle;
 pub mod postgres;
+pub mod read_only;
 pub mod sqlite;
 pub mod sqlserver;
 
-use crate::db::schema::{Row, SchemaInfo, TableInfo};
+use crate::db::document::{
+    DocumentQuery, DocumentWrite, DocumentWriteOptions, DocumentWriteResult,
+};
+use crate::db::pool::PoolConfig;
+use crate::db::schema::{Row, SchemaFilter, SchemaInfo, TableInfo};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -36,6 +43,25 @@ impl std::fmt::Display for DatabaseEngine {
     }
 }
 
+impl DatabaseEngine {
+    /// Schema tables belong to when named without one, on engines with
+    /// schemas.
+    pub fn default_schema(&self) -> Option<&'static str> {
+        match self {
+            DatabaseEngine::SqlServer => Some("dbo"),
+            DatabaseEngine::PostgreSql => Some("public"),
+            DatabaseEngine::Sqlite => Some("main"),
+            _ => None,
+        }
+    }
+
+    /// Whether tables outside the default schema are listed as
+    /// `schema.table`. Elsewhere a dot is part of the table name.
+    pub fn qualifies_table_names(&self) -> bool {
+        matches!(self, DatabaseEngine::SqlServer | DatabaseEngine::PostgreSql)
+    }
+}
+
 /// Connection configuration for a database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionConfig {
@@ -49,6 +75,15 @@ pub struct ConnectionConfig {
     pub file_path: Option<String>,
     pub read_only: bool,
     pub connection_timeout_secs: u64,
+    #[serde(default)]
+    pub pool: PoolConfig,
+    /// Documents sampled to infer a collection's schema on document
+    /// engines.
+    #[serde(default)]
+    pub schema_sample_size: Option<u32>,
+    /// Schemas and tables the connection lists
+    #[serde(default)]
+    pub schema_filter: SchemaFilter,
 }
 
 impl Default for ConnectionConfig {
@@ -64,10 +99,26 @@ impl Default for ConnectionConfig {
             file_path: None,
             read_only: true,
             connection_timeout_secs: 30,
+            pool: PoolConfig::default(),
+            schema_sample_size: None,
+            schema_filter: SchemaFilter::default(),
         }
     }
 }
 
+/// Create an unconnected connector for `config.engine`.
+pub fn create_connector(config: ConnectionConfig) -> Box<dyn DatabaseConnector> {
+    match config.engine {
+        DatabaseEngine::SqlServer => Box::new(sqlserver::SqlServerConnector::new(config)),
+        DatabaseEngine::PostgreSql => Box::new(postgres::PostgresConnector::new(config)),
+        DatabaseEngine::MySql => Box::new(mysql::MySqlConnector::new(config)),
+        DatabaseEngine::Sqlite => Box::new(sqlite::SqliteConnector::new(config)),
+        DatabaseEngine::MongoDb => Box::new(mongodb_connector::MongoDbConnector::new(config)),
+        DatabaseEngine::Oracle => Box::new(oracle::OracleConnector::new(config)),
+        DatabaseEngine::CosmosDb => Box::new(cosmosdb::CosmosDbConnector::new(config)),
+    }
+}
+
 /// The core trait that all database connectors must implement
 #[async_trait]
 pub trait DatabaseConnector: Send + Sync {
@@ -112,6 +163,50 @@ pub trait DatabaseConnector: Send + Sync {
     /// Get the database engine type
     fn engine(&self) -> DatabaseEngine;
 
+    /// Whether the connection refuses writes (`ConnectionConfig::read_only`)
+    fn is_read_only(&self) -> bool;
+
+    /// Whether a transaction begun on this connection is still open.
+    /// Connectors without transactions keep the default.
+    fn in_transaction(&self) -> bool {
+        false
+    }
+
+    /// Whether `begin_transaction` can succeed here. Connectors whose
+    /// support depends on the server they are connected to override it.
+    async fn supports_transactions(&self) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
     /// Get the row count for a table
     async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64>;
+
+    /// Read the documents of a collection matching a query. Only document
+    /// stores take these; `get_rows` reads whole tables everywhere.
+    async fn find_documents(
+        &self,
+        collection: &str,
+        _query: &DocumentQuery,
+    ) -> anyhow::Result<Vec<Row>> {
+        anyhow::bail!(
+            "{} does not take document queries (collection {})",
+            self.engine(),
+            collection
+        )
+    }
+
+    /// Apply document writes to a collection in batches. Only document
+    /// stores take these; SQL engines are written through `execute_query`.
+    async fn write_documents(
+        &self,
+        collection: &str,
+        _writes: &[DocumentWrite],
+        _options: &DocumentWriteOptions,
+    ) -> anyhow::Result<DocumentWriteResult> {
+        anyhow::bail!(
+            "{} does not take document writes (collection {})",
+            self.engine(),
+            collection
+        )
+    }
 }
@@ -1,18 +1,47 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
-use crate::db::schema::{ColumnInfo, Row, SchemaInfo, TableInfo};
-use anyhow::{anyhow, Context};
+use super::mongodb_schema::{index_infos, SchemaSampler};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::document::{
+    DocumentQuery, DocumentWrite, DocumentWriteError, DocumentWriteOptions, DocumentWriteResult,
+};
+use crate::db::schema::{Row, SchemaInfo, TableInfo};
+use crate::db::type_mapper::CanonicalType;
+use crate::db::typed_value::TypedValue;
+use crate::db::{typed_value, value_converter};
+use anyhow::{anyhow, bail, Context};
 use async_trait::async_trait;
-use mongodb::bson::{doc, Bson, Document};
-use mongodb::{Client, Database};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, spec::BinarySubtype, Binary, Bson, Decimal128, Document};
+use mongodb::error::UNKNOWN_TRANSACTION_COMMIT_RESULT;
+use mongodb::options::{ClientOptions, ReadPreference, SelectionCriteria};
+use mongodb::{Client, ClientSession, Database};
+use tokio::sync::Mutex;
+
+/// Documents sampled for schema inference unless the connection sets
+/// `schema_sample_size`.
+const DEFAULT_SCHEMA_SAMPLE_SIZE: u32 = 1000;
+
+/// Times a commit whose outcome is unknown is retried.
+const COMMIT_RETRIES: usize = 3;
+
+/// Documents fetched per cursor round trip.
+const READ_BATCH_SIZE: u32 = 1000;
+
+/// Encoded size a write batch stays under, clear of the 16 MiB limit on
+/// a command document.
+const MAX_BATCH_BYTES: usize = 8 * 1024 * 1024;
 
 /// MongoDB connector using the official mongodb driver.
 ///
 /// MongoDB is schema-less, so schema introspection works by sampling
-/// documents from each collection to infer field names and types.
+/// documents from each collection to infer field paths and types.
+///
+/// Transactions need a replica set or sharded cluster. While one is open,
+/// commands, reads and document writes all run in its session.
 pub struct MongoDbConnector {
     config: ConnectionConfig,
     client: Option<Client>,
     database: Option<Database>,
+    session: Mutex<Option<ClientSession>>,
 }
 
 impl MongoDbConnector {
@@ -21,11 +50,12 @@ impl MongoDbConnector {
             config,
             client: None,
             database: None,
+            session: Mutex::new(None),
         }
     }
 
     /// Build a MongoDB connection URI from config.
-    fn build_uri(&self) -> String {
+    pub(crate) fn build_uri(&self) -> String {
         if let Some(ref conn_str) = self.config.connection_string {
             return conn_str.clone();
         }
@@ -45,7 +75,7 @@ impl MongoDbConnector {
     }
 
     /// Get the database name from config.
-    fn database_name(&self) -> String {
+    pub(crate) fn database_name(&self) -> String {
         self.config
             .database
             .clone()
@@ -59,8 +89,21 @@ impl MongoDbConnector {
             .ok_or_else(|| anyhow!("Not connected to MongoDB"))
     }
 
+    /// Run `command`, in the open transaction if there is one.
+    async fn run_command(
+        &self,
+        db: &Database,
+        command: Document,
+    ) -> mongodb::error::Result<Document> {
+        let mut session = self.session.lock().await;
+        match session.as_mut() {
+            Some(session) => db.run_command(command).session(session).await,
+            None => db.run_command(command).await,
+        }
+    }
+
     /// Infer the BSON type as a string for schema purposes.
-    fn bson_type_name(value: &Bson) -> &'static str {
+    pub(super) fn bson_type_name(value: &Bson) -> &'static str {
         match value {
             Bson::Double(_) => "double",
             Bson::String(_) => "string",
@@ -75,6 +118,12 @@ impl MongoDbConnector {
             Bson::Int64(_) => "long",
             Bson::Timestamp(_) => "timestamp",
             Bson::Decimal128(_) => "decimal",
+            Bson::RegularExpression(_) => "regex",
+            Bson::JavaScriptCode(_) => "javascript",
+            Bson::JavaScriptCodeWithScope(_) => "javascriptWithScope",
+            Bson::MinKey => "minKey",
+            Bson::MaxKey => "maxKey",
+            Bson::Undefined => "undefined",
             _ => "unknown",
         }
     }
@@ -85,9 +134,16 @@ impl DatabaseConnector for MongoDbConnector {
     async fn connect(&mut self) -> anyhow::Result<()> {
         let uri = self.build_uri();
 
-        let client = Client::with_uri_str(&uri)
+        let mut options = ClientOptions::parse(&uri)
             .await
             .context("Failed to create MongoDB client")?;
+        if self.config.read_only && options.selection_criteria.is_none() {
+            // Prefer secondaries for reads; writes are refused by the guard
+            options.selection_criteria = Some(SelectionCriteria::ReadPreference(
+                ReadPreference::SecondaryPreferred { options: None },
+            ));
+        }
+        let client = Client::with_options(options).context("Failed to create MongoDB client")?;
 
         // Ping to verify the connection
         let db_name = self.database_name();
@@ -102,6 +158,8 @@ impl DatabaseConnector for MongoDbConnector {
     }
 
     async fn disconnect(&mut self) -> anyhow::Result<()> {
+        // Dropping an open session aborts its transaction
+        *self.session.get_mut() = None;
         self.database = None;
         self.client = None;
         Ok(())
@@ -133,10 +191,7 @@ impl DatabaseConnector for MongoDbConnector {
             }
         }
 
-        Ok(SchemaInfo {
-            database_name: db_name,
-            tables: table_infos,
-        })
+        Ok(SchemaInfo::with_tables(db_name, table_infos))
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
@@ -145,102 +200,102 @@ impl DatabaseConnector for MongoDbConnector {
             .list_collection_names()
             .await
             .context("Failed to list MongoDB collections")?;
+        let db_name = self.database_name();
+        names.retain(|n| self.config.schema_filter.includes_table(&db_name, n));
         names.sort();
         Ok(names)
     }
 
+    /// Infers the columns from a random `$sample` of documents, sized by
+    /// the connection's `schema_sample_size`.
     async fn get_table_info(&self, table_name: &str) -> anyhow::Result<TableInfo> {
         let db = self.db()?;
         let collection = db.collection::<Document>(table_name);
+        let sample_size = self
+            .config
+            .schema_sample_size
+            .unwrap_or(DEFAULT_SCHEMA_SAMPLE_SIZE)
+            .max(1);
 
-        // Sample up to 100 documents to infer schema
         use futures_util::TryStreamExt;
         let mut cursor = collection
-            .find(doc! {})
-            .limit(100)
+            .aggregate(vec![doc! { "$sample": { "size": sample_size as i64 } }])
             .await
-            .context("Failed to query collection for schema inference")?;
-
-        let mut field_types: std::collections::HashMap<String, String> =
-            std::collections::HashMap::new();
-
+            .context("Failed to sample collection for schema inference")?;
+        let mut sampler = SchemaSampler::default();
         while let Some(doc) = cursor.try_next().await? {
-            for (key, value) in doc.iter() {
-                field_types
-                    .entry(key.clone())
-                    .or_insert_with(|| Self::bson_type_name(value).to_string());
-            }
+            sampler.add(&doc);
         }
 
-        // Sort fields by name for consistent ordering
-        let mut field_list: Vec<(String, String)> = field_types.into_iter().collect();
-        field_list.sort_by(|a, b| a.0.cmp(&b.0));
-
-        let columns: Vec<ColumnInfo> = field_list
-            .iter()
-            .enumerate()
-            .map(|(i, (name, dtype))| ColumnInfo {
-                name: name.clone(),
-                data_type: dtype.clone(),
-                is_nullable: true, // MongoDB fields are always nullable
-                is_primary_key: name == "_id",
-                max_length: None,
-                precision: None,
-                scale: None,
-                default_value: None,
-                ordinal_position: (i + 1) as i32,
-            })
-            .collect();
+        // Views have no indexes of their own and refuse listIndexes
+        let indexes = match db.run_command(doc! { "listIndexes": table_name }).await {
+            Ok(reply) => {
+                let specs: Vec<Document> = reply
+                    .get_document("cursor")
+                    .and_then(|c| c.get_array("firstBatch"))
+                    .map(|batch| {
+                        batch
+                            .iter()
+                            .filter_map(Bson::as_document)
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                index_infos(&specs)
+            }
+            Err(e) => {
+                log::warn!("Failed to list indexes of {}: {}", table_name, e);
+                Vec::new()
+            }
+        };
 
         let row_count = self.get_row_count(table_name).await.ok();
 
         Ok(TableInfo {
             schema_name: self.database_name(),
             table_name: table_name.to_string(),
-            columns,
-            indexes: Vec::new(), // Could be enhanced later with list_indexes
+            columns: sampler.columns(),
+            indexes,
             constraints: Vec::new(),
             row_count,
+            field_stats: sampler.stats(),
         })
     }
 
+    /// Reads every document when `limit` is `None`, in `_id` order so
+    /// offsets stay stable across calls.
     async fn get_rows(
         &self,
         table_name: &str,
         limit: Option<u64>,
         offset: Option<u64>,
     ) -> anyhow::Result<Vec<Row>> {
-        let db = self.db()?;
-        let collection = db.collection::<Document>(table_name);
-
-        let limit_val = limit.unwrap_or(100) as i64;
-        let skip_val = offset.unwrap_or(0);
-
-        use futures_util::TryStreamExt;
-        let mut cursor = collection
-            .find(doc! {})
-            .skip(skip_val)
-            .limit(limit_val)
-            .await
-            .context("Failed to query MongoDB collection")?;
-
-        let mut rows = Vec::new();
-        while let Some(doc) = cursor.try_next().await? {
-            rows.push(bson_doc_to_row(&doc));
-        }
-
-        Ok(rows)
+        let query = DocumentQuery {
+            limit,
+            offset,
+            ..Default::default()
+        };
+        self.find_documents(table_name, &query).await
     }
 
     async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
-        let db = self.db()?;
-
         // Interpret the query as a JSON command document
         let command: Document = serde_json::from_str(query)
             .map_err(|e| anyhow!("Query must be a valid JSON document: {}", e))?;
 
-        let result = db
-            .run_command(command)
+        let name = command.keys().next().map(String::as_str).unwrap_or_default();
+        let writes_output = match command.get("pipeline") {
+            Some(Bson::Array(stages)) => stages.iter().any(|stage| {
+                matches!(stage, Bson::Document(d) if d.contains_key("$out") || d.contains_key("$merge"))
+            }),
+            _ => false,
+        };
+        read_only::check_command(&self.config, name, writes_output)?;
+
+        let db = self.db()?;
+
+        let result = self
+            .run_command(db, command)
             .await
             .context("Failed to execute MongoDB command")?;
 
@@ -264,20 +319,67 @@ impl DatabaseConnector for MongoDbConnector {
         Ok(rows)
     }
 
+    /// Fails on a standalone server, which has no transactions.
     async fn begin_transaction(&mut self) -> anyhow::Result<()> {
-        // MongoDB transactions require replica sets.
-        // For now, this is a no-op that logs a warning.
-        log::warn!("MongoDB transactions require a replica set. Transaction not started.");
+        if self.session.get_mut().is_some() {
+            bail!("A MongoDB transaction is already in progress");
+        }
+        if !self.supports_transactions().await? {
+            bail!(
+                "MongoDB transactions need a replica set or sharded cluster, but {} is a \
+                 standalone server. Run it as a single-node replica set to use transactions.",
+                self.config.host.as_deref().unwrap_or("the server")
+            );
+        }
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| anyhow!("Not connected to MongoDB"))?;
+        let mut session = client
+            .start_session()
+            .await
+            .context("Failed to start MongoDB session")?;
+        session
+            .start_transaction()
+            .await
+            .context("Failed to start MongoDB transaction")?;
+        *self.session.get_mut() = Some(session);
         Ok(())
     }
 
+    /// Retries commits whose outcome is unknown, as after a failover.
     async fn commit_transaction(&mut self) -> anyhow::Result<()> {
-        log::warn!("MongoDB transactions require a replica set. Commit is a no-op.");
-        Ok(())
+        let mut session = self
+            .session
+            .get_mut()
+            .take()
+            .ok_or_else(|| anyhow!("No MongoDB transaction in progress"))?;
+        let mut retries = 0;
+        loop {
+            match session.commit_transaction().await {
+                Ok(()) => return Ok(()),
+                Err(e)
+                    if retries < COMMIT_RETRIES
+                        && e.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) =>
+                {
+                    retries += 1;
+                }
+                Err(e) => {
+                    return Err(
+                        anyhow::Error::new(e).context("Failed to commit MongoDB transaction")
+                    )
+                }
+            }
+        }
     }
 
     async fn rollback_transaction(&mut self) -> anyhow::Result<()> {
-        log::warn!("MongoDB transactions require a replica set. Rollback is a no-op.");
+        if let Some(mut session) = self.session.get_mut().take() {
+            session
+                .abort_transaction()
+                .await
+                .context("Failed to abort MongoDB transaction")?;
+        }
         Ok(())
     }
 
@@ -285,6 +387,25 @@ impl DatabaseConnector for MongoDbConnector {
         DatabaseEngine::MongoDb
     }
 
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    /// Only replica sets and sharded clusters have transactions.
+    async fn supports_transactions(&self) -> anyhow::Result<bool> {
+        let hello = self
+            .db()?
+            .run_command(doc! { "hello": 1 })
+            .await
+            .context("Failed to read the MongoDB deployment type")?;
+        Ok(deployment_has_transactions(&hello))
+    }
+
+    fn in_transaction(&self) -> bool {
+        // The session only exists between begin and commit or abort
+        self.session.try_lock().map_or(true, |s| s.is_some())
+    }
+
     async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
         let db = self.db()?;
         let collection = db.collection::<Document>(table_name);
@@ -296,10 +417,322 @@ impl DatabaseConnector for MongoDbConnector {
 
         Ok(count as i64)
     }
+
+    async fn find_documents(
+        &self,
+        collection: &str,
+        query: &DocumentQuery,
+    ) -> anyhow::Result<Vec<Row>> {
+        let db = self.db()?;
+        let filter = query_document(query.filter.as_ref(), "filter")?.unwrap_or_default();
+        let handle = db.collection::<Document>(collection);
+        let mut find = handle
+            .find(filter)
+            .sort(doc! { "_id": 1 })
+            .batch_size(READ_BATCH_SIZE);
+        if let Some(projection) = query_document(query.projection.as_ref(), "projection")? {
+            find = find.projection(projection);
+        }
+        if let Some(offset) = query.offset {
+            find = find.skip(offset);
+        }
+        if let Some(limit) = query.limit {
+            find = find.limit(i64::try_from(limit).unwrap_or(i64::MAX));
+        }
+
+        use futures_util::TryStreamExt;
+        let query_failed = || format!("Failed to query MongoDB collection {}", collection);
+        let read_failed = || format!("Failed to read MongoDB collection {}", collection);
+        let mut rows = Vec::new();
+        let mut session = self.session.lock().await;
+        match session.as_mut() {
+            Some(session) => {
+                let mut cursor = find
+                    .session(&mut *session)
+                    .await
+                    .with_context(query_failed)?;
+                while let Some(doc) = cursor
+                    .next(session)
+                    .await
+                    .transpose()
+                    .with_context(read_failed)?
+                {
+                    rows.push(bson_doc_to_row(&doc));
+                }
+            }
+            None => {
+                let mut cursor = find.await.with_context(query_failed)?;
+                while let Some(doc) = cursor.try_next().await.with_context(read_failed)? {
+                    rows.push(bson_doc_to_row(&doc));
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Runs consecutive writes of one kind as a single `insert`, `update`
+    /// or `delete` command, the commands behind `insertMany` and
+    /// `bulkWrite`. Replacements and updates upsert; deletes remove every
+    /// match.
+    async fn write_documents(
+        &self,
+        collection: &str,
+        writes: &[DocumentWrite],
+        options: &DocumentWriteOptions,
+    ) -> anyhow::Result<DocumentWriteResult> {
+        let db = self.db()?;
+        let statements: Vec<Document> = writes.iter().map(write_statement).collect();
+        let sizes: Vec<usize> = statements
+            .iter()
+            .map(|d| mongodb::bson::to_vec(d).map_or(0, |bytes| bytes.len()))
+            .collect();
+
+        let mut result = DocumentWriteResult::default();
+        let mut start = 0;
+        while start < writes.len() {
+            let kind = writes[start].kind();
+            read_only::check_command(&self.config, kind, false)?;
+            let mut end = start + 1;
+            let mut bytes = sizes[start];
+            while end < writes.len()
+                && end - start < options.batch_size.max(1)
+                && writes[end].kind() == kind
+                && bytes + sizes[end] <= MAX_BATCH_BYTES
+            {
+                bytes += sizes[end];
+                end += 1;
+            }
+
+            let batch: Vec<Bson> = statements[start..end]
+                .iter()
+                .cloned()
+                .map(Bson::Document)
+                .collect();
+            let field = match kind {
+                "insert" => "documents",
+                "update" => "updates",
+                _ => "deletes",
+            };
+            let mut command = Document::new();
+            command.insert(kind, collection);
+            command.insert(field, batch);
+            command.insert("ordered", options.ordered);
+            let failed = match self.run_command(db, command).await {
+                Ok(reply) => record_write_reply(&reply, kind, start, &mut result),
+                Err(e) => {
+                    // Nothing in the batch is known to have been written
+                    let message = format!("{:#}", anyhow::Error::new(e));
+                    result
+                        .errors
+                        .extend((start..end).map(|index| DocumentWriteError {
+                            index,
+                            code: None,
+                            message: message.clone(),
+                        }));
+                    true
+                }
+            };
+            if failed && options.ordered {
+                mark_not_attempted(&mut result, start, writes.len());
+                break;
+            }
+            start = end;
+        }
+        Ok(result)
+    }
+}
+
+/// Whether the server answering `hello` belongs to a deployment with
+/// transactions: a replica set member or a `mongos` router.
+fn deployment_has_transactions(hello: &Document) -> bool {
+    hello.contains_key("setName") || hello.get_str("msg").is_ok_and(|m| m == "isdbgrid")
+}
+
+/// Parse a filter or projection given in the JSON syntax `execute_query`
+/// takes.
+fn query_document(
+    value: Option<&serde_json::Value>,
+    what: &str,
+) -> anyhow::Result<Option<Document>> {
+    value
+        .map(|v| {
+            serde_json::from_value::<Document>(v.clone())
+                .map_err(|e| anyhow!("The {} must be a JSON document: {}", what, e))
+        })
+        .transpose()
+}
+
+/// Entry of an `insert`, `update` or `delete` command for one write.
+fn write_statement(write: &DocumentWrite) -> Document {
+    match write {
+        DocumentWrite::Insert { document } => row_to_bson_doc(document),
+        DocumentWrite::Replace { filter, document } => doc! {
+            "q": row_to_bson_doc(filter),
+            "u": row_to_bson_doc(document),
+            "upsert": true,
+        },
+        DocumentWrite::Update { filter, set } => doc! {
+            "q": row_to_bson_doc(filter),
+            "u": { "$set": row_to_bson_doc(set) },
+            "upsert": true,
+        },
+        DocumentWrite::Delete { filter } => doc! {
+            "q": row_to_bson_doc(filter),
+            "limit": 0,
+        },
+    }
+}
+
+/// Add a write command's reply to `result`, indexing its write errors from
+/// `offset`. Returns whether any write failed.
+fn record_write_reply(
+    reply: &Document,
+    kind: &str,
+    offset: usize,
+    result: &mut DocumentWriteResult,
+) -> bool {
+    let count = |key: &str| match reply.get(key) {
+        Some(Bson::Int32(n)) => *n as u64,
+        Some(Bson::Int64(n)) => *n as u64,
+        _ => 0,
+    };
+    let n = count("n");
+    match kind {
+        "insert" => result.inserted += n,
+        "update" => {
+            let upserted = match reply.get("upserted") {
+                Some(Bson::Array(ids)) => ids.len() as u64,
+                _ => 0,
+            };
+            result.upserted += upserted;
+            result.matched += n.saturating_sub(upserted);
+            result.modified += count("nModified");
+        }
+        _ => result.deleted += n,
+    }
+    if let Ok(concern) = reply.get_document("writeConcernError") {
+        log::warn!(
+            "MongoDB {} did not satisfy the write concern: {}",
+            kind,
+            concern.get_str("errmsg").unwrap_or_default()
+        );
+    }
+
+    let Ok(errors) = reply.get_array("writeErrors") else {
+        return false;
+    };
+    for error in errors {
+        let Bson::Document(error) = error else {
+            continue;
+        };
+        let index = match error.get("index") {
+            Some(Bson::Int32(i)) => *i as usize,
+            Some(Bson::Int64(i)) => *i as usize,
+            _ => 0,
+        };
+        result.errors.push(DocumentWriteError {
+            index: offset + index,
+            code: error.get_i32("code").ok(),
+            message: error
+                .get_str("errmsg")
+                .unwrap_or("Write failed")
+                .to_string(),
+        });
+    }
+    !errors.is_empty()
+}
+
+/// After an ordered batch starting at `start` failed, list every write past
+/// its first failure as not attempted: the server stopped there, so the
+/// rest of that batch was skipped along with every later batch.
+fn mark_not_attempted(result: &mut DocumentWriteResult, start: usize, total: usize) {
+    let listed: Vec<usize> = result
+        .errors
+        .iter()
+        .map(|e| e.index)
+        .filter(|&i| i >= start)
+        .collect();
+    let first = listed.iter().copied().min().unwrap_or(start);
+    result.errors.extend(
+        (first + 1..total)
+            .filter(|i| !listed.contains(i))
+            .map(|index| DocumentWriteError {
+                index,
+                code: None,
+                message: "Not attempted after an earlier write failed".to_string(),
+            }),
+    );
+}
+
+/// Convert a row to a BSON document, `_id` first and the other fields in
+/// name order.
+pub(crate) fn row_to_bson_doc(row: &Row) -> Document {
+    let mut keys: Vec<&String> = row.keys().collect();
+    keys.sort_by_key(|k| (k.as_str() != "_id", k.as_str()));
+    keys.into_iter()
+        .map(|k| {
+            let value = match &row[k] {
+                // ObjectIds are read back as hex; write them as ObjectIds again
+                serde_json::Value::String(s) if k == "_id" => ObjectId::parse_str(s)
+                    .map_or_else(|_| Bson::String(s.clone()), Bson::ObjectId),
+                other => json_to_bson(other),
+            };
+            (k.clone(), value)
+        })
+        .collect()
+}
+
+/// Convert a serde_json::Value to BSON. Tagged values become their native
+/// BSON types: dates and datetimes are stored as UTC dates, times as text.
+fn json_to_bson(value: &serde_json::Value) -> Bson {
+    if let Some(typed) = TypedValue::from_json(value) {
+        return match typed {
+            TypedValue::Decimal(text) => text
+                .parse::<Decimal128>()
+                .map_or(Bson::String(text), Bson::Decimal128),
+            TypedValue::Bytes(bytes) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes,
+            }),
+            TypedValue::Date(d) => {
+                let utc = d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+                Bson::DateTime(mongodb::bson::DateTime::from_millis(utc.timestamp_millis()))
+            }
+            TypedValue::Time(_) => Bson::String(typed.to_text()),
+            TypedValue::DateTime(dt) => Bson::DateTime(mongodb::bson::DateTime::from_millis(
+                dt.and_utc().timestamp_millis(),
+            )),
+            TypedValue::Timestamp(ts) => {
+                Bson::DateTime(mongodb::bson::DateTime::from_millis(ts.timestamp_millis()))
+            }
+            TypedValue::Uuid(u) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Uuid,
+                bytes: u.as_bytes().to_vec(),
+            }),
+        };
+    }
+    match value {
+        serde_json::Value::Null => Bson::Null,
+        serde_json::Value::Bool(b) => Bson::Boolean(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i32::try_from(i).map_or(Bson::Int64(i), Bson::Int32),
+            None => n
+                .as_u64()
+                .and_then(|u| u.to_string().parse::<Decimal128>().ok())
+                .map_or_else(|| Bson::Double(n.as_f64().unwrap_or_default()), Bson::Decimal128),
+        },
+        serde_json::Value::String(s) => Bson::String(s.clone()),
+        serde_json::Value::Array(items) => Bson::Array(items.iter().map(json_to_bson).collect()),
+        serde_json::Value::Object(map) => Bson::Document(
+            map.iter()
+                .map(|(k, v)| (k.clone(), json_to_bson(v)))
+                .collect(),
+        ),
+    }
 }
 
 /// Convert a BSON Document to our Row type (HashMap<String, serde_json::Value>).
-fn bson_doc_to_row(doc: &Document) -> Row {
+pub(crate) fn bson_doc_to_row(doc: &Document) -> Row {
     let mut map = std::collections::HashMap::new();
     for (key, value) in doc.iter() {
         map.insert(key.clone(), bson_to_json(value));
@@ -327,16 +760,41 @@ fn bson_to_json(bson: &Bson) -> serde_json::Value {
         Bson::Int32(v) => serde_json::json!(*v),
         Bson::Int64(v) => serde_json::json!(*v),
         Bson::ObjectId(oid) => serde_json::Value::String(oid.to_hex()),
-        Bson::DateTime(dt) => serde_json::Value::String(
-            dt.try_to_rfc3339_string()
-                .unwrap_or_else(|_| format!("{:?}", dt)),
-        ),
-        Bson::Decimal128(d) => serde_json::Value::String(d.to_string()),
+        Bson::DateTime(dt) => {
+            match chrono::DateTime::from_timestamp_millis(dt.timestamp_millis()) {
+                Some(utc) => typed_value::timestamp(utc.fixed_offset()),
+                None => serde_json::Value::String(format!("{:?}", dt)),
+            }
+        }
+        Bson::Decimal128(d) => {
+            let text = d.to_string();
+            if typed_value::is_decimal_text(&text) {
+                typed_value::decimal(text)
+            } else {
+                // Exponent form (e.g. "1.5E+3"); NaN and Infinity stay text
+                match value_converter::convert_value(
+                    &serde_json::Value::String(text.clone()),
+                    &CanonicalType::Decimal {
+                        precision: 0,
+                        scale: 0,
+                    },
+                ) {
+                    Ok(v) => v,
+                    Err(_) => serde_json::Value::String(text),
+                }
+            }
+        }
         Bson::Timestamp(ts) => serde_json::json!({
             "t": ts.time,
             "i": ts.increment
         }),
-        Bson::Binary(bin) => serde_json::Value::String(hex::encode(&bin.bytes)),
+        Bson::Binary(bin) => match bin.subtype {
+            BinarySubtype::Uuid => match uuid::Uuid::from_slice(&bin.bytes) {
+                Ok(u) => typed_value::uuid(u),
+                Err(_) => typed_value::bytes(&bin.bytes),
+            },
+            _ => typed_value::bytes(&bin.bytes),
+        },
         _ => serde_json::Value::String(format!("{:?}", bson)),
     }
 }
@@ -473,6 +931,138 @@ mod tests {
         assert_eq!(row["arr"], serde_json::json!([1, 2, 3]));
     }
 
+    #[test]
+    fn test_row_to_bson_doc() {
+        let row: Row = [
+            ("_id", serde_json::json!("65a1b2c3d4e5f60718293a4b")),
+            ("code", serde_json::json!("65a1b2c3d4e5f60718293a4b")),
+            ("qty", serde_json::json!(5)),
+            ("big", serde_json::json!(5_000_000_000i64)),
+            ("price", typed_value::decimal("12.50")),
+            ("day", serde_json::json!({ "$date": "2024-03-01" })),
+            ("at", serde_json::json!({ "$time": "08:30:00" })),
+            ("tags", serde_json::json!(["a", { "$uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8" }])),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        let doc = row_to_bson_doc(&row);
+
+        assert_eq!(doc.keys().next().map(String::as_str), Some("_id"));
+        assert!(matches!(doc.get("_id"), Some(Bson::ObjectId(_))));
+        assert_eq!(doc.get_str("code").unwrap(), "65a1b2c3d4e5f60718293a4b");
+        assert_eq!(doc.get_i32("qty").unwrap(), 5);
+        assert_eq!(doc.get_i64("big").unwrap(), 5_000_000_000);
+        assert_eq!(doc.get("price").unwrap().to_string(), "12.50");
+        assert_eq!(
+            doc.get_datetime("day").unwrap().timestamp_millis(),
+            1_709_251_200_000
+        );
+        assert_eq!(doc.get_str("at").unwrap(), "08:30:00");
+        let tags = doc.get_array("tags").unwrap();
+        assert!(matches!(&tags[1], Bson::Binary(b) if b.subtype == BinarySubtype::Uuid));
+
+        // Reading the document back gives the tagged values again
+        let back = bson_doc_to_row(&doc);
+        assert_eq!(back["price"], typed_value::decimal("12.50"));
+        assert_eq!(back["tags"][1], row["tags"][1]);
+    }
+
+    #[test]
+    fn test_deployment_has_transactions() {
+        let replica_set = doc! { "isWritablePrimary": true, "setName": "rs0" };
+        let router = doc! { "isWritablePrimary": true, "msg": "isdbgrid" };
+        assert!(deployment_has_transactions(&replica_set));
+        assert!(deployment_has_transactions(&router));
+        assert!(!deployment_has_transactions(&doc! { "isWritablePrimary": true }));
+    }
+
+    #[test]
+    fn test_query_document() {
+        let filter = serde_json::json!({ "status": "active", "qty": { "$gt": 5 } });
+        assert_eq!(
+            query_document(Some(&filter), "filter").unwrap(),
+            Some(doc! { "status": "active", "qty": { "$gt": 5 } })
+        );
+        assert_eq!(query_document(None, "filter").unwrap(), None);
+        let err = query_document(Some(&serde_json::json!([1])), "projection").unwrap_err();
+        assert!(err.to_string().contains("projection"));
+    }
+
+    #[test]
+    fn test_write_statement() {
+        let row = |pairs: &[(&str, serde_json::Value)]| -> Row {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+        };
+        let filter = row(&[("id", serde_json::json!(1))]);
+        let update = write_statement(&DocumentWrite::Update {
+            filter: filter.clone(),
+            set: row(&[("qty", serde_json::json!(2))]),
+        });
+        assert_eq!(update, doc! { "q": { "id": 1 }, "u": { "$set": { "qty": 2 } }, "upsert": true });
+        let replace = write_statement(&DocumentWrite::Replace {
+            filter: filter.clone(),
+            document: row(&[("id", serde_json::json!(1)), ("qty", serde_json::json!(2))]),
+        });
+        assert_eq!(replace, doc! { "q": { "id": 1 }, "u": { "id": 1, "qty": 2 }, "upsert": true });
+        let delete = write_statement(&DocumentWrite::Delete { filter });
+        assert_eq!(delete, doc! { "q": { "id": 1 }, "limit": 0 });
+    }
+
+    #[test]
+    fn test_record_write_reply() {
+        let mut result = DocumentWriteResult::default();
+        let reply = doc! {
+            "n": 3,
+            "nModified": 1,
+            "upserted": [{ "index": 2, "_id": 7 }],
+            "writeErrors": [{ "index": 1, "code": 11000, "errmsg": "duplicate key" }],
+            "ok": 1.0,
+        };
+        assert!(record_write_reply(&reply, "update", 10, &mut result));
+        assert_eq!((result.matched, result.modified, result.upserted), (2, 1, 1));
+        assert_eq!(
+            result.errors,
+            vec![DocumentWriteError {
+                index: 11,
+                code: Some(11000),
+                message: "duplicate key".to_string(),
+            }]
+        );
+
+        assert!(!record_write_reply(&doc! { "n": 4, "ok": 1.0 }, "insert", 0, &mut result));
+        assert!(!record_write_reply(&doc! { "n": 2i64, "ok": 1.0 }, "delete", 0, &mut result));
+        assert_eq!((result.inserted, result.deleted), (4, 2));
+    }
+
+    #[test]
+    fn test_mark_not_attempted() {
+        let error = |index| DocumentWriteError {
+            index,
+            code: Some(11000),
+            message: "duplicate key".to_string(),
+        };
+        // A failure at 2 in an earlier batch, then one at 12 in the batch
+        // starting at 10
+        let mut result = DocumentWriteResult {
+            errors: vec![error(2), error(12)],
+            ..Default::default()
+        };
+        mark_not_attempted(&mut result, 10, 16);
+        let indexes: Vec<usize> = result.errors.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![2, 12, 13, 14, 15]);
+        assert_eq!(result.errors[2].code, None);
+
+        // A batch whose command failed already lists all of its writes
+        let mut result = DocumentWriteResult {
+            errors: (0..4).map(error).collect(),
+            ..Default::default()
+        };
+        mark_not_attempted(&mut result, 0, 6);
+        let indexes: Vec<usize> = result.errors.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3, 4, 5]);
+    }
+
     #[tokio::test]
     async fn test_not_connected_by_default() {
         let config = ConnectionConfig {
@@ -0,0 +1,262 @@
+//! Schema inference for MongoDB collections.
+//!
+//! A collection's columns are the dotted paths found in a sample of its
+//! documents: nested documents add one column per field, and the fields of
+//! documents inside arrays extend the array's path. Each path keeps the
+//! counts of the BSON types seen at it, how often it is null or missing and
+//! its longest string.
+
+use std::collections::BTreeMap;
+
+use mongodb::bson::{Bson, Document};
+
+use super::mongodb_connector::MongoDbConnector;
+use crate::db::schema::{ColumnInfo, FieldStats, IndexInfo};
+
+/// Numeric BSON types, narrowest first.
+const NUMERIC_TYPES: [&str; 4] = ["int", "long", "double", "decimal"];
+
+#[derive(Debug, Default)]
+struct FieldTally {
+    type_counts: BTreeMap<String, u64>,
+    /// Documents holding the path
+    present: u64,
+    /// Documents holding only nulls at the path
+    null: u64,
+    max_string_length: Option<u32>,
+}
+
+/// Field statistics gathered over sampled documents.
+#[derive(Debug, Default)]
+pub struct SchemaSampler {
+    documents: u64,
+    fields: BTreeMap<String, FieldTally>,
+}
+
+impl SchemaSampler {
+    pub fn add(&mut self, doc: &Document) {
+        self.documents += 1;
+        // Whether each path held a non-null value in this document
+        let mut seen = BTreeMap::new();
+        for (key, value) in doc {
+            self.tally(key.clone(), value, &mut seen);
+        }
+        for (path, non_null) in seen {
+            if let Some(tally) = self.fields.get_mut(&path) {
+                tally.present += 1;
+                if !non_null {
+                    tally.null += 1;
+                }
+            }
+        }
+    }
+
+    fn tally(&mut self, path: String, value: &Bson, seen: &mut BTreeMap<String, bool>) {
+        let tally = self.fields.entry(path.clone()).or_default();
+        *tally
+            .type_counts
+            .entry(MongoDbConnector::bson_type_name(value).to_string())
+            .or_default() += 1;
+        if let Bson::String(s) = value {
+            let len = s.chars().count() as u32;
+            tally.max_string_length = Some(tally.max_string_length.map_or(len, |m| m.max(len)));
+        }
+        *seen.entry(path.clone()).or_default() |= !matches!(value, Bson::Null);
+
+        let nested: Vec<&Document> = match value {
+            Bson::Document(d) => vec![d],
+            Bson::Array(items) => items.iter().filter_map(Bson::as_document).collect(),
+            _ => Vec::new(),
+        };
+        for d in nested {
+            for (key, value) in d {
+                self.tally(format!("{}.{}", path, key), value, seen);
+            }
+        }
+    }
+
+    /// One column per path, in path order, typed by [`dominant_type`].
+    /// Every column is nullable and unbounded: documents outside the sample
+    /// may lack the path or hold longer strings, so sampled nullability and
+    /// lengths are only reported by [`Self::stats`].
+    pub fn columns(&self) -> Vec<ColumnInfo> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, (path, tally))| {
+                let data_type = dominant_type(&tally.type_counts);
+                ColumnInfo {
+                    name: path.clone(),
+                    is_nullable: true, // MongoDB fields are always nullable
+                    is_primary_key: path == "_id",
+                    max_length: None,
+                    data_type,
+                    precision: None,
+                    scale: None,
+                    default_value: None,
+                    ordinal_position: (i + 1) as i32,
+                }
+            })
+            .collect()
+    }
+
+    pub fn stats(&self) -> Vec<FieldStats> {
+        let ratio = |count: u64| {
+            if self.documents == 0 {
+                0.0
+            } else {
+                count as f64 / self.documents as f64
+            }
+        };
+        self.fields
+            .iter()
+            .map(|(path, tally)| FieldStats {
+                path: path.clone(),
+                type_counts: tally.type_counts.clone(),
+                null_ratio: ratio(tally.null),
+                missing_ratio: ratio(self.documents - tally.present),
+                max_string_length: tally.max_string_length,
+            })
+            .collect()
+    }
+}
+
+/// Type a column takes from the types seen at its path: the widest when
+/// every non-null value is numeric, the most frequent otherwise, and
+/// "null" when nothing else was seen.
+fn dominant_type(type_counts: &BTreeMap<String, u64>) -> String {
+    let non_null: Vec<(&String, &u64)> = type_counts.iter().filter(|(t, _)| *t != "null").collect();
+    if !non_null.is_empty()
+        && non_null
+            .iter()
+            .all(|(t, _)| NUMERIC_TYPES.contains(&t.as_str()))
+    {
+        if let Some(widest) = NUMERIC_TYPES
+            .iter()
+            .rev()
+            .find(|t| type_counts.contains_key(**t))
+        {
+            return widest.to_string();
+        }
+    }
+    non_null
+        .into_iter()
+        // Ties go to the first type by name
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map_or_else(|| "null".to_string(), |(t, _)| t.clone())
+}
+
+/// Indexes described by a collection's `listIndexes` specifications. The
+/// index type is the key's special kind (`text`, `2dsphere`, `hashed`), or
+/// "btree" for ascending and descending keys.
+pub fn index_infos(specs: &[Document]) -> Vec<IndexInfo> {
+    specs
+        .iter()
+        .filter_map(|spec| {
+            let name = spec.get_str("name").ok()?.to_string();
+            let key = spec.get_document("key").ok()?;
+            let index_type = key.values().find_map(Bson::as_str).unwrap_or("btree");
+            Some(IndexInfo {
+                columns: key.keys().cloned().collect(),
+                is_unique: name == "_id_" || spec.get_bool("unique").unwrap_or(false),
+                is_clustered: spec.get_bool("clustered").unwrap_or(false),
+                index_type: index_type.to_string(),
+                name,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn test_sampler_paths_and_stats() {
+        let mut sampler = SchemaSampler::default();
+        sampler.add(&doc! {
+            "_id": 1,
+            "name": "Ada",
+            "address": { "city": "Oslo", "zip": Bson::Null },
+            "lines": [{ "sku": "a", "qty": 1 }, { "sku": "bb", "qty": 2.5 }],
+        });
+        sampler.add(&doc! { "_id": 2, "name": Bson::Null, "lines": [] });
+        sampler.add(&doc! { "_id": 3, "name": "Grace", "address": { "city": 7 } });
+        sampler.add(&doc! { "_id": 4_i64, "name": "Barbara" });
+
+        let columns = sampler.columns();
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "_id",
+                "address",
+                "address.city",
+                "address.zip",
+                "lines",
+                "lines.qty",
+                "lines.sku",
+                "name"
+            ]
+        );
+        let column = |name: &str| columns.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(column("_id").data_type, "long");
+        assert!(column("_id").is_primary_key);
+        assert_eq!(column("lines.qty").data_type, "double");
+        assert_eq!(column("address.city").data_type, "int");
+        assert_eq!(column("address.zip").data_type, "null");
+        assert_eq!(column("name").data_type, "string");
+        assert!(columns.iter().all(|c| c.is_nullable && c.max_length.is_none()));
+
+        let stats = sampler.stats();
+        let name = stats.iter().find(|s| s.path == "name").unwrap();
+        assert_eq!(name.null_ratio, 0.25);
+        assert_eq!(name.missing_ratio, 0.0);
+        assert_eq!(name.type_counts["string"], 3);
+        assert_eq!(name.type_counts["null"], 1);
+        assert_eq!(name.max_string_length, Some(7));
+        let sku = stats.iter().find(|s| s.path == "lines.sku").unwrap();
+        assert_eq!(sku.type_counts["string"], 2);
+        assert_eq!(sku.missing_ratio, 0.75);
+        assert_eq!(sku.max_string_length, Some(2));
+    }
+
+    #[test]
+    fn test_dominant_type() {
+        let counts = |pairs: &[(&str, u64)]| {
+            pairs
+                .iter()
+                .map(|(t, n)| (t.to_string(), *n))
+                .collect::<BTreeMap<_, _>>()
+        };
+        assert_eq!(dominant_type(&counts(&[("int", 9), ("long", 1)])), "long");
+        assert_eq!(
+            dominant_type(&counts(&[("string", 2), ("bool", 5), ("null", 9)])),
+            "bool"
+        );
+        assert_eq!(
+            dominant_type(&counts(&[("string", 2), ("date", 2)])),
+            "date"
+        );
+        assert_eq!(dominant_type(&counts(&[("null", 3)])), "null");
+    }
+
+    #[test]
+    fn test_index_infos() {
+        let specs = vec![
+            doc! { "v": 2, "key": { "_id": 1 }, "name": "_id_" },
+            doc! { "v": 2, "key": { "email": 1, "created": -1 }, "name": "email_1_created_-1",
+            "unique": true },
+            doc! { "v": 2, "key": { "_fts": "text", "_ftsx": 1 }, "name": "body_text" },
+        ];
+        let indexes = index_infos(&specs);
+        assert_eq!(indexes.len(), 3);
+        assert!(indexes[0].is_unique);
+        assert_eq!(indexes[1].columns, vec!["email", "created"]);
+        assert!(indexes[1].is_unique);
+        assert_eq!(indexes[1].index_type, "btree");
+        assert_eq!(indexes[2].index_type, "text");
+        assert!(!indexes[2].is_unique);
+    }
+}
@@ -1,16 +1,51 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::catalog::{self, CatalogQueries};
 use crate::db::schema::{
     ColumnInfo, ConstraintInfo, ConstraintType, IndexInfo, Row, SchemaInfo, TableInfo,
 };
+use crate::db::typed_value;
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use mysql_async::prelude::*;
-use mysql_async::{Conn, Opts, OptsBuilder, Pool};
+use mysql_async::{Conn, Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts};
+
+/// Views, routines and triggers of the current database. MySQL has no
+/// sequences or user-defined types.
+const CATALOG: CatalogQueries = CatalogQueries {
+    views: Some(
+        "SELECT TABLE_SCHEMA AS schema_name, TABLE_NAME AS name, \
+         VIEW_DEFINITION AS definition FROM information_schema.VIEWS \
+         WHERE TABLE_SCHEMA = DATABASE() ORDER BY TABLE_NAME",
+    ),
+    routines: Some(
+        "SELECT r.ROUTINE_SCHEMA AS schema_name, r.ROUTINE_NAME AS name, \
+         r.ROUTINE_TYPE AS routine_type, \
+         COALESCE((SELECT GROUP_CONCAT(CONCAT_WS(' ', p.PARAMETER_MODE, p.PARAMETER_NAME, \
+         p.DTD_IDENTIFIER) ORDER BY p.ORDINAL_POSITION SEPARATOR ', ') \
+         FROM information_schema.PARAMETERS p \
+         WHERE p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA AND p.SPECIFIC_NAME = r.SPECIFIC_NAME \
+         AND p.ORDINAL_POSITION > 0), '') AS signature, \
+         CASE WHEN r.ROUTINE_TYPE = 'FUNCTION' THEN r.DTD_IDENTIFIER END AS return_type, \
+         r.ROUTINE_DEFINITION AS body \
+         FROM information_schema.ROUTINES r \
+         WHERE r.ROUTINE_SCHEMA = DATABASE() ORDER BY r.ROUTINE_NAME",
+    ),
+    triggers: Some(
+        "SELECT TRIGGER_SCHEMA AS schema_name, TRIGGER_NAME AS name, \
+         EVENT_OBJECT_TABLE AS table_name, ACTION_TIMING AS timing, \
+         EVENT_MANIPULATION AS event, ACTION_STATEMENT AS definition \
+         FROM information_schema.TRIGGERS WHERE TRIGGER_SCHEMA = DATABASE() \
+         ORDER BY EVENT_OBJECT_TABLE, TRIGGER_NAME",
+    ),
+    sequences: None,
+    user_types: None,
+};
 
 /// MySQL connector using mysql_async
 pub struct MySqlConnector {
     config: ConnectionConfig,
     pool: Option<Pool>,
+    in_transaction: bool,
 }
 
 impl MySqlConnector {
@@ -18,11 +53,12 @@ impl MySqlConnector {
         Self {
             config,
             pool: None,
+            in_transaction: false,
         }
     }
 
     /// Build MySQL connection options from our config.
-    fn build_opts(&self) -> anyhow::Result<Opts> {
+    pub(crate) fn build_opts(&self) -> anyhow::Result<Opts> {
         if let Some(ref conn_str) = self.config.connection_string {
             return Opts::from_url(conn_str)
                 .map_err(|e| anyhow!("Invalid MySQL connection string: {}", e));
@@ -73,7 +109,18 @@ impl MySqlConnector {
 impl DatabaseConnector for MySqlConnector {
     async fn connect(&mut self) -> anyhow::Result<()> {
         let opts = self.build_opts()?;
-        let pool = Pool::new(opts);
+        let mut init = opts.init().to_vec();
+        if self.config.read_only {
+            // Init statements run on every pooled connection as it opens
+            init.push("SET SESSION TRANSACTION READ ONLY".to_string());
+        }
+        // One connector is one session, as with the other engines: a single
+        // connection that is not reset between calls, so session settings and
+        // transactions carry over. Concurrency comes from `db::pool`.
+        let pool_opts = PoolOpts::default()
+            .with_constraints(PoolConstraints::new(1, 1).expect("1 <= 1"))
+            .with_reset_connection(false);
+        let pool = Pool::new(OptsBuilder::from_opts(opts).init(init).pool_opts(pool_opts));
 
         // Test the connection by getting a conn and pinging
         let mut conn = pool
@@ -90,6 +137,7 @@ impl DatabaseConnector for MySqlConnector {
         if let Some(pool) = self.pool.take() {
             pool.disconnect().await.context("Failed to disconnect MySQL pool")?;
         }
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -116,10 +164,10 @@ impl DatabaseConnector for MySqlConnector {
             }
         }
 
-        Ok(SchemaInfo {
-            database_name: db_name,
-            tables: table_infos,
-        })
+        let mut schema = SchemaInfo::with_tables(db_name, table_infos);
+        catalog::read_schema_objects(self, &CATALOG, &self.config.schema_filter, &mut schema)
+            .await;
+        Ok(schema)
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
@@ -131,12 +179,16 @@ impl DatabaseConnector for MySqlConnector {
                 "SELECT TABLE_NAME FROM information_schema.TABLES \
                  WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'BASE TABLE' \
                  ORDER BY TABLE_NAME",
-                (db,),
+                (&db,),
             )
             .await
             .context("Failed to query MySQL tables")?;
 
-        Ok(rows)
+        let filter = &self.config.schema_filter;
+        Ok(rows
+            .into_iter()
+            .filter(|t| filter.includes_table(&db, t))
+            .collect())
     }
 
     async fn get_table_info(&self, table_name: &str) -> anyhow::Result<TableInfo> {
@@ -152,6 +204,7 @@ impl DatabaseConnector for MySqlConnector {
             indexes,
             constraints,
             row_count,
+            field_stats: Vec::new(),
         })
     }
 
@@ -175,6 +228,7 @@ impl DatabaseConnector for MySqlConnector {
     }
 
     async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
+        read_only::check_sql(&self.config, query)?;
         let mut conn = self.get_conn().await?;
 
         let result: Vec<mysql_async::Row> = conn
@@ -194,6 +248,7 @@ impl DatabaseConnector for MySqlConnector {
         conn.query_drop("START TRANSACTION")
             .await
             .context("Failed to begin MySQL transaction")?;
+        self.in_transaction = true;
         Ok(())
     }
 
@@ -202,6 +257,7 @@ impl DatabaseConnector for MySqlConnector {
         conn.query_drop("COMMIT")
             .await
             .context("Failed to commit MySQL transaction")?;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -210,6 +266,7 @@ impl DatabaseConnector for MySqlConnector {
         conn.query_drop("ROLLBACK")
             .await
             .context("Failed to rollback MySQL transaction")?;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -217,6 +274,14 @@ impl DatabaseConnector for MySqlConnector {
         DatabaseEngine::MySql
     }
 
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
     async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
         let mut conn = self.get_conn().await?;
         let query = format!(
@@ -386,39 +451,106 @@ fn mysql_row_to_map(row: &mysql_async::Row) -> Row {
     map
 }
 
+/// Character-set id MySQL reports for binary strings and BLOBs.
+const BINARY_CHARSET: u16 = 63;
+
 /// Convert a MySQL column value at the given index to serde_json::Value.
 fn mysql_value_to_json(row: &mysql_async::Row, idx: usize) -> serde_json::Value {
+    let column = row.columns_ref().get(idx);
+    value_to_json(row.as_ref(idx), column)
+}
+
+/// Convert a MySQL value to serde_json::Value, typed by its column when
+/// known. Shared with the binlog reader, whose rows carry the same values.
+///
+/// The text protocol delivers most values as bytes, so the column type
+/// decides whether they become exact decimals, binary, temporal values or
+/// plain strings.
+pub(crate) fn value_to_json(
+    value: Option<&mysql_async::Value>,
+    column: Option<&mysql_async::Column>,
+) -> serde_json::Value {
+    use mysql_async::consts::ColumnType;
     use mysql_async::Value;
 
-    match row.as_ref(idx) {
+    let column_type = column.map(|c| (c.column_type(), c.character_set()));
+
+    match value {
         Some(Value::NULL) | None => serde_json::Value::Null,
         Some(Value::Int(v)) => serde_json::json!(*v),
         Some(Value::UInt(v)) => serde_json::json!(*v),
         Some(Value::Float(v)) => serde_json::json!(*v),
         Some(Value::Double(v)) => serde_json::json!(*v),
-        Some(Value::Bytes(b)) => {
-            // Try to interpret as UTF-8 string first
-            match String::from_utf8(b.clone()) {
-                Ok(s) => serde_json::Value::String(s),
-                Err(_) => {
-                    // Fall back to base64 or hex representation
-                    serde_json::Value::String(hex::encode(b))
+        Some(Value::Bytes(b)) => match column_type {
+            Some((ColumnType::MYSQL_TYPE_NEWDECIMAL | ColumnType::MYSQL_TYPE_DECIMAL, _)) => {
+                let text = String::from_utf8_lossy(b).into_owned();
+                if typed_value::is_decimal_text(&text) {
+                    typed_value::decimal(text)
+                } else {
+                    serde_json::Value::String(text)
                 }
             }
+            Some((
+                ColumnType::MYSQL_TYPE_TINY_BLOB
+                | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+                | ColumnType::MYSQL_TYPE_LONG_BLOB
+                | ColumnType::MYSQL_TYPE_BLOB
+                | ColumnType::MYSQL_TYPE_STRING
+                | ColumnType::MYSQL_TYPE_VAR_STRING
+                | ColumnType::MYSQL_TYPE_VARCHAR,
+                BINARY_CHARSET,
+            )) => typed_value::bytes(b),
+            Some((ColumnType::MYSQL_TYPE_DATE, _)) => {
+                let text = String::from_utf8_lossy(b);
+                match chrono::NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+                    Ok(d) => typed_value::date(d),
+                    // Zero dates ('0000-00-00') have no calendar equivalent
+                    Err(_) => serde_json::Value::String(text.into_owned()),
+                }
+            }
+            Some((ColumnType::MYSQL_TYPE_DATETIME | ColumnType::MYSQL_TYPE_TIMESTAMP, _)) => {
+                let text = String::from_utf8_lossy(b);
+                match chrono::NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f") {
+                    Ok(dt) => typed_value::datetime(dt),
+                    Err(_) => serde_json::Value::String(text.into_owned()),
+                }
+            }
+            Some((ColumnType::MYSQL_TYPE_TIME, _)) => {
+                // TIME can hold durations beyond 24h; only time-of-day is tagged
+                let text = String::from_utf8_lossy(b);
+                match chrono::NaiveTime::parse_from_str(&text, "%H:%M:%S%.f") {
+                    Ok(t) => typed_value::time(t),
+                    Err(_) => serde_json::Value::String(text.into_owned()),
+                }
+            }
+            _ => match String::from_utf8(b.clone()) {
+                Ok(s) => serde_json::Value::String(s),
+                Err(_) => typed_value::bytes(b),
+            },
+        },
+        Some(Value::Date(y, m, d, h, min, s, us)) => {
+            let date = chrono::NaiveDate::from_ymd_opt(*y as i32, *m as u32, *d as u32);
+            let time =
+                chrono::NaiveTime::from_hms_micro_opt(*h as u32, *min as u32, *s as u32, *us);
+            match (date, time, column_type) {
+                (Some(date), _, Some((ColumnType::MYSQL_TYPE_DATE, _))) => typed_value::date(date),
+                (Some(date), Some(time), _) => typed_value::datetime(date.and_time(time)),
+                _ => serde_json::Value::String(format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                    y, m, d, h, min, s
+                )),
+            }
         }
-        Some(Value::Date(y, m, d, h, min, s, _us)) => {
-            serde_json::Value::String(format!(
-                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-                y, m, d, h, min, s
-            ))
-        }
-        Some(Value::Time(neg, d, h, min, s, _us)) => {
+        Some(Value::Time(neg, d, h, min, s, us)) => {
             let sign = if *neg { "-" } else { "" };
             let total_hours = *d * 24 + (*h as u32);
-            serde_json::Value::String(format!(
-                "{}{:02}:{:02}:{:02}",
-                sign, total_hours, min, s
-            ))
+            match chrono::NaiveTime::from_hms_micro_opt(total_hours, *min as u32, *s as u32, *us) {
+                Some(t) if !*neg => typed_value::time(t),
+                _ => serde_json::Value::String(format!(
+                    "{}{:02}:{:02}:{:02}",
+                    sign, total_hours, min, s
+                )),
+            }
         }
     }
 }
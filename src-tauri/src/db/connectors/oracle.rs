@@ -1,80 +1,555 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
-use crate::db::schema::{Row, SchemaInfo, TableInfo};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::schema::{
+    ColumnInfo, ConstraintInfo, ConstraintType, IndexInfo, Row, SchemaInfo, TableInfo,
+};
+use crate::db::typed_value;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use oracle::sql_type::OracleType;
+use oracle::Connection;
+use std::sync::Arc;
 
-/// Oracle connector - STUB IMPLEMENTATION
-/// Oracle Instant Client is not available in this environment.
-/// All database methods have todo!() bodies.
-/// The interface is defined to match the DatabaseConnector trait so the
-/// project compiles and Oracle can be added later.
+/// Oracle connector using the `oracle` crate (ODPI-C).
+///
+/// ODPI-C loads the Oracle client library at runtime rather than link time,
+/// so the app builds and runs without Instant Client; `connect` simply
+/// returns an error explaining what is missing. No pure-Rust thin-protocol
+/// driver is mature enough to replace it yet.
+///
+/// The driver is blocking, so every call runs on the blocking thread pool.
 pub struct OracleConnector {
-    #[allow(dead_code)]
     config: ConnectionConfig,
+    conn: Option<Arc<Connection>>,
+    /// While `false`, each statement is committed as soon as it runs, which
+    /// matches the autocommit behaviour of the other connectors.
+    in_transaction: bool,
 }
 
 impl OracleConnector {
     pub fn new(config: ConnectionConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            conn: None,
+            in_transaction: false,
+        }
+    }
+
+    /// Build an EZConnect string (`//host:port/service`) from our config.
+    /// `database` is the service name; it defaults to `FREEPDB1`, the
+    /// pluggable database of the `gvenzl/oracle-free` image.
+    fn build_connect_string(&self) -> String {
+        if let Some(ref conn_str) = self.config.connection_string {
+            return conn_str.clone();
+        }
+        format!(
+            "//{}:{}/{}",
+            self.config.host.as_deref().unwrap_or("localhost"),
+            self.config.port.unwrap_or(1521),
+            self.config.database.as_deref().unwrap_or("FREEPDB1")
+        )
+    }
+
+    fn conn(&self) -> anyhow::Result<Arc<Connection>> {
+        self.conn
+            .clone()
+            .ok_or_else(|| anyhow!("Not connected to Oracle"))
+    }
+
+    /// Run a blocking driver call against the open connection.
+    async fn with_conn<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> anyhow::Result<T> + Send + 'static,
+    {
+        let conn = self.conn()?;
+        tokio::task::spawn_blocking(move || f(&conn))
+            .await
+            .context("Oracle worker thread panicked")?
+    }
+
+    /// Quote an identifier exactly as stored in the data dictionary.
+    fn quote_ident(name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    /// OCI rejects a trailing `;` on SQL statements, but PL/SQL blocks
+    /// need theirs. `SqlGenerator` terminates every statement.
+    fn strip_statement_terminator(sql: &str) -> &str {
+        let trimmed = sql.trim_end();
+        let upper = trimmed.to_uppercase();
+        if upper.ends_with("END;") {
+            return trimmed;
+        }
+        trimmed.strip_suffix(';').unwrap_or(trimmed).trim_end()
+    }
+
+    /// Run a query and convert every row.
+    fn query_rows(
+        conn: &Connection,
+        sql: &str,
+        params: &[&dyn oracle::sql_type::ToSql],
+    ) -> anyhow::Result<Vec<Row>> {
+        Self::collect_rows(conn.query(sql, params)?)
+    }
+
+    fn collect_rows(result_set: oracle::ResultSet<'_, oracle::Row>) -> anyhow::Result<Vec<Row>> {
+        let columns: Vec<(String, OracleType)> = result_set
+            .column_info()
+            .iter()
+            .map(|c| (c.name().to_string(), c.oracle_type().clone()))
+            .collect();
+        let mut rows = Vec::new();
+        for row in result_set {
+            let row = row?;
+            rows.push(Self::row_to_map(&columns, row.sql_values()));
+        }
+        Ok(rows)
+    }
+
+    fn row_to_map(columns: &[(String, OracleType)], values: &[oracle::SqlValue]) -> Row {
+        columns
+            .iter()
+            .zip(values)
+            .map(|((name, ty), value)| (name.clone(), Self::value_to_json(value, ty)))
+            .collect()
+    }
+
+    /// Convert a single column value to serde_json::Value
+    fn value_to_json(value: &oracle::SqlValue, ty: &OracleType) -> serde_json::Value {
+        if value.is_null().unwrap_or(true) {
+            return serde_json::Value::Null;
+        }
+        match ty {
+            // Integral NUMBER columns that fit in i64 stay plain numbers
+            OracleType::Number(p, 0) if (1..=18).contains(p) => value
+                .get::<i64>()
+                .map(|v| serde_json::json!(v))
+                .unwrap_or(serde_json::Value::Null),
+            OracleType::Int64 => value
+                .get::<i64>()
+                .map(|v| serde_json::json!(v))
+                .unwrap_or(serde_json::Value::Null),
+            // Other NUMBERs are fetched as text so no digits are lost
+            OracleType::Number(_, _) => value
+                .get::<String>()
+                .map(|s| {
+                    let text = normalize_number_text(&s);
+                    if typed_value::is_decimal_text(&text) {
+                        typed_value::decimal(text)
+                    } else {
+                        serde_json::Value::String(s)
+                    }
+                })
+                .unwrap_or(serde_json::Value::Null),
+            OracleType::BinaryFloat | OracleType::BinaryDouble | OracleType::Float(_) => value
+                .get::<f64>()
+                .map(|v| serde_json::json!(v))
+                .unwrap_or(serde_json::Value::Null),
+            OracleType::Boolean => value
+                .get::<bool>()
+                .map(serde_json::Value::Bool)
+                .unwrap_or(serde_json::Value::Null),
+            OracleType::Raw(_) | OracleType::LongRaw | OracleType::BLOB => value
+                .get::<Vec<u8>>()
+                .map(|v| typed_value::bytes(&v))
+                .unwrap_or(serde_json::Value::Null),
+            // Oracle DATE carries a time of day
+            OracleType::Date | OracleType::Timestamp(_) => value
+                .get::<chrono::NaiveDateTime>()
+                .map(typed_value::datetime)
+                .unwrap_or(serde_json::Value::Null),
+            OracleType::TimestampTZ(_) | OracleType::TimestampLTZ(_) => value
+                .get::<chrono::DateTime<chrono::FixedOffset>>()
+                .map(typed_value::timestamp)
+                .unwrap_or(serde_json::Value::Null),
+            _ => value
+                .get::<String>()
+                .map(serde_json::Value::String)
+                .unwrap_or(serde_json::Value::Null),
+        }
+    }
+}
+
+/// Oracle may render fractions without a leading zero (`.5`, `-.5`).
+fn normalize_number_text(text: &str) -> String {
+    let text = text.trim();
+    if let Some(rest) = text.strip_prefix("-.") {
+        format!("-0.{}", rest)
+    } else if let Some(rest) = text.strip_prefix('.') {
+        format!("0.{}", rest)
+    } else {
+        text.to_string()
     }
 }
 
 #[async_trait]
 impl DatabaseConnector for OracleConnector {
     async fn connect(&mut self) -> anyhow::Result<()> {
-        todo!("Oracle connect - requires Oracle Instant Client")
+        let user = self.config.username.clone().unwrap_or_default();
+        let password = self.config.password.clone().unwrap_or_default();
+        let connect_string = self.build_connect_string();
+        let timeout = std::time::Duration::from_secs(self.config.connection_timeout_secs);
+
+        let connect = tokio::task::spawn_blocking(move || {
+            Connection::connect(user, password, connect_string)
+        });
+        let conn = tokio::time::timeout(timeout, connect)
+            .await
+            .map_err(|_| anyhow!("Timed out connecting to Oracle"))?
+            .context("Oracle worker thread panicked")?
+            .context("Failed to connect to Oracle (is the Oracle client library installed?)")?;
+
+        self.conn = Some(Arc::new(conn));
+        self.in_transaction = false;
+        Ok(())
     }
 
     async fn disconnect(&mut self) -> anyhow::Result<()> {
-        todo!("Oracle disconnect - requires Oracle Instant Client")
+        if let Some(conn) = self.conn.take() {
+            tokio::task::spawn_blocking(move || {
+                // Uncommitted work is rolled back on close
+                if let Err(e) = conn.close() {
+                    log::warn!("Oracle close failed: {}", e);
+                }
+            })
+            .await
+            .ok();
+        }
+        self.in_transaction = false;
+        Ok(())
     }
 
     async fn is_connected(&self) -> bool {
-        false
+        if self.conn.is_none() {
+            return false;
+        }
+        self.with_conn(|conn| Ok(conn.ping()?)).await.is_ok()
     }
 
     async fn get_schema(&self) -> anyhow::Result<SchemaInfo> {
-        todo!("Oracle get_schema - requires Oracle Instant Client")
+        let db_name = self.current_schema().await?;
+        let tables = self.get_tables().await?;
+        let mut table_infos = Vec::new();
+
+        for table_name in &tables {
+            match self.get_table_info(table_name).await {
+                Ok(info) => table_infos.push(info),
+                Err(e) => {
+                    log::warn!("Failed to get info for table {}: {}", table_name, e);
+                }
+            }
+        }
+
+        Ok(SchemaInfo::with_tables(db_name, table_infos))
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
-        todo!("Oracle get_tables - requires Oracle Instant Client")
+        let owner = self.current_schema().await?;
+        let filter = self.config.schema_filter.clone();
+        self.with_conn(move |conn| {
+            let rows = conn
+                .query_as::<String>(
+                    "SELECT table_name FROM all_tables \
+                     WHERE owner = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') \
+                       AND nested = 'NO' AND secondary = 'N' \
+                     ORDER BY table_name",
+                    &[],
+                )
+                .context("Failed to query tables")?;
+            let mut tables = Vec::new();
+            for row in rows {
+                let name = row?;
+                if filter.includes_table(&owner, &name) {
+                    tables.push(name);
+                }
+            }
+            Ok(tables)
+        })
+        .await
     }
 
-    async fn get_table_info(&self, _table_name: &str) -> anyhow::Result<TableInfo> {
-        todo!("Oracle get_table_info - requires Oracle Instant Client")
+    async fn get_table_info(&self, table_name: &str) -> anyhow::Result<TableInfo> {
+        let schema_name = self.current_schema().await?;
+        let columns = self.get_columns(table_name).await?;
+        if columns.is_empty() {
+            return Err(anyhow!("Table '{}' not found", table_name));
+        }
+        let indexes = self.get_indexes(table_name).await?;
+        let constraints = self.get_constraints(table_name).await?;
+        let row_count = self.get_row_count(table_name).await.ok();
+
+        Ok(TableInfo {
+            schema_name,
+            table_name: table_name.to_string(),
+            columns,
+            indexes,
+            constraints,
+            row_count,
+            field_stats: Vec::new(),
+        })
     }
 
     async fn get_rows(
         &self,
-        _table_name: &str,
-        _limit: Option<u64>,
-        _offset: Option<u64>,
+        table_name: &str,
+        limit: Option<u64>,
+        offset: Option<u64>,
     ) -> anyhow::Result<Vec<Row>> {
-        todo!("Oracle get_rows - requires Oracle Instant Client")
+        let limit = limit.unwrap_or(100);
+        let offset = offset.unwrap_or(0);
+
+        let query = format!(
+            "SELECT * FROM {} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            Self::quote_ident(table_name),
+            offset,
+            limit
+        );
+
+        self.execute_query(&query).await
     }
 
-    async fn execute_query(&self, _query: &str) -> anyhow::Result<Vec<Row>> {
-        todo!("Oracle execute_query - requires Oracle Instant Client")
+    async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
+        // Oracle only has per-transaction read-only mode, which would pin a
+        // snapshot for the life of the session, so rely on the guard alone
+        read_only::check_sql(&self.config, query)?;
+        let sql = Self::strip_statement_terminator(query).to_string();
+        let autocommit = !self.in_transaction;
+        self.with_conn(move |conn| {
+            let mut stmt = conn
+                .statement(&sql)
+                .build()
+                .context("Failed to prepare query")?;
+            if stmt.is_query() {
+                let result_set = stmt.query(&[]).context("Failed to execute query")?;
+                return Self::collect_rows(result_set);
+            }
+            stmt.execute(&[]).context("Failed to execute query")?;
+            if autocommit {
+                conn.commit().context("Failed to commit statement")?;
+            }
+            Ok(Vec::new())
+        })
+        .await
     }
 
     async fn begin_transaction(&mut self) -> anyhow::Result<()> {
-        todo!("Oracle begin_transaction - requires Oracle Instant Client")
+        // Oracle starts a transaction implicitly with the first DML
+        self.conn()?;
+        self.in_transaction = true;
+        Ok(())
     }
 
     async fn commit_transaction(&mut self) -> anyhow::Result<()> {
-        todo!("Oracle commit_transaction - requires Oracle Instant Client")
+        self.with_conn(|conn| conn.commit().context("Failed to commit transaction"))
+            .await?;
+        self.in_transaction = false;
+        Ok(())
     }
 
     async fn rollback_transaction(&mut self) -> anyhow::Result<()> {
-        todo!("Oracle rollback_transaction - requires Oracle Instant Client")
+        self.with_conn(|conn| conn.rollback().context("Failed to rollback transaction"))
+            .await?;
+        self.in_transaction = false;
+        Ok(())
     }
 
     fn engine(&self) -> DatabaseEngine {
         DatabaseEngine::Oracle
     }
 
-    async fn get_row_count(&self, _table_name: &str) -> anyhow::Result<i64> {
-        todo!("Oracle get_row_count - requires Oracle Instant Client")
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
+    async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
+        let query = format!("SELECT COUNT(*) FROM {}", Self::quote_ident(table_name));
+        self.with_conn(move |conn| {
+            conn.query_row_as::<i64>(&query, &[])
+                .context("Failed to query row count")
+        })
+        .await
+    }
+}
+
+/// Private helper methods for schema introspection
+impl OracleConnector {
+    async fn current_schema(&self) -> anyhow::Result<String> {
+        self.with_conn(|conn| {
+            conn.query_row_as::<String>(
+                "SELECT SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') FROM dual",
+                &[],
+            )
+            .context("Failed to query current schema")
+        })
+        .await
+    }
+
+    async fn get_columns(&self, table_name: &str) -> anyhow::Result<Vec<ColumnInfo>> {
+        let table = table_name.to_string();
+        self.with_conn(move |conn| {
+            let pk_columns: Vec<String> = conn
+                .query_as::<String>(
+                    "SELECT cc.column_name \
+                     FROM all_constraints c \
+                     JOIN all_cons_columns cc \
+                       ON cc.owner = c.owner AND cc.constraint_name = c.constraint_name \
+                     WHERE c.owner = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') \
+                       AND c.table_name = :1 AND c.constraint_type = 'P'",
+                    &[&table],
+                )?
+                .collect::<Result<_, _>>()?;
+
+            let rows = Self::query_rows(
+                conn,
+                "SELECT column_name, data_type, nullable, data_length, char_length, \
+                        data_precision, data_scale, data_default, column_id \
+                 FROM all_tab_columns \
+                 WHERE owner = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') AND table_name = :1 \
+                 ORDER BY column_id",
+                &[&table],
+            )
+            .context("Failed to query columns")?;
+
+            let text = |row: &Row, key: &str| row.get(key).and_then(|v| v.as_str()).map(String::from);
+            let int = |row: &Row, key: &str| {
+                row.get(key)
+                    .and_then(|v| v.as_i64().or_else(|| typed_value::to_plain(v).as_str()?.parse().ok()))
+                    .map(|v| v as i32)
+            };
+
+            let mut columns = Vec::new();
+            for row in &rows {
+                let name = text(row, "COLUMN_NAME").unwrap_or_default();
+                let data_type = text(row, "DATA_TYPE").unwrap_or_default();
+                let upper = data_type.to_uppercase();
+                // CHAR_LENGTH is in characters; RAW lengths are bytes
+                let max_length = if upper.contains("CHAR") {
+                    int(row, "CHAR_LENGTH")
+                } else if upper == "RAW" {
+                    int(row, "DATA_LENGTH")
+                } else {
+                    None
+                };
+                columns.push(ColumnInfo {
+                    is_primary_key: pk_columns.contains(&name),
+                    name,
+                    data_type,
+                    is_nullable: text(row, "NULLABLE").as_deref() != Some("N"),
+                    max_length,
+                    precision: int(row, "DATA_PRECISION"),
+                    scale: int(row, "DATA_SCALE"),
+                    default_value: text(row, "DATA_DEFAULT")
+                        .map(|d| d.trim().to_string())
+                        .filter(|d| !d.is_empty()),
+                    ordinal_position: int(row, "COLUMN_ID").unwrap_or(0),
+                });
+            }
+            Ok(columns)
+        })
+        .await
+    }
+
+    async fn get_indexes(&self, table_name: &str) -> anyhow::Result<Vec<IndexInfo>> {
+        let table = table_name.to_string();
+        self.with_conn(move |conn| {
+            let rows = conn
+                .query_as::<(String, String, String, String)>(
+                    "SELECT i.index_name, i.uniqueness, i.index_type, ic.column_name \
+                     FROM all_indexes i \
+                     JOIN all_ind_columns ic \
+                       ON ic.index_owner = i.owner AND ic.index_name = i.index_name \
+                     WHERE i.table_owner = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') \
+                       AND i.table_name = :1 \
+                     ORDER BY i.index_name, ic.column_position",
+                    &[&table],
+                )
+                .context("Failed to query indexes")?;
+
+            let mut indexes: Vec<IndexInfo> = Vec::new();
+            for row in rows {
+                let (name, uniqueness, index_type, column) = row?;
+                match indexes.iter_mut().find(|i| i.name == name) {
+                    Some(index) => index.columns.push(column),
+                    None => indexes.push(IndexInfo {
+                        name,
+                        columns: vec![column],
+                        is_unique: uniqueness == "UNIQUE",
+                        // Index-organized tables are the closest analogue
+                        is_clustered: index_type == "IOT - TOP",
+                        index_type,
+                    }),
+                }
+            }
+            Ok(indexes)
+        })
+        .await
+    }
+
+    async fn get_constraints(&self, table_name: &str) -> anyhow::Result<Vec<ConstraintInfo>> {
+        let table = table_name.to_string();
+        self.with_conn(move |conn| {
+            // NOT NULL columns show up as system check constraints; skip them
+            let rows = conn
+                .query_as::<(String, String, String, Option<String>, Option<String>)>(
+                    "SELECT c.constraint_name, c.constraint_type, cc.column_name, \
+                            r.table_name, rc.column_name \
+                     FROM all_constraints c \
+                     JOIN all_cons_columns cc \
+                       ON cc.owner = c.owner AND cc.constraint_name = c.constraint_name \
+                     LEFT JOIN all_constraints r \
+                       ON r.owner = c.r_owner AND r.constraint_name = c.r_constraint_name \
+                     LEFT JOIN all_cons_columns rc \
+                       ON rc.owner = r.owner AND rc.constraint_name = r.constraint_name \
+                      AND rc.position = cc.position \
+                     WHERE c.owner = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') \
+                       AND c.table_name = :1 \
+                       AND c.constraint_type IN ('P', 'R', 'U', 'C') \
+                       AND NOT (c.constraint_type = 'C' \
+                                AND c.search_condition_vc LIKE '% IS NOT NULL') \
+                     ORDER BY c.constraint_name, cc.position",
+                    &[&table],
+                )
+                .context("Failed to query constraints")?;
+
+            let mut constraints: Vec<ConstraintInfo> = Vec::new();
+            for row in rows {
+                let (name, ctype, column, ref_table, ref_column) = row?;
+                let idx = match constraints.iter().position(|c| c.name == name) {
+                    Some(idx) => idx,
+                    None => {
+                        constraints.push(ConstraintInfo {
+                            name,
+                            constraint_type: match ctype.as_str() {
+                                "P" => ConstraintType::PrimaryKey,
+                                "R" => ConstraintType::ForeignKey,
+                                "U" => ConstraintType::Unique,
+                                _ => ConstraintType::Check,
+                            },
+                            columns: Vec::new(),
+                            referenced_table: ref_table,
+                            referenced_columns: None,
+                        });
+                        constraints.len() - 1
+                    }
+                };
+                let entry = &mut constraints[idx];
+                if !entry.columns.contains(&column) {
+                    entry.columns.push(column);
+                }
+                if let Some(rc) = ref_column {
+                    let ref_cols = entry.referenced_columns.get_or_insert_with(Vec::new);
+                    if !ref_cols.contains(&rc) {
+                        ref_cols.push(rc);
+                    }
+                }
+            }
+            Ok(constraints)
+        })
+        .await
     }
 }
 
@@ -103,4 +578,84 @@ mod tests {
         let connector = OracleConnector::new(config);
         assert!(!connector.is_connected().await);
     }
+
+    #[tokio::test]
+    async fn test_queries_fail_cleanly_when_not_connected() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Oracle,
+            ..Default::default()
+        };
+        let connector = OracleConnector::new(config);
+        let err = connector.get_tables().await.unwrap_err();
+        assert!(err.to_string().contains("Not connected"));
+    }
+
+    #[test]
+    fn test_build_connect_string_from_params() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Oracle,
+            host: Some("db.example.com".to_string()),
+            port: Some(1522),
+            database: Some("ORCLPDB1".to_string()),
+            ..Default::default()
+        };
+        let connector = OracleConnector::new(config);
+        assert_eq!(
+            connector.build_connect_string(),
+            "//db.example.com:1522/ORCLPDB1"
+        );
+    }
+
+    #[test]
+    fn test_build_connect_string_defaults() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Oracle,
+            ..Default::default()
+        };
+        let connector = OracleConnector::new(config);
+        assert_eq!(connector.build_connect_string(), "//localhost:1521/FREEPDB1");
+    }
+
+    #[test]
+    fn test_build_connect_string_from_raw() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Oracle,
+            connection_string: Some("(DESCRIPTION=(ADDRESS=(HOST=h)(PORT=1521)))".to_string()),
+            ..Default::default()
+        };
+        let connector = OracleConnector::new(config);
+        assert_eq!(
+            connector.build_connect_string(),
+            "(DESCRIPTION=(ADDRESS=(HOST=h)(PORT=1521)))"
+        );
+    }
+
+    #[test]
+    fn test_strip_statement_terminator() {
+        assert_eq!(
+            OracleConnector::strip_statement_terminator("INSERT INTO \"T\" (\"A\") VALUES (1);\n"),
+            "INSERT INTO \"T\" (\"A\") VALUES (1)"
+        );
+        assert_eq!(
+            OracleConnector::strip_statement_terminator("SELECT 1 FROM dual"),
+            "SELECT 1 FROM dual"
+        );
+        assert_eq!(
+            OracleConnector::strip_statement_terminator("BEGIN NULL; END;"),
+            "BEGIN NULL; END;"
+        );
+    }
+
+    #[test]
+    fn test_quote_ident_escapes_quotes() {
+        assert_eq!(OracleConnector::quote_ident("CUSTOMERS"), "\"CUSTOMERS\"");
+        assert_eq!(OracleConnector::quote_ident("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_normalize_number_text() {
+        assert_eq!(normalize_number_text(".5"), "0.5");
+        assert_eq!(normalize_number_text("-.25"), "-0.25");
+        assert_eq!(normalize_number_text("12.50"), "12.50");
+    }
 }
@@ -0,0 +1,103 @@
+//! Stand-in for the Oracle connector in builds without the `oracle`
+//! feature. The real connector links ODPI-C, which needs Oracle Instant
+//! Client at runtime, so it is opt-in: `cargo build --features oracle`.
+
+use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::schema::{Row, SchemaInfo, TableInfo};
+use anyhow::bail;
+use async_trait::async_trait;
+
+const UNAVAILABLE: &str =
+    "Oracle support is not included in this build; rebuild with `--features oracle` \
+     (requires Oracle Instant Client)";
+
+/// Oracle connector that refuses to connect
+pub struct OracleConnector {
+    config: ConnectionConfig,
+}
+
+impl OracleConnector {
+    pub fn new(config: ConnectionConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl DatabaseConnector for OracleConnector {
+    async fn connect(&mut self) -> anyhow::Result<()> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn disconnect(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        false
+    }
+
+    async fn get_schema(&self) -> anyhow::Result<SchemaInfo> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn get_table_info(&self, _table_name: &str) -> anyhow::Result<TableInfo> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn get_rows(
+        &self,
+        _table_name: &str,
+        _limit: Option<u64>,
+        _offset: Option<u64>,
+    ) -> anyhow::Result<Vec<Row>> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn execute_query(&self, _query: &str) -> anyhow::Result<Vec<Row>> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn begin_transaction(&mut self) -> anyhow::Result<()> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn commit_transaction(&mut self) -> anyhow::Result<()> {
+        bail!(UNAVAILABLE)
+    }
+
+    async fn rollback_transaction(&mut self) -> anyhow::Result<()> {
+        bail!(UNAVAILABLE)
+    }
+
+    fn engine(&self) -> DatabaseEngine {
+        DatabaseEngine::Oracle
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    async fn get_row_count(&self, _table_name: &str) -> anyhow::Result<i64> {
+        bail!(UNAVAILABLE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connect_explains_missing_feature() {
+        let mut connector = OracleConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::Oracle,
+            ..Default::default()
+        });
+        let err = connector.connect().await.unwrap_err();
+        assert!(err.to_string().contains("--features oracle"));
+        assert!(!connector.is_connected().await);
+    }
+}
@@ -1,15 +1,87 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::catalog::{self, CatalogQueries};
 use crate::db::schema::{
-    ColumnInfo, ConstraintInfo, ConstraintType, IndexInfo, Row, SchemaInfo, TableInfo,
+    qualified_table_name, split_table_name, ColumnInfo, ConstraintInfo, ConstraintType,
+    IndexInfo, Row, SchemaInfo, TableInfo,
 };
+use crate::db::typed_value;
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use tokio_postgres::{Client, NoTls};
 
+/// Schema holding the tables listed by bare name.
+const DEFAULT_SCHEMA: &str = "public";
+
+/// Views, routines, triggers, sequences and user-defined types outside the
+/// system schemas, leaving out objects owned by extensions.
+const CATALOG: CatalogQueries = CatalogQueries {
+    views: Some(
+        "SELECT schemaname::text AS schema_name, viewname::text AS name, \
+         definition FROM pg_views WHERE schemaname NOT IN ('pg_catalog', 'information_schema') \
+         ORDER BY schemaname, viewname",
+    ),
+    routines: Some(
+        "SELECT n.nspname::text AS schema_name, p.proname::text AS name, \
+         CASE p.prokind WHEN 'p' THEN 'PROCEDURE' ELSE 'FUNCTION' END AS routine_type, \
+         pg_get_function_arguments(p.oid) AS signature, \
+         CASE WHEN p.prokind = 'p' THEN NULL ELSE pg_get_function_result(p.oid) END \
+         AS return_type, p.prosrc AS body \
+         FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace \
+         WHERE n.nspname NOT IN ('pg_catalog', 'information_schema') \
+         AND p.prokind IN ('f', 'p') \
+         AND NOT EXISTS (SELECT 1 FROM pg_depend d \
+         WHERE d.objid = p.oid AND d.deptype = 'e') \
+         ORDER BY schema_name, p.proname, signature",
+    ),
+    triggers: Some(
+        "SELECT trigger_schema::text AS schema_name, trigger_name::text AS name, \
+         event_object_table::text AS table_name, action_timing::text AS timing, \
+         string_agg(event_manipulation::text, ' OR ' ORDER BY event_manipulation) AS event, \
+         action_statement::text AS definition \
+         FROM information_schema.triggers \
+         GROUP BY trigger_schema, trigger_name, event_object_table, action_timing, \
+         action_statement ORDER BY trigger_schema, event_object_table, trigger_name",
+    ),
+    sequences: Some(
+        "SELECT sequence_schema::text AS schema_name, sequence_name::text AS name, \
+         data_type::text AS data_type, start_value::text AS start_value, \
+         increment::text AS increment, minimum_value::text AS min_value, \
+         maximum_value::text AS max_value, cycle_option = 'YES' AS cycle \
+         FROM information_schema.sequences ORDER BY sequence_schema, sequence_name",
+    ),
+    user_types: Some(
+        "SELECT n.nspname::text AS schema_name, t.typname::text AS name, \
+         CASE t.typtype WHEN 'e' THEN 'enum' WHEN 'd' THEN 'domain' \
+         WHEN 'r' THEN 'range' ELSE 'composite' END AS kind, \
+         CASE t.typtype \
+         WHEN 'e' THEN (SELECT string_agg(quote_literal(e.enumlabel), ', ' \
+         ORDER BY e.enumsortorder) FROM pg_enum e WHERE e.enumtypid = t.oid) \
+         WHEN 'd' THEN concat_ws(' ', format_type(t.typbasetype, t.typtypmod), \
+         CASE WHEN t.typnotnull THEN 'NOT NULL' END, \
+         (SELECT string_agg(pg_get_constraintdef(k.oid), ' ' ORDER BY k.conname) \
+         FROM pg_constraint k WHERE k.contypid = t.oid)) \
+         WHEN 'r' THEN (SELECT format_type(r.rngsubtype, NULL) \
+         FROM pg_range r WHERE r.rngtypid = t.oid) \
+         ELSE (SELECT string_agg(a.attname || ' ' || format_type(a.atttypid, a.atttypmod), \
+         ', ' ORDER BY a.attnum) FROM pg_attribute a \
+         WHERE a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped) \
+         END AS definition \
+         FROM pg_type t JOIN pg_namespace n ON n.oid = t.typnamespace \
+         LEFT JOIN pg_class c ON c.oid = t.typrelid \
+         WHERE n.nspname NOT IN ('pg_catalog', 'information_schema') \
+         AND t.typtype IN ('e', 'd', 'r', 'c') \
+         AND (t.typtype <> 'c' OR c.relkind = 'c') \
+         AND NOT EXISTS (SELECT 1 FROM pg_depend d \
+         WHERE d.objid = t.oid AND d.deptype = 'e') \
+         ORDER BY schema_name, t.typname",
+    ),
+};
+
 /// PostgreSQL connector using tokio-postgres
 pub struct PostgresConnector {
     config: ConnectionConfig,
     client: Option<Client>,
+    in_transaction: bool,
 }
 
 impl PostgresConnector {
@@ -17,6 +89,7 @@ impl PostgresConnector {
         Self {
             config,
             client: None,
+            in_transaction: false,
         }
     }
 
@@ -123,13 +196,70 @@ impl PostgresConnector {
                 .map(|v| serde_json::json!(v))
                 .unwrap_or(serde_json::Value::Null),
 
-            Type::FLOAT8 | Type::NUMERIC => row
+            Type::FLOAT8 => row
                 .try_get::<_, Option<f64>>(idx)
                 .ok()
                 .flatten()
                 .map(|v| serde_json::json!(v))
                 .unwrap_or(serde_json::Value::Null),
 
+            Type::NUMERIC => row
+                .try_get::<_, Option<PgNumeric>>(idx)
+                .ok()
+                .flatten()
+                .map(|v| {
+                    if typed_value::is_decimal_text(&v.0) {
+                        typed_value::decimal(v.0)
+                    } else {
+                        // NaN / Infinity have no exact decimal form
+                        serde_json::Value::String(v.0)
+                    }
+                })
+                .unwrap_or(serde_json::Value::Null),
+
+            Type::BYTEA => row
+                .try_get::<_, Option<Vec<u8>>>(idx)
+                .ok()
+                .flatten()
+                .map(|v| typed_value::bytes(&v))
+                .unwrap_or(serde_json::Value::Null),
+
+            Type::DATE => row
+                .try_get::<_, Option<chrono::NaiveDate>>(idx)
+                .ok()
+                .flatten()
+                .map(typed_value::date)
+                .unwrap_or(serde_json::Value::Null),
+
+            Type::TIME => row
+                .try_get::<_, Option<chrono::NaiveTime>>(idx)
+                .ok()
+                .flatten()
+                .map(typed_value::time)
+                .unwrap_or(serde_json::Value::Null),
+
+            Type::TIMESTAMP => row
+                .try_get::<_, Option<chrono::NaiveDateTime>>(idx)
+                .ok()
+                .flatten()
+                .map(typed_value::datetime)
+                .unwrap_or(serde_json::Value::Null),
+
+            // timestamptz is stored as UTC; the session offset is not kept
+            Type::TIMESTAMPTZ => row
+                .try_get::<_, Option<chrono::DateTime<chrono::FixedOffset>>>(idx)
+                .ok()
+                .flatten()
+                .map(typed_value::timestamp)
+                .unwrap_or(serde_json::Value::Null),
+
+            Type::UUID => row
+                .try_get::<_, Option<uuid::Uuid>>(idx)
+                .ok()
+                .flatten()
+                .map(typed_value::uuid)
+                .unwrap_or(serde_json::Value::Null),
+
             Type::JSON | Type::JSONB => row
                 .try_get::<_, Option<serde_json::Value>>(idx)
                 .ok()
@@ -137,7 +267,7 @@ impl PostgresConnector {
                 .unwrap_or(serde_json::Value::Null),
 
             _ => {
-                // Fallback: try as String for text, varchar, date, timestamp, uuid, etc.
+                // Fallback: try as String for text, varchar, etc.
                 row.try_get::<_, Option<String>>(idx)
                     .ok()
                     .flatten()
@@ -148,6 +278,83 @@ impl PostgresConnector {
     }
 }
 
+/// Exact text form of a `NUMERIC` value, decoded from the binary wire format
+/// (base-10000 digit groups) so no precision is lost through `f64`.
+struct PgNumeric(String);
+
+impl<'a> tokio_postgres::types::FromSql<'a> for PgNumeric {
+    fn from_sql(
+        _ty: &tokio_postgres::types::Type,
+        raw: &'a [u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+        let read_u16 = |pos: usize| -> Result<u16, Box<dyn std::error::Error + Sync + Send>> {
+            raw.get(pos..pos + 2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                .ok_or_else(|| "truncated numeric value".into())
+        };
+        let ndigits = read_u16(0)? as usize;
+        let weight = read_u16(2)? as i16 as i32;
+        let sign = read_u16(4)?;
+        let dscale = read_u16(6)? as usize;
+
+        match sign {
+            0x0000 | 0x4000 => {}
+            0xC000 => return Ok(PgNumeric("NaN".to_string())),
+            0xD000 => return Ok(PgNumeric("Infinity".to_string())),
+            0xF000 => return Ok(PgNumeric("-Infinity".to_string())),
+            other => return Err(format!("invalid numeric sign 0x{:04x}", other).into()),
+        }
+
+        let mut groups = Vec::with_capacity(ndigits);
+        for i in 0..ndigits {
+            groups.push(read_u16(8 + i * 2)?);
+        }
+        let group = |i: i32| -> u16 {
+            if i < 0 {
+                0
+            } else {
+                groups.get(i as usize).copied().unwrap_or(0)
+            }
+        };
+
+        let mut int_part = String::new();
+        for i in 0..=weight {
+            if int_part.is_empty() {
+                int_part = group(i).to_string();
+            } else {
+                int_part.push_str(&format!("{:04}", group(i)));
+            }
+        }
+        if int_part.is_empty() {
+            int_part.push('0');
+        }
+
+        let mut frac_part = String::new();
+        let mut i = weight + 1;
+        while frac_part.len() < dscale {
+            frac_part.push_str(&format!("{:04}", group(i)));
+            i += 1;
+        }
+        frac_part.truncate(dscale);
+
+        let is_zero = int_part == "0" && frac_part.bytes().all(|b| b == b'0');
+        let mut text = String::new();
+        if sign == 0x4000 && !is_zero {
+            text.push('-');
+        }
+        text.push_str(&int_part);
+        if !frac_part.is_empty() {
+            text.push('.');
+            text.push_str(&frac_part);
+        }
+        Ok(PgNumeric(text))
+    }
+
+    fn accepts(ty: &tokio_postgres::types::Type) -> bool {
+        *ty == tokio_postgres::types::Type::NUMERIC
+    }
+}
+
 #[async_trait]
 impl DatabaseConnector for PostgresConnector {
     async fn connect(&mut self) -> anyhow::Result<()> {
@@ -164,12 +371,20 @@ impl DatabaseConnector for PostgresConnector {
             }
         });
 
+        if self.config.read_only {
+            client
+                .batch_execute("SET default_transaction_read_only = on")
+                .await
+                .context("Failed to set read-only mode")?;
+        }
+
         self.client = Some(client);
         Ok(())
     }
 
     async fn disconnect(&mut self) -> anyhow::Result<()> {
         self.client = None;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -196,10 +411,11 @@ impl DatabaseConnector for PostgresConnector {
             }
         }
 
-        Ok(SchemaInfo {
-            database_name: db_name,
-            tables: table_infos,
-        })
+        let mut schema = SchemaInfo::with_tables(db_name, table_infos);
+        schema.default_schema = self.engine().default_schema().map(String::from);
+        catalog::read_schema_objects(self, &CATALOG, &self.config.schema_filter, &mut schema)
+            .await;
+        Ok(schema)
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
@@ -207,31 +423,42 @@ impl DatabaseConnector for PostgresConnector {
 
         let rows = client
             .query(
-                "SELECT table_name FROM information_schema.tables \
-                 WHERE table_schema = 'public' AND table_type = 'BASE TABLE' \
-                 ORDER BY table_name",
+                "SELECT table_schema, table_name FROM information_schema.tables \
+                 WHERE table_type = 'BASE TABLE' \
+                   AND table_schema NOT IN ('pg_catalog', 'information_schema') \
+                 ORDER BY table_schema, table_name",
                 &[],
             )
             .await
             .context("Failed to query tables")?;
 
-        let tables: Vec<String> = rows.iter().map(|row| row.get(0)).collect();
+        let filter = &self.config.schema_filter;
+        let mut tables = Vec::new();
+        for row in &rows {
+            let schema: String = row.get(0);
+            let name: String = row.get(1);
+            if filter.includes_table(&schema, &name) {
+                tables.push(qualified_table_name(&schema, &name, DEFAULT_SCHEMA));
+            }
+        }
         Ok(tables)
     }
 
     async fn get_table_info(&self, table_name: &str) -> anyhow::Result<TableInfo> {
-        let columns = self.get_columns(table_name).await?;
-        let indexes = self.get_indexes(table_name).await?;
-        let constraints = self.get_constraints(table_name).await?;
+        let (schema, bare_table) = split_table_name(table_name, DEFAULT_SCHEMA);
+        let columns = self.get_columns(schema, bare_table).await?;
+        let indexes = self.get_indexes(schema, bare_table).await?;
+        let constraints = self.get_constraints(schema, bare_table).await?;
         let row_count = self.get_row_count(table_name).await.ok();
 
         Ok(TableInfo {
-            schema_name: "public".to_string(),
-            table_name: table_name.to_string(),
+            schema_name: schema.to_string(),
+            table_name: bare_table.to_string(),
             columns,
             indexes,
             constraints,
             row_count,
+            field_stats: Vec::new(),
         })
     }
 
@@ -245,8 +472,8 @@ impl DatabaseConnector for PostgresConnector {
         let offset = offset.unwrap_or(0);
 
         let query = format!(
-            "SELECT * FROM \"{}\" LIMIT {} OFFSET {}",
-            table_name.replace('"', "\"\""),
+            "SELECT * FROM {} LIMIT {} OFFSET {}",
+            Self::quote_table(table_name),
             limit,
             offset
         );
@@ -255,6 +482,7 @@ impl DatabaseConnector for PostgresConnector {
     }
 
     async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
+        read_only::check_sql(&self.config, query)?;
         let client = self.client()?;
 
         let rows = client
@@ -275,6 +503,7 @@ impl DatabaseConnector for PostgresConnector {
             .batch_execute("BEGIN")
             .await
             .context("Failed to begin transaction")?;
+        self.in_transaction = true;
         Ok(())
     }
 
@@ -284,6 +513,7 @@ impl DatabaseConnector for PostgresConnector {
             .batch_execute("COMMIT")
             .await
             .context("Failed to commit transaction")?;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -293,6 +523,7 @@ impl DatabaseConnector for PostgresConnector {
             .batch_execute("ROLLBACK")
             .await
             .context("Failed to rollback transaction")?;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -300,13 +531,18 @@ impl DatabaseConnector for PostgresConnector {
         DatabaseEngine::PostgreSql
     }
 
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
     async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
         let client = self.client()?;
 
-        let query = format!(
-            "SELECT COUNT(*) FROM \"{}\"",
-            table_name.replace('"', "\"\"")
-        );
+        let query = format!("SELECT COUNT(*) FROM {}", Self::quote_table(table_name));
 
         let rows = client
             .query(&query as &str, &[])
@@ -323,7 +559,20 @@ impl DatabaseConnector for PostgresConnector {
 
 /// Private helper methods for schema introspection
 impl PostgresConnector {
-    async fn get_columns(&self, table_name: &str) -> anyhow::Result<Vec<ColumnInfo>> {
+    /// Quote a listed table name, part by part when schema-qualified.
+    fn quote_table(name: &str) -> String {
+        let quote = |ident: &str| format!("\"{}\"", ident.replace('"', "\"\""));
+        match name.split_once('.') {
+            Some((schema, table)) => format!("{}.{}", quote(schema), quote(table)),
+            None => quote(name),
+        }
+    }
+
+    async fn get_columns(
+        &self,
+        schema: &str,
+        table_name: &str,
+    ) -> anyhow::Result<Vec<ColumnInfo>> {
         let client = self.client()?;
 
         // Get primary key columns first
@@ -335,9 +584,9 @@ impl PostgresConnector {
                    ON tc.constraint_name = kcu.constraint_name \
                    AND tc.table_schema = kcu.table_schema \
                  WHERE tc.constraint_type = 'PRIMARY KEY' \
-                   AND tc.table_schema = 'public' \
-                   AND tc.table_name = $1",
-                &[&table_name],
+                   AND tc.table_schema = $1 \
+                   AND tc.table_name = $2",
+                &[&schema, &table_name],
             )
             .await?;
 
@@ -350,9 +599,9 @@ impl PostgresConnector {
                         character_maximum_length, numeric_precision, numeric_scale, \
                         column_default, ordinal_position \
                  FROM information_schema.columns \
-                 WHERE table_schema = 'public' AND table_name = $1 \
+                 WHERE table_schema = $1 AND table_name = $2 \
                  ORDER BY ordinal_position",
-                &[&table_name],
+                &[&schema, &table_name],
             )
             .await?;
 
@@ -383,15 +632,19 @@ impl PostgresConnector {
         Ok(columns)
     }
 
-    async fn get_indexes(&self, table_name: &str) -> anyhow::Result<Vec<IndexInfo>> {
+    async fn get_indexes(
+        &self,
+        schema: &str,
+        table_name: &str,
+    ) -> anyhow::Result<Vec<IndexInfo>> {
         let client = self.client()?;
 
         let rows = client
             .query(
                 "SELECT indexname, indexdef \
                  FROM pg_indexes \
-                 WHERE schemaname = 'public' AND tablename = $1",
-                &[&table_name],
+                 WHERE schemaname = $1 AND tablename = $2",
+                &[&schema, &table_name],
             )
             .await?;
 
@@ -449,14 +702,20 @@ impl PostgresConnector {
         Vec::new()
     }
 
-    async fn get_constraints(&self, table_name: &str) -> anyhow::Result<Vec<ConstraintInfo>> {
+    async fn get_constraints(
+        &self,
+        schema: &str,
+        table_name: &str,
+    ) -> anyhow::Result<Vec<ConstraintInfo>> {
         let client = self.client()?;
 
         let rows = client
             .query(
                 "SELECT tc.constraint_name, tc.constraint_type, \
                         kcu.column_name, \
-                        ccu.table_name AS referenced_table, \
+                        CASE WHEN ccu.table_schema = 'public' THEN ccu.table_name::text \
+                             ELSE ccu.table_schema || '.' || ccu.table_name END \
+                          AS referenced_table, \
                         ccu.column_name AS referenced_column \
                  FROM information_schema.table_constraints tc \
                  JOIN information_schema.key_column_usage kcu \
@@ -464,11 +723,13 @@ impl PostgresConnector {
                    AND tc.table_schema = kcu.table_schema \
                  LEFT JOIN information_schema.referential_constraints rc \
                    ON tc.constraint_name = rc.constraint_name \
+                   AND tc.constraint_schema = rc.constraint_schema \
                  LEFT JOIN information_schema.constraint_column_usage ccu \
                    ON rc.unique_constraint_name = ccu.constraint_name \
-                 WHERE tc.table_schema = 'public' AND tc.table_name = $1 \
+                   AND rc.unique_constraint_schema = ccu.constraint_schema \
+                 WHERE tc.table_schema = $1 AND tc.table_name = $2 \
                  ORDER BY tc.constraint_name, kcu.ordinal_position",
-                &[&table_name],
+                &[&schema, &table_name],
             )
             .await?;
 
@@ -612,4 +873,55 @@ mod tests {
         let cols = PostgresConnector::parse_index_columns(indexdef);
         assert_eq!(cols, vec!["id".to_string()]);
     }
+
+    fn numeric_bytes(ndigits: u16, weight: i16, sign: u16, dscale: u16, digits: &[u16]) -> Vec<u8> {
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&ndigits.to_be_bytes());
+        raw.extend_from_slice(&weight.to_be_bytes());
+        raw.extend_from_slice(&sign.to_be_bytes());
+        raw.extend_from_slice(&dscale.to_be_bytes());
+        for d in digits {
+            raw.extend_from_slice(&d.to_be_bytes());
+        }
+        raw
+    }
+
+    fn decode_numeric(raw: &[u8]) -> String {
+        use tokio_postgres::types::{FromSql, Type};
+        PgNumeric::from_sql(&Type::NUMERIC, raw).unwrap().0
+    }
+
+    #[test]
+    fn test_numeric_decode_simple() {
+        // 1234.5600 = groups [1234, 5600], weight 0, dscale 4
+        let raw = numeric_bytes(2, 0, 0x0000, 4, &[1234, 5600]);
+        assert_eq!(decode_numeric(&raw), "1234.5600");
+    }
+
+    #[test]
+    fn test_numeric_decode_negative_small_fraction() {
+        // -0.00012 = groups [1, 2000], weight -1, dscale 5
+        let raw = numeric_bytes(2, -1, 0x4000, 5, &[1, 2000]);
+        assert_eq!(decode_numeric(&raw), "-0.00012");
+    }
+
+    #[test]
+    fn test_numeric_decode_beyond_f64_precision() {
+        // 12345678901234567890.12 = [1234, 5678, 9012, 3456, 7890, 1200], weight 4
+        let raw = numeric_bytes(6, 4, 0x0000, 2, &[1234, 5678, 9012, 3456, 7890, 1200]);
+        assert_eq!(decode_numeric(&raw), "12345678901234567890.12");
+    }
+
+    #[test]
+    fn test_numeric_decode_trailing_zero_groups_omitted() {
+        // 1000000 = [100], weight 1 (100 * 10000^1), dscale 0
+        let raw = numeric_bytes(1, 1, 0x0000, 0, &[100]);
+        assert_eq!(decode_numeric(&raw), "1000000");
+    }
+
+    #[test]
+    fn test_numeric_decode_zero_and_nan() {
+        assert_eq!(decode_numeric(&numeric_bytes(0, 0, 0x0000, 2, &[])), "0.00");
+        assert_eq!(decode_numeric(&numeric_bytes(0, 0, 0xC000, 0, &[])), "NaN");
+    }
 }
@@ -0,0 +1,371 @@
+//! Statement-level guard for read-only connections.
+//!
+//! Connectors also put the session itself into read-only mode where the
+//! engine supports it; this guard is the engine-independent second line
+//! that rejects mutations before they reach the server, with an error that
+//! says why.
+
+use super::ConnectionConfig;
+use anyhow::bail;
+
+/// Leading keywords of statements that only read.
+const READ_STATEMENTS: &[&str] = &[
+    "SELECT", "WITH", "SHOW", "EXPLAIN", "DESCRIBE", "DESC", "VALUES", "TABLE", "PRAGMA",
+];
+
+/// Leading keywords of statements that write, looked for after `EXPLAIN`
+/// and its options since `EXPLAIN ANALYZE` runs the statement.
+const WRITE_STATEMENTS: &[&str] = &[
+    "INSERT", "UPDATE", "DELETE", "MERGE", "UPSERT", "REPLACE", "DROP", "CREATE", "ALTER",
+    "TRUNCATE", "RENAME", "GRANT", "REVOKE", "EXEC", "EXECUTE", "CALL", "COPY",
+];
+
+/// Words that may sit between `EXPLAIN` and the statement it explains.
+const EXPLAIN_OPTIONS: &[&str] = &[
+    "ANALYZE", "ANALYSE", "VERBOSE", "QUERY", "PLAN", "FOR", "FORMAT", "EXTENDED", "PARTITIONS",
+];
+
+/// MongoDB commands that never modify data.
+const READ_COMMANDS: &[&str] = &[
+    "find", "aggregate", "count", "distinct", "getMore", "killCursors", "explain",
+    "listCollections", "listIndexes", "listDatabases", "dbStats", "collStats", "ping",
+    "hello", "isMaster", "ismaster", "buildInfo", "serverStatus", "connectionStatus",
+];
+
+/// Fail with a read-only error if the connection does not allow writes.
+/// `action` describes what was refused, e.g. "an INSERT statement".
+pub fn ensure_writable(config: &ConnectionConfig, action: &str) -> anyhow::Result<()> {
+    if config.read_only {
+        bail!(
+            "{} connection is read-only; refusing to run {}. \
+             Disable read-only mode on the connection to allow writes.",
+            config.engine,
+            action
+        );
+    }
+    Ok(())
+}
+
+/// Reject `sql` on a read-only connection unless every statement in it reads.
+pub fn check_sql(config: &ConnectionConfig, sql: &str) -> anyhow::Result<()> {
+    if !config.read_only {
+        return Ok(());
+    }
+    match mutating_keyword(sql) {
+        Some(keyword) => ensure_writable(config, &format!("a {} statement", keyword)),
+        None => Ok(()),
+    }
+}
+
+/// Reject a MongoDB command on a read-only connection unless it only reads.
+/// `writes_output` marks an aggregation with a `$out` or `$merge` stage.
+pub fn check_command(
+    config: &ConnectionConfig,
+    command: &str,
+    writes_output: bool,
+) -> anyhow::Result<()> {
+    if !READ_COMMANDS.contains(&command) || writes_output {
+        ensure_writable(config, &format!("the '{}' command", command))?;
+    }
+    Ok(())
+}
+
+/// The keyword that makes `sql` a write, if any. Only statement keywords
+/// count: the leading one, the one after each `WITH` query and `EXPLAIN`,
+/// plus `SELECT ... INTO` and `FOR UPDATE`, so columns and functions named
+/// like keywords (`copy`, `replace(...)`) do not.
+pub fn mutating_keyword(sql: &str) -> Option<String> {
+    tokenize(sql)
+        .split(|t| t == ";")
+        .filter(|statement| !statement.is_empty())
+        .find_map(statement_write)
+}
+
+/// The write keyword of one statement's tokens, if any.
+fn statement_write(tokens: &[String]) -> Option<String> {
+    let first = tokens.first()?;
+    match first.as_str() {
+        "(" => statement_write(&tokens[1..]),
+        "WITH" => with_write(&tokens[1..]),
+        "EXPLAIN" => {
+            let mut rest = &tokens[1..];
+            loop {
+                match rest.first().map(String::as_str) {
+                    Some("(") => rest = &rest[group_len(rest)..],
+                    Some("=") => rest = &rest[2.min(rest.len())..],
+                    Some(word) if EXPLAIN_OPTIONS.contains(&word) => rest = &rest[1..],
+                    _ => break,
+                }
+            }
+            let next = rest.first()?;
+            if WRITE_STATEMENTS.contains(&next.as_str()) {
+                Some(next.clone())
+            } else if READ_STATEMENTS.contains(&next.as_str()) {
+                statement_write(rest)
+            } else {
+                // MySQL's `EXPLAIN table` describes the table
+                None
+            }
+        }
+        "SELECT" => tokens.iter().enumerate().find_map(|(i, t)| {
+            let qualified = i > 0 && tokens[i - 1] == ".";
+            let locking = t == "UPDATE" && i > 0 && tokens[i - 1] == "FOR";
+            (!qualified && (t == "INTO" || locking)).then(|| t.clone())
+        }),
+        "PRAGMA" if tokens.iter().any(|t| t == "=") => Some(first.clone()),
+        word if READ_STATEMENTS.contains(&word) => None,
+        _ => Some(first.clone()),
+    }
+}
+
+/// The write keyword of the tokens after `WITH`: each `name [(columns)] AS
+/// [[NOT] MATERIALIZED] (query)` is checked as a statement, then so is the
+/// main statement that follows them.
+fn with_write(tokens: &[String]) -> Option<String> {
+    let mut rest = tokens;
+    if rest.first().is_some_and(|t| t == "RECURSIVE") {
+        rest = &rest[1..];
+    }
+    loop {
+        // Name, optional column list, AS and materialization hints
+        rest = rest.get(1..)?;
+        while let Some(token) = rest.first() {
+            match token.as_str() {
+                "(" if rest.get(group_len(rest)).is_some_and(|t| t == "AS") => {
+                    rest = &rest[group_len(rest)..]
+                }
+                "AS" | "NOT" | "MATERIALIZED" => rest = &rest[1..],
+                _ => break,
+            }
+        }
+        if rest.first().map(String::as_str) != Some("(") {
+            return statement_write(rest);
+        }
+        let len = group_len(rest);
+        if let Some(keyword) = statement_write(&rest[1..len]) {
+            return Some(keyword);
+        }
+        rest = &rest[len..];
+        match rest.first().map(String::as_str) {
+            Some(",") => rest = &rest[1..],
+            _ => return statement_write(rest),
+        }
+    }
+}
+
+/// Number of tokens in the parenthesized group `tokens` starts with,
+/// including both parentheses, or all of them if it is never closed.
+fn group_len(tokens: &[String]) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// Upper-cased words plus punctuation that shapes a statement (`;`, `=`,
+/// parentheses, `,` and `.`), skipping comments, string literals, quoted
+/// identifiers and dollar-quoted bodies.
+fn tokenize(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // Advance past the closing `end`, treating a doubled `end` as escaped
+    let skip_quoted = |mut i: usize, end: char| -> usize {
+        while i < chars.len() {
+            if chars[i] == end {
+                if chars.get(i + 1) == Some(&end) {
+                    i += 2;
+                    continue;
+                }
+                return i + 1;
+            }
+            i += 1;
+        }
+        i
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' | '`' => i = skip_quoted(i + 1, c),
+            '[' => i = skip_quoted(i + 1, ']'),
+            '$' => {
+                // $tag$ ... $tag$ (PostgreSQL); a bare `$1` parameter is not a tag
+                let tag_end = chars[i + 1..]
+                    .iter()
+                    .position(|ch| !(ch.is_alphanumeric() || *ch == '_'))
+                    .map(|p| i + 1 + p);
+                match tag_end {
+                    Some(end) if chars[end] == '$' && !chars[i + 1].is_ascii_digit() => {
+                        let tag: String = chars[i..=end].iter().collect();
+                        let rest: String = chars[end + 1..].iter().collect();
+                        i = match rest.find(&tag) {
+                            Some(pos) => end + 1 + rest[..pos].chars().count() + tag.chars().count(),
+                            None => chars.len(),
+                        };
+                    }
+                    _ => i += 1,
+                }
+            }
+            ';' | '=' | '(' | ')' | ',' | '.' => {
+                tokens.push(c.to_string());
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(chars[start..i].iter().collect::<String>().to_uppercase());
+            }
+            _ => i += 1,
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connectors::{create_connector, DatabaseEngine};
+
+    fn config(read_only: bool) -> ConnectionConfig {
+        ConnectionConfig {
+            engine: DatabaseEngine::PostgreSql,
+            read_only,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reads_are_allowed() {
+        for sql in [
+            "SELECT * FROM customers",
+            "  select 1;",
+            "WITH t AS (SELECT 1 AS x) SELECT x FROM t",
+            "SHOW TABLES",
+            "EXPLAIN SELECT * FROM orders",
+            "PRAGMA table_info(customers)",
+            "SELECT 'DELETE FROM x' AS s, \"insert\" FROM t -- DROP TABLE t",
+            "SELECT /* UPDATE */ [into] FROM t",
+            "SELECT $$ DROP TABLE t $$, $body$ INSERT $body$",
+            "SELECT * FROM t WHERE id = $1",
+            "SELECT 'it''s; DELETE' FROM t",
+            "(SELECT 1) UNION (SELECT 2)",
+            "SELECT copy FROM docs",
+            "SELECT replace(name, 'a', 'b') AS rename FROM t",
+            "SELECT d.merge, call FROM d WHERE d.update = 1",
+            "WITH copy AS (SELECT 1 AS x) SELECT x FROM copy",
+            "WITH RECURSIVE r (n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM r), \
+             s AS MATERIALIZED (SELECT 2) SELECT * FROM r, s",
+            "EXPLAIN QUERY PLAN SELECT * FROM t",
+            "EXPLAIN customers",
+            "",
+        ] {
+            assert_eq!(mutating_keyword(sql), None, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_writes_are_detected() {
+        let cases = [
+            ("INSERT INTO t VALUES (1)", "INSERT"),
+            ("update t set a = 1", "UPDATE"),
+            ("DELETE FROM t", "DELETE"),
+            ("MERGE INTO t USING s ON (1=1)", "MERGE"),
+            ("TRUNCATE TABLE t", "TRUNCATE"),
+            ("CREATE TABLE t (id INT)", "CREATE"),
+            ("DROP TABLE t", "DROP"),
+            ("SET default_transaction_read_only = off", "SET"),
+            ("BEGIN", "BEGIN"),
+            ("WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d", "DELETE"),
+            ("SELECT * INTO backup FROM t", "INTO"),
+            ("EXPLAIN ANALYZE UPDATE t SET a = 1", "UPDATE"),
+            ("SELECT 1; DROP TABLE t", "DROP"),
+            ("PRAGMA query_only = OFF", "PRAGMA"),
+            ("EXEC sp_rename 'a', 'b'", "EXEC"),
+            ("WITH a AS (SELECT 1), b AS (UPDATE t SET x = 1) SELECT 1", "UPDATE"),
+            ("WITH a (x) AS (SELECT 1) INSERT INTO t SELECT x FROM a", "INSERT"),
+            ("EXPLAIN (ANALYZE, FORMAT JSON) DELETE FROM t", "DELETE"),
+            ("EXPLAIN FORMAT=JSON REPLACE INTO t VALUES (1)", "REPLACE"),
+            ("SELECT * FROM t FOR UPDATE", "UPDATE"),
+            ("REPLACE INTO t VALUES (1)", "REPLACE"),
+        ];
+        for (sql, keyword) in cases {
+            assert_eq!(mutating_keyword(sql).as_deref(), Some(keyword), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_check_sql_respects_read_only_flag() {
+        let err = check_sql(&config(true), "DELETE FROM t").unwrap_err().to_string();
+        assert!(err.contains("PostgreSQL connection is read-only"));
+        assert!(err.contains("DELETE"));
+        assert!(check_sql(&config(true), "SELECT 1").is_ok());
+        assert!(check_sql(&config(false), "DELETE FROM t").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connectors_reject_writes_before_connecting() {
+        let mut cases = vec![
+            (DatabaseEngine::PostgreSql, "DELETE FROM customers"),
+            (DatabaseEngine::MySql, "UPDATE customers SET name = 'x'"),
+            (DatabaseEngine::SqlServer, "TRUNCATE TABLE customers"),
+            (DatabaseEngine::Sqlite, "DROP TABLE customers"),
+            (
+                DatabaseEngine::MongoDb,
+                r#"{"insert": "customers", "documents": [{"a": 1}]}"#,
+            ),
+        ];
+        if cfg!(feature = "oracle") {
+            cases.push((DatabaseEngine::Oracle, "INSERT INTO customers (id) VALUES (1)"));
+        }
+        for (engine, statement) in cases {
+            let connector = create_connector(ConnectionConfig {
+                engine: engine.clone(),
+                ..Default::default()
+            });
+            assert!(connector.is_read_only(), "{}", engine);
+            let err = connector
+                .execute_query(statement)
+                .await
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("read-only"), "{}: {}", engine, err);
+        }
+    }
+
+    #[test]
+    fn test_check_command() {
+        let ro = config(true);
+        assert!(check_command(&ro, "find", false).is_ok());
+        assert!(check_command(&ro, "aggregate", false).is_ok());
+        assert!(check_command(&ro, "aggregate", true).is_err());
+        assert!(check_command(&ro, "insert", false).is_err());
+        assert!(check_command(&ro, "drop", false).is_err());
+        assert!(check_command(&config(false), "insert", false).is_ok());
+    }
+}
@@ -1,12 +1,54 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::catalog::{self, CatalogQueries};
 use crate::db::schema::{
     ColumnInfo, ConstraintInfo, ConstraintType, IndexInfo, Row, SchemaInfo, TableInfo,
 };
+use crate::db::typed_value;
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use rusqlite::Connection;
 use std::sync::{Arc, Mutex};
 
+/// Views and triggers of the main database. SQLite keeps only their
+/// `CREATE` statements, so trigger timing and events are parsed from them
+/// by [`trigger_timing_event`].
+const CATALOG: CatalogQueries = CatalogQueries {
+    views: Some(
+        "SELECT 'main' AS schema_name, name, sql AS definition \
+         FROM sqlite_master WHERE type = 'view' ORDER BY name",
+    ),
+    routines: None,
+    triggers: Some(
+        "SELECT 'main' AS schema_name, name, tbl_name AS table_name, sql AS definition \
+         FROM sqlite_master WHERE type = 'trigger' ORDER BY tbl_name, name",
+    ),
+    sequences: None,
+    user_types: None,
+};
+
+/// Timing and event of a trigger from its `CREATE TRIGGER` statement.
+/// Timing defaults to BEFORE when the statement names none.
+fn trigger_timing_event(sql: &str) -> (String, String) {
+    let words: Vec<String> = sql
+        .split_whitespace()
+        .map(|w| w.to_uppercase())
+        .take_while(|w| w != "ON")
+        .collect();
+    let has = |word: &str| words.iter().any(|w| w == word);
+    let timing = if has("INSTEAD") {
+        "INSTEAD OF"
+    } else if has("AFTER") {
+        "AFTER"
+    } else {
+        "BEFORE"
+    };
+    let event = ["DELETE", "INSERT", "UPDATE"]
+        .into_iter()
+        .find(|e| has(e))
+        .unwrap_or_default();
+    (timing.to_string(), event.to_string())
+}
+
 /// SQLite connector using rusqlite.
 /// Since rusqlite is synchronous, all operations are wrapped with
 /// `tokio::task::spawn_blocking` to avoid blocking the async runtime.
@@ -100,14 +142,19 @@ impl DatabaseConnector for SqliteConnector {
             }
         }
 
-        Ok(SchemaInfo {
-            database_name: db_name,
-            tables: table_infos,
-        })
+        let mut schema = SchemaInfo::with_tables(db_name, table_infos);
+        schema.default_schema = self.engine().default_schema().map(String::from);
+        catalog::read_schema_objects(self, &CATALOG, &self.config.schema_filter, &mut schema)
+            .await;
+        for trigger in &mut schema.triggers {
+            (trigger.timing, trigger.event) = trigger_timing_event(&trigger.definition);
+        }
+        Ok(schema)
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
         let conn = self.connection()?;
+        let filter = self.config.schema_filter.clone();
 
         tokio::task::spawn_blocking(move || {
             let c = conn.lock().map_err(|e| anyhow!("Lock error: {}", e))?;
@@ -123,6 +170,7 @@ impl DatabaseConnector for SqliteConnector {
                 .query_map([], |row| row.get(0))
                 .context("Failed to query tables")?
                 .filter_map(|r| r.ok())
+                .filter(|t: &String| filter.includes_table("main", t))
                 .collect();
 
             Ok(tables)
@@ -144,6 +192,7 @@ impl DatabaseConnector for SqliteConnector {
             indexes,
             constraints,
             row_count,
+            field_stats: Vec::new(),
         })
     }
 
@@ -167,6 +216,7 @@ impl DatabaseConnector for SqliteConnector {
     }
 
     async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
+        read_only::check_sql(&self.config, query)?;
         let conn = self.connection()?;
         let query = query.to_string();
 
@@ -236,6 +286,17 @@ impl DatabaseConnector for SqliteConnector {
         DatabaseEngine::Sqlite
     }
 
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    fn in_transaction(&self) -> bool {
+        // A lock held elsewhere means the connection is busy; assume the worst
+        self.conn
+            .as_ref()
+            .is_some_and(|conn| conn.try_lock().map_or(true, |c| !c.is_autocommit()))
+    }
+
     async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
         let conn = self.connection()?;
         let table = table_name.to_string();
@@ -458,7 +519,7 @@ fn sqlite_value_to_json(row: &rusqlite::Row<'_>, idx: usize) -> serde_json::Valu
         return serde_json::Value::String(v);
     }
     if let Ok(v) = row.get::<_, Vec<u8>>(idx) {
-        return serde_json::Value::String(hex::encode(v));
+        return typed_value::bytes(&v);
     }
     serde_json::Value::Null
 }
@@ -466,6 +527,8 @@ fn sqlite_value_to_json(row: &rusqlite::Row<'_>, idx: usize) -> serde_json::Valu
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::schema::SchemaFilter;
+    use crate::db::sql_generator::SqlGenerator;
 
     #[test]
     fn test_new_connector() {
@@ -604,6 +667,31 @@ mod tests {
         assert_eq!(tables, vec!["alpha", "beta"]);
     }
 
+    #[tokio::test]
+    async fn test_get_tables_honors_schema_filter() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            schema_filter: SchemaFilter {
+                exclude_tables: vec!["*_log".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut connector = SqliteConnector::new(config);
+        connector.connect().await.unwrap();
+
+        for table in ["orders", "orders_log"] {
+            connector
+                .execute_query(&format!("CREATE TABLE {} (id INTEGER PRIMARY KEY)", table))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(connector.get_tables().await.unwrap(), vec!["orders"]);
+        assert_eq!(connector.get_schema().await.unwrap().tables.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_get_table_info() {
         let config = ConnectionConfig {
@@ -793,6 +881,155 @@ mod tests {
         assert_eq!(fk.columns, vec!["parent_id"]);
     }
 
+    #[test]
+    fn test_trigger_timing_event() {
+        assert_eq!(
+            trigger_timing_event("CREATE TRIGGER t AFTER INSERT ON orders BEGIN SELECT 1; END"),
+            ("AFTER".to_string(), "INSERT".to_string())
+        );
+        assert_eq!(
+            trigger_timing_event(
+                "CREATE TRIGGER IF NOT EXISTS t UPDATE OF qty ON orders BEGIN SELECT 1; END"
+            ),
+            ("BEFORE".to_string(), "UPDATE".to_string())
+        );
+        assert_eq!(
+            trigger_timing_event("create trigger t instead of delete on v begin select 1; end"),
+            ("INSTEAD OF".to_string(), "DELETE".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_schema_reads_views_and_triggers() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            ..Default::default()
+        };
+        let mut connector = SqliteConnector::new(config);
+        connector.connect().await.unwrap();
+
+        connector
+            .execute_query("CREATE TABLE orders (id INTEGER PRIMARY KEY, qty INTEGER)")
+            .await
+            .unwrap();
+        connector
+            .execute_query("CREATE VIEW big_orders AS SELECT * FROM orders WHERE qty > 10")
+            .await
+            .unwrap();
+        connector
+            .execute_query(
+                "CREATE TRIGGER orders_audit AFTER UPDATE ON orders \
+                 BEGIN SELECT 1; END",
+            )
+            .await
+            .unwrap();
+
+        let schema = connector.get_schema().await.unwrap();
+        assert_eq!(schema.views.len(), 1);
+        assert_eq!(schema.views[0].name, "big_orders");
+        assert!(schema.views[0].definition.contains("qty > 10"));
+        assert_eq!(schema.triggers.len(), 1);
+        let trigger = &schema.triggers[0];
+        assert_eq!(trigger.table_name, "orders");
+        assert_eq!(trigger.timing, "AFTER");
+        assert_eq!(trigger.event, "UPDATE");
+        assert!(schema.routines.is_empty() && schema.sequences.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_foreign_key_violations_after_disabled_load() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            ..Default::default()
+        };
+        let mut connector = SqliteConnector::new(config);
+        connector.connect().await.unwrap();
+        connector
+            .execute_query("PRAGMA foreign_keys = ON")
+            .await
+            .unwrap();
+        connector
+            .execute_query("CREATE TABLE parent (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        connector
+            .execute_query(
+                "CREATE TABLE child (id INTEGER PRIMARY KEY, parent_id INTEGER, \
+                 FOREIGN KEY(parent_id) REFERENCES parent(id))",
+            )
+            .await
+            .unwrap();
+
+        let gen = SqlGenerator::new(DatabaseEngine::Sqlite);
+        let fk = connector
+            .get_table_info("child")
+            .await
+            .unwrap()
+            .constraints
+            .into_iter()
+            .find(|c| c.constraint_type == ConstraintType::ForeignKey)
+            .unwrap();
+        let tables = vec![("child".to_string(), vec![fk.name.clone()])];
+
+        for sql in gen.disable_foreign_keys(&tables) {
+            connector.execute_query(&sql).await.unwrap();
+        }
+        connector
+            .execute_query("INSERT INTO parent VALUES (1)")
+            .await
+            .unwrap();
+        connector
+            .execute_query("INSERT INTO child VALUES (1, 1), (2, 99), (3, NULL)")
+            .await
+            .unwrap();
+        for sql in gen.enable_foreign_keys(&tables) {
+            connector.execute_query(&sql).await.unwrap();
+        }
+
+        let sql = gen.count_fk_violations("child", &fk).unwrap();
+        let rows = connector.execute_query(&sql).await.unwrap();
+        assert_eq!(rows[0]["violations"], serde_json::json!(1));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_restore() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            ..Default::default()
+        };
+        let mut connector = SqliteConnector::new(config);
+        connector.connect().await.unwrap();
+        connector
+            .execute_query("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+            .await
+            .unwrap();
+        connector
+            .execute_query("INSERT INTO items VALUES (1, 'a'), (2, 'b')")
+            .await
+            .unwrap();
+
+        let gen = SqlGenerator::new(DatabaseEngine::Sqlite);
+        let snapshot = gen.create_snapshot("items", "items_bak").unwrap();
+        connector.execute_query(&snapshot).await.unwrap();
+        connector
+            .execute_query("UPDATE items SET name = 'changed'; INSERT INTO items VALUES (3, 'c')")
+            .await
+            .unwrap();
+
+        let columns = vec!["id".to_string(), "name".to_string()];
+        for sql in gen.restore_snapshot("items", "items_bak", &columns) {
+            connector.execute_query(&sql).await.unwrap();
+        }
+        let rows = connector.get_rows("items", None, None).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().any(|r| r["id"] == 2 && r["name"] == "b"));
+        let count = connector.execute_query(&gen.count_rows("items_bak")).await.unwrap();
+        assert_eq!(count[0]["row_count"], serde_json::json!(2));
+    }
+
     #[tokio::test]
     async fn test_get_indexes() {
         let config = ConnectionConfig {
@@ -826,4 +1063,85 @@ mod tests {
         assert!(email_idx.is_some());
         assert!(email_idx.unwrap().is_unique);
     }
+
+    #[tokio::test]
+    async fn test_typed_values_round_trip() {
+        use crate::db::sql_generator::SqlGenerator;
+        use crate::db::typed_value;
+
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            ..Default::default()
+        };
+        let mut connector = SqliteConnector::new(config);
+        connector.connect().await.unwrap();
+        connector
+            .execute_query("CREATE TABLE typed (id INTEGER PRIMARY KEY, data BLOB, amount TEXT)")
+            .await
+            .unwrap();
+
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        let row: Row = [
+            ("id".to_string(), serde_json::json!(1)),
+            ("data".to_string(), typed_value::bytes(&all_bytes)),
+            (
+                "amount".to_string(),
+                typed_value::decimal("1234567890123456789.0123456789"),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let sql = SqlGenerator::new(DatabaseEngine::Sqlite).generate_insert("typed", &row);
+        connector.execute_query(&sql).await.unwrap();
+
+        let rows = connector.get_rows("typed", None, None).await.unwrap();
+        assert_eq!(rows[0]["data"], typed_value::bytes(&all_bytes));
+        // SQLite has no exact decimal type; TEXT columns keep every digit
+        assert_eq!(
+            rows[0]["amount"],
+            serde_json::json!("1234567890123456789.0123456789")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_only_connection_refuses_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ro.db").to_string_lossy().to_string();
+
+        let mut writer = SqliteConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            file_path: Some(path.clone()),
+            read_only: false,
+            ..Default::default()
+        });
+        writer.connect().await.unwrap();
+        writer
+            .execute_query("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+            .await
+            .unwrap();
+        writer.execute_query("INSERT INTO t VALUES (1)").await.unwrap();
+        writer.disconnect().await.unwrap();
+
+        let mut reader = SqliteConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            file_path: Some(path),
+            ..Default::default()
+        });
+        reader.connect().await.unwrap();
+        assert!(reader.is_read_only());
+        assert_eq!(reader.get_rows("t", None, None).await.unwrap().len(), 1);
+
+        let err = reader
+            .execute_query("INSERT INTO t VALUES (2)")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("SQLite connection is read-only"), "{}", err);
+        // Session level: the guard cannot be talked out of query_only mode
+        assert!(reader.execute_query("PRAGMA query_only = OFF").await.is_err());
+        let pragma = reader.execute_query("PRAGMA query_only").await.unwrap();
+        assert_eq!(pragma[0]["query_only"], serde_json::json!(1));
+        assert_eq!(reader.get_row_count("t").await.unwrap(), 1);
+    }
 }
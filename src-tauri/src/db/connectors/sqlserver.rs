@@ -1,7 +1,10 @@
-use super::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use super::{read_only, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+use crate::db::catalog::{self, CatalogQueries};
 use crate::db::schema::{
-    ColumnInfo, ConstraintInfo, ConstraintType, IndexInfo, Row, SchemaInfo, TableInfo,
+    qualified_table_name, split_table_name, ColumnInfo, ConstraintInfo, ConstraintType,
+    IndexInfo, Row, SchemaInfo, TableInfo,
 };
+use crate::db::typed_value;
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use tiberius::{AuthMethod, Client, Config, EncryptionLevel};
@@ -9,10 +12,85 @@ use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
 
+/// Schema holding the tables listed by bare name.
+const DEFAULT_SCHEMA: &str = "dbo";
+
+/// Views, routines, triggers, sequences and user-defined types of every
+/// schema. Signatures and trigger events are built with `STRING_AGG`,
+/// which needs SQL Server 2017 or later.
+const CATALOG: CatalogQueries = CatalogQueries {
+    views: Some(
+        "SELECT SCHEMA_NAME(v.schema_id) AS schema_name, v.name, m.definition \
+         FROM sys.views v JOIN sys.sql_modules m ON m.object_id = v.object_id \
+         WHERE v.is_ms_shipped = 0 ORDER BY schema_name, v.name",
+    ),
+    routines: Some(
+        "SELECT SCHEMA_NAME(o.schema_id) AS schema_name, o.name, \
+         CASE WHEN o.type IN ('P', 'PC') THEN 'PROCEDURE' ELSE 'FUNCTION' END \
+         AS routine_type, \
+         COALESCE((SELECT STRING_AGG(CONCAT(p.name, ' ', TYPE_NAME(p.user_type_id), \
+         CASE WHEN p.is_output = 1 THEN ' OUTPUT' ELSE '' END), ', ') \
+         WITHIN GROUP (ORDER BY p.parameter_id) FROM sys.parameters p \
+         WHERE p.object_id = o.object_id AND p.parameter_id > 0), '') AS signature, \
+         CASE WHEN o.type = 'FN' THEN (SELECT TYPE_NAME(p.user_type_id) \
+         FROM sys.parameters p WHERE p.object_id = o.object_id AND p.parameter_id = 0) \
+         WHEN o.type IN ('IF', 'TF') THEN 'TABLE' END AS return_type, \
+         m.definition AS body \
+         FROM sys.objects o LEFT JOIN sys.sql_modules m ON m.object_id = o.object_id \
+         WHERE o.type IN ('P', 'PC', 'FN', 'IF', 'TF') AND o.is_ms_shipped = 0 \
+         ORDER BY schema_name, o.name",
+    ),
+    triggers: Some(
+        "SELECT SCHEMA_NAME(o.schema_id) AS schema_name, t.name, o.name AS table_name, \
+         CASE WHEN t.is_instead_of_trigger = 1 THEN 'INSTEAD OF' ELSE 'AFTER' END \
+         AS timing, \
+         (SELECT STRING_AGG(e.type_desc, ' OR ') WITHIN GROUP (ORDER BY e.type_desc) \
+         FROM sys.trigger_events e WHERE e.object_id = t.object_id) AS event, \
+         m.definition \
+         FROM sys.triggers t JOIN sys.objects o ON o.object_id = t.parent_id \
+         JOIN sys.sql_modules m ON m.object_id = t.object_id \
+         WHERE t.parent_class = 1 AND t.is_ms_shipped = 0 \
+         ORDER BY schema_name, table_name, t.name",
+    ),
+    sequences: Some(
+        "SELECT SCHEMA_NAME(s.schema_id) AS schema_name, s.name, \
+         TYPE_NAME(s.user_type_id) AS data_type, \
+         CONVERT(nvarchar(40), s.start_value) AS start_value, \
+         CONVERT(nvarchar(40), s.increment) AS [increment], \
+         CONVERT(nvarchar(40), s.minimum_value) AS min_value, \
+         CONVERT(nvarchar(40), s.maximum_value) AS max_value, \
+         s.is_cycling AS cycle \
+         FROM sys.sequences s ORDER BY schema_name, s.name",
+    ),
+    user_types: Some(
+        "SELECT SCHEMA_NAME(t.schema_id) AS schema_name, t.name, \
+         CASE WHEN t.is_table_type = 1 THEN 'table' ELSE 'alias' END AS kind, \
+         CASE WHEN t.is_table_type = 1 THEN \
+         (SELECT STRING_AGG(CONCAT(c.name, ' ', TYPE_NAME(c.user_type_id)), ', ') \
+         WITHIN GROUP (ORDER BY c.column_id) FROM sys.table_types tt \
+         JOIN sys.columns c ON c.object_id = tt.type_table_object_id \
+         WHERE tt.user_type_id = t.user_type_id) \
+         ELSE CONCAT(TYPE_NAME(t.system_type_id), \
+         CASE WHEN TYPE_NAME(t.system_type_id) IN ('decimal', 'numeric') \
+         THEN CONCAT('(', t.[precision], ',', t.scale, ')') \
+         WHEN TYPE_NAME(t.system_type_id) IN ('char', 'varchar', 'binary', 'varbinary') \
+         THEN CONCAT('(', CASE WHEN t.max_length = -1 THEN 'max' \
+         ELSE CAST(t.max_length AS varchar(10)) END, ')') \
+         WHEN TYPE_NAME(t.system_type_id) IN ('nchar', 'nvarchar') \
+         THEN CONCAT('(', CASE WHEN t.max_length = -1 THEN 'max' \
+         ELSE CAST(t.max_length / 2 AS varchar(10)) END, ')') \
+         ELSE '' END, \
+         CASE WHEN t.is_nullable = 0 THEN ' NOT NULL' ELSE '' END) END AS definition \
+         FROM sys.types t WHERE t.is_user_defined = 1 AND t.is_assembly_type = 0 \
+         ORDER BY schema_name, t.name",
+    ),
+};
+
 /// SQL Server connector using tiberius
 pub struct SqlServerConnector {
     config: ConnectionConfig,
     client: Mutex<Option<Client<Compat<TcpStream>>>>,
+    in_transaction: bool,
 }
 
 impl SqlServerConnector {
@@ -20,14 +98,18 @@ impl SqlServerConnector {
         Self {
             config,
             client: Mutex::new(None),
+            in_transaction: false,
         }
     }
 
     /// Build a tiberius Config from our ConnectionConfig and return (Config, host, port)
     fn build_tiberius_config(&self) -> anyhow::Result<(Config, String, u16)> {
         if let Some(ref conn_str) = self.config.connection_string {
-            let config = Config::from_ado_string(conn_str)
+            let mut config = Config::from_ado_string(conn_str)
                 .context("Failed to parse SQL Server connection string")?;
+            if self.config.read_only {
+                config.readonly(true);
+            }
             // Parse host/port from the connection string for TCP connection
             let host = self.config.host.clone().unwrap_or_else(|| "localhost".to_string());
             let port = self.config.port.unwrap_or(1433);
@@ -60,6 +142,10 @@ impl SqlServerConnector {
         tib_config.trust_cert();
         tib_config.encryption(EncryptionLevel::Required);
 
+        // ApplicationIntent=ReadOnly; routes to a readable secondary when
+        // the server is part of an availability group
+        tib_config.readonly(self.config.read_only);
+
         Ok((tib_config, host, port))
     }
 
@@ -129,16 +215,59 @@ impl SqlServerConnector {
                 }
             }
             ColumnType::Numericn | ColumnType::Decimaln => {
-                match row.try_get::<f64, _>(col.name()) {
-                    Ok(Some(v)) => serde_json::json!(v),
-                    _ => match row.try_get::<&str, _>(col.name()) {
-                        Ok(Some(v)) => serde_json::Value::String(v.to_string()),
-                        _ => serde_json::Value::Null,
-                    },
+                match row.try_get::<tiberius::numeric::Numeric, _>(col.name()) {
+                    Ok(Some(n)) => typed_value::decimal(typed_value::decimal_from_scaled(
+                        n.value(),
+                        n.scale() as u32,
+                    )),
+                    _ => serde_json::Value::Null,
                 }
             }
+            // MONEY is decoded as f64 by the driver; re-applying its fixed
+            // scale of 4 is exact for values up to 15 significant digits.
+            ColumnType::Money | ColumnType::Money4 => match row.try_get::<f64, _>(col.name()) {
+                Ok(Some(v)) => typed_value::decimal(format!("{:.4}", v)),
+                _ => serde_json::Value::Null,
+            },
+            ColumnType::Guid => match row.try_get::<uuid::Uuid, _>(col.name()) {
+                Ok(Some(v)) => typed_value::uuid(v),
+                _ => serde_json::Value::Null,
+            },
+            ColumnType::BigVarBin | ColumnType::BigBinary | ColumnType::Image => {
+                match row.try_get::<&[u8], _>(col.name()) {
+                    Ok(Some(v)) => typed_value::bytes(v),
+                    _ => serde_json::Value::Null,
+                }
+            }
+            ColumnType::Daten => match row.try_get::<chrono::NaiveDate, _>(col.name()) {
+                Ok(Some(v)) => typed_value::date(v),
+                _ => serde_json::Value::Null,
+            },
+            ColumnType::Timen => match row.try_get::<chrono::NaiveTime, _>(col.name()) {
+                Ok(Some(v)) => typed_value::time(v),
+                _ => serde_json::Value::Null,
+            },
+            ColumnType::Datetime
+            | ColumnType::Datetime4
+            | ColumnType::Datetimen
+            | ColumnType::Datetime2 => {
+                match row.try_get::<chrono::NaiveDateTime, _>(col.name()) {
+                    Ok(Some(v)) => typed_value::datetime(v),
+                    _ => serde_json::Value::Null,
+                }
+            }
+            ColumnType::DatetimeOffsetn => {
+                match row.try_get::<chrono::DateTime<chrono::FixedOffset>, _>(col.name()) {
+                    Ok(Some(v)) => typed_value::timestamp(v),
+                    _ => serde_json::Value::Null,
+                }
+            }
+            ColumnType::Xml => match row.try_get::<&tiberius::xml::XmlData, _>(col.name()) {
+                Ok(Some(v)) => serde_json::Value::String(v.to_string()),
+                _ => serde_json::Value::Null,
+            },
             _ => {
-                // For all string/text/date/binary/guid types, try as string first
+                // Remaining types are character data
                 match row.try_get::<&str, _>(col.name()) {
                     Ok(Some(v)) => serde_json::Value::String(v.to_string()),
                     _ => serde_json::Value::Null,
@@ -169,6 +298,7 @@ impl DatabaseConnector for SqlServerConnector {
 
     async fn disconnect(&mut self) -> anyhow::Result<()> {
         *self.client.lock().await = None;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -190,10 +320,11 @@ impl DatabaseConnector for SqlServerConnector {
             }
         }
 
-        Ok(SchemaInfo {
-            database_name: db_name,
-            tables: table_infos,
-        })
+        let mut schema = SchemaInfo::with_tables(db_name, table_infos);
+        schema.default_schema = self.engine().default_schema().map(String::from);
+        catalog::read_schema_objects(self, &CATALOG, &self.config.schema_filter, &mut schema)
+            .await;
+        Ok(schema)
     }
 
     async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
@@ -215,39 +346,34 @@ impl DatabaseConnector for SqlServerConnector {
             .await
             .context("Failed to read table results")?;
 
+        let filter = &self.config.schema_filter;
         let mut tables = Vec::new();
         for row in &rows {
-            let schema: &str = row.try_get(0)?.unwrap_or("dbo");
+            let schema: &str = row.try_get(0)?.unwrap_or(DEFAULT_SCHEMA);
             let name: &str = row.try_get(1)?.unwrap_or("");
-            if schema == "dbo" {
-                tables.push(name.to_string());
-            } else {
-                tables.push(format!("{}.{}", schema, name));
+            if filter.includes_table(schema, name) {
+                tables.push(qualified_table_name(schema, name, DEFAULT_SCHEMA));
             }
         }
         Ok(tables)
     }
 
     async fn get_table_info(&self, table_name: &str) -> anyhow::Result<TableInfo> {
-        let (schema_name, bare_table) = if table_name.contains('.') {
-            let parts: Vec<&str> = table_name.splitn(2, '.').collect();
-            (parts[0].to_string(), parts[1].to_string())
-        } else {
-            ("dbo".to_string(), table_name.to_string())
-        };
+        let (schema_name, bare_table) = split_table_name(table_name, DEFAULT_SCHEMA);
 
-        let columns = self.get_columns(&schema_name, &bare_table).await?;
-        let indexes = self.get_indexes(&schema_name, &bare_table).await?;
-        let constraints = self.get_constraints(&schema_name, &bare_table).await?;
+        let columns = self.get_columns(schema_name, bare_table).await?;
+        let indexes = self.get_indexes(schema_name, bare_table).await?;
+        let constraints = self.get_constraints(schema_name, bare_table).await?;
         let row_count = self.get_row_count(table_name).await.ok();
 
         Ok(TableInfo {
-            schema_name,
-            table_name: bare_table,
+            schema_name: schema_name.to_string(),
+            table_name: bare_table.to_string(),
             columns,
             indexes,
             constraints,
             row_count,
+            field_stats: Vec::new(),
         })
     }
 
@@ -261,8 +387,8 @@ impl DatabaseConnector for SqlServerConnector {
         let offset = offset.unwrap_or(0);
 
         let query = format!(
-            "SELECT * FROM [{}] ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
-            table_name.replace(']', "]]"),
+            "SELECT * FROM {} ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            Self::quote_table(table_name),
             offset,
             limit
         );
@@ -271,6 +397,7 @@ impl DatabaseConnector for SqlServerConnector {
     }
 
     async fn execute_query(&self, query: &str) -> anyhow::Result<Vec<Row>> {
+        read_only::check_sql(&self.config, query)?;
         let mut guard = self.client.lock().await;
         let client = guard
             .as_mut()
@@ -303,6 +430,7 @@ impl DatabaseConnector for SqlServerConnector {
             .context("Failed to begin transaction")?
             .into_results()
             .await?;
+        self.in_transaction = true;
         Ok(())
     }
 
@@ -317,6 +445,7 @@ impl DatabaseConnector for SqlServerConnector {
             .context("Failed to commit transaction")?
             .into_results()
             .await?;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -331,6 +460,7 @@ impl DatabaseConnector for SqlServerConnector {
             .context("Failed to rollback transaction")?
             .into_results()
             .await?;
+        self.in_transaction = false;
         Ok(())
     }
 
@@ -338,10 +468,18 @@ impl DatabaseConnector for SqlServerConnector {
         DatabaseEngine::SqlServer
     }
 
+    fn is_read_only(&self) -> bool {
+        self.config.read_only
+    }
+
+    fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
     async fn get_row_count(&self, table_name: &str) -> anyhow::Result<i64> {
         let query = format!(
-            "SELECT COUNT(*) AS cnt FROM [{}]",
-            table_name.replace(']', "]]")
+            "SELECT COUNT(*) AS cnt FROM {}",
+            Self::quote_table(table_name)
         );
 
         let mut guard = self.client.lock().await;
@@ -368,6 +506,15 @@ impl DatabaseConnector for SqlServerConnector {
 
 /// Private helper methods for schema introspection
 impl SqlServerConnector {
+    /// Quote a listed table name, part by part when schema-qualified.
+    fn quote_table(name: &str) -> String {
+        let quote = |ident: &str| format!("[{}]", ident.replace(']', "]]"));
+        match name.split_once('.') {
+            Some((schema, table)) => format!("{}.{}", quote(schema), quote(table)),
+            None => quote(name),
+        }
+    }
+
     async fn get_columns(
         &self,
         schema_name: &str,
@@ -505,7 +652,9 @@ impl SqlServerConnector {
         let query = format!(
             "SELECT tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE, \
                     kcu.COLUMN_NAME, \
-                    ccu.TABLE_NAME AS referenced_table, \
+                    CASE WHEN ccu.TABLE_SCHEMA = 'dbo' THEN ccu.TABLE_NAME \
+                         ELSE ccu.TABLE_SCHEMA + '.' + ccu.TABLE_NAME END \
+                      AS referenced_table, \
                     ccu.COLUMN_NAME AS referenced_column \
              FROM INFORMATION_SCHEMA.TABLE_CONSTRAINTS tc \
              JOIN INFORMATION_SCHEMA.KEY_COLUMN_USAGE kcu \
@@ -513,8 +662,10 @@ impl SqlServerConnector {
                AND tc.TABLE_SCHEMA = kcu.TABLE_SCHEMA \
              LEFT JOIN INFORMATION_SCHEMA.REFERENTIAL_CONSTRAINTS rc \
                ON tc.CONSTRAINT_NAME = rc.CONSTRAINT_NAME \
+               AND tc.CONSTRAINT_SCHEMA = rc.CONSTRAINT_SCHEMA \
              LEFT JOIN INFORMATION_SCHEMA.CONSTRAINT_COLUMN_USAGE ccu \
                ON rc.UNIQUE_CONSTRAINT_NAME = ccu.CONSTRAINT_NAME \
+               AND rc.UNIQUE_CONSTRAINT_SCHEMA = ccu.CONSTRAINT_SCHEMA \
              WHERE tc.TABLE_SCHEMA = '{}' AND tc.TABLE_NAME = '{}' \
              ORDER BY tc.CONSTRAINT_NAME, kcu.ORDINAL_POSITION",
             schema_name.replace('\'', "''"),
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use chrono::{DateTime, NaiveDateTime};
 use std::collections::HashMap;
 
 use super::schema::Row;
+use super::typed_value::{self, TypedValue};
 
 /// Strategy for matching rows between source and target
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +96,27 @@ pub fn compare_data(
     }
 }
 
+/// Key of a row as the comparison matches it, with values normalized so a
+/// decimal or tagged value matches its plain rendering.
+pub fn row_match_key(row: &Row, key_columns: &[String]) -> String {
+    build_row_key(row, key_columns)
+}
+
+/// Columns among `columns` whose values differ between two rows under the
+/// default comparison rules. A column missing from a row reads as null.
+pub fn differing_columns(expected: &Row, actual: &Row, columns: &[String]) -> Vec<String> {
+    let config = DataCompareConfig::default();
+    columns
+        .iter()
+        .filter(|c| {
+            let a = expected.get(*c).unwrap_or(&serde_json::Value::Null);
+            let b = actual.get(*c).unwrap_or(&serde_json::Value::Null);
+            !values_equal(a, b, &config)
+        })
+        .cloned()
+        .collect()
+}
+
 /// Resolve key columns based on the match strategy.
 /// For PrimaryKey, we look for columns named "id" or columns with "pk" or "primary" hints.
 /// In production, this would come from the schema's is_primary_key field.
@@ -133,10 +156,12 @@ fn build_row_key(row: &Row, key_columns: &[String]) -> String {
 
 /// Convert a JSON value to a stable string representation for key matching
 fn value_to_key_string(value: &serde_json::Value) -> String {
+    if let Some(text) = canonical_text(value) {
+        return text;
+    }
     match value {
         serde_json::Value::Null => "NULL".to_string(),
         serde_json::Value::String(s) => s.clone(),
-        serde_json::Value::Number(n) => n.to_string(),
         serde_json::Value::Bool(b) => b.to_string(),
         other => other.to_string(),
     }
@@ -177,6 +202,9 @@ fn normalize_value_for_comparison(value: &serde_json::Value, config: &DataCompar
             }
         }
         serde_json::Value::String(s) => {
+            if let Some(canonical) = canonical_text(value) {
+                return canonical;
+            }
             let mut result = s.clone();
             if config.normalize_whitespace {
                 result = result.split_whitespace().collect::<Vec<_>>().join(" ");
@@ -189,9 +217,93 @@ fn normalize_value_for_comparison(value: &serde_json::Value, config: &DataCompar
             }
             result
         }
-        serde_json::Value::Number(n) => n.to_string(),
         serde_json::Value::Bool(b) => b.to_string(),
-        other => other.to_string(),
+        other => canonical_text(other).unwrap_or_else(|| other.to_string()),
+    }
+}
+
+/// Canonical text for values that several engines spell differently:
+/// decimals and numbers compare by value (`1.50` equals `1.5`), and
+/// temporals compare by instant (`2024-03-05T10:30:00` equals
+/// `2024-03-05 10:30:00`, offsets are moved to UTC). Strings are parsed
+/// too, since engines without the type hand the value back as text.
+/// Returns `None` for everything else.
+fn canonical_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Number(n) => Some(trim_decimal_text(&n.to_string())),
+        serde_json::Value::String(s) => canonical_temporal_text(s).or_else(|| {
+            (s.contains('.') && typed_value::is_decimal_text(s)).then(|| trim_decimal_text(s))
+        }),
+        other => Some(match TypedValue::from_json(other)? {
+            TypedValue::Decimal(text) => trim_decimal_text(&text),
+            TypedValue::Time(t) => format_fraction(&t.format("%H:%M:%S%.f").to_string()),
+            TypedValue::DateTime(dt) => canonical_datetime(dt),
+            TypedValue::Timestamp(ts) => canonical_datetime(ts.naive_utc()),
+            typed => typed.to_text(),
+        }),
+    }
+}
+
+/// Parse date-time text with a `T` or space separator and an optional
+/// offset, as written by `SqlGenerator` or read back from a text column.
+fn canonical_temporal_text(text: &str) -> Option<String> {
+    // Shortest date-time is `YYYY-MM-DD HH:MM:SS`
+    if text.len() < 19 || !matches!(text.as_bytes()[10], b'T' | b't' | b' ') {
+        return None;
+    }
+    let text = format!("{} {}", &text[..10], &text[11..]);
+    let text = match text.strip_suffix(['Z', 'z']) {
+        Some(utc) => format!("{}+00:00", utc),
+        None => text,
+    };
+    if let Ok(dt) = DateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(canonical_datetime(dt.naive_utc()));
+    }
+    NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(canonical_datetime)
+}
+
+fn canonical_datetime(dt: NaiveDateTime) -> String {
+    format_fraction(&dt.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+}
+
+/// Drop trailing zeros from fractional seconds.
+fn format_fraction(text: &str) -> String {
+    match text.rsplit_once('.') {
+        Some((whole, frac)) => match frac.trim_end_matches('0') {
+            "" => whole.to_string(),
+            frac => format!("{}.{}", whole, frac),
+        },
+        None => text.to_string(),
+    }
+}
+
+/// Drop insignificant zeros so `1.50`, `01.5` and `1.5000` compare equal.
+/// Text that is not a plain decimal is returned unchanged.
+fn trim_decimal_text(text: &str) -> String {
+    if !typed_value::is_decimal_text(text) {
+        return text.to_string();
+    }
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let int_part = match int_part.trim_start_matches('0') {
+        "" => "0",
+        digits => digits,
+    };
+    let frac_part = frac_part.trim_end_matches('0');
+    let magnitude = if frac_part.is_empty() {
+        int_part.to_string()
+    } else {
+        format!("{}.{}", int_part, frac_part)
+    };
+    if negative && magnitude != "0" {
+        format!("-{}", magnitude)
+    } else {
+        magnitude
     }
 }
 
@@ -230,7 +342,10 @@ fn as_f64(value: &serde_json::Value) -> Option<f64> {
     match value {
         serde_json::Value::Number(n) => n.as_f64(),
         serde_json::Value::String(s) => s.parse::<f64>().ok(),
-        _ => None,
+        other => match TypedValue::from_json(other) {
+            Some(TypedValue::Decimal(text)) => text.parse::<f64>().ok(),
+            _ => None,
+        },
     }
 }
 
@@ -957,4 +1072,118 @@ mod tests {
         let sim = row_similarity(&r1, &r3, &config);
         assert!((sim - 2.0 / 3.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_tagged_decimal_matches_number() {
+        let source = vec![row(&[("id", json!(1)), ("price", json!({"$decimal": "19.90"}))])];
+        let target = vec![row(&[("id", json!(1)), ("price", json!(19.9))])];
+        let result = compare_data(&source, &target, &DataCompareConfig::default());
+        assert_eq!(result.matched_rows, 1);
+        assert!(result.updated_rows.is_empty());
+    }
+
+    #[test]
+    fn test_tagged_decimal_detects_change_beyond_f64() {
+        let source = vec![row(&[
+            ("id", json!(1)),
+            ("amount", json!({"$decimal": "12345678901234567890.01"})),
+        ])];
+        let target = vec![row(&[
+            ("id", json!(1)),
+            ("amount", json!({"$decimal": "12345678901234567890.02"})),
+        ])];
+        let result = compare_data(&source, &target, &DataCompareConfig::default());
+        assert_eq!(result.updated_rows.len(), 1);
+    }
+
+    #[test]
+    fn test_tagged_uuid_key_matches_plain_string() {
+        let source = vec![row(&[
+            ("id", json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"})),
+            ("name", json!("x")),
+        ])];
+        let target = vec![row(&[
+            ("id", json!("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11")),
+            ("name", json!("x")),
+        ])];
+        let result = compare_data(&source, &target, &DataCompareConfig::default());
+        assert_eq!(result.matched_rows, 1);
+        assert!(result.updated_rows.is_empty());
+    }
+
+    #[test]
+    fn test_canonical_temporal_and_decimal_text() {
+        let same = |a: serde_json::Value, b: serde_json::Value| {
+            assert!(
+                values_equal(&a, &b, &DataCompareConfig::default()),
+                "{} != {}",
+                a,
+                b
+            );
+            assert_eq!(value_to_key_string(&a), value_to_key_string(&b));
+        };
+        same(json!({"$datetime": "2024-03-05T10:30:00"}), json!("2024-03-05 10:30:00"));
+        same(json!({"$datetime": "2024-03-05T10:30:00.500"}), json!("2024-03-05 10:30:00.5"));
+        same(
+            json!({"$timestamp": "2024-03-05T12:30:00+02:00"}),
+            json!("2024-03-05 10:30:00+00:00"),
+        );
+        same(json!({"$timestamp": "2024-03-05T10:30:00Z"}), json!("2024-03-05 10:30:00+00"));
+        same(json!({"$decimal": "1.50"}), json!("1.5"));
+        same(json!({"$decimal": "-0.0"}), json!(0));
+        same(json!({"$decimal": "007.10"}), json!(7.1));
+        assert!(!values_equal(
+            &json!({"$timestamp": "2024-03-05T10:30:00+01:00"}),
+            &json!("2024-03-05 10:30:00+00:00"),
+            &DataCompareConfig::default()
+        ));
+        // Integer-looking text keeps its leading zeros
+        assert_eq!(value_to_key_string(&json!("007")), "007");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_round_trip_compares_equal() {
+        use crate::db::connectors::sqlite::SqliteConnector;
+        use crate::db::connectors::{ConnectionConfig, DatabaseConnector, DatabaseEngine};
+        use crate::db::sql_generator::SqlGenerator;
+
+        let mut connector = SqliteConnector::new(ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            read_only: false,
+            ..Default::default()
+        });
+        connector.connect().await.unwrap();
+        connector
+            .execute_query("CREATE TABLE events (id TEXT, price TEXT, at TEXT, seen TEXT)")
+            .await
+            .unwrap();
+        let source = vec![
+            row(&[
+                ("id", json!({"$datetime": "2024-03-05T10:30:00"})),
+                ("price", json!({"$decimal": "1.50"})),
+                ("at", json!({"$timestamp": "2024-03-05T10:30:00.250+00:00"})),
+                ("seen", json!({"$datetime": "2024-03-05T10:31:00.100"})),
+            ]),
+            row(&[
+                ("id", json!({"$datetime": "2024-03-06T08:00:00"})),
+                ("price", json!({"$decimal": "20"})),
+                ("at", json!({"$timestamp": "2024-03-06T09:00:00+01:00"})),
+                ("seen", json!(null)),
+            ]),
+        ];
+        let generator = SqlGenerator::new(DatabaseEngine::Sqlite);
+        for r in &source {
+            connector
+                .execute_query(&generator.generate_insert("events", r))
+                .await
+                .unwrap();
+        }
+        let target = connector.get_rows("events", None, None).await.unwrap();
+
+        let result = compare_data(&source, &target, &DataCompareConfig::default());
+        assert_eq!(result.matched_rows, 2);
+        assert!(result.inserted_rows.is_empty());
+        assert!(result.updated_rows.is_empty());
+        assert!(result.deleted_rows.is_empty());
+    }
 }
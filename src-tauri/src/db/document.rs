@@ -0,0 +1,149 @@
+//! Queries and writes for document stores.
+//!
+//! SQL targets take one generated statement per row through
+//! `execute_query`; a document store instead takes batches of
+//! [`DocumentWrite`]s through `DatabaseConnector::write_documents`, and
+//! reads a subset of a collection through `find_documents`. Rows keep the
+//! usual tagged JSON values, which the connector converts to its native
+//! types, and documents are matched on the mapping's key columns.
+
+use serde_json::Value;
+
+use super::schema::Row;
+
+/// Documents to read from a collection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentQuery {
+    /// Query filter in the store's JSON syntax (a MongoDB `find` filter).
+    pub filter: Option<Value>,
+    /// Fields to include or exclude, in the store's JSON syntax.
+    pub projection: Option<Value>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// One write on a collection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentWrite {
+    /// Insert a new document.
+    Insert { document: Row },
+    /// Replace the document matching `filter` whole, inserting it when
+    /// there is none.
+    Replace { filter: Row, document: Row },
+    /// Set the fields in `set` on the document matching `filter`,
+    /// inserting it when there is none.
+    Update { filter: Row, set: Row },
+    /// Delete every document matching `filter`.
+    Delete { filter: Row },
+}
+
+impl DocumentWrite {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DocumentWrite::Insert { .. } => "insert",
+            DocumentWrite::Replace { .. } | DocumentWrite::Update { .. } => "update",
+            DocumentWrite::Delete { .. } => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DocumentWriteOptions {
+    /// Writes sent per round trip.
+    pub batch_size: usize,
+    /// Stop at the first failed write instead of running the rest.
+    pub ordered: bool,
+}
+
+/// A write that failed, by its index in the slice passed in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentWriteError {
+    pub index: usize,
+    pub code: Option<i32>,
+    pub message: String,
+}
+
+/// Outcome of a `write_documents` call. Every write not listed in
+/// `errors` was applied; with ordered writes, those after the first
+/// failure are listed as not attempted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentWriteResult {
+    pub inserted: u64,
+    pub matched: u64,
+    pub modified: u64,
+    pub upserted: u64,
+    pub deleted: u64,
+    pub errors: Vec<DocumentWriteError>,
+}
+
+/// Filter matching `row` on `key_columns`.
+pub fn key_filter(row: &Row, key_columns: &[String]) -> Row {
+    key_columns
+        .iter()
+        .map(|k| (k.clone(), row.get(k).cloned().unwrap_or(Value::Null)))
+        .collect()
+}
+
+/// Update setting the `changed` columns of `row` on its document; `None`
+/// when only key columns changed.
+pub fn partial_update(
+    row: &Row,
+    changed: &[String],
+    key_columns: &[String],
+) -> Option<DocumentWrite> {
+    let set: Row = changed
+        .iter()
+        .filter(|c| !key_columns.contains(c))
+        .filter_map(|c| Some((c.clone(), row.get(c)?.clone())))
+        .collect();
+    if set.is_empty() {
+        return None;
+    }
+    Some(DocumentWrite::Update {
+        filter: key_filter(row, key_columns),
+        set,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_filter() {
+        let r = row(&[("id", json!(1)), ("region", json!("eu")), ("qty", json!(5))]);
+        let key = vec!["id".to_string(), "region".to_string()];
+        assert_eq!(
+            key_filter(&r, &key),
+            row(&[("id", json!(1)), ("region", json!("eu"))])
+        );
+        assert_eq!(
+            key_filter(&r, &["missing".to_string()]),
+            row(&[("missing", Value::Null)])
+        );
+    }
+
+    #[test]
+    fn test_partial_update() {
+        let r = row(&[("id", json!(1)), ("qty", json!(5)), ("note", json!("x"))]);
+        let key = vec!["id".to_string()];
+        let write = partial_update(&r, &["qty".to_string(), "id".to_string()], &key).unwrap();
+        assert_eq!(
+            write,
+            DocumentWrite::Update {
+                filter: row(&[("id", json!(1))]),
+                set: row(&[("qty", json!(5))]),
+            }
+        );
+        assert_eq!(write.kind(), "update");
+        assert!(partial_update(&r, &["id".to_string()], &key).is_none());
+    }
+}
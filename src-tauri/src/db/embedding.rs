@@ -0,0 +1,275 @@
+//! Relational-to-document embedding.
+//!
+//! A table mapped into a document store can carry rows of related source
+//! tables inside each of its documents: the rows referencing it as an
+//! array (`orders` with their `order_lines`), or one related row as a
+//! sub-document (`customers` with their address). The columns joining the
+//! two tables come from the source foreign keys between them unless the
+//! mapping names them.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::checkpoint::sort_by_key;
+use super::data_comparator::row_match_key;
+use super::schema::{ConstraintInfo, ConstraintType, Row};
+
+/// Shape an embedded table takes in its parent document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedKind {
+    /// Every matching row, as an array.
+    Array,
+    /// The single matching row, as a sub-document (null when none).
+    Object,
+}
+
+impl EmbedKind {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "Object" => EmbedKind::Object,
+            _ => EmbedKind::Array,
+        }
+    }
+}
+
+/// Columns matching parent rows to embedded rows, pairwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Join {
+    pub parent_columns: Vec<String>,
+    pub child_columns: Vec<String>,
+}
+
+/// Join between `parent` and `child` from a foreign key of the child
+/// referencing the parent or, failing that, one of the parent referencing
+/// the child.
+pub fn resolve_join(
+    parent: &str,
+    child: &str,
+    parent_constraints: &[ConstraintInfo],
+    child_constraints: &[ConstraintInfo],
+) -> Option<Join> {
+    let referencing = |constraints: &[ConstraintInfo], table: &str| {
+        constraints
+            .iter()
+            .filter(|c| c.constraint_type == ConstraintType::ForeignKey)
+            .find(|c| {
+                c.referenced_table
+                    .as_deref()
+                    .is_some_and(|t| same_table(t, table))
+            })
+            .and_then(|c| Some((c.columns.clone(), c.referenced_columns.clone()?)))
+    };
+    if let Some((columns, referenced)) = referencing(child_constraints, parent) {
+        return Some(Join {
+            parent_columns: referenced,
+            child_columns: columns,
+        });
+    }
+    referencing(parent_constraints, child).map(|(columns, referenced)| Join {
+        parent_columns: columns,
+        child_columns: referenced,
+    })
+}
+
+/// Table names match exactly or, when one is schema-qualified, by name.
+fn same_table(a: &str, b: &str) -> bool {
+    let name = |t: &str| t.rsplit('.').next().unwrap_or(t).to_string();
+    a == b || (a.contains('.') != b.contains('.') && name(a) == name(b))
+}
+
+/// Key values `row` joins on; `None` when any is null.
+fn join_key(row: &Row, columns: &[String]) -> Option<String> {
+    columns
+        .iter()
+        .all(|c| row.get(c).is_some_and(|v| !v.is_null()))
+        .then(|| row_match_key(row, columns))
+}
+
+/// Set `field` on every parent from the children joined to it. Arrays
+/// leave out the join columns, which only repeat the parent's key, and are
+/// sorted by their remaining columns so documents compare stably between
+/// runs. Returns the number of parents given a sub-document while more
+/// than one child matched.
+pub fn embed(
+    parents: &mut [Row],
+    children: Vec<Row>,
+    field: &str,
+    kind: EmbedKind,
+    join: &Join,
+) -> usize {
+    let mut groups: HashMap<String, Vec<Row>> = HashMap::new();
+    for mut child in children {
+        let Some(key) = join_key(&child, &join.child_columns) else {
+            continue;
+        };
+        if kind == EmbedKind::Array {
+            for column in &join.child_columns {
+                child.remove(column);
+            }
+        }
+        groups.entry(key).or_default().push(child);
+    }
+
+    let mut ambiguous = 0;
+    for parent in parents.iter_mut() {
+        let matched = join_key(parent, &join.parent_columns)
+            .and_then(|key| groups.get(&key))
+            .cloned()
+            .unwrap_or_default();
+        let value = match kind {
+            EmbedKind::Array => {
+                let mut rows = matched;
+                if let Some(first) = rows.first() {
+                    let mut columns: Vec<String> = first.keys().cloned().collect();
+                    columns.sort();
+                    sort_by_key(&mut rows, &columns);
+                }
+                Value::Array(rows.into_iter().map(row_value).collect())
+            }
+            EmbedKind::Object => {
+                if matched.len() > 1 {
+                    ambiguous += 1;
+                }
+                matched.into_iter().next().map_or(Value::Null, row_value)
+            }
+        };
+        parent.insert(field.to_string(), value);
+    }
+    ambiguous
+}
+
+/// Key rows selecting the children of `parents`, named by the child's join
+/// columns.
+pub fn child_keys(parents: &[Row], join: &Join) -> Vec<Row> {
+    parents
+        .iter()
+        .filter(|p| join_key(p, &join.parent_columns).is_some())
+        .map(|p| {
+            join.parent_columns
+                .iter()
+                .zip(&join.child_columns)
+                .map(|(from, to)| (to.clone(), p[from].clone()))
+                .collect()
+        })
+        .collect()
+}
+
+fn row_value(row: Row) -> Value {
+    Value::Object(row.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn fk(columns: &[&str], table: &str, referenced: &[&str]) -> ConstraintInfo {
+        ConstraintInfo {
+            name: format!("fk_{}", table),
+            constraint_type: ConstraintType::ForeignKey,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            referenced_table: Some(table.to_string()),
+            referenced_columns: Some(referenced.iter().map(|c| c.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_resolve_join() {
+        // order_lines.order_id -> orders.id
+        let lines = vec![fk(&["order_id"], "orders", &["id"])];
+        assert_eq!(
+            resolve_join("orders", "order_lines", &[], &lines),
+            Some(Join {
+                parent_columns: vec!["id".into()],
+                child_columns: vec!["order_id".into()],
+            })
+        );
+        // customers.address_id -> sales.addresses.id
+        let customers = vec![fk(&["address_id"], "sales.addresses", &["id"])];
+        assert_eq!(
+            resolve_join("customers", "addresses", &customers, &[]),
+            Some(Join {
+                parent_columns: vec!["address_id".into()],
+                child_columns: vec!["id".into()],
+            })
+        );
+        assert_eq!(resolve_join("orders", "products", &lines, &[]), None);
+    }
+
+    #[test]
+    fn test_embed_array() {
+        let mut orders = vec![
+            row(&[("id", json!(1))]),
+            row(&[("id", json!(2))]),
+            row(&[("id", Value::Null)]),
+        ];
+        let lines = vec![
+            row(&[("order_id", json!(1)), ("sku", json!("b"))]),
+            row(&[("order_id", json!(1)), ("sku", json!("a"))]),
+            row(&[("order_id", Value::Null), ("sku", json!("c"))]),
+        ];
+        let join = Join {
+            parent_columns: vec!["id".into()],
+            child_columns: vec!["order_id".into()],
+        };
+        assert_eq!(
+            embed(&mut orders, lines, "lines", EmbedKind::Array, &join),
+            0
+        );
+        assert_eq!(orders[0]["lines"], json!([{ "sku": "a" }, { "sku": "b" }]));
+        assert_eq!(orders[1]["lines"], json!([]));
+        assert_eq!(orders[2]["lines"], json!([]));
+    }
+
+    #[test]
+    fn test_embed_object() {
+        let mut customers = vec![
+            row(&[("id", json!(1)), ("address_id", json!(10))]),
+            row(&[("id", json!(2)), ("address_id", Value::Null)]),
+        ];
+        let addresses = vec![
+            row(&[("id", json!(10)), ("city", json!("Oslo"))]),
+            row(&[("id", json!(10)), ("city", json!("Bergen"))]),
+        ];
+        let join = Join {
+            parent_columns: vec!["address_id".into()],
+            child_columns: vec!["id".into()],
+        };
+        let ambiguous = embed(
+            &mut customers,
+            addresses,
+            "address",
+            EmbedKind::Object,
+            &join,
+        );
+        assert_eq!(ambiguous, 1);
+        assert_eq!(customers[0]["address"], json!({ "id": 10, "city": "Oslo" }));
+        assert_eq!(customers[1]["address"], Value::Null);
+    }
+
+    #[test]
+    fn test_child_keys() {
+        let parents = vec![
+            row(&[("id", json!(1)), ("name", json!("x"))]),
+            row(&[("id", Value::Null)]),
+        ];
+        let join = Join {
+            parent_columns: vec!["id".into()],
+            child_columns: vec!["order_id".into()],
+        };
+        assert_eq!(
+            child_keys(&parents, &join),
+            vec![row(&[("order_id", json!(1))])]
+        );
+        assert_eq!(EmbedKind::parse("Object"), EmbedKind::Object);
+        assert_eq!(EmbedKind::parse("Array"), EmbedKind::Array);
+    }
+}
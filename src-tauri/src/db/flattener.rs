@@ -0,0 +1,561 @@
+//! Document-to-relational flattening.
+//!
+//! Nested documents map to columns named by their dotted paths with the
+//! dots replaced (`address.city` becomes `address_city`). Arrays become
+//! child tables: one row per element, keyed by the document's `_id`
+//! (`<collection>_id`), the ordinals of any enclosing array elements
+//! (`<path>_ordinal`) and the element's own `ordinal`. Scalar elements go
+//! to a `value` column. A plan for a collection names those tables, infers
+//! their column types from the sampled schema and documents, and carries
+//! the DDL to create them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::connectors::DatabaseEngine;
+use super::schema::{ColumnInfo, ConstraintInfo, ConstraintType, Row, TableInfo};
+use super::sql_generator::SqlGenerator;
+use super::type_mapper::{from_canonical, to_canonical, CanonicalType};
+use super::typed_value::TypedValue;
+
+/// Position of a row's element in its array.
+pub const ORDINAL_COLUMN: &str = "ordinal";
+/// Column holding the elements of an array of scalars.
+pub const VALUE_COLUMN: &str = "value";
+/// Document key every child table refers back to.
+const ROOT_KEY: &str = "_id";
+/// Length of a string document key. Keys must be bounded to be indexed,
+/// and a sample cannot tell how long later ones get, so this is generous.
+const KEY_VARCHAR: u32 = 255;
+
+/// Column name of a dotted path.
+pub fn column_name(path: &str) -> String {
+    path.replace('.', "_")
+}
+
+/// Column of a child table holding its document's `_id`.
+fn root_key_column(collection: &str) -> String {
+    format!("{}_id", column_name(collection))
+}
+
+fn ordinal_column(array_path: &str) -> String {
+    format!("{}_{}", column_name(array_path), ORDINAL_COLUMN)
+}
+
+fn is_nested(value: &Value) -> bool {
+    value.is_object() && TypedValue::from_json(value).is_none()
+}
+
+/// Rows of the table at `array_path` (the documents themselves when
+/// `None`), with nested documents flattened into columns. Arrays are left
+/// out of the rows holding them; they are rows of their own tables.
+pub fn flatten_documents(docs: &[Row], collection: &str, array_path: Option<&str>) -> Vec<Row> {
+    let Some(path) = array_path else {
+        return docs
+            .iter()
+            .map(|doc| {
+                let mut row = Row::new();
+                flatten_into(doc.iter(), "", &mut row);
+                row
+            })
+            .collect();
+    };
+    let parts: Vec<&str> = path.split('.').collect();
+    let mut rows = Vec::new();
+    for doc in docs {
+        let mut inherited = Row::new();
+        inherited.insert(
+            root_key_column(collection),
+            doc.get(ROOT_KEY).cloned().unwrap_or(Value::Null),
+        );
+        let object: Map<String, Value> = doc.clone().into_iter().collect();
+        explode(&object, &parts, "", inherited, &mut rows);
+    }
+    rows
+}
+
+/// Add the scalar fields under `fields` to `row`, nested documents by
+/// their dotted paths.
+fn flatten_into<'a>(
+    fields: impl Iterator<Item = (&'a String, &'a Value)>,
+    prefix: &str,
+    row: &mut Row,
+) {
+    for (key, value) in fields {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Array(_) => {}
+            Value::Object(fields) if is_nested(value) => flatten_into(fields.iter(), &path, row),
+            _ => {
+                row.insert(column_name(&path), value.clone());
+            }
+        }
+    }
+}
+
+/// Follow `parts` down from `object`, emitting a row per element of the
+/// array the path ends at. `walked` is the dotted path from the document
+/// to `object`.
+fn explode(
+    object: &Map<String, Value>,
+    parts: &[&str],
+    walked: &str,
+    inherited: Row,
+    rows: &mut Vec<Row>,
+) {
+    let Some((field, rest)) = parts.split_first() else {
+        return;
+    };
+    let path = if walked.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", walked, field)
+    };
+    match object.get(*field) {
+        Some(Value::Array(items)) if rest.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                let mut row = inherited.clone();
+                row.insert(ORDINAL_COLUMN.to_string(), Value::from(i));
+                match item {
+                    Value::Object(fields) if is_nested(item) => {
+                        flatten_into(fields.iter(), "", &mut row)
+                    }
+                    Value::Array(_) => {}
+                    _ => {
+                        row.insert(VALUE_COLUMN.to_string(), item.clone());
+                    }
+                }
+                rows.push(row);
+            }
+        }
+        Some(Value::Array(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                if let Value::Object(element) = item {
+                    let mut row = inherited.clone();
+                    row.insert(ordinal_column(&path), Value::from(i));
+                    explode(element, rest, &path, row, rows);
+                }
+            }
+        }
+        Some(value @ Value::Object(nested)) if is_nested(value) => {
+            explode(nested, rest, &path, inherited, rows)
+        }
+        _ => {}
+    }
+}
+
+/// Dotted paths of every array in `fields`, arrays inside array elements
+/// included.
+fn collect_array_paths<'a>(
+    fields: impl Iterator<Item = (&'a String, &'a Value)>,
+    prefix: &str,
+    paths: &mut BTreeSet<String>,
+) {
+    for (key, value) in fields {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Array(items) => {
+                paths.insert(path.clone());
+                for item in items {
+                    if let Value::Object(fields) = item {
+                        collect_array_paths(fields.iter(), &path, paths);
+                    }
+                }
+            }
+            Value::Object(fields) if is_nested(value) => {
+                collect_array_paths(fields.iter(), &path, paths)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// One table of a flattened collection.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatTable {
+    /// Dotted path of the array the rows come from; `None` for the table
+    /// of the documents themselves.
+    pub array_path: Option<String>,
+    pub parent_table: Option<String>,
+    pub key_columns: Vec<String>,
+    /// Columns in the target engine's native types, with the primary key
+    /// and the foreign key to the parent table.
+    pub info: TableInfo,
+    /// CREATE TABLE statement; `None` for engines without SQL.
+    pub ddl: Option<String>,
+}
+
+/// Tables `collection` flattens into on `engine`, parents first. The root
+/// table is named `root_table` and child tables `<root_table>_<path>`.
+/// Column types come from the sampled `schema` where it is specific and
+/// from the `sample` documents otherwise.
+pub fn plan_flattening(
+    collection: &str,
+    root_table: &str,
+    schema: &[ColumnInfo],
+    sample: &[Row],
+    engine: &DatabaseEngine,
+) -> Vec<FlatTable> {
+    let mut paths = BTreeSet::new();
+    for doc in sample {
+        collect_array_paths(doc.iter(), "", &mut paths);
+    }
+    let sampled: BTreeMap<String, CanonicalType> = schema
+        .iter()
+        .map(|c| {
+            (
+                column_name(&c.name),
+                to_canonical(&DatabaseEngine::MongoDb, &c.data_type),
+            )
+        })
+        .collect();
+    let table_name = |path: Option<&str>| match path {
+        Some(p) => format!("{}_{}", root_table, column_name(p)),
+        None => root_table.to_string(),
+    };
+    let sql_gen = SqlGenerator::new(engine.clone());
+
+    let root_rows = flatten_documents(sample, collection, None);
+    let root_key_type = match column_type(&root_rows, ROOT_KEY, sampled.get(ROOT_KEY)) {
+        CanonicalType::Text => CanonicalType::Varchar(KEY_VARCHAR),
+        other => other,
+    };
+    let mut tables = Vec::new();
+    for path in std::iter::once(None).chain(paths.iter().map(|p| Some(p.as_str()))) {
+        let name = table_name(path);
+        // Arrays enclosing this one, outermost first
+        let ancestors: Vec<&String> = paths
+            .iter()
+            .filter(|a| path.is_some_and(|p| p.starts_with(&format!("{}.", a))))
+            .collect();
+        let parent = path.map(|_| table_name(ancestors.last().map(|a| a.as_str())));
+
+        let mut keys: Vec<(String, CanonicalType)> = Vec::new();
+        let mut foreign: Vec<(String, String)> = Vec::new();
+        match path {
+            None => keys.push((ROOT_KEY.to_string(), root_key_type.clone())),
+            Some(_) => {
+                let root_fk = root_key_column(collection);
+                keys.push((root_fk.clone(), root_key_type.clone()));
+                let parent_key = if ancestors.is_empty() {
+                    ROOT_KEY.to_string()
+                } else {
+                    root_fk.clone()
+                };
+                foreign.push((root_fk, parent_key));
+                for (i, ancestor) in ancestors.iter().enumerate() {
+                    let column = ordinal_column(ancestor);
+                    let parent_column = if i + 1 == ancestors.len() {
+                        ORDINAL_COLUMN.to_string()
+                    } else {
+                        column.clone()
+                    };
+                    keys.push((column.clone(), CanonicalType::Int));
+                    foreign.push((column, parent_column));
+                }
+                keys.push((ORDINAL_COLUMN.to_string(), CanonicalType::Int));
+            }
+        }
+
+        let rows = match path {
+            None => root_rows.clone(),
+            Some(p) => flatten_documents(sample, collection, Some(p)),
+        };
+        let mut names: BTreeSet<String> = rows.iter().flat_map(|r| r.keys().cloned()).collect();
+        if path.is_none() {
+            // Scalar fields no sampled document happened to hold, leaving
+            // out those of array elements
+            names.extend(
+                schema
+                    .iter()
+                    .filter(|c| !matches!(c.data_type.as_str(), "object" | "array"))
+                    .filter(|c| !paths.iter().any(|a| c.name.starts_with(&format!("{}.", a))))
+                    .map(|c| column_name(&c.name)),
+            );
+        }
+        let key_columns: Vec<String> = keys.iter().map(|(k, _)| k.clone()).collect();
+        let mut columns: Vec<(String, CanonicalType, bool)> =
+            keys.into_iter().map(|(k, t)| (k, t, true)).collect();
+        for column in names.into_iter().filter(|n| !key_columns.contains(n)) {
+            let known = path.is_none().then(|| sampled.get(&column)).flatten();
+            let canonical = column_type(&rows, &column, known);
+            columns.push((column, canonical, false));
+        }
+
+        let mut constraints = vec![ConstraintInfo {
+            name: format!("pk_{}", name),
+            constraint_type: ConstraintType::PrimaryKey,
+            columns: key_columns.clone(),
+            referenced_table: None,
+            referenced_columns: None,
+        }];
+        if let Some(parent) = &parent {
+            constraints.push(ConstraintInfo {
+                name: format!("fk_{}_{}", name, parent),
+                constraint_type: ConstraintType::ForeignKey,
+                columns: foreign.iter().map(|(c, _)| c.clone()).collect(),
+                referenced_table: Some(parent.clone()),
+                referenced_columns: Some(foreign.iter().map(|(_, p)| p.clone()).collect()),
+            });
+        }
+        let info = TableInfo {
+            schema_name: String::new(),
+            table_name: name.clone(),
+            columns: columns
+                .into_iter()
+                .enumerate()
+                .map(|(i, (column, canonical, key))| ColumnInfo {
+                    name: column,
+                    data_type: from_canonical(engine, &canonical),
+                    is_nullable: !key,
+                    is_primary_key: key,
+                    max_length: None,
+                    precision: None,
+                    scale: None,
+                    default_value: None,
+                    ordinal_position: (i + 1) as i32,
+                })
+                .collect(),
+            indexes: Vec::new(),
+            constraints,
+            row_count: None,
+            field_stats: Vec::new(),
+        };
+        tables.push(FlatTable {
+            array_path: path.map(str::to_string),
+            parent_table: parent,
+            key_columns,
+            ddl: sql_gen.create_table(&name, &info.columns, &info.constraints),
+            info,
+        });
+    }
+    tables
+}
+
+/// Type of `column`: the sampled schema's when it names a specific one,
+/// inferred from the values in `rows` otherwise.
+fn column_type(rows: &[Row], column: &str, sampled: Option<&CanonicalType>) -> CanonicalType {
+    let inferred = infer_type(rows.iter().filter_map(|r| r.get(column)));
+    match sampled {
+        Some(CanonicalType::Text | CanonicalType::Json | CanonicalType::Unknown(_)) | None => {
+            inferred
+        }
+        Some(known) => known.clone(),
+    }
+}
+
+/// Narrowest type holding every non-null value: integers widen to
+/// doubles, and strings and values of unrelated kinds are TEXT. Strings
+/// are not sized from the sample, as documents outside it may be longer.
+fn infer_type<'a>(values: impl Iterator<Item = &'a Value>) -> CanonicalType {
+    let mut kinds = BTreeSet::new();
+    let mut max_scale = 0u8;
+    for value in values {
+        let kind = match TypedValue::from_json(value) {
+            Some(TypedValue::Decimal(text)) => {
+                let scale = text.split_once('.').map_or(0, |(_, f)| f.len());
+                max_scale = max_scale.max(scale.min(38) as u8);
+                "decimal"
+            }
+            Some(TypedValue::Bytes(_)) => "bytes",
+            Some(TypedValue::Date(_)) => "date",
+            Some(TypedValue::Time(_)) => "time",
+            Some(TypedValue::DateTime(_)) => "datetime",
+            Some(TypedValue::Timestamp(_)) => "timestamp",
+            Some(TypedValue::Uuid(_)) => "uuid",
+            None => match value {
+                Value::Null => continue,
+                Value::Bool(_) => "bool",
+                Value::Number(n) if n.is_i64() || n.is_u64() => "int",
+                Value::Number(_) => "float",
+                Value::String(_) => "string",
+                _ => "json",
+            },
+        };
+        kinds.insert(kind);
+    }
+    let kinds: Vec<&str> = kinds.into_iter().collect();
+    match kinds.as_slice() {
+        [] => CanonicalType::Text,
+        ["int"] => CanonicalType::BigInt,
+        ["float"] | ["float", "int"] => CanonicalType::Double,
+        ["decimal"] | ["decimal", "int"] => CanonicalType::Decimal {
+            precision: 38,
+            scale: max_scale,
+        },
+        ["bool"] => CanonicalType::Boolean,
+        ["bytes"] => CanonicalType::Blob,
+        ["date"] => CanonicalType::Date,
+        ["time"] => CanonicalType::Time,
+        ["datetime"] => CanonicalType::DateTime,
+        ["timestamp"] | ["datetime", "timestamp"] => CanonicalType::Timestamp,
+        ["uuid"] => CanonicalType::Uuid,
+        ["json"] => CanonicalType::Json,
+        _ => CanonicalType::Text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn doc(value: Value) -> Row {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn sample() -> Vec<Row> {
+        vec![
+            doc(json!({
+                "_id": "65a1b2c3d4e5f60718293a4b",
+                "customer": { "name": "Ada", "address": { "city": "Oslo" } },
+                "total": typed_value_decimal("12.50"),
+                "lines": [
+                    { "sku": "a", "qty": 1, "tags": ["x", "y"] },
+                    { "sku": "b", "qty": 2 }
+                ],
+                "notes": ["rush"]
+            })),
+            doc(json!({ "_id": "65a1b2c3d4e5f60718293a4c", "lines": [] })),
+        ]
+    }
+
+    fn typed_value_decimal(text: &str) -> Value {
+        crate::db::typed_value::decimal(text)
+    }
+
+    #[test]
+    fn test_flatten_root() {
+        let rows = flatten_documents(&sample(), "orders", None);
+        assert_eq!(rows[0]["customer_address_city"], json!("Oslo"));
+        assert_eq!(rows[0]["customer_name"], json!("Ada"));
+        assert_eq!(rows[0]["total"], typed_value_decimal("12.50"));
+        assert!(!rows[0].contains_key("lines"));
+        assert_eq!(rows[1].len(), 1);
+    }
+
+    #[test]
+    fn test_flatten_arrays() {
+        let lines = flatten_documents(&sample(), "orders", Some("lines"));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["orders_id"], json!("65a1b2c3d4e5f60718293a4b"));
+        assert_eq!(lines[1]["ordinal"], json!(1));
+        assert_eq!(lines[1]["sku"], json!("b"));
+        assert!(!lines[0].contains_key("tags"));
+
+        let tags = flatten_documents(&sample(), "orders", Some("lines.tags"));
+        assert_eq!(
+            tags,
+            vec![
+                doc(
+                    json!({ "orders_id": "65a1b2c3d4e5f60718293a4b", "lines_ordinal": 0,
+                            "ordinal": 0, "value": "x" })
+                ),
+                doc(
+                    json!({ "orders_id": "65a1b2c3d4e5f60718293a4b", "lines_ordinal": 0,
+                            "ordinal": 1, "value": "y" })
+                ),
+            ]
+        );
+        assert!(flatten_documents(&sample(), "orders", Some("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_plan_flattening() {
+        let schema = vec![ColumnInfo {
+            name: "status".into(),
+            data_type: "string".into(),
+            is_nullable: true,
+            is_primary_key: false,
+            max_length: None,
+            precision: None,
+            scale: None,
+            default_value: None,
+            ordinal_position: 1,
+        }];
+        let plan = plan_flattening(
+            "orders",
+            "orders",
+            &schema,
+            &sample(),
+            &DatabaseEngine::PostgreSql,
+        );
+        let names: Vec<&str> = plan.iter().map(|t| t.info.table_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "orders",
+                "orders_lines",
+                "orders_lines_tags",
+                "orders_notes"
+            ]
+        );
+
+        let root = &plan[0];
+        assert_eq!(root.key_columns, vec!["_id".to_string()]);
+        let column = |t: &FlatTable, name: &str| {
+            t.info
+                .columns
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap()
+                .data_type
+                .clone()
+        };
+        assert_eq!(column(root, "_id"), "VARCHAR(255)");
+        assert_eq!(column(root, "total"), "NUMERIC(38,2)");
+        assert_eq!(column(root, "status"), "TEXT");
+        assert!(root
+            .ddl
+            .as_deref()
+            .unwrap()
+            .starts_with("CREATE TABLE \"orders\" (\"_id\" VARCHAR(255) NOT NULL"));
+
+        let tags = &plan[2];
+        assert_eq!(tags.parent_table.as_deref(), Some("orders_lines"));
+        assert_eq!(
+            tags.key_columns,
+            vec!["orders_id", "lines_ordinal", "ordinal"]
+        );
+        let fk = &tags.info.constraints[1];
+        assert_eq!(fk.columns, vec!["orders_id", "lines_ordinal"]);
+        assert_eq!(
+            fk.referenced_columns.as_deref().unwrap(),
+            ["orders_id", "ordinal"]
+        );
+        assert_eq!(column(tags, "orders_id"), "VARCHAR(255)");
+        assert_eq!(column(tags, "value"), "TEXT");
+
+        let lines = &plan[1];
+        assert_eq!(
+            lines.info.constraints[1]
+                .referenced_columns
+                .as_deref()
+                .unwrap(),
+            ["_id"]
+        );
+        assert_eq!(column(lines, "qty"), "BIGINT");
+    }
+
+    #[test]
+    fn test_infer_type() {
+        let infer = |values: Vec<Value>| infer_type(values.iter());
+        assert_eq!(infer(vec![json!(1), json!(2.5)]), CanonicalType::Double);
+        assert_eq!(infer(vec![json!(1), Value::Null]), CanonicalType::BigInt);
+        assert_eq!(infer(vec![json!("a"), json!(1)]), CanonicalType::Text);
+        assert_eq!(infer(vec![json!("x".repeat(5000))]), CanonicalType::Text);
+        assert_eq!(infer(vec![json!("x".repeat(100))]), CanonicalType::Text);
+        assert_eq!(infer(vec![]), CanonicalType::Text);
+    }
+}
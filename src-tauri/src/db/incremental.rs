@@ -0,0 +1,131 @@
+//! Watermark-based incremental sync.
+//!
+//! A table synced incrementally only reads source rows whose watermark
+//! column (such as `updated_at` or a rowversion) is at or past the highest
+//! value seen by the previous sync. Rows sharing that value are read again,
+//! as a row committed after the previous sync may carry it too; matching
+//! them by key against the target leaves those already synced unchanged. Rows flagged by a soft-delete column are
+//! removed from the target instead of being written to it.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use super::checkpoint::compare_keys;
+use super::schema::Row;
+
+/// Whether a soft-delete flag value marks the row deleted: `true`, a
+/// non-zero number, or text such as "1", "true", "y" or "yes".
+pub fn is_flag_set(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => matches!(
+            s.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "t" | "y" | "yes"
+        ),
+        _ => false,
+    }
+}
+
+/// Split rows into live rows and rows flagged deleted by `flag_column`.
+pub fn split_soft_deleted(rows: Vec<Row>, flag_column: &str) -> (Vec<Row>, Vec<Row>) {
+    rows.into_iter()
+        .partition(|row| !row.get(flag_column).is_some_and(is_flag_set))
+}
+
+/// Rows whose `column` is at or past `since`; rows with a null or missing
+/// watermark are left out.
+pub fn rows_since(rows: Vec<Row>, column: &str, since: &Value) -> Vec<Row> {
+    rows.into_iter()
+        .filter(|row| match row.get(column) {
+            None | Some(Value::Null) => false,
+            Some(value) => {
+                compare_keys(std::slice::from_ref(value), std::slice::from_ref(since))
+                    != Ordering::Less
+            }
+        })
+        .collect()
+}
+
+/// Highest non-null value of `column` across the rows, if any.
+pub fn max_watermark<'a>(rows: impl IntoIterator<Item = &'a Row>, column: &str) -> Option<Value> {
+    rows.into_iter()
+        .filter_map(|row| row.get(column))
+        .filter(|value| !value.is_null())
+        .max_by(|a, b| compare_keys(std::slice::from_ref(*a), std::slice::from_ref(*b)))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_flag_set() {
+        for set in [
+            json!(true),
+            json!(1),
+            json!("Y"),
+            json!(" yes "),
+            json!("true"),
+        ] {
+            assert!(is_flag_set(&set), "{}", set);
+        }
+        for unset in [json!(false), json!(0), json!("N"), json!(""), Value::Null] {
+            assert!(!is_flag_set(&unset), "{}", unset);
+        }
+    }
+
+    #[test]
+    fn test_split_soft_deleted() {
+        let rows = vec![
+            row(&[("id", json!(1)), ("deleted", json!(false))]),
+            row(&[("id", json!(2)), ("deleted", json!(true))]),
+            row(&[("id", json!(3))]),
+        ];
+        let (live, deleted) = split_soft_deleted(rows, "deleted");
+        assert_eq!(live.len(), 2);
+        assert_eq!(
+            deleted,
+            vec![row(&[("id", json!(2)), ("deleted", json!(true))])]
+        );
+    }
+
+    #[test]
+    fn test_rows_since_and_max_watermark() {
+        let rows = vec![
+            row(&[
+                ("id", json!(1)),
+                ("updated_at", json!({"$datetime": "2024-01-01T08:00:00"})),
+            ]),
+            row(&[
+                ("id", json!(2)),
+                ("updated_at", json!({"$datetime": "2024-01-03T08:00:00"})),
+            ]),
+            row(&[("id", json!(3)), ("updated_at", Value::Null)]),
+            row(&[
+                ("id", json!(4)),
+                ("updated_at", json!({"$datetime": "2024-01-02T08:00:00"})),
+            ]),
+        ];
+        assert_eq!(
+            max_watermark(&rows, "updated_at"),
+            Some(json!({"$datetime": "2024-01-03T08:00:00"}))
+        );
+
+        let since = json!({"$datetime": "2024-01-02T08:00:00"});
+        let newer = rows_since(rows, "updated_at", &since);
+        let ids: Vec<Value> = newer.iter().map(|r| r["id"].clone()).collect();
+        assert_eq!(ids, vec![json!(2), json!(4)]);
+        assert_eq!(max_watermark(&[], "updated_at"), None);
+    }
+}
@@ -352,7 +352,7 @@ pub fn execute_migration(
 // ---------------------------------------------------------------------------
 
 /// Determine the effective key columns for matching rows.
-fn effective_key_columns(config: &MigrationConfig, sample_rows: &[Row]) -> Vec<String> {
+pub fn effective_key_columns(config: &MigrationConfig, sample_rows: &[Row]) -> Vec<String> {
     if !config.key_columns.is_empty() {
         return config.key_columns.clone();
     }
@@ -1,9 +1,24 @@
+pub mod backup;
+pub mod catalog;
+pub mod cdc;
+pub mod checkpoint;
 pub mod comparator;
 pub mod connectors;
 pub mod data_comparator;
+pub mod document;
+pub mod embedding;
+pub mod flattener;
+pub mod incremental;
 pub mod migrator;
+pub mod pool;
 pub mod registry;
+pub mod renames;
+pub mod scheduler;
 pub mod schema;
 pub mod sql_generator;
 pub mod transformer;
 pub mod type_mapper;
+pub mod type_report;
+pub mod typed_value;
+pub mod undo;
+pub mod value_converter;
@@ -0,0 +1,724 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+use super::connectors::{create_connector, ConnectionConfig, DatabaseConnector, DatabaseEngine};
+
+/// Builds an unconnected connector for a config.
+pub type ConnectorFactory =
+    Arc<dyn Fn(ConnectionConfig) -> Box<dyn DatabaseConnector> + Send + Sync>;
+
+/// Sizing and lifetime settings for a connection pool.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PoolConfig {
+    /// Connections opened up front and kept even when idle
+    pub min_size: usize,
+    /// Upper bound on connections checked out at once
+    pub max_size: usize,
+    /// Idle connections above `min_size` are closed after this long
+    pub idle_timeout_secs: u64,
+    /// Connections idle longer than this are pinged before being reused;
+    /// also how often idle connections are checked and reaped in the
+    /// background
+    pub health_check_interval_secs: u64,
+    /// How long `acquire` waits for a free connection
+    pub acquire_timeout_secs: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 1,
+            max_size: 4,
+            idle_timeout_secs: 300,
+            health_check_interval_secs: 30,
+            acquire_timeout_secs: 30,
+        }
+    }
+}
+
+/// Point-in-time pool occupancy.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStatus {
+    pub size: usize,
+    pub idle: usize,
+    pub in_use: usize,
+    pub max_size: usize,
+}
+
+struct IdleConnection {
+    conn: Box<dyn DatabaseConnector>,
+    idle_since: Instant,
+}
+
+/// A pool of live connectors sharing one `ConnectionConfig`.
+///
+/// Connections are checked out with [`ConnectionPool::acquire`] and return
+/// to the pool when the [`PooledConnection`] is dropped. A connection
+/// released with its transaction still open is disconnected rather than
+/// handed to the next borrower. A background task pings idle connections
+/// and disconnects those past their idle timeout; it is aborted when the
+/// pool is dropped.
+pub struct ConnectionPool {
+    config: ConnectionConfig,
+    pool_config: PoolConfig,
+    factory: ConnectorFactory,
+    /// Most recently returned last, so reuse is LIFO and reaping takes the
+    /// oldest from the front
+    idle: std::sync::Mutex<Vec<IdleConnection>>,
+    permits: Arc<Semaphore>,
+    maintenance: std::sync::Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ConnectionPool {
+    /// Open a pool using the connector for `config.engine`.
+    pub async fn open(config: ConnectionConfig) -> anyhow::Result<Arc<Self>> {
+        Self::with_factory(config, Arc::new(create_connector)).await
+    }
+
+    /// Open a pool whose connections come from `factory`. Opens `min_size`
+    /// connections (at least one, so bad settings fail here).
+    pub async fn with_factory(
+        config: ConnectionConfig,
+        factory: ConnectorFactory,
+    ) -> anyhow::Result<Arc<Self>> {
+        let pool_config = effective_pool_config(&config);
+        let pool = Arc::new(Self {
+            permits: Arc::new(Semaphore::new(pool_config.max_size)),
+            idle: std::sync::Mutex::new(Vec::new()),
+            maintenance: std::sync::Mutex::new(None),
+            config,
+            pool_config,
+            factory,
+        });
+
+        for _ in 0..pool.pool_config.min_size.max(1) {
+            let conn = pool.open_connection().await?;
+            pool.idle.lock().unwrap().push(IdleConnection {
+                conn,
+                idle_since: Instant::now(),
+            });
+        }
+        match Handle::try_current() {
+            Ok(runtime) => {
+                let task = runtime.spawn(maintain(Arc::downgrade(&pool)));
+                *pool.maintenance.lock().unwrap() = Some(task);
+            }
+            Err(_) => log::warn!(
+                "No Tokio runtime; {} pool runs without background health checks",
+                pool.config.engine
+            ),
+        }
+        Ok(pool)
+    }
+
+    pub fn engine(&self) -> DatabaseEngine {
+        self.config.engine.clone()
+    }
+
+    pub fn config(&self) -> &ConnectionConfig {
+        &self.config
+    }
+
+    pub fn pool_config(&self) -> &PoolConfig {
+        &self.pool_config
+    }
+
+    /// Check out a connection, waiting up to `acquire_timeout_secs` when
+    /// all `max_size` connections are in use.
+    pub async fn acquire(self: &Arc<Self>) -> anyhow::Result<PooledConnection> {
+        let timeout = Duration::from_secs(self.pool_config.acquire_timeout_secs);
+        let permit = tokio::time::timeout(timeout, self.permits.clone().acquire_owned())
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Timed out waiting for a free {} connection ({} in use)",
+                    self.config.engine,
+                    self.pool_config.max_size
+                )
+            })?
+            .map_err(|_| anyhow!("{} connection pool is closed", self.config.engine))?;
+
+        let health_interval = Duration::from_secs(self.pool_config.health_check_interval_secs);
+        loop {
+            let candidate = self.idle.lock().unwrap().pop();
+            let Some(idle) = candidate else {
+                let conn = self.open_connection().await?;
+                return Ok(PooledConnection::new(conn, self.clone(), permit));
+            };
+            if idle.idle_since.elapsed() >= health_interval && !idle.conn.is_connected().await {
+                log::info!(
+                    "Discarding dead {} connection from pool",
+                    self.config.engine
+                );
+                disconnect_all(&self.config.engine, vec![idle.conn]).await;
+                continue;
+            }
+            return Ok(PooledConnection::new(idle.conn, self.clone(), permit));
+        }
+    }
+
+    /// Ping every idle connection, disconnecting dead ones and topping the
+    /// pool back up to `min_size`. Returns how many were discarded.
+    pub async fn health_check(&self) -> anyhow::Result<usize> {
+        let idle: Vec<IdleConnection> = std::mem::take(&mut *self.idle.lock().unwrap());
+        let mut alive = Vec::with_capacity(idle.len());
+        let mut dead = Vec::new();
+        for entry in idle {
+            if entry.conn.is_connected().await {
+                alive.push(entry);
+            } else {
+                dead.push(entry.conn);
+            }
+        }
+        let discarded = dead.len();
+        disconnect_all(&self.config.engine, dead).await;
+        {
+            // Keep anything released meanwhile after the older survivors
+            let mut idle = self.idle.lock().unwrap();
+            alive.append(&mut idle);
+            *idle = alive;
+        }
+
+        while !self.permits.is_closed() && self.status().size < self.pool_config.min_size {
+            let conn = self.open_connection().await?;
+            self.idle.lock().unwrap().insert(
+                0,
+                IdleConnection {
+                    conn,
+                    idle_since: Instant::now(),
+                },
+            );
+        }
+        Ok(discarded)
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        let idle = self.idle.lock().unwrap().len();
+        let in_use = self.pool_config.max_size - self.permits.available_permits();
+        PoolStatus {
+            size: idle + in_use,
+            idle,
+            in_use,
+            max_size: self.pool_config.max_size,
+        }
+    }
+
+    /// Stop handing out connections and disconnect the idle ones.
+    /// Connections still checked out are disconnected when released.
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.permits.close();
+        let idle: Vec<IdleConnection> = std::mem::take(&mut *self.idle.lock().unwrap());
+        for mut entry in idle {
+            entry.conn.disconnect().await?;
+        }
+        Ok(())
+    }
+
+    async fn open_connection(&self) -> anyhow::Result<Box<dyn DatabaseConnector>> {
+        let mut conn = (self.factory)(self.config.clone());
+        conn.connect()
+            .await
+            .with_context(|| format!("Failed to open {} connection", self.config.engine))?;
+        Ok(conn)
+    }
+
+    /// Return a connection and disconnect idle ones past their timeout. A
+    /// connection still inside a transaction (an early return between
+    /// begin and commit) is disconnected instead, which ends the session
+    /// and with it the transaction.
+    fn release(&self, conn: Box<dyn DatabaseConnector>) {
+        if self.permits.is_closed() {
+            self.discard(vec![conn]);
+            return;
+        }
+        if conn.in_transaction() {
+            log::warn!(
+                "Discarding {} connection released with an open transaction",
+                self.config.engine
+            );
+            self.discard(vec![conn]);
+            return;
+        }
+        let expired = {
+            let mut idle = self.idle.lock().unwrap();
+            idle.push(IdleConnection {
+                conn,
+                idle_since: Instant::now(),
+            });
+            // The connection being released still holds its permit
+            self.reap_idle(&mut idle, 1)
+        };
+        self.discard(expired);
+    }
+
+    /// Take idle connections past `idle_timeout_secs` out of `idle`, oldest
+    /// first, without going below `min_size`. `releasing` connections are
+    /// counted as in use but are already in `idle`.
+    fn reap_idle(
+        &self,
+        idle: &mut Vec<IdleConnection>,
+        releasing: usize,
+    ) -> Vec<Box<dyn DatabaseConnector>> {
+        let timeout = Duration::from_secs(self.pool_config.idle_timeout_secs);
+        let in_use = self.pool_config.max_size - self.permits.available_permits();
+        let mut size = idle.len() + in_use.saturating_sub(releasing);
+        let mut expired = Vec::new();
+        while size > self.pool_config.min_size
+            && idle.first().is_some_and(|c| c.idle_since.elapsed() >= timeout)
+        {
+            expired.push(idle.remove(0).conn);
+            size -= 1;
+        }
+        expired
+    }
+
+    /// Disconnect `conns` from synchronous code (a drop) on a spawned task.
+    /// Without a runtime to spawn on they are dropped as they are.
+    fn discard(&self, conns: Vec<Box<dyn DatabaseConnector>>) {
+        if conns.is_empty() {
+            return;
+        }
+        match Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(disconnect_all(self.config.engine.clone(), conns));
+            }
+            Err(_) => log::warn!(
+                "No Tokio runtime; dropping {} {} connection(s) without disconnecting",
+                conns.len(),
+                self.config.engine
+            ),
+        }
+    }
+}
+
+impl Drop for ConnectionPool {
+    fn drop(&mut self) {
+        if let Some(task) = self.maintenance.get_mut().unwrap().take() {
+            task.abort();
+        }
+        let idle = std::mem::take(self.idle.get_mut().unwrap());
+        self.discard(idle.into_iter().map(|entry| entry.conn).collect());
+    }
+}
+
+/// Disconnect each connection, logging rather than returning failures
+/// since the connections are being thrown away.
+async fn disconnect_all(engine: impl std::fmt::Display, conns: Vec<Box<dyn DatabaseConnector>>) {
+    for mut conn in conns {
+        if let Err(e) = conn.disconnect().await {
+            log::warn!("Failed to disconnect {} connection: {:#}", engine, e);
+        }
+    }
+}
+
+/// Background upkeep of a pool: every `health_check_interval_secs` (at
+/// least a second), disconnect dead idle connections, refill to `min_size`
+/// and disconnect idle ones past their timeout. Ends once the pool is
+/// closed or dropped.
+async fn maintain(pool: Weak<ConnectionPool>) {
+    let period = match pool.upgrade() {
+        Some(pool) => Duration::from_secs(pool.pool_config.health_check_interval_secs.max(1)),
+        None => return,
+    };
+    let mut ticks = tokio::time::interval(period);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ticks.tick().await;
+    loop {
+        ticks.tick().await;
+        let Some(pool) = pool.upgrade() else {
+            return;
+        };
+        if pool.permits.is_closed() {
+            return;
+        }
+        if let Err(e) = pool.health_check().await {
+            log::warn!("{} pool health check failed: {:#}", pool.config.engine, e);
+        }
+        let expired = pool.reap_idle(&mut pool.idle.lock().unwrap(), 0);
+        disconnect_all(&pool.config.engine, expired).await;
+    }
+}
+
+/// Clamp the pool settings to what the engine can share safely.
+fn effective_pool_config(config: &ConnectionConfig) -> PoolConfig {
+    let mut pool = config.pool.clone();
+    pool.max_size = pool.max_size.max(1);
+    // Each connection to ":memory:" is a separate database
+    let in_memory = config.engine == DatabaseEngine::Sqlite
+        && config
+            .file_path
+            .as_deref()
+            .or(config.connection_string.as_deref())
+            .map_or(true, |p| p == ":memory:");
+    if in_memory {
+        pool.max_size = 1;
+    }
+    pool.min_size = pool.min_size.min(pool.max_size);
+    pool
+}
+
+/// A checked-out connection; derefs to the connector and goes back to its
+/// pool on drop.
+pub struct PooledConnection {
+    conn: Option<Box<dyn DatabaseConnector>>,
+    pool: Arc<ConnectionPool>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledConnection {
+    fn new(
+        conn: Box<dyn DatabaseConnector>,
+        pool: Arc<ConnectionPool>,
+        permit: OwnedSemaphorePermit,
+    ) -> Self {
+        Self {
+            conn: Some(conn),
+            pool,
+            _permit: permit,
+        }
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = dyn DatabaseConnector;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_deref().expect("connection present until drop")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_deref_mut().expect("connection present until drop")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.release(conn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::{Row, SchemaInfo, TableInfo};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Connector that only tracks whether it is alive.
+    struct FakeConnector {
+        alive: Arc<AtomicBool>,
+        opened: Arc<AtomicUsize>,
+        closed: Arc<AtomicUsize>,
+        in_transaction: bool,
+    }
+
+    #[async_trait]
+    impl DatabaseConnector for FakeConnector {
+        async fn connect(&mut self) -> anyhow::Result<()> {
+            self.opened.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> anyhow::Result<()> {
+            self.closed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+        async fn is_connected(&self) -> bool {
+            self.alive.load(Ordering::SeqCst)
+        }
+        async fn get_schema(&self) -> anyhow::Result<SchemaInfo> {
+            Err(anyhow!("not supported by fake"))
+        }
+        async fn get_tables(&self) -> anyhow::Result<Vec<String>> {
+            Ok(vec!["t".to_string()])
+        }
+        async fn get_table_info(&self, _: &str) -> anyhow::Result<TableInfo> {
+            Err(anyhow!("not supported by fake"))
+        }
+        async fn get_rows(&self, _: &str, _: Option<u64>, _: Option<u64>) -> anyhow::Result<Vec<Row>> {
+            Ok(Vec::new())
+        }
+        async fn execute_query(&self, _: &str) -> anyhow::Result<Vec<Row>> {
+            Ok(Vec::new())
+        }
+        async fn begin_transaction(&mut self) -> anyhow::Result<()> {
+            self.in_transaction = true;
+            Ok(())
+        }
+        async fn commit_transaction(&mut self) -> anyhow::Result<()> {
+            self.in_transaction = false;
+            Ok(())
+        }
+        async fn rollback_transaction(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn engine(&self) -> DatabaseEngine {
+            DatabaseEngine::PostgreSql
+        }
+        fn is_read_only(&self) -> bool {
+            true
+        }
+        fn in_transaction(&self) -> bool {
+            self.in_transaction
+        }
+        async fn get_row_count(&self, _: &str) -> anyhow::Result<i64> {
+            Ok(0)
+        }
+    }
+
+    struct Harness {
+        alive: Arc<AtomicBool>,
+        opened: Arc<AtomicUsize>,
+        closed: Arc<AtomicUsize>,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            Self {
+                alive: Arc::new(AtomicBool::new(true)),
+                opened: Arc::new(AtomicUsize::new(0)),
+                closed: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        async fn pool(&self, pool: PoolConfig) -> Arc<ConnectionPool> {
+            let (alive, opened, closed) =
+                (self.alive.clone(), self.opened.clone(), self.closed.clone());
+            let factory: ConnectorFactory = Arc::new(move |_| {
+                Box::new(FakeConnector {
+                    alive: alive.clone(),
+                    opened: opened.clone(),
+                    closed: closed.clone(),
+                    in_transaction: false,
+                })
+            });
+            let config = ConnectionConfig {
+                engine: DatabaseEngine::PostgreSql,
+                pool,
+                ..Default::default()
+            };
+            ConnectionPool::with_factory(config, factory).await.unwrap()
+        }
+
+        fn opened(&self) -> usize {
+            self.opened.load(Ordering::SeqCst)
+        }
+
+        /// Disconnects so far, once spawned disconnects have run.
+        async fn closed(&self) -> usize {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.closed.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opens_min_size_and_reuses() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                min_size: 2,
+                ..Default::default()
+            })
+            .await;
+        assert_eq!(h.opened(), 2);
+        assert_eq!(pool.status().idle, 2);
+
+        let conn = pool.acquire().await.unwrap();
+        assert_eq!(conn.get_tables().await.unwrap(), vec!["t"]);
+        assert_eq!(pool.status().in_use, 1);
+        drop(conn);
+        let _ = pool.acquire().await.unwrap();
+        assert_eq!(h.opened(), 2, "idle connections are reused");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_checkouts_up_to_max() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                max_size: 3,
+                acquire_timeout_secs: 0,
+                ..Default::default()
+            })
+            .await;
+        let a = pool.acquire().await.unwrap();
+        let b = pool.acquire().await.unwrap();
+        let c = pool.acquire().await.unwrap();
+        assert_eq!(h.opened(), 3);
+        assert_eq!(pool.status().in_use, 3);
+
+        let err = pool.acquire().await.err().unwrap().to_string();
+        assert!(err.contains("Timed out"), "{}", err);
+
+        drop((a, b));
+        assert_eq!(pool.status().idle, 2);
+        drop(c);
+        assert_eq!(pool.status().size, 3);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_release() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                max_size: 1,
+                ..Default::default()
+            })
+            .await;
+        let held = pool.acquire().await.unwrap();
+        let waiter = {
+            let pool = pool.clone();
+            tokio::spawn(async move { pool.acquire().await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        drop(held);
+        waiter.await.unwrap().unwrap();
+        assert_eq!(h.opened(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dead_connections_are_replaced_on_checkout() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                health_check_interval_secs: 0,
+                ..Default::default()
+            })
+            .await;
+        h.alive.store(false, Ordering::SeqCst);
+        let _conn = pool.acquire().await.unwrap();
+        assert_eq!(h.opened(), 2, "dead idle connection replaced");
+    }
+
+    #[tokio::test]
+    async fn test_health_check_refills_to_min_size() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                min_size: 2,
+                ..Default::default()
+            })
+            .await;
+        h.alive.store(false, Ordering::SeqCst);
+        assert_eq!(pool.health_check().await.unwrap(), 2);
+        assert_eq!(pool.status().idle, 2);
+        assert_eq!(h.opened(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_shrinks_to_min_size() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                min_size: 1,
+                max_size: 3,
+                idle_timeout_secs: 0,
+                ..Default::default()
+            })
+            .await;
+        let conns = vec![
+            pool.acquire().await.unwrap(),
+            pool.acquire().await.unwrap(),
+            pool.acquire().await.unwrap(),
+        ];
+        drop(conns);
+        assert_eq!(pool.status().size, 1);
+        assert_eq!(h.closed().await, 2, "expired connections disconnected");
+    }
+
+    #[tokio::test]
+    async fn test_open_transaction_is_not_pooled() {
+        let h = Harness::new();
+        let pool = h.pool(PoolConfig::default()).await;
+        let mut conn = pool.acquire().await.unwrap();
+        conn.begin_transaction().await.unwrap();
+        drop(conn);
+        assert_eq!(pool.status().size, 0, "connection discarded");
+        assert_eq!(h.closed().await, 1, "discarded connection disconnected");
+
+        let mut conn = pool.acquire().await.unwrap();
+        conn.begin_transaction().await.unwrap();
+        conn.commit_transaction().await.unwrap();
+        drop(conn);
+        assert_eq!(pool.status().idle, 1);
+    }
+
+    #[tokio::test]
+    async fn test_background_health_check_without_checkouts() {
+        let h = Harness::new();
+        let pool = h
+            .pool(PoolConfig {
+                min_size: 2,
+                health_check_interval_secs: 1,
+                ..Default::default()
+            })
+            .await;
+        h.alive.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        assert!(h.opened() >= 4, "dead idle connections replaced in the background");
+        assert_eq!(pool.status().idle, 2);
+        assert!(h.closed().await >= 2, "dead idle connections disconnected");
+    }
+
+    #[tokio::test]
+    async fn test_drop_stops_background_task() {
+        let h = Harness::new();
+        let pool = h.pool(PoolConfig::default()).await;
+        let task = pool
+            .maintenance
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .abort_handle();
+        assert!(!task.is_finished());
+        drop(pool);
+        assert_eq!(h.closed().await, 1, "idle connection disconnected");
+        assert!(task.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_close_rejects_new_checkouts() {
+        let h = Harness::new();
+        let pool = h.pool(PoolConfig::default()).await;
+        let held = pool.acquire().await.unwrap();
+        pool.close().await.unwrap();
+        assert!(pool.acquire().await.is_err());
+        drop(held);
+        assert_eq!(pool.status().idle, 0);
+    }
+
+    #[test]
+    fn test_in_memory_sqlite_is_single_connection() {
+        let config = ConnectionConfig {
+            engine: DatabaseEngine::Sqlite,
+            pool: PoolConfig {
+                min_size: 2,
+                max_size: 8,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let effective = effective_pool_config(&config);
+        assert_eq!((effective.min_size, effective.max_size), (1, 1));
+
+        let file = ConnectionConfig {
+            file_path: Some("/data/app.db".to_string()),
+            ..config
+        };
+        assert_eq!(effective_pool_config(&file).max_size, 8);
+    }
+}
@@ -1,24 +1,19 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-
-use super::connectors::{
-    cosmosdb::CosmosDbConnector,
-    mongodb_connector::MongoDbConnector,
-    mysql::MySqlConnector,
-    oracle::OracleConnector,
-    postgres::PostgresConnector,
-    sqlite::SqliteConnector,
-    sqlserver::SqlServerConnector,
-    ConnectionConfig, DatabaseConnector, DatabaseEngine,
-};
+
+use super::connectors::ConnectionConfig;
+use super::pool::ConnectionPool;
 use crate::db::migrator::CancellationToken;
 
-pub type SharedConnector = Arc<Mutex<Box<dyn DatabaseConnector>>>;
+pub type SharedPool = Arc<ConnectionPool>;
 
-/// Holds live database connections keyed by a user-chosen ID.
+/// Holds a connection pool per user-chosen ID.
+///
+/// Callers clone the pool out with [`ConnectionRegistry::get`] and release
+/// the registry lock before checking out a connection, so work against
+/// different databases, or several connections to one, runs concurrently.
 pub struct ConnectionRegistry {
-    connections: HashMap<String, SharedConnector>,
+    connections: HashMap<String, SharedPool>,
 }
 
 impl Default for ConnectionRegistry {
@@ -34,32 +29,35 @@ impl ConnectionRegistry {
         }
     }
 
-    /// Create a connector for the given engine, connect it, and store it.
+    /// Open a pool for the given config and store it, closing any pool
+    /// previously registered under the same ID.
     pub async fn connect(&mut self, id: String, config: ConnectionConfig) -> anyhow::Result<()> {
-        let mut connector: Box<dyn DatabaseConnector> = match config.engine {
-            DatabaseEngine::SqlServer => Box::new(SqlServerConnector::new(config)),
-            DatabaseEngine::PostgreSql => Box::new(PostgresConnector::new(config)),
-            DatabaseEngine::MySql => Box::new(MySqlConnector::new(config)),
-            DatabaseEngine::Sqlite => Box::new(SqliteConnector::new(config)),
-            DatabaseEngine::MongoDb => Box::new(MongoDbConnector::new(config)),
-            DatabaseEngine::Oracle => Box::new(OracleConnector::new(config)),
-            DatabaseEngine::CosmosDb => Box::new(CosmosDbConnector::new(config)),
-        };
-        connector.connect().await?;
-        self.connections.insert(id, Arc::new(Mutex::new(connector)));
+        let pool = ConnectionPool::open(config).await?;
+        if let Some(old) = self.insert(id, pool) {
+            old.close().await?;
+        }
         Ok(())
     }
 
-    /// Get a shared reference to a live connector.
-    pub fn get(&self, id: &str) -> Option<SharedConnector> {
+    /// Register an open pool, returning the one it replaces.
+    pub fn insert(&mut self, id: String, pool: SharedPool) -> Option<SharedPool> {
+        self.connections.insert(id, pool)
+    }
+
+    /// Get a shared reference to a registered pool.
+    pub fn get(&self, id: &str) -> Option<SharedPool> {
         self.connections.get(id).cloned()
     }
 
-    /// Disconnect and remove a connector.
+    /// Unregister a pool without closing it.
+    pub fn remove(&mut self, id: &str) -> Option<SharedPool> {
+        self.connections.remove(id)
+    }
+
+    /// Close and remove a pool.
     pub async fn disconnect(&mut self, id: &str) -> anyhow::Result<()> {
-        if let Some(conn) = self.connections.remove(id) {
-            let mut guard = conn.lock().await;
-            guard.disconnect().await?;
+        if let Some(pool) = self.connections.remove(id) {
+            pool.close().await?;
         }
         Ok(())
     }
@@ -99,4 +97,8 @@ impl MigrationState {
     pub fn remove(&mut self, id: &str) {
         self.tokens.remove(id);
     }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.tokens.contains_key(id)
+    }
 }
@@ -0,0 +1,254 @@
+//! Similarity scoring behind rename detection in schema comparison.
+//!
+//! An object found only in the source and one found only in the target are
+//! scored between 0 and 1 on how alike their structure is. Pairs scoring at
+//! least the threshold are proposed as renames, best score first, each
+//! object taking part in at most one.
+
+use std::collections::HashSet;
+
+use super::comparator::normalize_type;
+use super::schema::{ColumnInfo, TableInfo};
+
+/// Lowest score at which a table pair is proposed as a rename.
+pub const TABLE_RENAME_THRESHOLD: f64 = 0.75;
+
+/// Lowest score at which a column pair is proposed as a rename.
+pub const COLUMN_RENAME_THRESHOLD: f64 = 0.7;
+
+/// Fewest column names two tables must share to be scored at all; a lone
+/// `id` column says nothing about whether one table became the other.
+const MIN_SHARED_COLUMNS: usize = 2;
+
+/// Pairs of `removed` and `added` indexes proposed as renames, with their
+/// scores, best first.
+pub fn match_renames<S, T>(
+    removed: &[S],
+    added: &[T],
+    threshold: f64,
+    score: impl Fn(&S, &T) -> f64,
+) -> Vec<(usize, usize, f64)> {
+    let score = &score;
+    let mut candidates: Vec<(usize, usize, f64)> = removed
+        .iter()
+        .enumerate()
+        .flat_map(|(i, r)| added.iter().enumerate().map(move |(j, a)| (i, j, score(r, a))))
+        .filter(|(_, _, s)| *s >= threshold)
+        .collect();
+    // Ties go to the earlier pair
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+
+    let (mut used_removed, mut used_added) = (HashSet::new(), HashSet::new());
+    candidates
+        .into_iter()
+        .filter(|(i, j, _)| {
+            !used_removed.contains(i)
+                && !used_added.contains(j)
+                && used_removed.insert(*i)
+                && used_added.insert(*j)
+        })
+        .collect()
+}
+
+/// How alike two tables are: shared column names weigh most, then the
+/// column types position by position, whether shared columns kept their
+/// positions, and the primary key and indexes.
+pub fn table_similarity(source: &TableInfo, target: &TableInfo) -> f64 {
+    if source.columns.is_empty() || target.columns.is_empty() {
+        return 0.0;
+    }
+    let source_cols = by_position(&source.columns);
+    let target_cols = by_position(&target.columns);
+
+    let source_names: HashSet<String> = source_cols.iter().map(|c| c.name.to_lowercase()).collect();
+    let target_names: HashSet<String> = target_cols.iter().map(|c| c.name.to_lowercase()).collect();
+    let shared = source_names.intersection(&target_names).count();
+    if shared < MIN_SHARED_COLUMNS {
+        return 0.0;
+    }
+    let names = jaccard(&source_names, &target_names);
+
+    let longest = source_cols.len().max(target_cols.len());
+    let same_types = source_cols
+        .iter()
+        .zip(&target_cols)
+        .filter(|(s, t)| normalize_type(&s.data_type) == normalize_type(&t.data_type))
+        .count();
+    let types = same_types as f64 / longest as f64;
+
+    let kept_position = source_cols
+        .iter()
+        .zip(&target_cols)
+        .filter(|(s, t)| s.name.eq_ignore_ascii_case(&t.name))
+        .count();
+    let positions = kept_position as f64 / shared as f64;
+
+    let primary_key = |cols: &[&ColumnInfo]| -> HashSet<String> {
+        cols.iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.to_lowercase())
+            .collect()
+    };
+    let keys = if primary_key(&source_cols) == primary_key(&target_cols) {
+        1.0
+    } else {
+        0.0
+    };
+
+    let index_columns = |table: &TableInfo| -> HashSet<String> {
+        table
+            .indexes
+            .iter()
+            .map(|i| i.columns.join(",").to_lowercase())
+            .collect()
+    };
+    let indexes = jaccard(&index_columns(source), &index_columns(target));
+
+    0.45 * names + 0.25 * types + 0.1 * positions + 0.1 * keys + 0.1 * indexes
+}
+
+/// How alike two columns are: the type weighs most, then how much the
+/// names resemble each other, the position, nullability, key membership
+/// and size.
+pub fn column_similarity(source: &ColumnInfo, target: &ColumnInfo) -> f64 {
+    let same = |equal: bool| if equal { 1.0 } else { 0.0 };
+    let types = same(normalize_type(&source.data_type) == normalize_type(&target.data_type));
+    let names = name_similarity(&source.name, &target.name);
+    let position = same(source.ordinal_position == target.ordinal_position);
+    let flags = (same(source.is_nullable == target.is_nullable)
+        + same(source.is_primary_key == target.is_primary_key))
+        / 2.0;
+    let size = same(
+        source.max_length == target.max_length
+            && source.precision == target.precision
+            && source.scale == target.scale,
+    );
+
+    0.4 * types + 0.35 * names + 0.1 * position + 0.1 * flags + 0.05 * size
+}
+
+/// Dice coefficient of the names' character bigrams, ignoring case.
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.to_lowercase().chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let mut remaining = b.clone();
+    let common = a
+        .iter()
+        .filter(|bigram| match remaining.iter().position(|r| r == *bigram) {
+            Some(i) => {
+                remaining.swap_remove(i);
+                true
+            }
+            None => false,
+        })
+        .count();
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+fn by_position(columns: &[ColumnInfo]) -> Vec<&ColumnInfo> {
+    let mut sorted: Vec<&ColumnInfo> = columns.iter().collect();
+    sorted.sort_by_key(|c| c.ordinal_position);
+    sorted
+}
+
+/// Share of the union both sets hold; 1 when both are empty.
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        1.0
+    } else {
+        a.intersection(b).count() as f64 / union as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::IndexInfo;
+
+    fn col(name: &str, data_type: &str, position: i32, pk: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: !pk,
+            is_primary_key: pk,
+            max_length: None,
+            precision: None,
+            scale: None,
+            default_value: None,
+            ordinal_position: position,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnInfo>) -> TableInfo {
+        TableInfo {
+            schema_name: "dbo".to_string(),
+            table_name: name.to_string(),
+            columns,
+            indexes: vec![IndexInfo {
+                name: format!("pk_{}", name),
+                columns: vec!["id".to_string()],
+                is_unique: true,
+                is_clustered: true,
+                index_type: "btree".to_string(),
+            }],
+            constraints: vec![],
+            row_count: None,
+            field_stats: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_table_similarity() {
+        let customers = table(
+            "customers",
+            vec![col("id", "int", 1, true), col("name", "varchar", 2, false)],
+        );
+        let clients = table(
+            "clients",
+            vec![col("id", "int", 1, true), col("name", "varchar", 2, false)],
+        );
+        assert!((table_similarity(&customers, &clients) - 1.0).abs() < 1e-9);
+
+        let invoices = table(
+            "invoices",
+            vec![col("id", "int", 1, true), col("total", "decimal", 2, false)],
+        );
+        assert!(table_similarity(&customers, &invoices) < TABLE_RENAME_THRESHOLD);
+
+        let ids = |name| table(name, vec![col("id", "int", 1, true)]);
+        assert_eq!(table_similarity(&ids("legacy"), &ids("products")), 0.0);
+    }
+
+    #[test]
+    fn test_column_similarity() {
+        let cust_id = col("cust_id", "int", 2, false);
+        assert!(column_similarity(&cust_id, &col("customer_id", "int", 3, false)) >= 0.7);
+        assert!(column_similarity(&cust_id, &col("customer_id", "varchar", 2, false)) < 0.7);
+        assert!(
+            column_similarity(&col("fax", "varchar", 4, false), &col("email", "varchar", 4, false))
+                < COLUMN_RENAME_THRESHOLD
+        );
+    }
+
+    #[test]
+    fn test_match_renames_takes_best_pairs() {
+        let scores = [[0.9, 0.8], [0.95, 0.5]];
+        let pairs = match_renames(&[0, 1], &[0, 1], 0.7, |r: &usize, a: &usize| scores[*r][*a]);
+        assert_eq!(pairs, vec![(1, 0, 0.95), (0, 1, 0.8)]);
+        assert!(match_renames(&[0], &[0], 0.99, |_: &i32, _: &i32| 0.9).is_empty());
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("Email", "email"), 1.0);
+        assert_eq!(name_similarity("ab", "cd"), 0.0);
+        assert!(name_similarity("cust_id", "customer_id") > 0.6);
+    }
+}
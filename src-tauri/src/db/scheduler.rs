@@ -0,0 +1,341 @@
+//! FK-aware scheduling for multi-table migrations.
+//!
+//! Tables are grouped into units: a single table, or every table of a
+//! foreign key cycle. A table referencing itself is a cycle of its own. Units form a DAG (a unit depends on the units holding
+//! its parent tables), so independent units can run in parallel while a
+//! child never starts before its parents finish.
+
+use std::collections::{HashMap, HashSet};
+
+/// A group of tables migrated together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationUnit {
+    /// Target table names, in request order.
+    pub tables: Vec<String>,
+    /// Indexes of the units that must finish first.
+    pub depends_on: Vec<usize>,
+    /// The tables reference each other (or the one table references
+    /// itself) in a cycle, so no table order satisfies the foreign keys and
+    /// they must be relaxed while loading.
+    pub cyclic: bool,
+}
+
+/// Tracks which units are ready, running and done.
+#[derive(Debug)]
+pub struct Schedule {
+    units: Vec<MigrationUnit>,
+    /// Unfinished dependencies per unit.
+    waiting_on: Vec<usize>,
+    /// Units that depend on each unit.
+    dependents: Vec<Vec<usize>>,
+    started: Vec<bool>,
+    finished: usize,
+}
+
+impl Schedule {
+    /// Build the schedule for `tables` (target names) given each table's FK
+    /// parents. Parents outside `tables` are ignored, self-references only
+    /// mark the table's unit cyclic, and a table listed twice is scheduled
+    /// once.
+    pub fn new(tables: &[String], fk_deps: &HashMap<String, Vec<String>>) -> Self {
+        let units = build_units(tables, fk_deps);
+        let mut dependents = vec![Vec::new(); units.len()];
+        for (i, unit) in units.iter().enumerate() {
+            for &dep in &unit.depends_on {
+                dependents[dep].push(i);
+            }
+        }
+        Self {
+            waiting_on: units.iter().map(|u| u.depends_on.len()).collect(),
+            started: vec![false; units.len()],
+            dependents,
+            finished: 0,
+            units,
+        }
+    }
+
+    pub fn units(&self) -> &[MigrationUnit] {
+        &self.units
+    }
+
+    pub fn unit(&self, index: usize) -> &MigrationUnit {
+        &self.units[index]
+    }
+
+    /// Take the next unit whose dependencies have all finished, marking it
+    /// started. Units become ready in dependency order, ties in request order.
+    pub fn next_ready(&mut self) -> Option<usize> {
+        let index = (0..self.units.len()).find(|&i| !self.started[i] && self.waiting_on[i] == 0)?;
+        self.started[index] = true;
+        Some(index)
+    }
+
+    /// Mark a started unit finished, releasing its dependents.
+    pub fn complete(&mut self, index: usize) {
+        self.finished += 1;
+        for &child in &self.dependents[index] {
+            self.waiting_on[child] = self.waiting_on[child].saturating_sub(1);
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished == self.units.len()
+    }
+
+    /// Tables in an order that respects the schedule when run one at a time.
+    pub fn linear_order(&self) -> Vec<String> {
+        self.units
+            .iter()
+            .flat_map(|u| u.tables.iter().cloned())
+            .collect()
+    }
+}
+
+/// Group tables into strongly connected components (Tarjan) over the
+/// child -> parent edges. Tarjan emits a component only after everything it
+/// reaches, so the result is already ordered parents first.
+fn build_units(tables: &[String], fk_deps: &HashMap<String, Vec<String>>) -> Vec<MigrationUnit> {
+    let mut seen = HashSet::new();
+    let tables: Vec<&String> = tables.iter().filter(|t| seen.insert(t.as_str())).collect();
+    let index_of: HashMap<&str, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.as_str(), i))
+        .collect();
+    let edges: Vec<Vec<usize>> = tables
+        .iter()
+        .map(|t| {
+            let mut parents: Vec<usize> = fk_deps
+                .get(*t)
+                .into_iter()
+                .flatten()
+                .filter_map(|p| index_of.get(p.as_str()).copied())
+                .collect();
+            parents.sort_unstable();
+            parents.dedup();
+            parents
+        })
+        .collect();
+
+    let mut tarjan = Tarjan {
+        edges: &edges,
+        next_index: 0,
+        index: vec![None; tables.len()],
+        low_link: vec![0; tables.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; tables.len()],
+        components: Vec::new(),
+    };
+    for node in 0..tables.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+    let components = tarjan.components;
+
+    let mut component_of = vec![0; tables.len()];
+    for (c, members) in components.iter().enumerate() {
+        for &m in members {
+            component_of[m] = c;
+        }
+    }
+
+    components
+        .iter()
+        .enumerate()
+        .map(|(c, members)| {
+            let mut members = members.clone();
+            members.sort_unstable();
+            let mut depends_on: Vec<usize> = members
+                .iter()
+                .flat_map(|&m| edges[m].iter().map(|&p| component_of[p]))
+                .filter(|&d| d != c)
+                .collect();
+            depends_on.sort_unstable();
+            depends_on.dedup();
+            MigrationUnit {
+                cyclic: members.len() > 1 || edges[members[0]].contains(&members[0]),
+                tables: members.iter().map(|&m| tables[m].clone()).collect(),
+                depends_on,
+            }
+        })
+        .collect()
+}
+
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    next_index: usize,
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &next in &self.edges[node] {
+            match self.index[next] {
+                None => {
+                    self.visit(next);
+                    self.low_link[node] = self.low_link[node].min(self.low_link[next]);
+                }
+                Some(idx) if self.on_stack[next] => {
+                    self.low_link[node] = self.low_link[node].min(idx);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn deps(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(child, parents)| (child.to_string(), names(parents)))
+            .collect()
+    }
+
+    fn position(order: &[String], table: &str) -> usize {
+        order.iter().position(|t| t == table).unwrap()
+    }
+
+    #[test]
+    fn test_independent_tables_are_all_ready() {
+        let mut schedule = Schedule::new(&names(&["a", "b", "c"]), &HashMap::new());
+        assert_eq!(schedule.next_ready(), Some(0));
+        assert_eq!(schedule.next_ready(), Some(1));
+        assert_eq!(schedule.next_ready(), Some(2));
+        assert_eq!(schedule.next_ready(), None);
+        assert!(schedule.units().iter().all(|u| !u.cyclic));
+    }
+
+    #[test]
+    fn test_children_wait_for_parents() {
+        let tables = names(&["order_items", "orders", "customers", "products"]);
+        let fk = deps(&[
+            ("order_items", &["orders", "products"]),
+            ("orders", &["customers"]),
+        ]);
+        let mut schedule = Schedule::new(&tables, &fk);
+
+        let mut ready = Vec::new();
+        while let Some(i) = schedule.next_ready() {
+            ready.push(schedule.unit(i).tables[0].clone());
+        }
+        ready.sort();
+        assert_eq!(ready, names(&["customers", "products"]));
+
+        let customers = schedule
+            .units()
+            .iter()
+            .position(|u| u.tables[0] == "customers")
+            .unwrap();
+        schedule.complete(customers);
+        let orders = schedule.next_ready().unwrap();
+        assert_eq!(schedule.unit(orders).tables, names(&["orders"]));
+        assert_eq!(schedule.next_ready(), None);
+
+        schedule.complete(orders);
+        assert_eq!(schedule.next_ready(), None, "products still running");
+        let products = schedule
+            .units()
+            .iter()
+            .position(|u| u.tables[0] == "products")
+            .unwrap();
+        schedule.complete(products);
+        let items = schedule.next_ready().unwrap();
+        assert_eq!(schedule.unit(items).tables, names(&["order_items"]));
+        schedule.complete(items);
+        assert!(schedule.is_finished());
+    }
+
+    #[test]
+    fn test_cycle_becomes_one_unit() {
+        let tables = names(&["employees", "departments", "projects"]);
+        let fk = deps(&[
+            ("employees", &["departments"]),
+            ("departments", &["employees"]),
+            ("projects", &["departments"]),
+        ]);
+        let schedule = Schedule::new(&tables, &fk);
+        assert_eq!(schedule.units().len(), 2);
+
+        let cycle = &schedule.units()[0];
+        assert!(cycle.cyclic);
+        assert_eq!(cycle.tables, names(&["employees", "departments"]));
+        assert!(cycle.depends_on.is_empty());
+
+        let projects = &schedule.units()[1];
+        assert!(!projects.cyclic);
+        assert_eq!(projects.depends_on, vec![0]);
+    }
+
+    #[test]
+    fn test_self_reference_is_cyclic_and_unknown_parents_are_ignored() {
+        let tables = names(&["categories", "items"]);
+        let fk = deps(&[
+            ("categories", &["categories"]),
+            ("items", &["categories", "suppliers"]),
+        ]);
+        let schedule = Schedule::new(&tables, &fk);
+        assert_eq!(schedule.units().len(), 2);
+        let cyclic: Vec<bool> = schedule.units().iter().map(|u| u.cyclic).collect();
+        assert_eq!(cyclic, vec![true, false]);
+        assert!(schedule.units()[0].depends_on.is_empty());
+        assert_eq!(schedule.linear_order(), names(&["categories", "items"]));
+    }
+
+    #[test]
+    fn test_linear_order_puts_parents_first() {
+        let tables = names(&["c", "b", "a", "d"]);
+        let fk = deps(&[("c", &["b"]), ("b", &["a"]), ("d", &["c", "a"])]);
+        let order = Schedule::new(&tables, &fk).linear_order();
+        assert!(position(&order, "a") < position(&order, "b"));
+        assert!(position(&order, "b") < position(&order, "c"));
+        assert!(position(&order, "c") < position(&order, "d"));
+    }
+
+    #[test]
+    fn test_duplicate_tables_are_scheduled_once() {
+        let schedule = Schedule::new(&names(&["a", "b", "a"]), &deps(&[("b", &["a"])]));
+        assert_eq!(schedule.linear_order(), names(&["a", "b"]));
+    }
+
+    #[test]
+    fn test_nested_cycles_merge() {
+        let tables = names(&["a", "b", "c", "d"]);
+        let fk = deps(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"]), ("d", &["a"])]);
+        let schedule = Schedule::new(&tables, &fk);
+        assert_eq!(schedule.units().len(), 2);
+        assert_eq!(schedule.units()[0].tables, names(&["a", "b", "c"]));
+        assert!(schedule.units()[0].cyclic);
+        assert_eq!(schedule.units()[1].depends_on, vec![0]);
+    }
+}
@@ -1,10 +1,56 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Represents the complete schema of a database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaInfo {
     pub database_name: String,
+    /// Schema objects belong to when named without one (`dbo`, `public`,
+    /// `main`); `None` on engines without schemas.
+    #[serde(default)]
+    pub default_schema: Option<String>,
     pub tables: Vec<TableInfo>,
+    #[serde(default)]
+    pub views: Vec<ViewInfo>,
+    /// Stored procedures and functions
+    #[serde(default)]
+    pub routines: Vec<RoutineInfo>,
+    #[serde(default)]
+    pub triggers: Vec<TriggerInfo>,
+    #[serde(default)]
+    pub sequences: Vec<SequenceInfo>,
+    #[serde(default)]
+    pub user_types: Vec<UserTypeInfo>,
+}
+
+impl SchemaInfo {
+    /// Name an object is matched under across databases: bare in this
+    /// database's default schema, or on engines without one in the schema
+    /// named after the database (MySQL, Oracle and the document engines);
+    /// `schema.name` anywhere else.
+    pub fn qualify(&self, schema: &str, name: &str) -> String {
+        let default = self.default_schema.as_deref().unwrap_or(&self.database_name);
+        if schema.is_empty() || schema == default {
+            name.to_string()
+        } else {
+            format!("{}.{}", schema, name)
+        }
+    }
+
+    /// Schema holding only `tables`, for engines without other objects.
+    pub fn with_tables(database_name: String, tables: Vec<TableInfo>) -> Self {
+        Self {
+            database_name,
+            default_schema: None,
+            tables,
+            views: Vec::new(),
+            routines: Vec::new(),
+            triggers: Vec::new(),
+            sequences: Vec::new(),
+            user_types: Vec::new(),
+        }
+    }
 }
 
 /// Represents a single database table
@@ -16,6 +62,10 @@ pub struct TableInfo {
     pub indexes: Vec<IndexInfo>,
     pub constraints: Vec<ConstraintInfo>,
     pub row_count: Option<i64>,
+    /// Per-field statistics of the documents sampled to infer a
+    /// document collection's columns; empty for relational tables.
+    #[serde(default)]
+    pub field_stats: Vec<FieldStats>,
 }
 
 /// Represents a column in a table
@@ -32,6 +82,21 @@ pub struct ColumnInfo {
     pub ordinal_position: i32,
 }
 
+/// Sampled statistics of one document field
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldStats {
+    /// Dotted path of the field; fields of array elements extend the
+    /// array's path (`lines.sku`).
+    pub path: String,
+    /// Number of sampled values of each BSON type, nulls included
+    pub type_counts: BTreeMap<String, u64>,
+    /// Share of sampled documents holding only nulls at the path
+    pub null_ratio: f64,
+    /// Share of sampled documents without the path
+    pub missing_ratio: f64,
+    pub max_string_length: Option<u32>,
+}
+
 /// Represents an index on a table
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexInfo {
@@ -62,5 +127,208 @@ pub enum ConstraintType {
     Default,
 }
 
+/// Represents a view and the query defining it
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ViewInfo {
+    pub schema_name: String,
+    pub name: String,
+    pub definition: String,
+}
+
+/// Represents a stored procedure or function
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct RoutineInfo {
+    pub schema_name: String,
+    pub name: String,
+    /// "PROCEDURE" or "FUNCTION"
+    pub routine_type: String,
+    /// Parameter list as the engine reports it, e.g. `id integer, OUT total numeric`
+    pub signature: String,
+    pub return_type: Option<String>,
+    pub body: String,
+}
+
+/// Represents a trigger on a table
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TriggerInfo {
+    pub schema_name: String,
+    pub name: String,
+    pub table_name: String,
+    /// "BEFORE", "AFTER" or "INSTEAD OF"
+    pub timing: String,
+    /// Firing events joined with " OR ", e.g. `INSERT OR UPDATE`
+    pub event: String,
+    pub definition: String,
+}
+
+/// Represents a sequence. Bounds are kept as text since they can exceed
+/// 64 bits (SQL Server `decimal(38)` sequences).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SequenceInfo {
+    pub schema_name: String,
+    pub name: String,
+    pub data_type: String,
+    pub start_value: String,
+    pub increment: String,
+    pub min_value: String,
+    pub max_value: String,
+    pub cycle: bool,
+}
+
+/// Represents a user-defined type
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct UserTypeInfo {
+    pub schema_name: String,
+    pub name: String,
+    /// "enum", "domain", "composite", "range", "alias" or "table"
+    pub kind: String,
+    /// Enum labels, base type, or attribute list, depending on `kind`
+    pub definition: String,
+}
+
+/// Which tables a connection lists. Patterns match names with `*` and `?`
+/// wildcards, ignoring case. A table pattern holding a `.` matches the
+/// schema-qualified name (`sales.order_*`), others the bare table name.
+/// Empty include lists include everything; excludes win over includes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SchemaFilter {
+    pub include_schemas: Vec<String>,
+    pub exclude_schemas: Vec<String>,
+    pub include_tables: Vec<String>,
+    pub exclude_tables: Vec<String>,
+}
+
+impl SchemaFilter {
+    pub fn includes_schema(&self, schema: &str) -> bool {
+        selects(&self.include_schemas, &self.exclude_schemas, |p| {
+            matches_pattern(p, schema)
+        })
+    }
+
+    pub fn includes_table(&self, schema: &str, table: &str) -> bool {
+        let qualified = format!("{}.{}", schema, table);
+        self.includes_schema(schema)
+            && selects(&self.include_tables, &self.exclude_tables, |p| {
+                matches_pattern(p, if p.contains('.') { &qualified } else { table })
+            })
+    }
+}
+
+fn selects(include: &[String], exclude: &[String], matches: impl Fn(&str) -> bool) -> bool {
+    (include.is_empty() || include.iter().any(|p| matches(p)))
+        && !exclude.iter().any(|p| matches(p))
+}
+
+/// Case-insensitive match of `name` against a `*`/`?` wildcard pattern.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    // Positions to resume from after the last `*`
+    let (mut p, mut n, mut star) = (0, 0, None);
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((sp, sn)) = star {
+            p = sp + 1;
+            n = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Name a table is listed under: bare in the engine's default schema,
+/// `schema.table` anywhere else.
+pub fn qualified_table_name(schema: &str, table: &str, default_schema: &str) -> String {
+    if schema == default_schema {
+        table.to_string()
+    } else {
+        format!("{}.{}", schema, table)
+    }
+}
+
+/// Split a listed table name into its schema and bare name; bare names
+/// belong to `default_schema`.
+pub fn split_table_name<'a>(name: &'a str, default_schema: &'a str) -> (&'a str, &'a str) {
+    name.split_once('.').unwrap_or((default_schema, name))
+}
+
 /// Represents a database row as a map of column names to JSON values
 pub type Row = std::collections::HashMap<String, serde_json::Value>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("order*", "Orders"));
+        assert!(matches_pattern("*_log", "audit_log"));
+        assert!(matches_pattern("t?st", "test"));
+        assert!(matches_pattern("*", ""));
+        assert!(!matches_pattern("order*", "reorder"));
+        assert!(!matches_pattern("t?st", "toast"));
+    }
+
+    #[test]
+    fn test_schema_filter() {
+        let filter = SchemaFilter {
+            include_schemas: vec!["sales".into(), "dbo".into()],
+            exclude_tables: vec!["*_log".into(), "dbo.tmp*".into()],
+            ..Default::default()
+        };
+        assert!(filter.includes_table("sales", "orders"));
+        assert!(!filter.includes_table("archive", "orders"));
+        assert!(!filter.includes_table("sales", "audit_log"));
+        assert!(!filter.includes_table("dbo", "tmp_import"));
+        assert!(filter.includes_table("sales", "tmp_import"));
+        assert!(SchemaFilter::default().includes_table("any", "thing"));
+    }
+
+    #[test]
+    fn test_qualify() {
+        let schema = SchemaInfo::with_tables("shop".to_string(), Vec::new());
+        assert_eq!(schema.qualify("", "orders"), "orders");
+        assert_eq!(schema.qualify("shop", "orders"), "orders");
+        assert_eq!(schema.qualify("dbo", "orders"), "dbo.orders");
+        assert_eq!(schema.qualify("sales", "orders"), "sales.orders");
+    }
+
+    #[test]
+    fn test_qualify_strips_only_the_engine_default_schema() {
+        let postgres = SchemaInfo {
+            default_schema: Some("public".to_string()),
+            ..SchemaInfo::with_tables("shop".to_string(), Vec::new())
+        };
+        assert_eq!(postgres.qualify("public", "orders"), "orders");
+        assert_eq!(postgres.qualify("dbo", "orders"), "dbo.orders");
+        assert_eq!(postgres.qualify("shop", "orders"), "shop.orders");
+
+        let sqlserver = SchemaInfo {
+            default_schema: Some("dbo".to_string()),
+            ..SchemaInfo::with_tables("shop".to_string(), Vec::new())
+        };
+        assert_eq!(sqlserver.qualify("dbo", "orders"), "orders");
+        assert_eq!(sqlserver.qualify("public", "x"), "public.x");
+    }
+
+    #[test]
+    fn test_table_name_qualification() {
+        assert_eq!(qualified_table_name("dbo", "orders", "dbo"), "orders");
+        assert_eq!(qualified_table_name("sales", "orders", "dbo"), "sales.orders");
+        assert_eq!(split_table_name("sales.orders", "public"), ("sales", "orders"));
+        assert_eq!(split_table_name("orders", "public"), ("public", "orders"));
+    }
+}
@@ -1,5 +1,6 @@
 use super::connectors::DatabaseEngine;
-use super::schema::{ColumnInfo, Row};
+use super::schema::{split_table_name, ColumnInfo, ConstraintInfo, ConstraintType, Row};
+use super::typed_value::TypedValue;
 
 /// Engine-aware SQL statement generator.
 pub struct SqlGenerator {
@@ -20,8 +21,23 @@ impl SqlGenerator {
         }
     }
 
+    /// Quote a table name for the target engine. On engines that list
+    /// tables as `schema.table`, a qualified name (`sales.orders`) is
+    /// quoted part by part; elsewhere a dot belongs to the name.
+    pub fn quote_table(&self, name: &str) -> String {
+        match name.split_once('.') {
+            Some((schema, table)) if self.engine.qualifies_table_names() => {
+                format!("{}.{}", self.quote_ident(schema), self.quote_ident(table))
+            }
+            _ => self.quote_ident(name),
+        }
+    }
+
     /// Convert a serde_json::Value to an SQL literal.
     fn value_to_sql(&self, value: &serde_json::Value) -> String {
+        if let Some(typed) = TypedValue::from_json(value) {
+            return self.typed_value_to_sql(&typed);
+        }
         match value {
             serde_json::Value::Null => "NULL".to_string(),
             serde_json::Value::Bool(b) => match self.engine {
@@ -44,6 +60,50 @@ impl SqlGenerator {
         }
     }
 
+    /// Render a tagged value as an engine-specific literal that preserves it
+    /// exactly.
+    fn typed_value_to_sql(&self, typed: &TypedValue) -> String {
+        let text = typed.to_text();
+        match typed {
+            // SQLite would coerce a bare literal to REAL, so keep the digits as text
+            TypedValue::Decimal(_) if self.engine == DatabaseEngine::Sqlite => {
+                format!("'{}'", text)
+            }
+            TypedValue::Decimal(_) => text,
+            TypedValue::Bytes(_) => match self.engine {
+                DatabaseEngine::SqlServer => format!("0x{}", text),
+                DatabaseEngine::PostgreSql => format!("'\\x{}'::bytea", text),
+                DatabaseEngine::Oracle => format!("HEXTORAW('{}')", text),
+                _ => format!("X'{}'", text),
+            },
+            TypedValue::Date(_) => match self.engine {
+                DatabaseEngine::Oracle => format!("DATE '{}'", text),
+                _ => format!("'{}'", text),
+            },
+            TypedValue::Time(t) => {
+                format!("'{}'", trim_fraction(&t.format("%H:%M:%S%.f").to_string()))
+            }
+            TypedValue::DateTime(dt) => {
+                let formatted = trim_fraction(&dt.format("%Y-%m-%d %H:%M:%S%.f").to_string());
+                match self.engine {
+                    DatabaseEngine::Oracle => format!("TIMESTAMP '{}'", formatted),
+                    _ => format!("'{}'", formatted),
+                }
+            }
+            TypedValue::Timestamp(ts) => {
+                let local = trim_fraction(&ts.format("%Y-%m-%d %H:%M:%S%.f").to_string());
+                match self.engine {
+                    // Oracle wants the offset as a separate field
+                    DatabaseEngine::Oracle => {
+                        format!("TIMESTAMP '{} {}'", local, ts.format("%:z"))
+                    }
+                    _ => format!("'{}{}'", local, ts.format("%:z")),
+                }
+            }
+            TypedValue::Uuid(_) => format!("'{}'", text),
+        }
+    }
+
     /// Generate an INSERT statement.
     pub fn generate_insert(&self, table: &str, row: &Row) -> String {
         let mut cols: Vec<&String> = row.keys().collect();
@@ -52,7 +112,7 @@ impl SqlGenerator {
         let val_list = cols.iter().map(|c| self.value_to_sql(&row[*c])).collect::<Vec<_>>().join(", ");
         format!(
             "INSERT INTO {} ({}) VALUES ({});",
-            self.quote_ident(table),
+            self.quote_table(table),
             col_list,
             val_list
         )
@@ -77,7 +137,7 @@ impl SqlGenerator {
             .join(" AND ");
         format!(
             "UPDATE {} SET {} WHERE {};",
-            self.quote_ident(table),
+            self.quote_table(table),
             set_clause,
             where_clause
         )
@@ -115,7 +175,7 @@ impl SqlGenerator {
             .join(" AND ");
         format!(
             "UPDATE {} SET {} WHERE {};",
-            self.quote_ident(table),
+            self.quote_table(table),
             set_clause,
             where_clause
         )
@@ -201,10 +261,317 @@ impl SqlGenerator {
             .join(" AND ");
         format!(
             "DELETE FROM {} WHERE {};",
-            self.quote_ident(table),
+            self.quote_table(table),
             where_clause
         )
     }
+
+    /// Statements that stop foreign key enforcement while loading `tables`,
+    /// given as (table, FK constraint names). PostgreSQL, MySQL and SQLite
+    /// switch checks off for the session; SQL Server and Oracle disable the
+    /// constraints on each table. Empty for engines without FK constraints.
+    pub fn disable_foreign_keys(&self, tables: &[(String, Vec<String>)]) -> Vec<String> {
+        match self.engine {
+            DatabaseEngine::PostgreSql => {
+                vec!["SET session_replication_role = replica;".to_string()]
+            }
+            DatabaseEngine::MySql => vec!["SET FOREIGN_KEY_CHECKS = 0;".to_string()],
+            DatabaseEngine::Sqlite => vec!["PRAGMA foreign_keys = OFF;".to_string()],
+            DatabaseEngine::SqlServer => tables
+                .iter()
+                .map(|(t, _)| format!("ALTER TABLE {} NOCHECK CONSTRAINT ALL;", self.quote_table(t)))
+                .collect(),
+            DatabaseEngine::Oracle => self.per_constraint(tables, "DISABLE CONSTRAINT"),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Statements that undo [`Self::disable_foreign_keys`]. Where the engine
+    /// supports it the constraints are re-validated against the loaded rows.
+    pub fn enable_foreign_keys(&self, tables: &[(String, Vec<String>)]) -> Vec<String> {
+        match self.engine {
+            DatabaseEngine::PostgreSql => {
+                vec!["SET session_replication_role = DEFAULT;".to_string()]
+            }
+            DatabaseEngine::MySql => vec!["SET FOREIGN_KEY_CHECKS = 1;".to_string()],
+            DatabaseEngine::Sqlite => vec!["PRAGMA foreign_keys = ON;".to_string()],
+            DatabaseEngine::SqlServer => tables
+                .iter()
+                .map(|(t, _)| {
+                    format!("ALTER TABLE {} WITH CHECK CHECK CONSTRAINT ALL;", self.quote_table(t))
+                })
+                .collect(),
+            DatabaseEngine::Oracle => self.per_constraint(tables, "ENABLE VALIDATE CONSTRAINT"),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Statement that postpones FK checks to commit for the current
+    /// transaction, for engines that can. PostgreSQL and Oracle only defer
+    /// constraints declared `DEFERRABLE`.
+    pub fn defer_constraints(&self) -> Option<String> {
+        match self.engine {
+            DatabaseEngine::PostgreSql | DatabaseEngine::Oracle => {
+                Some("SET CONSTRAINTS ALL DEFERRED;".to_string())
+            }
+            DatabaseEngine::Sqlite => Some("PRAGMA defer_foreign_keys = ON;".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Query listing, one `name` per row, the foreign keys on `tables` that
+    /// [`Self::defer_constraints`] leaves enforced because they are not
+    /// declared `DEFERRABLE`. `None` where every foreign key can be deferred.
+    pub fn non_deferrable_foreign_keys(&self, tables: &[String]) -> Option<String> {
+        let literal = |s: &str| self.value_to_sql(&serde_json::Value::String(s.to_string()));
+        match self.engine {
+            DatabaseEngine::PostgreSql => {
+                let relations = tables
+                    .iter()
+                    .map(|t| format!("{}::regclass", literal(&self.quote_table(t))))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some(format!(
+                    "SELECT conname AS name FROM pg_constraint \
+                     WHERE contype = 'f' AND NOT condeferrable AND conrelid IN ({});",
+                    relations
+                ))
+            }
+            DatabaseEngine::Oracle => {
+                let names = tables
+                    .iter()
+                    .map(|t| literal(split_table_name(t, "").1))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some(format!(
+                    "SELECT constraint_name AS name FROM all_constraints \
+                     WHERE owner = SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') \
+                     AND constraint_type = 'R' AND deferrable = 'NOT DEFERRABLE' \
+                     AND table_name IN ({})",
+                    names
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Query counting rows of `table` whose non-NULL key in `fk` has no
+    /// matching parent row. `None` when the constraint is not a foreign key
+    /// or its referenced columns are unknown.
+    pub fn count_fk_violations(&self, table: &str, fk: &ConstraintInfo) -> Option<String> {
+        let parent = fk.referenced_table.as_deref()?;
+        let parent_cols = fk.referenced_columns.as_ref()?;
+        if fk.constraint_type != ConstraintType::ForeignKey
+            || fk.columns.is_empty()
+            || fk.columns.len() != parent_cols.len()
+        {
+            return None;
+        }
+        let not_null = fk
+            .columns
+            .iter()
+            .map(|c| format!("c.{} IS NOT NULL", self.quote_ident(c)))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let matches = fk
+            .columns
+            .iter()
+            .zip(parent_cols)
+            .map(|(c, p)| format!("p.{} = c.{}", self.quote_ident(p), self.quote_ident(c)))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        Some(format!(
+            "SELECT COUNT(*) AS violations FROM {} c WHERE {} AND NOT EXISTS (SELECT 1 FROM {} p WHERE {});",
+            self.quote_table(table),
+            not_null,
+            self.quote_table(parent),
+            matches
+        ))
+    }
+
+    /// Statement copying `table`'s rows into a new table `backup`. `None`
+    /// for engines without SQL tables.
+    pub fn create_snapshot(&self, table: &str, backup: &str) -> Option<String> {
+        let (table, backup) = (self.quote_table(table), self.quote_table(backup));
+        match self.engine {
+            DatabaseEngine::SqlServer => Some(format!("SELECT * INTO {} FROM {};", backup, table)),
+            DatabaseEngine::PostgreSql
+            | DatabaseEngine::MySql
+            | DatabaseEngine::Sqlite
+            | DatabaseEngine::Oracle => {
+                Some(format!("CREATE TABLE {} AS SELECT * FROM {};", backup, table))
+            }
+            _ => None,
+        }
+    }
+
+    /// CREATE TABLE statement for `columns`, whose types are already native
+    /// to the engine, with the primary and foreign keys among
+    /// `constraints`. `None` for engines without SQL tables.
+    pub fn create_table(
+        &self,
+        table: &str,
+        columns: &[ColumnInfo],
+        constraints: &[ConstraintInfo],
+    ) -> Option<String> {
+        if matches!(self.engine, DatabaseEngine::MongoDb | DatabaseEngine::CosmosDb) {
+            return None;
+        }
+        let quote_list = |names: &[String]| {
+            names.iter().map(|c| self.quote_ident(c)).collect::<Vec<_>>().join(", ")
+        };
+        let mut parts: Vec<String> = columns
+            .iter()
+            .map(|c| {
+                let null = if c.is_nullable { "" } else { " NOT NULL" };
+                format!("{} {}{}", self.quote_ident(&c.name), c.data_type, null)
+            })
+            .collect();
+        for constraint in constraints {
+            let name = self.quote_ident(&constraint.name);
+            match (&constraint.constraint_type, &constraint.referenced_table) {
+                (ConstraintType::PrimaryKey, _) => parts.push(format!(
+                    "CONSTRAINT {} PRIMARY KEY ({})",
+                    name,
+                    quote_list(&constraint.columns)
+                )),
+                (ConstraintType::ForeignKey, Some(parent)) => parts.push(format!(
+                    "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+                    name,
+                    quote_list(&constraint.columns),
+                    self.quote_table(parent),
+                    quote_list(constraint.referenced_columns.as_deref().unwrap_or_default())
+                )),
+                _ => {}
+            }
+        }
+        Some(format!("CREATE TABLE {} ({});", self.quote_table(table), parts.join(", ")))
+    }
+
+    /// Statements replacing `table`'s rows with those of its snapshot
+    /// `backup`. Identity columns keep their snapshot values.
+    pub fn restore_snapshot(&self, table: &str, backup: &str, columns: &[String]) -> Vec<String> {
+        let quoted = self.quote_table(table);
+        let col_list = columns.iter().map(|c| self.quote_ident(c)).collect::<Vec<_>>().join(", ");
+        let copy = format!("SELECT {} FROM {}", col_list, self.quote_table(backup));
+        let delete = format!("DELETE FROM {};", quoted);
+        match self.engine {
+            DatabaseEngine::SqlServer => {
+                let has_identity = format!(
+                    "IF OBJECTPROPERTY(OBJECT_ID(N'{}'), 'TableHasIdentity') = 1",
+                    table.replace('\'', "''")
+                );
+                vec![
+                    delete,
+                    format!("{} SET IDENTITY_INSERT {} ON;", has_identity, quoted),
+                    format!("INSERT INTO {} ({}) {};", quoted, col_list, copy),
+                    format!("{} SET IDENTITY_INSERT {} OFF;", has_identity, quoted),
+                ]
+            }
+            DatabaseEngine::PostgreSql => vec![
+                delete,
+                format!("INSERT INTO {} ({}) OVERRIDING SYSTEM VALUE {};", quoted, col_list, copy),
+            ],
+            _ => vec![delete, format!("INSERT INTO {} ({}) {};", quoted, col_list, copy)],
+        }
+    }
+
+    /// Query for the rows of `table` whose `column` is at or past `since`,
+    /// in watermark order. Rows sharing the stored watermark are read again,
+    /// since rows written later can still get that same value. `None` for
+    /// engines without SQL.
+    pub fn select_rows_since(
+        &self,
+        table: &str,
+        column: &str,
+        since: &serde_json::Value,
+    ) -> Option<String> {
+        if matches!(self.engine, DatabaseEngine::MongoDb | DatabaseEngine::CosmosDb) {
+            return None;
+        }
+        let column = self.quote_ident(column);
+        Some(format!(
+            "SELECT * FROM {} WHERE {} >= {} ORDER BY {};",
+            self.quote_table(table),
+            column,
+            self.value_to_sql(since),
+            column
+        ))
+    }
+
+    /// Query for the rows of `table` matching any of `keys` on
+    /// `key_columns`. `None` for engines without SQL or when there is
+    /// nothing to match.
+    pub fn select_by_keys(
+        &self,
+        table: &str,
+        key_columns: &[String],
+        keys: &[Row],
+    ) -> Option<String> {
+        if matches!(self.engine, DatabaseEngine::MongoDb | DatabaseEngine::CosmosDb)
+            || key_columns.is_empty()
+            || keys.is_empty()
+        {
+            return None;
+        }
+        let value = |row: &Row, k: &String| {
+            self.value_to_sql(row.get(k).unwrap_or(&serde_json::Value::Null))
+        };
+        let condition = if let [column] = key_columns {
+            let values = keys.iter().map(|r| value(r, column)).collect::<Vec<_>>().join(", ");
+            format!("{} IN ({})", self.quote_ident(column), values)
+        } else {
+            keys.iter()
+                .map(|row| {
+                    let terms = key_columns
+                        .iter()
+                        .map(|k| format!("{} = {}", self.quote_ident(k), value(row, k)))
+                        .collect::<Vec<_>>()
+                        .join(" AND ");
+                    format!("({})", terms)
+                })
+                .collect::<Vec<_>>()
+                .join(" OR ")
+        };
+        Some(format!("SELECT * FROM {} WHERE {};", self.quote_table(table), condition))
+    }
+
+    /// Query counting the rows of `table`.
+    pub fn count_rows(&self, table: &str) -> String {
+        format!("SELECT COUNT(*) AS row_count FROM {};", self.quote_table(table))
+    }
+
+    /// Generate a DROP TABLE statement.
+    pub fn drop_table(&self, table: &str) -> String {
+        format!("DROP TABLE {};", self.quote_table(table))
+    }
+
+    fn per_constraint(&self, tables: &[(String, Vec<String>)], action: &str) -> Vec<String> {
+        tables
+            .iter()
+            .flat_map(|(t, names)| {
+                names.iter().map(move |n| {
+                    format!("ALTER TABLE {} {} {};", self.quote_table(t), action, self.quote_ident(n))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Drop trailing zeros from fractional seconds; SQL Server rejects more than
+/// seven fractional digits even when the extra ones are zero.
+fn trim_fraction(text: &str) -> String {
+    match text.rsplit_once('.') {
+        Some((whole, frac)) if frac.bytes().all(|b| b.is_ascii_digit()) => {
+            let frac = frac.trim_end_matches('0');
+            if frac.is_empty() {
+                whole.to_string()
+            } else {
+                format!("{}.{}", whole, frac)
+            }
+        }
+        _ => text.to_string(),
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +603,24 @@ mod tests {
         assert!(sql.contains("[id]"));
     }
 
+    #[test]
+    fn test_schema_qualified_table() {
+        let r = row(&[("id", json!(1))]);
+        let gen = SqlGenerator::new(DatabaseEngine::SqlServer);
+        assert!(gen.generate_insert("sales.orders", &r).contains("INSERT INTO [sales].[orders]"));
+        let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        assert_eq!(
+            gen.generate_delete("archive.orders", &r, &["id".to_string()]),
+            "DELETE FROM \"archive\".\"orders\" WHERE \"id\" = 1;"
+        );
+        let gen = SqlGenerator::new(DatabaseEngine::MySql);
+        assert_eq!(gen.quote_table("orders"), "`orders`");
+        // Without listed schemas a dot is part of the name
+        assert_eq!(gen.quote_table("logs.2024"), "`logs.2024`");
+        let gen = SqlGenerator::new(DatabaseEngine::Sqlite);
+        assert_eq!(gen.quote_table("events.v2"), "\"events.v2\"");
+    }
+
     #[test]
     fn test_update() {
         let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
@@ -427,4 +812,288 @@ mod tests {
         assert!(prepared.is_some());
         assert!(warnings.is_empty());
     }
+
+    #[test]
+    fn test_decimal_literal_is_exact() {
+        let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        let r = row(&[(
+            "amount",
+            crate::db::typed_value::decimal("12345678901234567890.12"),
+        )]);
+        let sql = gen.generate_insert("t", &r);
+        assert!(sql.contains("VALUES (12345678901234567890.12)"));
+    }
+
+    #[test]
+    fn test_decimal_literal_quoted_for_sqlite() {
+        let gen = SqlGenerator::new(DatabaseEngine::Sqlite);
+        let r = row(&[("amount", crate::db::typed_value::decimal("0.10"))]);
+        let sql = gen.generate_insert("t", &r);
+        assert!(sql.contains("VALUES ('0.10')"));
+    }
+
+    #[test]
+    fn test_binary_literal_per_engine() {
+        let r = row(&[("data", crate::db::typed_value::bytes(&[0x00, 0xff]))]);
+        let cases = [
+            (DatabaseEngine::SqlServer, "0x00ff"),
+            (DatabaseEngine::PostgreSql, "'\\x00ff'::bytea"),
+            (DatabaseEngine::MySql, "X'00ff'"),
+            (DatabaseEngine::Sqlite, "X'00ff'"),
+            (DatabaseEngine::Oracle, "HEXTORAW('00ff')"),
+        ];
+        for (engine, expected) in cases {
+            let sql = SqlGenerator::new(engine).generate_insert("t", &r);
+            assert!(sql.contains(expected), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_temporal_and_uuid_literals() {
+        let gen = SqlGenerator::new(DatabaseEngine::SqlServer);
+        let r = row(&[
+            ("d", json!({"$date": "2024-03-05"})),
+            ("dt", json!({"$datetime": "2024-03-05T10:30:00.1234567"})),
+            ("ts", json!({"$timestamp": "2024-03-05T10:30:00+02:00"})),
+            ("u", json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"})),
+        ]);
+        let sql = gen.generate_insert("t", &r);
+        assert!(sql.contains("'2024-03-05'"));
+        assert!(sql.contains("'2024-03-05 10:30:00.1234567'"));
+        assert!(sql.contains("'2024-03-05 10:30:00+02:00'"));
+        assert!(sql.contains("'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'"));
+    }
+
+    #[test]
+    fn test_oracle_temporal_literals() {
+        let gen = SqlGenerator::new(DatabaseEngine::Oracle);
+        let r = row(&[("d", json!({"$date": "2024-03-05"}))]);
+        assert!(gen.generate_insert("t", &r).contains("DATE '2024-03-05'"));
+        let r = row(&[("ts", json!({"$timestamp": "2024-03-05T10:30:00.5+02:00"}))]);
+        assert!(gen
+            .generate_insert("t", &r)
+            .contains("TIMESTAMP '2024-03-05 10:30:00.5 +02:00'"));
+    }
+
+    #[test]
+    fn test_untagged_object_still_stringified() {
+        let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        let r = row(&[("doc", json!({"$decimal": "not a number"}))]);
+        let sql = gen.generate_insert("t", &r);
+        assert!(sql.contains("'{\"$decimal\":\"not a number\"}'"));
+    }
+
+    #[test]
+    fn test_foreign_key_toggles_per_engine() {
+        let tables = vec![
+            ("orders".to_string(), vec!["fk_orders_customer".to_string()]),
+            ("customers".to_string(), vec!["fk_customers_last_order".to_string()]),
+        ];
+
+        let pg = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        assert_eq!(pg.disable_foreign_keys(&tables), vec!["SET session_replication_role = replica;"]);
+        assert_eq!(pg.enable_foreign_keys(&tables), vec!["SET session_replication_role = DEFAULT;"]);
+
+        let my = SqlGenerator::new(DatabaseEngine::MySql);
+        assert_eq!(my.disable_foreign_keys(&tables), vec!["SET FOREIGN_KEY_CHECKS = 0;"]);
+        assert_eq!(my.enable_foreign_keys(&tables), vec!["SET FOREIGN_KEY_CHECKS = 1;"]);
+
+        let lite = SqlGenerator::new(DatabaseEngine::Sqlite);
+        assert_eq!(lite.disable_foreign_keys(&tables), vec!["PRAGMA foreign_keys = OFF;"]);
+
+        let ss = SqlGenerator::new(DatabaseEngine::SqlServer);
+        assert_eq!(
+            ss.disable_foreign_keys(&tables),
+            vec![
+                "ALTER TABLE [orders] NOCHECK CONSTRAINT ALL;",
+                "ALTER TABLE [customers] NOCHECK CONSTRAINT ALL;",
+            ]
+        );
+        assert_eq!(
+            ss.enable_foreign_keys(&tables)[0],
+            "ALTER TABLE [orders] WITH CHECK CHECK CONSTRAINT ALL;"
+        );
+
+        let ora = SqlGenerator::new(DatabaseEngine::Oracle);
+        assert_eq!(
+            ora.disable_foreign_keys(&tables)[1],
+            "ALTER TABLE \"customers\" DISABLE CONSTRAINT \"fk_customers_last_order\";"
+        );
+        assert_eq!(
+            ora.enable_foreign_keys(&tables)[0],
+            "ALTER TABLE \"orders\" ENABLE VALIDATE CONSTRAINT \"fk_orders_customer\";"
+        );
+
+        let mongo = SqlGenerator::new(DatabaseEngine::MongoDb);
+        assert!(mongo.disable_foreign_keys(&tables).is_empty());
+    }
+
+    #[test]
+    fn test_defer_constraints_per_engine() {
+        assert_eq!(
+            SqlGenerator::new(DatabaseEngine::PostgreSql).defer_constraints().as_deref(),
+            Some("SET CONSTRAINTS ALL DEFERRED;")
+        );
+        assert_eq!(
+            SqlGenerator::new(DatabaseEngine::Sqlite).defer_constraints().as_deref(),
+            Some("PRAGMA defer_foreign_keys = ON;")
+        );
+        assert_eq!(SqlGenerator::new(DatabaseEngine::MySql).defer_constraints(), None);
+        assert_eq!(SqlGenerator::new(DatabaseEngine::SqlServer).defer_constraints(), None);
+    }
+
+    #[test]
+    fn test_non_deferrable_foreign_keys() {
+        let tables = vec!["orders".to_string(), "sales.o'items".to_string()];
+        let pg = SqlGenerator::new(DatabaseEngine::PostgreSql)
+            .non_deferrable_foreign_keys(&tables)
+            .unwrap();
+        assert!(pg.contains("NOT condeferrable"));
+        assert!(pg.contains(r#"conrelid IN ('"orders"'::regclass, '"sales"."o''items"'::regclass)"#));
+
+        let ora = SqlGenerator::new(DatabaseEngine::Oracle)
+            .non_deferrable_foreign_keys(&tables)
+            .unwrap();
+        assert!(ora.contains("deferrable = 'NOT DEFERRABLE'"));
+        assert!(ora.ends_with("table_name IN ('orders', 'o''items')"));
+
+        let sqlite = SqlGenerator::new(DatabaseEngine::Sqlite);
+        assert_eq!(sqlite.non_deferrable_foreign_keys(&tables), None);
+    }
+
+    #[test]
+    fn test_count_fk_violations() {
+        let fk = ConstraintInfo {
+            name: "fk_items_order".to_string(),
+            constraint_type: ConstraintType::ForeignKey,
+            columns: vec!["order_id".to_string(), "region".to_string()],
+            referenced_table: Some("orders".to_string()),
+            referenced_columns: Some(vec!["id".to_string(), "region".to_string()]),
+        };
+        let gen = SqlGenerator::new(DatabaseEngine::SqlServer);
+        assert_eq!(
+            gen.count_fk_violations("order_items", &fk).unwrap(),
+            "SELECT COUNT(*) AS violations FROM [order_items] c \
+             WHERE c.[order_id] IS NOT NULL AND c.[region] IS NOT NULL \
+             AND NOT EXISTS (SELECT 1 FROM [orders] p WHERE p.[id] = c.[order_id] AND p.[region] = c.[region]);"
+        );
+
+        let unknown_parent_cols = ConstraintInfo {
+            referenced_columns: None,
+            ..fk.clone()
+        };
+        assert_eq!(gen.count_fk_violations("order_items", &unknown_parent_cols), None);
+        let unique = ConstraintInfo {
+            constraint_type: ConstraintType::Unique,
+            ..fk
+        };
+        assert_eq!(gen.count_fk_violations("order_items", &unique), None);
+    }
+    #[test]
+    fn test_snapshot_statements() {
+        let pg = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        assert_eq!(
+            pg.create_snapshot("orders", "upsert_bak_1_orders").unwrap(),
+            "CREATE TABLE \"upsert_bak_1_orders\" AS SELECT * FROM \"orders\";"
+        );
+        let cols = vec!["id".to_string(), "total".to_string()];
+        assert_eq!(
+            pg.restore_snapshot("orders", "upsert_bak_1_orders", &cols),
+            vec![
+                "DELETE FROM \"orders\";".to_string(),
+                "INSERT INTO \"orders\" (\"id\", \"total\") OVERRIDING SYSTEM VALUE \
+                 SELECT \"id\", \"total\" FROM \"upsert_bak_1_orders\";"
+                    .to_string(),
+            ]
+        );
+
+        let mssql = SqlGenerator::new(DatabaseEngine::SqlServer);
+        assert_eq!(
+            mssql.create_snapshot("orders", "bak").unwrap(),
+            "SELECT * INTO [bak] FROM [orders];"
+        );
+        let restore = mssql.restore_snapshot("orders", "bak", &cols);
+        assert_eq!(restore.len(), 4);
+        assert!(restore[1].ends_with("SET IDENTITY_INSERT [orders] ON;"));
+        assert_eq!(restore[2], "INSERT INTO [orders] ([id], [total]) SELECT [id], [total] FROM [bak];");
+
+        assert_eq!(SqlGenerator::new(DatabaseEngine::MySql).drop_table("bak"), "DROP TABLE `bak`;");
+        assert_eq!(mssql.count_rows("bak"), "SELECT COUNT(*) AS row_count FROM [bak];");
+        assert_eq!(SqlGenerator::new(DatabaseEngine::MongoDb).create_snapshot("c", "bak"), None);
+    }
+
+    #[test]
+    fn test_create_table() {
+        let column = |name: &str, data_type: &str, key: bool| ColumnInfo {
+            name: name.into(),
+            data_type: data_type.into(),
+            is_nullable: !key,
+            is_primary_key: key,
+            max_length: None,
+            precision: None,
+            scale: None,
+            default_value: None,
+            ordinal_position: 0,
+        };
+        let columns = vec![
+            column("order_id", "INT", true),
+            column("ordinal", "INT", true),
+            column("sku", "VARCHAR(16)", false),
+        ];
+        let keys = vec!["order_id".to_string(), "ordinal".to_string()];
+        let constraints = vec![
+            ConstraintInfo {
+                name: "pk_lines".into(),
+                constraint_type: ConstraintType::PrimaryKey,
+                columns: keys,
+                referenced_table: None,
+                referenced_columns: None,
+            },
+            ConstraintInfo {
+                name: "fk_lines_orders".into(),
+                constraint_type: ConstraintType::ForeignKey,
+                columns: vec!["order_id".into()],
+                referenced_table: Some("orders".into()),
+                referenced_columns: Some(vec!["id".into()]),
+            },
+        ];
+        let mysql = SqlGenerator::new(DatabaseEngine::MySql);
+        assert_eq!(
+            mysql.create_table("lines", &columns, &constraints).unwrap(),
+            "CREATE TABLE `lines` (`order_id` INT NOT NULL, `ordinal` INT NOT NULL, \
+             `sku` VARCHAR(16), CONSTRAINT `pk_lines` PRIMARY KEY (`order_id`, `ordinal`), \
+             CONSTRAINT `fk_lines_orders` FOREIGN KEY (`order_id`) REFERENCES `orders` (`id`));"
+        );
+        let mongo = SqlGenerator::new(DatabaseEngine::MongoDb);
+        assert_eq!(mongo.create_table("lines", &columns, &constraints), None);
+    }
+
+    #[test]
+    fn test_incremental_selects() {
+        let pg = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        assert_eq!(
+            pg.select_rows_since("orders", "updated_at", &json!({"$datetime": "2024-01-01T08:00:00"}))
+                .unwrap(),
+            "SELECT * FROM \"orders\" WHERE \"updated_at\" >= '2024-01-01 08:00:00' ORDER BY \"updated_at\";"
+        );
+
+        let keys = vec![
+            row(&[("id", json!(1)), ("region", json!("eu"))]),
+            row(&[("id", json!(2)), ("region", json!("us"))]),
+        ];
+        let id = vec!["id".to_string()];
+        assert_eq!(
+            pg.select_by_keys("orders", &id, &keys).unwrap(),
+            "SELECT * FROM \"orders\" WHERE \"id\" IN (1, 2);"
+        );
+        let composite = vec!["id".to_string(), "region".to_string()];
+        assert_eq!(
+            pg.select_by_keys("orders", &composite, &keys).unwrap(),
+            "SELECT * FROM \"orders\" WHERE (\"id\" = 1 AND \"region\" = 'eu') \
+             OR (\"id\" = 2 AND \"region\" = 'us');"
+        );
+        assert_eq!(pg.select_by_keys("orders", &id, &[]), None);
+        let mongo = SqlGenerator::new(DatabaseEngine::MongoDb);
+        assert_eq!(mongo.select_rows_since("orders", "v", &json!(1)), None);
+    }
 }
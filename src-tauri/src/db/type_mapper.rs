@@ -1,11 +1,16 @@
 use super::connectors::DatabaseEngine;
+use super::schema::ColumnInfo;
+use anyhow::{anyhow, Context};
 use serde::{Deserialize, Serialize};
 
 /// Canonical type system for cross-engine type mapping
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum CanonicalType {
     Boolean,
+    /// Signed 8-bit integer (MySQL `TINYINT`)
     TinyInt,
+    /// Unsigned 8-bit integer (SQL Server `TINYINT`, MySQL `TINYINT UNSIGNED`)
+    UnsignedTinyInt,
     SmallInt,
     Int,
     BigInt,
@@ -33,12 +38,24 @@ pub enum CanonicalType {
 }
 
 /// A type mapping override configured by the user
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `source_type` is a pattern over the native type: a base name with
+/// optional parameter constraints, e.g. `text`, `nvarchar(max)`,
+/// `varchar(n>4000)` or `decimal(p>18,*)`. A pattern without parentheses
+/// matches any parameters; `*` as the base name matches any type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct TypeOverride {
     pub source_engine: DatabaseEngine,
     pub source_type: String,
     pub target_engine: DatabaseEngine,
     pub target_type: String,
+    /// Restrict the override to one source table (case-insensitive)
+    #[serde(default)]
+    pub table: Option<String>,
+    /// Restrict the override to one source column (case-insensitive)
+    #[serde(default)]
+    pub column: Option<String>,
 }
 
 /// Result of a type mapping that includes warnings about potential issues
@@ -47,6 +64,9 @@ pub struct TypeMappingResult {
     pub target_type: String,
     pub warnings: Vec<String>,
     pub is_lossy: bool,
+    /// Label of the user override that produced `target_type`, if any
+    #[serde(default)]
+    pub applied_override: Option<String>,
 }
 
 /// Parse a native type string into base type and optional parameters.
@@ -112,14 +132,85 @@ pub fn map_type(
     from_canonical(target_engine, &canonical)
 }
 
+/// Rebuild a full native type string for a column.
+///
+/// `information_schema` usually reports the bare type name (`numeric`,
+/// `varbinary`) with length, precision and scale in separate fields; this
+/// folds them back in so `to_canonical` and override patterns see
+/// `numeric(18,4)` or `varbinary(max)`.
+pub fn native_type_with_params(column: &ColumnInfo) -> String {
+    let declared = column.data_type.trim().to_lowercase();
+    if declared.contains('(') {
+        return declared;
+    }
+    match (column.precision, column.scale, column.max_length) {
+        _ if is_decimal_name(&declared) => match (column.precision, column.scale) {
+            (Some(p), Some(s)) if p > 0 => format!("{}({},{})", declared, p, s),
+            (Some(p), None) if p > 0 => format!("{}({})", declared, p),
+            _ => declared,
+        },
+        (_, _, Some(-1)) => format!("{}(max)", declared),
+        (_, _, Some(len)) if len > 0 && is_sized_name(&declared) => {
+            format!("{}({})", declared, len)
+        }
+        _ => declared,
+    }
+}
+
+fn is_decimal_name(name: &str) -> bool {
+    matches!(name, "decimal" | "numeric" | "number" | "dec" | "fixed")
+}
+
+fn is_sized_name(name: &str) -> bool {
+    matches!(
+        name,
+        "char"
+            | "varchar"
+            | "nchar"
+            | "nvarchar"
+            | "binary"
+            | "varbinary"
+            | "character"
+            | "character varying"
+            | "varchar2"
+            | "nvarchar2"
+            | "raw"
+    )
+}
+
 /// Map a native type with warnings about potential precision loss or compatibility issues
 pub fn map_type_with_warnings(
     source_engine: &DatabaseEngine,
     target_engine: &DatabaseEngine,
     native_type: &str,
+) -> TypeMappingResult {
+    map_type_with_overrides(
+        source_engine,
+        target_engine,
+        native_type,
+        &TypeOverrideRegistry::default(),
+        None,
+        None,
+    )
+}
+
+/// Map a native type, letting a matching user override replace the built-in
+/// target type. Warnings are computed against whichever target type wins, so
+/// a lossy override is still reported as lossy.
+pub fn map_type_with_overrides(
+    source_engine: &DatabaseEngine,
+    target_engine: &DatabaseEngine,
+    native_type: &str,
+    overrides: &TypeOverrideRegistry,
+    table: Option<&str>,
+    column: Option<&str>,
 ) -> TypeMappingResult {
     let canonical = to_canonical(source_engine, native_type);
-    let target_type = from_canonical(target_engine, &canonical);
+    let matched = overrides.find(source_engine, target_engine, native_type, table, column);
+    let target_type = match matched {
+        Some(o) => o.target_type.clone(),
+        None => from_canonical(target_engine, &canonical),
+    };
     let mut warnings = Vec::new();
     let mut is_lossy = false;
 
@@ -207,6 +298,267 @@ pub fn map_type_with_warnings(
         target_type,
         warnings,
         is_lossy,
+        applied_override: matched.map(|o| o.label()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// User overrides
+// ---------------------------------------------------------------------------
+
+impl TypeOverride {
+    /// Human-readable identifier shown in dry runs and reports.
+    pub fn label(&self) -> String {
+        let mut label = format!(
+            "{:?} {} -> {:?} {}",
+            self.source_engine, self.source_type, self.target_engine, self.target_type
+        );
+        match (&self.table, &self.column) {
+            (Some(t), Some(c)) => label.push_str(&format!(" [{}.{}]", t, c)),
+            (Some(t), None) => label.push_str(&format!(" [{}]", t)),
+            (None, Some(c)) => label.push_str(&format!(" [*.{}]", c)),
+            (None, None) => {}
+        }
+        label
+    }
+
+    /// Check that the source pattern parses and the target type is set.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        TypePattern::parse(&self.source_type)?;
+        if self.target_type.trim().is_empty() {
+            anyhow::bail!("Override for '{}' has an empty target type", self.source_type);
+        }
+        Ok(())
+    }
+
+    pub fn matches(
+        &self,
+        source_engine: &DatabaseEngine,
+        target_engine: &DatabaseEngine,
+        native_type: &str,
+        table: Option<&str>,
+        column: Option<&str>,
+    ) -> bool {
+        let scope_matches = |wanted: &Option<String>, actual: Option<&str>| match wanted {
+            Some(w) => actual.is_some_and(|a| a.eq_ignore_ascii_case(w)),
+            None => true,
+        };
+        self.source_engine == *source_engine
+            && self.target_engine == *target_engine
+            && scope_matches(&self.table, table)
+            && scope_matches(&self.column, column)
+            && TypePattern::parse(&self.source_type)
+                .map(|p| p.matches(native_type))
+                .unwrap_or(false)
+    }
+
+    /// Column-scoped overrides beat table-scoped ones, which beat global ones.
+    fn specificity(&self) -> u8 {
+        (self.column.is_some() as u8) * 2 + self.table.is_some() as u8
+    }
+}
+
+/// Ordered set of user overrides. When several match, the most specific
+/// one wins; ties go to the one registered first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TypeOverrideRegistry {
+    overrides: Vec<TypeOverride>,
+}
+
+/// Project file layout: `{ "typeOverrides": [ ... ] }`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeOverrideFile {
+    type_overrides: Vec<TypeOverride>,
+}
+
+impl TypeOverrideRegistry {
+    pub fn new(overrides: Vec<TypeOverride>) -> anyhow::Result<Self> {
+        for o in &overrides {
+            o.validate()?;
+        }
+        Ok(Self { overrides })
+    }
+
+    /// Parse either a bare JSON array of overrides (as stored in settings)
+    /// or a project file object with a `typeOverrides` array.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let overrides = match serde_json::from_str::<Vec<TypeOverride>>(json) {
+            Ok(list) => list,
+            Err(_) => {
+                serde_json::from_str::<TypeOverrideFile>(json)
+                    .context("Invalid type override JSON")?
+                    .type_overrides
+            }
+        };
+        Self::new(overrides)
+    }
+
+    pub fn load_file(path: &std::path::Path) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read type override file {}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    /// Append `other`'s overrides after this registry's, at lower precedence.
+    pub fn extend(&mut self, other: TypeOverrideRegistry) {
+        self.overrides.extend(other.overrides);
+    }
+
+    pub fn overrides(&self) -> &[TypeOverride] {
+        &self.overrides
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn find(
+        &self,
+        source_engine: &DatabaseEngine,
+        target_engine: &DatabaseEngine,
+        native_type: &str,
+        table: Option<&str>,
+        column: Option<&str>,
+    ) -> Option<&TypeOverride> {
+        let mut best: Option<&TypeOverride> = None;
+        for o in &self.overrides {
+            if !o.matches(source_engine, target_engine, native_type, table, column) {
+                continue;
+            }
+            if best.map_or(true, |b| o.specificity() > b.specificity()) {
+                best = Some(o);
+            }
+        }
+        best
+    }
+}
+
+/// Constraint on one type parameter in an override pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum ParamPattern {
+    Any,
+    Max,
+    Equals(u64),
+    Compare(CompareOp, u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+/// Parsed form of `TypeOverride::source_type`.
+#[derive(Debug, Clone, PartialEq)]
+struct TypePattern {
+    base: String,
+    params: Option<Vec<ParamPattern>>,
+}
+
+impl TypePattern {
+    fn parse(pattern: &str) -> anyhow::Result<Self> {
+        let lower = pattern.trim().to_lowercase();
+        let Some(open) = lower.find('(') else {
+            if lower.is_empty() {
+                anyhow::bail!("Empty type pattern");
+            }
+            return Ok(Self {
+                base: lower,
+                params: None,
+            });
+        };
+        let inner = lower[open + 1..]
+            .strip_suffix(')')
+            .ok_or_else(|| anyhow!("Unclosed '(' in type pattern '{}'", pattern))?;
+        let params = inner
+            .split(',')
+            .map(|p| Self::parse_param(p.trim(), pattern))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            base: lower[..open].trim().to_string(),
+            params: Some(params),
+        })
+    }
+
+    fn parse_param(param: &str, pattern: &str) -> anyhow::Result<ParamPattern> {
+        if param == "*" {
+            return Ok(ParamPattern::Any);
+        }
+        if param == "max" {
+            return Ok(ParamPattern::Max);
+        }
+        if let Ok(n) = param.parse::<u64>() {
+            return Ok(ParamPattern::Equals(n));
+        }
+        // `<name><op><number>`, e.g. `n>4000`; the name is only a label
+        let op_start = param
+            .find(['<', '>', '=', '!'])
+            .ok_or_else(|| anyhow!("Invalid parameter '{}' in type pattern '{}'", param, pattern))?;
+        let rest = &param[op_start..];
+        let (op, len) = match rest {
+            r if r.starts_with(">=") => (CompareOp::Ge, 2),
+            r if r.starts_with("<=") => (CompareOp::Le, 2),
+            r if r.starts_with("!=") => (CompareOp::Ne, 2),
+            r if r.starts_with('>') => (CompareOp::Gt, 1),
+            r if r.starts_with('<') => (CompareOp::Lt, 1),
+            r if r.starts_with('=') => (CompareOp::Eq, 1),
+            _ => anyhow::bail!("Invalid operator in type pattern '{}'", pattern),
+        };
+        let value = rest[len..]
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| anyhow!("Invalid number in type pattern '{}'", pattern))?;
+        Ok(ParamPattern::Compare(op, value))
+    }
+
+    fn matches(&self, native_type: &str) -> bool {
+        let lower = native_type.trim().to_lowercase();
+        let (base, native_params) = match lower.find('(') {
+            Some(open) => (
+                lower[..open].trim(),
+                lower[open + 1..]
+                    .trim_end_matches(')')
+                    .split(',')
+                    .map(|p| p.trim())
+                    .collect::<Vec<_>>(),
+            ),
+            None => (lower.as_str(), Vec::new()),
+        };
+        if self.base != "*" && self.base != base {
+            return false;
+        }
+        let Some(params) = &self.params else {
+            return true;
+        };
+        if native_params.len() > params.len() {
+            return false;
+        }
+        params.iter().enumerate().all(|(i, p)| {
+            let actual = native_params.get(i).copied();
+            // `max` / -1 means unbounded and satisfies any lower bound
+            let value = actual.and_then(|a| match a {
+                "max" | "-1" => Some(u64::MAX),
+                other => other.parse::<u64>().ok(),
+            });
+            match p {
+                ParamPattern::Any => true,
+                ParamPattern::Max => value == Some(u64::MAX),
+                ParamPattern::Equals(n) => value == Some(*n),
+                ParamPattern::Compare(op, n) => value.is_some_and(|v| match op {
+                    CompareOp::Gt => v > *n,
+                    CompareOp::Ge => v >= *n,
+                    CompareOp::Lt => v < *n,
+                    CompareOp::Le => v <= *n,
+                    CompareOp::Eq => v == *n,
+                    CompareOp::Ne => v != *n,
+                }),
+            }
+        })
     }
 }
 
@@ -218,7 +570,7 @@ fn sqlserver_to_canonical(native: &str) -> CanonicalType {
     let (base, p1, p2) = parse_native_type(native);
     match base.as_str() {
         "bit" => CanonicalType::Boolean,
-        "tinyint" => CanonicalType::TinyInt,
+        "tinyint" => CanonicalType::UnsignedTinyInt,
         "smallint" => CanonicalType::SmallInt,
         "int" => CanonicalType::Int,
         "bigint" => CanonicalType::BigInt,
@@ -281,7 +633,7 @@ fn sqlserver_to_canonical(native: &str) -> CanonicalType {
 fn canonical_to_sqlserver(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "BIT".into(),
-        CanonicalType::TinyInt => "TINYINT".into(),
+        CanonicalType::TinyInt | CanonicalType::UnsignedTinyInt => "TINYINT".into(),
         CanonicalType::SmallInt => "SMALLINT".into(),
         CanonicalType::Int => "INT".into(),
         CanonicalType::BigInt => "BIGINT".into(),
@@ -369,7 +721,9 @@ fn postgres_to_canonical(native: &str) -> CanonicalType {
 fn canonical_to_postgres(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "BOOLEAN".into(),
-        CanonicalType::TinyInt | CanonicalType::SmallInt => "SMALLINT".into(),
+        CanonicalType::TinyInt | CanonicalType::UnsignedTinyInt | CanonicalType::SmallInt => {
+            "SMALLINT".into()
+        }
         CanonicalType::Int => "INTEGER".into(),
         CanonicalType::BigInt => "BIGINT".into(),
         CanonicalType::Float => "REAL".into(),
@@ -402,9 +756,11 @@ fn mysql_to_canonical(native: &str) -> CanonicalType {
     let (base, p1, p2) = parse_native_type(native);
     match base.as_str() {
         "boolean" | "bool" => CanonicalType::Boolean,
-        "tinyint" => {
+        "tinyint" | "tinyint unsigned" => {
             if p1 == Some(1) {
                 CanonicalType::Boolean
+            } else if native.contains("unsigned") {
+                CanonicalType::UnsignedTinyInt
             } else {
                 CanonicalType::TinyInt
             }
@@ -449,6 +805,7 @@ fn canonical_to_mysql(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "TINYINT(1)".into(),
         CanonicalType::TinyInt => "TINYINT".into(),
+        CanonicalType::UnsignedTinyInt => "TINYINT UNSIGNED".into(),
         CanonicalType::SmallInt => "SMALLINT".into(),
         CanonicalType::Int => "INT".into(),
         CanonicalType::BigInt => "BIGINT".into(),
@@ -516,6 +873,7 @@ fn canonical_to_sqlite(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "INTEGER".into(),
         CanonicalType::TinyInt
+        | CanonicalType::UnsignedTinyInt
         | CanonicalType::SmallInt
         | CanonicalType::Int
         | CanonicalType::BigInt => "INTEGER".into(),
@@ -602,7 +960,7 @@ fn oracle_to_canonical(native: &str) -> CanonicalType {
 fn canonical_to_oracle(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "NUMBER(1)".into(),
-        CanonicalType::TinyInt => "NUMBER(3)".into(),
+        CanonicalType::TinyInt | CanonicalType::UnsignedTinyInt => "NUMBER(3)".into(),
         CanonicalType::SmallInt => "NUMBER(5)".into(),
         CanonicalType::Int => "NUMBER(10)".into(),
         CanonicalType::BigInt => "NUMBER(19)".into(),
@@ -662,7 +1020,10 @@ fn mongodb_to_canonical(native: &str) -> CanonicalType {
 fn canonical_to_mongodb(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "bool".into(),
-        CanonicalType::TinyInt | CanonicalType::SmallInt | CanonicalType::Int => "int".into(),
+        CanonicalType::TinyInt
+        | CanonicalType::UnsignedTinyInt
+        | CanonicalType::SmallInt
+        | CanonicalType::Int => "int".into(),
         CanonicalType::BigInt => "long".into(),
         CanonicalType::Float | CanonicalType::Double => "double".into(),
         CanonicalType::Decimal { .. } => "decimal128".into(),
@@ -707,6 +1068,7 @@ fn canonical_to_cosmosdb(canonical: &CanonicalType) -> String {
     match canonical {
         CanonicalType::Boolean => "boolean".into(),
         CanonicalType::TinyInt
+        | CanonicalType::UnsignedTinyInt
         | CanonicalType::SmallInt
         | CanonicalType::Int
         | CanonicalType::BigInt => "number".into(),
@@ -816,7 +1178,7 @@ mod tests {
     fn test_sqlserver_tinyint() {
         assert_eq!(
             to_canonical(&DatabaseEngine::SqlServer, "TINYINT"),
-            CanonicalType::TinyInt
+            CanonicalType::UnsignedTinyInt
         );
     }
 
@@ -1495,6 +1857,14 @@ mod tests {
             to_canonical(&DatabaseEngine::MySql, "TINYINT"),
             CanonicalType::TinyInt
         );
+        assert_eq!(
+            to_canonical(&DatabaseEngine::MySql, "TINYINT UNSIGNED"),
+            CanonicalType::UnsignedTinyInt
+        );
+        assert_eq!(
+            map_type(&DatabaseEngine::SqlServer, &DatabaseEngine::MySql, "TINYINT"),
+            "TINYINT UNSIGNED"
+        );
     }
 
     #[test]
@@ -3510,4 +3880,195 @@ mod tests {
         assert_eq!(result.target_type, "TEXT");
         assert!(!result.warnings.is_empty());
     }
+
+    // -----------------------------------------------------------------------
+    // Override registry tests
+    // -----------------------------------------------------------------------
+
+    fn ovr(source_type: &str, target_type: &str) -> TypeOverride {
+        TypeOverride {
+            source_engine: DatabaseEngine::SqlServer,
+            source_type: source_type.to_string(),
+            target_engine: DatabaseEngine::PostgreSql,
+            target_type: target_type.to_string(),
+            table: None,
+            column: None,
+        }
+    }
+
+    fn pattern_matches(pattern: &str, native: &str) -> bool {
+        TypePattern::parse(pattern).unwrap().matches(native)
+    }
+
+    #[test]
+    fn test_pattern_bare_name_matches_any_params() {
+        assert!(pattern_matches("varchar", "VARCHAR"));
+        assert!(pattern_matches("varchar", "varchar(50)"));
+        assert!(!pattern_matches("varchar", "nvarchar(50)"));
+        assert!(pattern_matches("*", "money"));
+    }
+
+    #[test]
+    fn test_pattern_conditions() {
+        assert!(pattern_matches("varchar(n>4000)", "varchar(8000)"));
+        assert!(!pattern_matches("varchar(n>4000)", "varchar(4000)"));
+        assert!(pattern_matches("varchar(n>4000)", "varchar(max)"));
+        assert!(!pattern_matches("varchar(n>4000)", "varchar"));
+        assert!(pattern_matches("decimal(p>=19,*)", "decimal(38,4)"));
+        assert!(!pattern_matches("decimal(p>=19,*)", "decimal(18,4)"));
+        assert!(pattern_matches("decimal(p<=18,s=2)", "decimal(10,2)"));
+        assert!(pattern_matches("decimal(*,*)", "decimal"));
+    }
+
+    #[test]
+    fn test_pattern_literals_and_max() {
+        assert!(pattern_matches("nvarchar(max)", "NVARCHAR(MAX)"));
+        assert!(pattern_matches("nvarchar(max)", "nvarchar(-1)"));
+        assert!(!pattern_matches("nvarchar(max)", "nvarchar(100)"));
+        assert!(pattern_matches("char(1)", "char(1)"));
+        assert!(!pattern_matches("char(1)", "char(2)"));
+    }
+
+    #[test]
+    fn test_invalid_patterns_rejected() {
+        assert!(TypeOverrideRegistry::new(vec![ovr("varchar(n>", "text")]).is_err());
+        assert!(TypeOverrideRegistry::new(vec![ovr("varchar(n~4)", "text")]).is_err());
+        assert!(TypeOverrideRegistry::new(vec![ovr("varchar", " ")]).is_err());
+    }
+
+    #[test]
+    fn test_override_takes_precedence() {
+        let registry = TypeOverrideRegistry::new(vec![ovr("varchar(n>4000)", "TEXT")]).unwrap();
+        let result = map_type_with_overrides(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::PostgreSql,
+            "varchar(8000)",
+            &registry,
+            None,
+            None,
+        );
+        assert_eq!(result.target_type, "TEXT");
+        assert!(result.applied_override.unwrap().contains("varchar(n>4000)"));
+
+        let result = map_type_with_overrides(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::PostgreSql,
+            "varchar(100)",
+            &registry,
+            None,
+            None,
+        );
+        assert_eq!(result.target_type, "VARCHAR(100)");
+        assert_eq!(result.applied_override, None);
+    }
+
+    #[test]
+    fn test_override_engine_pair_must_match() {
+        let registry = TypeOverrideRegistry::new(vec![ovr("int", "BIGINT")]).unwrap();
+        let result = map_type_with_overrides(
+            &DatabaseEngine::MySql,
+            &DatabaseEngine::PostgreSql,
+            "int",
+            &registry,
+            None,
+            None,
+        );
+        assert_eq!(result.target_type, "INTEGER");
+        assert_eq!(result.applied_override, None);
+    }
+
+    #[test]
+    fn test_most_specific_override_wins() {
+        let mut by_table = ovr("varchar", "CITEXT");
+        by_table.table = Some("Users".to_string());
+        let mut by_column = ovr("varchar", "VARCHAR(320)");
+        by_column.table = Some("users".to_string());
+        by_column.column = Some("email".to_string());
+        let registry =
+            TypeOverrideRegistry::new(vec![ovr("varchar", "TEXT"), by_table, by_column]).unwrap();
+
+        let find = |table: Option<&str>, column: Option<&str>| {
+            registry
+                .find(
+                    &DatabaseEngine::SqlServer,
+                    &DatabaseEngine::PostgreSql,
+                    "varchar(255)",
+                    table,
+                    column,
+                )
+                .map(|o| o.target_type.clone())
+        };
+        assert_eq!(find(Some("users"), Some("EMAIL")).as_deref(), Some("VARCHAR(320)"));
+        assert_eq!(find(Some("users"), Some("name")).as_deref(), Some("CITEXT"));
+        assert_eq!(find(Some("orders"), Some("email")).as_deref(), Some("TEXT"));
+        assert_eq!(find(None, None).as_deref(), Some("TEXT"));
+    }
+
+    #[test]
+    fn test_first_registered_wins_ties() {
+        let mut registry = TypeOverrideRegistry::new(vec![ovr("varchar", "TEXT")]).unwrap();
+        registry.extend(TypeOverrideRegistry::new(vec![ovr("varchar", "CITEXT")]).unwrap());
+        let found = registry
+            .find(
+                &DatabaseEngine::SqlServer,
+                &DatabaseEngine::PostgreSql,
+                "varchar(10)",
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(found.target_type, "TEXT");
+    }
+
+    #[test]
+    fn test_lossy_override_still_warns() {
+        let registry = TypeOverrideRegistry::new(vec![ovr("bigint", "INTEGER")]).unwrap();
+        let result = map_type_with_overrides(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::PostgreSql,
+            "bigint",
+            &registry,
+            None,
+            None,
+        );
+        assert_eq!(result.target_type, "INTEGER");
+        assert!(result.applied_override.is_some());
+        assert!(result.is_lossy);
+    }
+
+    #[test]
+    fn test_registry_from_json_forms() {
+        let array = r#"[{"sourceEngine":"SqlServer","sourceType":"money","targetEngine":"PostgreSql","targetType":"NUMERIC(19,4)"}]"#;
+        let registry = TypeOverrideRegistry::from_json(array).unwrap();
+        assert_eq!(registry.overrides().len(), 1);
+        assert_eq!(registry.overrides()[0].table, None);
+
+        let file = r#"{"typeOverrides":[{"sourceEngine":"SqlServer","sourceType":"money","targetEngine":"PostgreSql","targetType":"NUMERIC(19,4)","table":"orders","column":"total"}]}"#;
+        let registry = TypeOverrideRegistry::from_json(file).unwrap();
+        assert_eq!(registry.overrides()[0].column.as_deref(), Some("total"));
+
+        assert!(TypeOverrideRegistry::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_native_type_with_params() {
+        let mut c = ColumnInfo {
+            name: "amount".to_string(),
+            data_type: "numeric".to_string(),
+            is_nullable: true,
+            is_primary_key: false,
+            max_length: None,
+            precision: Some(18),
+            scale: Some(4),
+            default_value: None,
+            ordinal_position: 1,
+        };
+        assert_eq!(native_type_with_params(&c), "numeric(18,4)");
+        c.data_type = "nvarchar".to_string();
+        c.max_length = Some(-1);
+        assert_eq!(native_type_with_params(&c), "nvarchar(max)");
+        c.data_type = "INT".to_string();
+        c.max_length = Some(4);
+        assert_eq!(native_type_with_params(&c), "int");
+    }
 }
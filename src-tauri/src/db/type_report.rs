@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+
+use super::connectors::DatabaseEngine;
+use super::schema::TableInfo;
+use super::type_mapper::{
+    from_canonical, map_type_with_overrides, map_type_with_warnings, native_type_with_params,
+    to_canonical, CanonicalType, TypeOverride, TypeOverrideRegistry,
+};
+
+/// How risky a column's type mapping is, ordered from safe to lossy.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MappingSeverity {
+    Ok,
+    Warning,
+    Lossy,
+}
+
+/// One column run through the type mapper.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeCompatibilityEntry {
+    pub table: String,
+    pub column: String,
+    pub source_type: String,
+    pub canonical_type: String,
+    pub target_type: String,
+    pub is_lossy: bool,
+    pub severity: MappingSeverity,
+    pub warnings: Vec<String>,
+    /// Override that would make the mapping lossless, if one exists
+    pub suggested_override: Option<TypeOverride>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SeveritySummary {
+    pub ok: usize,
+    pub warning: usize,
+    pub lossy: usize,
+}
+
+/// Compatibility matrix for every column of a source schema against one
+/// target engine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeCompatibilityReport {
+    pub source_engine: DatabaseEngine,
+    pub target_engine: DatabaseEngine,
+    /// Sorted by severity (lossy first), then table and column
+    pub entries: Vec<TypeCompatibilityEntry>,
+    pub summary: SeveritySummary,
+    /// Distinct suggestions, ready to paste into a `typeOverrides` file
+    pub suggested_overrides: Vec<TypeOverride>,
+}
+
+/// Export formats for [`TypeCompatibilityReport::render`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ReportFormat {
+    Csv,
+    Markdown,
+    Json,
+}
+
+impl TypeCompatibilityReport {
+    pub fn build(
+        source_engine: &DatabaseEngine,
+        target_engine: &DatabaseEngine,
+        tables: &[TableInfo],
+    ) -> Self {
+        let mut entries = Vec::new();
+        for table in tables {
+            for col in &table.columns {
+                let source_type = native_type_with_params(col);
+                let canonical = to_canonical(source_engine, &source_type);
+                let mapped = map_type_with_warnings(source_engine, target_engine, &source_type);
+                let severity = if mapped.is_lossy {
+                    MappingSeverity::Lossy
+                } else if !mapped.warnings.is_empty() {
+                    MappingSeverity::Warning
+                } else {
+                    MappingSeverity::Ok
+                };
+                let suggested_override = if mapped.is_lossy {
+                    suggest_override(source_engine, target_engine, &source_type, &canonical)
+                } else {
+                    None
+                };
+                entries.push(TypeCompatibilityEntry {
+                    table: table.table_name.clone(),
+                    column: col.name.clone(),
+                    source_type,
+                    canonical_type: format!("{:?}", canonical),
+                    target_type: mapped.target_type,
+                    is_lossy: mapped.is_lossy,
+                    severity,
+                    warnings: mapped.warnings,
+                    suggested_override,
+                });
+            }
+        }
+        entries.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.table.cmp(&b.table))
+                .then_with(|| a.column.cmp(&b.column))
+        });
+
+        let mut summary = SeveritySummary::default();
+        let mut suggested_overrides: Vec<TypeOverride> = Vec::new();
+        for entry in &entries {
+            match entry.severity {
+                MappingSeverity::Ok => summary.ok += 1,
+                MappingSeverity::Warning => summary.warning += 1,
+                MappingSeverity::Lossy => summary.lossy += 1,
+            }
+            if let Some(o) = &entry.suggested_override {
+                if !suggested_overrides.contains(o) {
+                    suggested_overrides.push(o.clone());
+                }
+            }
+        }
+
+        Self {
+            source_engine: source_engine.clone(),
+            target_engine: target_engine.clone(),
+            entries,
+            summary,
+            suggested_overrides,
+        }
+    }
+
+    pub fn render(&self, format: ReportFormat) -> anyhow::Result<String> {
+        Ok(match format {
+            ReportFormat::Csv => self.to_csv(),
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "table,column,source_type,canonical_type,target_type,severity,lossy,warnings,suggested_target_type\n",
+        );
+        for e in &self.entries {
+            let fields = [
+                e.table.clone(),
+                e.column.clone(),
+                e.source_type.clone(),
+                e.canonical_type.clone(),
+                e.target_type.clone(),
+                format!("{:?}", e.severity),
+                e.is_lossy.to_string(),
+                e.warnings.join("; "),
+                e.suggested_override
+                    .as_ref()
+                    .map(|o| o.target_type.clone())
+                    .unwrap_or_default(),
+            ];
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&line.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Type compatibility: {} -> {}\n\n| Severity | Columns |\n|---|---|\n| Lossy | {} |\n| Warning | {} |\n| Ok | {} |\n\n",
+            self.source_engine,
+            self.target_engine,
+            self.summary.lossy,
+            self.summary.warning,
+            self.summary.ok
+        );
+        out.push_str(
+            "| Table | Column | Source type | Canonical | Target type | Severity | Warnings | Suggested |\n",
+        );
+        out.push_str("|---|---|---|---|---|---|---|---|\n");
+        for e in &self.entries {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {:?} | {} | {} |\n",
+                md_cell(&e.table),
+                md_cell(&e.column),
+                md_cell(&e.source_type),
+                md_cell(&e.canonical_type),
+                md_cell(&e.target_type),
+                e.severity,
+                md_cell(&e.warnings.join("; ")),
+                e.suggested_override
+                    .as_ref()
+                    .map(|o| md_cell(&o.target_type))
+                    .unwrap_or_default(),
+            ));
+        }
+        out
+    }
+}
+
+/// Wider canonical types to try, in order, when the default mapping of
+/// `canonical` is lossy.
+fn lossless_candidates(canonical: &CanonicalType) -> Vec<CanonicalType> {
+    match canonical {
+        CanonicalType::Decimal { .. } => vec![canonical.clone(), CanonicalType::Text],
+        CanonicalType::BigInt => vec![
+            CanonicalType::BigInt,
+            CanonicalType::Decimal {
+                precision: 20,
+                scale: 0,
+            },
+        ],
+        CanonicalType::DateTime | CanonicalType::Timestamp => {
+            vec![CanonicalType::Timestamp, CanonicalType::DateTime]
+        }
+        CanonicalType::NChar(n) | CanonicalType::NVarchar(n) => {
+            vec![CanonicalType::NVarchar(*n), CanonicalType::NText]
+        }
+        CanonicalType::NText => vec![CanonicalType::NText],
+        _ => Vec::new(),
+    }
+}
+
+/// Find a target type that maps `source_type` without loss, expressed as a
+/// global override for the engine pair.
+fn suggest_override(
+    source_engine: &DatabaseEngine,
+    target_engine: &DatabaseEngine,
+    source_type: &str,
+    canonical: &CanonicalType,
+) -> Option<TypeOverride> {
+    // Prefer the exact native type as the pattern; fall back to the base
+    // name when its parameters are not numeric (e.g. MySQL enums).
+    let exact = source_type.trim().to_lowercase();
+    let probe = TypeOverride {
+        source_engine: source_engine.clone(),
+        source_type: exact.clone(),
+        target_engine: target_engine.clone(),
+        target_type: from_canonical(target_engine, canonical),
+        table: None,
+        column: None,
+    };
+    let pattern = if probe.validate().is_ok() {
+        exact
+    } else {
+        exact.split('(').next().unwrap_or("").trim().to_string()
+    };
+
+    lossless_candidates(canonical).into_iter().find_map(|candidate| {
+        let candidate_override = TypeOverride {
+            source_engine: source_engine.clone(),
+            source_type: pattern.clone(),
+            target_engine: target_engine.clone(),
+            target_type: from_canonical(target_engine, &candidate),
+            table: None,
+            column: None,
+        };
+        let registry = TypeOverrideRegistry::new(vec![candidate_override.clone()]).ok()?;
+        let mapped = map_type_with_overrides(
+            source_engine,
+            target_engine,
+            source_type,
+            &registry,
+            None,
+            None,
+        );
+        (mapped.applied_override.is_some() && !mapped.is_lossy).then_some(candidate_override)
+    })
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn md_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::ColumnInfo;
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            is_primary_key: false,
+            max_length: None,
+            precision: None,
+            scale: None,
+            default_value: None,
+            ordinal_position: 1,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnInfo>) -> TableInfo {
+        TableInfo {
+            schema_name: "dbo".to_string(),
+            table_name: name.to_string(),
+            columns,
+            indexes: vec![],
+            constraints: vec![],
+            row_count: None,
+            field_stats: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_report_aggregates_by_severity() {
+        let tables = vec![table(
+            "events",
+            vec![
+                column("id", "int"),
+                column("occurred_at", "datetime2"),
+                column("payload", "nvarchar(max)"),
+            ],
+        )];
+        let report = TypeCompatibilityReport::build(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::Sqlite,
+            &tables,
+        );
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(
+            report.summary.ok + report.summary.warning + report.summary.lossy,
+            3
+        );
+        assert!(report.summary.lossy >= 1);
+        // Lossy entries sort first
+        assert_eq!(report.entries[0].severity, MappingSeverity::Lossy);
+        let id = report.entries.iter().find(|e| e.column == "id").unwrap();
+        assert_eq!(id.severity, MappingSeverity::Ok);
+        assert_eq!(id.canonical_type, "Int");
+    }
+
+    #[test]
+    fn test_suggested_override_is_lossless() {
+        let tables = vec![table(
+            "orders",
+            vec![column("total", "decimal(38,10)"), column("fee", "money")],
+        )];
+        let report = TypeCompatibilityReport::build(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::Sqlite,
+            &tables,
+        );
+        assert_eq!(report.summary.lossy, 2);
+        assert_eq!(report.suggested_overrides.len(), 2);
+        for entry in &report.entries {
+            let o = entry.suggested_override.as_ref().unwrap();
+            assert_eq!(o.target_type, "TEXT");
+            let registry = TypeOverrideRegistry::new(vec![o.clone()]).unwrap();
+            let mapped = map_type_with_overrides(
+                &report.source_engine,
+                &report.target_engine,
+                &entry.source_type,
+                &registry,
+                None,
+                None,
+            );
+            assert!(!mapped.is_lossy);
+        }
+    }
+
+    #[test]
+    fn test_no_suggestion_when_target_cannot_do_better() {
+        let tables = vec![table("t", vec![column("at", "datetime2")])];
+        let report = TypeCompatibilityReport::build(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::Sqlite,
+            &tables,
+        );
+        assert!(report.entries[0].is_lossy);
+        assert!(report.entries[0].suggested_override.is_none());
+    }
+
+    #[test]
+    fn test_no_suggestion_for_lossless_columns() {
+        let tables = vec![table("t", vec![column("id", "bigint")])];
+        let report = TypeCompatibilityReport::build(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::PostgreSql,
+            &tables,
+        );
+        assert_eq!(report.entries[0].severity, MappingSeverity::Ok);
+        assert!(report.entries[0].suggested_override.is_none());
+        assert!(report.suggested_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_csv_escapes_fields() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("decimal(10,2)"), "\"decimal(10,2)\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let tables = vec![table("t", vec![column("amount", "decimal(10,2)")])];
+        let report = TypeCompatibilityReport::build(
+            &DatabaseEngine::SqlServer,
+            &DatabaseEngine::PostgreSql,
+            &tables,
+        );
+        let csv = report.render(ReportFormat::Csv).unwrap();
+        assert!(csv.starts_with("table,column,source_type"));
+        assert!(csv.contains("\"decimal(10,2)\""));
+        let md = report.render(ReportFormat::Markdown).unwrap();
+        assert!(md.contains("| t | amount | decimal(10,2) |"));
+        let json = report.render(ReportFormat::Json).unwrap();
+        let parsed: TypeCompatibilityReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
@@ -0,0 +1,278 @@
+//! Tagged JSON convention for values that plain JSON cannot carry exactly.
+//!
+//! `Row` stays a `HashMap<String, serde_json::Value>`, but connectors emit a
+//! single-key object for decimals, bytes, temporal values and UUIDs:
+//!
+//! | Tag          | Payload                                   |
+//! |--------------|-------------------------------------------|
+//! | `$decimal`   | exact decimal text, e.g. `"-12.50"`       |
+//! | `$binary`    | lowercase hex, e.g. `"deadbeef"`          |
+//! | `$date`      | `YYYY-MM-DD`                              |
+//! | `$time`      | `HH:MM:SS[.fffffffff]`                    |
+//! | `$datetime`  | `YYYY-MM-DDTHH:MM:SS[.f]` (no offset)     |
+//! | `$timestamp` | RFC 3339 with offset                      |
+//! | `$uuid`      | lowercase hyphenated UUID                 |
+//!
+//! Consumers that only need text (comparison, export, display) can call
+//! [`to_plain`] to unwrap tags back to strings.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::{Map, Value};
+
+pub const DECIMAL_TAG: &str = "$decimal";
+pub const BINARY_TAG: &str = "$binary";
+pub const DATE_TAG: &str = "$date";
+pub const TIME_TAG: &str = "$time";
+pub const DATETIME_TAG: &str = "$datetime";
+pub const TIMESTAMP_TAG: &str = "$timestamp";
+pub const UUID_TAG: &str = "$uuid";
+
+/// A value decoded from its tagged JSON form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    /// Validated decimal text: optional `-`, digits, optional `.digits`.
+    Decimal(String),
+    Bytes(Vec<u8>),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDateTime),
+    Timestamp(DateTime<FixedOffset>),
+    Uuid(uuid::Uuid),
+}
+
+impl TypedValue {
+    /// Decode a tagged object. Returns `None` for anything else, including
+    /// objects whose payload does not parse.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        if obj.len() != 1 {
+            return None;
+        }
+        let (tag, payload) = obj.iter().next()?;
+        let text = payload.as_str()?;
+        match tag.as_str() {
+            DECIMAL_TAG => is_decimal_text(text).then(|| TypedValue::Decimal(text.to_string())),
+            BINARY_TAG => hex::decode(text).ok().map(TypedValue::Bytes),
+            DATE_TAG => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(TypedValue::Date),
+            TIME_TAG => NaiveTime::parse_from_str(text, "%H:%M:%S%.f")
+                .ok()
+                .map(TypedValue::Time),
+            DATETIME_TAG => NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(TypedValue::DateTime),
+            TIMESTAMP_TAG => DateTime::parse_from_rfc3339(text)
+                .ok()
+                .map(TypedValue::Timestamp),
+            UUID_TAG => uuid::Uuid::parse_str(text).ok().map(TypedValue::Uuid),
+            _ => None,
+        }
+    }
+
+    /// Encode as a single-key tagged object.
+    pub fn to_json(&self) -> Value {
+        let (tag, text) = match self {
+            TypedValue::Decimal(_) => (DECIMAL_TAG, self.to_text()),
+            TypedValue::Bytes(_) => (BINARY_TAG, self.to_text()),
+            TypedValue::Date(_) => (DATE_TAG, self.to_text()),
+            TypedValue::Time(_) => (TIME_TAG, self.to_text()),
+            TypedValue::DateTime(_) => (DATETIME_TAG, self.to_text()),
+            TypedValue::Timestamp(_) => (TIMESTAMP_TAG, self.to_text()),
+            TypedValue::Uuid(_) => (UUID_TAG, self.to_text()),
+        };
+        let mut map = Map::with_capacity(1);
+        map.insert(tag.to_string(), Value::String(text));
+        Value::Object(map)
+    }
+
+    /// Canonical text form (the tagged payload).
+    pub fn to_text(&self) -> String {
+        match self {
+            TypedValue::Decimal(s) => s.clone(),
+            TypedValue::Bytes(b) => hex::encode(b),
+            TypedValue::Date(d) => d.format("%Y-%m-%d").to_string(),
+            TypedValue::Time(t) => t.format("%H:%M:%S%.f").to_string(),
+            TypedValue::DateTime(dt) => dt.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+            TypedValue::Timestamp(ts) => ts.to_rfc3339(),
+            TypedValue::Uuid(u) => u.hyphenated().to_string(),
+        }
+    }
+}
+
+/// Tag exact decimal text. Callers are expected to pass text produced by a
+/// database driver or by `value_converter`.
+pub fn decimal(text: impl Into<String>) -> Value {
+    TypedValue::Decimal(text.into()).to_json()
+}
+
+pub fn bytes(data: &[u8]) -> Value {
+    TypedValue::Bytes(data.to_vec()).to_json()
+}
+
+pub fn date(d: NaiveDate) -> Value {
+    TypedValue::Date(d).to_json()
+}
+
+pub fn time(t: NaiveTime) -> Value {
+    TypedValue::Time(t).to_json()
+}
+
+pub fn datetime(dt: NaiveDateTime) -> Value {
+    TypedValue::DateTime(dt).to_json()
+}
+
+pub fn timestamp(ts: DateTime<FixedOffset>) -> Value {
+    TypedValue::Timestamp(ts).to_json()
+}
+
+pub fn uuid(u: uuid::Uuid) -> Value {
+    TypedValue::Uuid(u).to_json()
+}
+
+/// Unwrap a tagged value to its canonical text; other values are returned
+/// unchanged.
+pub fn to_plain(value: &Value) -> Value {
+    match TypedValue::from_json(value) {
+        Some(tv) => Value::String(tv.to_text()),
+        None => value.clone(),
+    }
+}
+
+/// `true` for `-?digits[.digits]`, the only decimal shape the tag carries.
+pub fn is_decimal_text(text: &str) -> bool {
+    let unsigned = text.strip_prefix('-').unwrap_or(text);
+    let (int_part, frac_part) = match unsigned.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (unsigned, None),
+    };
+    !int_part.is_empty()
+        && int_part.bytes().all(|b| b.is_ascii_digit())
+        && frac_part.map_or(true, |f| !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Format an unscaled integer with `scale` implied decimal places, as used
+/// by the TDS and Decimal128 wire formats.
+pub fn decimal_from_scaled(unscaled: i128, scale: u32) -> String {
+    let negative = unscaled < 0;
+    let digits = unscaled.unsigned_abs().to_string();
+    let scale = scale as usize;
+    let body = if scale == 0 {
+        digits
+    } else if digits.len() > scale {
+        format!(
+            "{}.{}",
+            &digits[..digits.len() - scale],
+            &digits[digits.len() - scale..]
+        )
+    } else {
+        format!("0.{}{}", "0".repeat(scale - digits.len()), digits)
+    };
+    if negative {
+        format!("-{}", body)
+    } else {
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decimal_round_trip() {
+        let v = decimal("-123456789012345678901234567.000000001");
+        assert_eq!(
+            v,
+            json!({"$decimal": "-123456789012345678901234567.000000001"})
+        );
+        assert_eq!(
+            TypedValue::from_json(&v),
+            Some(TypedValue::Decimal(
+                "-123456789012345678901234567.000000001".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let v = bytes(&data);
+        assert_eq!(TypedValue::from_json(&v), Some(TypedValue::Bytes(data)));
+    }
+
+    #[test]
+    fn test_temporal_round_trip() {
+        let d = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let t = NaiveTime::from_hms_nano_opt(23, 59, 59, 123_456_700).unwrap();
+        let dt = d.and_time(t);
+        let ts = DateTime::parse_from_rfc3339("2024-02-29T23:59:59.1234567-05:30").unwrap();
+        for tv in [
+            TypedValue::Date(d),
+            TypedValue::Time(t),
+            TypedValue::DateTime(dt),
+            TypedValue::Timestamp(ts),
+        ] {
+            assert_eq!(TypedValue::from_json(&tv.to_json()), Some(tv));
+        }
+    }
+
+    #[test]
+    fn test_timestamp_keeps_offset() {
+        let ts = DateTime::parse_from_rfc3339("2024-01-01T10:00:00+02:00").unwrap();
+        assert_eq!(
+            timestamp(ts),
+            json!({"$timestamp": "2024-01-01T10:00:00+02:00"})
+        );
+    }
+
+    #[test]
+    fn test_uuid_round_trip() {
+        let u = uuid::Uuid::parse_str("A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11").unwrap();
+        let v = uuid(u);
+        assert_eq!(v, json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"}));
+        assert_eq!(TypedValue::from_json(&v), Some(TypedValue::Uuid(u)));
+    }
+
+    #[test]
+    fn test_untagged_objects_are_not_typed() {
+        assert_eq!(TypedValue::from_json(&json!({"a": "1"})), None);
+        assert_eq!(
+            TypedValue::from_json(&json!({"$decimal": "1", "x": 2})),
+            None
+        );
+        assert_eq!(TypedValue::from_json(&json!({"$decimal": "1e5"})), None);
+        assert_eq!(TypedValue::from_json(&json!({"$binary": "zz"})), None);
+        assert_eq!(TypedValue::from_json(&json!("12.5")), None);
+    }
+
+    #[test]
+    fn test_to_plain() {
+        assert_eq!(to_plain(&decimal("1.50")), json!("1.50"));
+        assert_eq!(to_plain(&bytes(&[0xde, 0xad])), json!("dead"));
+        assert_eq!(to_plain(&json!(5)), json!(5));
+    }
+
+    #[test]
+    fn test_is_decimal_text() {
+        assert!(is_decimal_text("0"));
+        assert!(is_decimal_text("-10.250"));
+        assert!(!is_decimal_text(""));
+        assert!(!is_decimal_text("-"));
+        assert!(!is_decimal_text("1."));
+        assert!(!is_decimal_text(".5"));
+        assert!(!is_decimal_text("1;DROP"));
+    }
+
+    #[test]
+    fn test_decimal_from_scaled() {
+        assert_eq!(decimal_from_scaled(12345, 2), "123.45");
+        assert_eq!(decimal_from_scaled(-5, 3), "-0.005");
+        assert_eq!(decimal_from_scaled(42, 0), "42");
+        assert_eq!(
+            decimal_from_scaled(i128::MAX, 10),
+            "17014118346046923173168730371.5884105727"
+        );
+    }
+}
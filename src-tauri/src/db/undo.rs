@@ -0,0 +1,382 @@
+//! Undo log for reversible migrations.
+//!
+//! Every write a migration applies is logged with what is needed to reverse
+//! it: an insert keeps the row written, an update the target row before it
+//! and the values it set, a delete the row removed. Undoing replays the
+//! inverses in FK order (see [`undo_order`]), but only if no logged row
+//! changed on the target since.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::data_comparator::{differing_columns, row_match_key};
+use super::document::DocumentWrite;
+use super::schema::Row;
+use super::sql_generator::SqlGenerator;
+
+/// Kind of write that was logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl UndoOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UndoOp::Insert => "insert",
+            UndoOp::Update => "update",
+            UndoOp::Delete => "delete",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "insert" => Some(UndoOp::Insert),
+            "update" => Some(UndoOp::Update),
+            "delete" => Some(UndoOp::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// One logged write on a target table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoEntry {
+    pub table: String,
+    pub op: UndoOp,
+    /// Key columns of the row.
+    pub key: Row,
+    /// Target row before an update or delete.
+    pub before: Option<Row>,
+    /// Values an insert or update wrote, key columns included.
+    pub after: Option<Row>,
+}
+
+impl UndoEntry {
+    pub fn inserted(table: &str, row: &Row, key_columns: &[String]) -> Self {
+        Self {
+            table: table.to_string(),
+            op: UndoOp::Insert,
+            key: project(row, key_columns),
+            before: None,
+            after: Some(row.clone()),
+        }
+    }
+
+    /// An update that set `changed` columns of `before` to their values in
+    /// `row`.
+    pub fn updated(
+        table: &str,
+        before: &Row,
+        row: &Row,
+        changed: &[String],
+        key_columns: &[String],
+    ) -> Self {
+        let mut after = project(row, key_columns);
+        after.extend(project(row, changed));
+        Self {
+            table: table.to_string(),
+            op: UndoOp::Update,
+            key: project(before, key_columns),
+            before: Some(before.clone()),
+            after: Some(after),
+        }
+    }
+
+    pub fn deleted(table: &str, row: &Row, key_columns: &[String]) -> Self {
+        Self {
+            table: table.to_string(),
+            op: UndoOp::Delete,
+            key: project(row, key_columns),
+            before: Some(row.clone()),
+            after: None,
+        }
+    }
+
+    fn key_columns(&self) -> Vec<String> {
+        let mut columns: Vec<String> = self.key.keys().cloned().collect();
+        columns.sort();
+        columns
+    }
+
+    /// Statement reversing this write: delete the inserted row, set the
+    /// updated columns back, or re-insert the deleted row. `None` when the
+    /// entry lacks the image it needs.
+    pub fn inverse_sql(&self, sql_gen: &SqlGenerator) -> Option<String> {
+        let key_columns = self.key_columns();
+        match self.op {
+            UndoOp::Insert => Some(sql_gen.generate_delete(&self.table, &self.key, &key_columns)),
+            UndoOp::Update => {
+                let before = self.before.as_ref()?;
+                let mut restore = before.clone();
+                restore.extend(self.key.clone());
+                let changed: Vec<String> = self
+                    .after
+                    .as_ref()?
+                    .keys()
+                    .filter(|c| !self.key.contains_key(*c))
+                    .cloned()
+                    .collect();
+                let sql =
+                    sql_gen.generate_partial_update(&self.table, &restore, &changed, &key_columns);
+                (!sql.is_empty()).then_some(sql)
+            }
+            UndoOp::Delete => Some(sql_gen.generate_insert(&self.table, self.before.as_ref()?)),
+        }
+    }
+
+    /// Document write reversing this write on a document store: delete the
+    /// inserted document, put back the whole document an update changed, or
+    /// re-insert the deleted one.
+    pub fn inverse_document_write(&self) -> Option<DocumentWrite> {
+        match self.op {
+            UndoOp::Insert => Some(DocumentWrite::Delete { filter: self.key.clone() }),
+            UndoOp::Update => Some(DocumentWrite::Replace {
+                filter: self.key.clone(),
+                document: self.before.clone()?,
+            }),
+            UndoOp::Delete => Some(DocumentWrite::Insert { document: self.before.clone()? }),
+        }
+    }
+}
+
+/// Order in which to replay the inverses of `entries`, given the tables
+/// parents first. Deleted rows are re-inserted parents first, updated rows
+/// are set back, then inserted rows are deleted children first, so no step
+/// leaves a row pointing at a parent that is missing. Within a table the
+/// newest write is reversed first.
+pub fn undo_order<'a>(entries: &'a [UndoEntry], table_order: &[String]) -> Vec<&'a UndoEntry> {
+    let rank = |e: &UndoEntry| {
+        table_order
+            .iter()
+            .position(|t| *t == e.table)
+            .unwrap_or(table_order.len()) as i64
+    };
+    let mut ordered: Vec<&UndoEntry> = entries.iter().rev().collect();
+    ordered.sort_by_key(|e| match e.op {
+        UndoOp::Delete => (0, rank(e)),
+        UndoOp::Update => (1, 0),
+        UndoOp::Insert => (2, -rank(e)),
+    });
+    ordered
+}
+
+/// A logged row that changed on the target after the migration, which
+/// blocks undoing it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoConflict {
+    pub table: String,
+    pub operation: String,
+    pub key: Row,
+    pub reason: String,
+}
+
+/// Check one table's entries against its current rows. Inserted and
+/// updated rows must still hold the values written; deleted rows must
+/// still be absent.
+pub fn find_conflicts(entries: &[&UndoEntry], current_rows: &[Row]) -> Vec<UndoConflict> {
+    let mut indexes: HashMap<Vec<String>, HashMap<String, &Row>> = HashMap::new();
+    let mut conflicts = Vec::new();
+    for entry in entries {
+        let key_columns = entry.key_columns();
+        let index = indexes.entry(key_columns.clone()).or_insert_with(|| {
+            current_rows
+                .iter()
+                .map(|row| (row_match_key(row, &key_columns), row))
+                .collect()
+        });
+        let current = index.get(&row_match_key(&entry.key, &key_columns));
+
+        let reason = match (entry.op, current, entry.after.as_ref()) {
+            (UndoOp::Delete, Some(_), _) => {
+                Some("a row with this key was inserted after the migration".to_string())
+            }
+            (UndoOp::Delete, None, _) => None,
+            (_, None, _) => Some("the row was deleted after the migration".to_string()),
+            (_, Some(current), Some(after)) => {
+                let mut columns: Vec<String> = after.keys().cloned().collect();
+                columns.sort();
+                let changed = differing_columns(after, current, &columns);
+                (!changed.is_empty())
+                    .then(|| format!("{} changed after the migration", changed.join(", ")))
+            }
+            (_, Some(_), None) => None,
+        };
+        if let Some(reason) = reason {
+            conflicts.push(UndoConflict {
+                table: entry.table.clone(),
+                operation: entry.op.as_str().to_string(),
+                key: entry.key.clone(),
+                reason,
+            });
+        }
+    }
+    conflicts
+}
+
+fn project(row: &Row, columns: &[String]) -> Row {
+    columns
+        .iter()
+        .filter_map(|c| row.get(c).map(|v| (c.clone(), v.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connectors::DatabaseEngine;
+    use serde_json::{json, Value};
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn key() -> Vec<String> {
+        vec!["id".to_string()]
+    }
+
+    #[test]
+    fn test_op_round_trip() {
+        for op in [UndoOp::Insert, UndoOp::Update, UndoOp::Delete] {
+            assert_eq!(UndoOp::parse(op.as_str()), Some(op));
+        }
+        assert_eq!(UndoOp::parse("upsert"), None);
+    }
+
+    #[test]
+    fn test_inverse_sql() {
+        let gen = SqlGenerator::new(DatabaseEngine::PostgreSql);
+        let inserted =
+            UndoEntry::inserted("t", &row(&[("id", json!(1)), ("name", json!("a"))]), &key());
+        assert_eq!(
+            inserted.inverse_sql(&gen).unwrap(),
+            "DELETE FROM \"t\" WHERE \"id\" = 1;"
+        );
+
+        let before = row(&[("id", json!(2)), ("name", json!("old")), ("qty", json!(5))]);
+        let after = row(&[("id", json!(2)), ("name", json!("new")), ("qty", json!(5))]);
+        let updated = UndoEntry::updated("t", &before, &after, &["name".to_string()], &key());
+        assert_eq!(updated.after.as_ref().unwrap().len(), 2);
+        assert_eq!(
+            updated.inverse_sql(&gen).unwrap(),
+            "UPDATE \"t\" SET \"name\" = 'old' WHERE \"id\" = 2;"
+        );
+
+        let deleted = UndoEntry::deleted("t", &before, &key());
+        assert!(deleted
+            .inverse_sql(&gen)
+            .unwrap()
+            .starts_with("INSERT INTO \"t\""));
+    }
+
+    #[test]
+    fn test_inverse_document_write() {
+        let doc = row(&[("id", json!(1)), ("name", json!("a"))]);
+        let inserted = UndoEntry::inserted("c", &doc, &key());
+        assert_eq!(
+            inserted.inverse_document_write(),
+            Some(DocumentWrite::Delete { filter: row(&[("id", json!(1))]) })
+        );
+        let updated = UndoEntry::updated(
+            "c",
+            &doc,
+            &row(&[("id", json!(1)), ("name", json!("b"))]),
+            &["name".to_string()],
+            &key(),
+        );
+        assert_eq!(
+            updated.inverse_document_write(),
+            Some(DocumentWrite::Replace { filter: row(&[("id", json!(1))]), document: doc.clone() })
+        );
+        let deleted = UndoEntry::deleted("c", &doc, &key());
+        assert_eq!(
+            deleted.inverse_document_write(),
+            Some(DocumentWrite::Insert { document: doc })
+        );
+    }
+
+    #[test]
+    fn test_undo_order_follows_foreign_keys() {
+        let r = |id: i64| row(&[("id", json!(id))]);
+        // A Mirror run: parents loaded first, then stale rows deleted
+        // children first
+        let entries = vec![
+            UndoEntry::inserted("parent", &r(1), &key()),
+            UndoEntry::inserted("child", &r(10), &key()),
+            UndoEntry::inserted("child", &r(11), &key()),
+            UndoEntry::updated("child", &r(12), &r(12), &[], &key()),
+            UndoEntry::deleted("child", &r(13), &key()),
+            UndoEntry::deleted("parent", &r(2), &key()),
+        ];
+        let order = vec!["parent".to_string(), "child".to_string()];
+        let steps: Vec<(UndoOp, &str, Value)> = undo_order(&entries, &order)
+            .into_iter()
+            .map(|e| (e.op, e.table.as_str(), e.key["id"].clone()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (UndoOp::Delete, "parent", json!(2)),
+                (UndoOp::Delete, "child", json!(13)),
+                (UndoOp::Update, "child", json!(12)),
+                (UndoOp::Insert, "child", json!(11)),
+                (UndoOp::Insert, "child", json!(10)),
+                (UndoOp::Insert, "parent", json!(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let inserted =
+            UndoEntry::inserted("t", &row(&[("id", json!(1)), ("name", json!("a"))]), &key());
+        let before = row(&[("id", json!(2)), ("name", json!("old"))]);
+        let updated = UndoEntry::updated(
+            "t",
+            &before,
+            &row(&[("id", json!(2)), ("name", json!("new"))]),
+            &["name".to_string()],
+            &key(),
+        );
+        let deleted =
+            UndoEntry::deleted("t", &row(&[("id", json!(3)), ("name", json!("c"))]), &key());
+        let entries = vec![&inserted, &updated, &deleted];
+
+        // Untouched since the migration
+        let current = vec![
+            row(&[("id", json!(1)), ("name", json!("a"))]),
+            row(&[("id", json!(2)), ("name", json!("new"))]),
+        ];
+        assert!(find_conflicts(&entries, &current).is_empty());
+
+        // Inserted row edited, updated row removed, deleted key re-inserted
+        let current = vec![
+            row(&[("id", json!(1)), ("name", json!("edited"))]),
+            row(&[("id", json!(3)), ("name", json!("again"))]),
+        ];
+        let conflicts = find_conflicts(&entries, &current);
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts[0].operation, "insert");
+        assert_eq!(conflicts[0].reason, "name changed after the migration");
+        assert_eq!(
+            conflicts[1].reason,
+            "the row was deleted after the migration"
+        );
+        assert_eq!(conflicts[2].operation, "delete");
+    }
+
+    #[test]
+    fn test_keys_match_across_representations() {
+        let inserted =
+            UndoEntry::inserted("t", &row(&[("id", json!({"$decimal": "1.50"}))]), &key());
+        let current = vec![row(&[("id", json!({"$decimal": "1.5"}))])];
+        assert!(find_conflicts(&[&inserted], &current).is_empty());
+    }
+}
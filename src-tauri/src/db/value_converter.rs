@@ -0,0 +1,1240 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::connectors::DatabaseEngine;
+use super::schema::{ColumnInfo, Row};
+use super::type_mapper::{native_type_with_params, to_canonical, CanonicalType};
+use super::typed_value::{self, TypedValue};
+
+/// Error raised when a value cannot be represented in the target column's type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("cannot convert {value} to {target}: {reason}")]
+pub struct ConversionError {
+    pub column: Option<String>,
+    pub value: String,
+    pub target: String,
+    pub reason: String,
+}
+
+impl ConversionError {
+    fn new(value: &Value, target: &CanonicalType, reason: impl Into<String>) -> Self {
+        let mut shown = value.to_string();
+        if shown.chars().count() > 64 {
+            shown = format!("{}...", shown.chars().take(64).collect::<String>());
+        }
+        Self {
+            column: None,
+            value: shown,
+            target: format!("{:?}", target),
+            reason: reason.into(),
+        }
+    }
+
+    fn for_column(mut self, column: &str) -> Self {
+        self.column = Some(column.to_string());
+        self
+    }
+}
+
+/// Resolve the canonical type of a target column.
+///
+/// `information_schema` usually reports the bare type name (`numeric`,
+/// `varbinary`) with length, precision and scale in separate fields, so
+/// those are folded back in before mapping. SQLite columns are resolved from
+/// their declared type name first because affinity rules would otherwise
+/// turn `DATETIME` or `DECIMAL(10,2)` into a plain double.
+pub fn column_canonical_type(engine: &DatabaseEngine, column: &ColumnInfo) -> CanonicalType {
+    let declared = column.data_type.trim().to_lowercase();
+    if declared.is_empty() {
+        return CanonicalType::Unknown(String::new());
+    }
+
+    if *engine == DatabaseEngine::Sqlite {
+        if let Some(t) = sqlite_declared_type(&declared) {
+            return t;
+        }
+    }
+
+    to_canonical(engine, &native_type_with_params(column))
+}
+
+/// Build a column name -> canonical type lookup for a target table.
+pub fn column_type_map(
+    engine: &DatabaseEngine,
+    columns: &[ColumnInfo],
+) -> HashMap<String, CanonicalType> {
+    columns
+        .iter()
+        .map(|c| (c.name.clone(), column_canonical_type(engine, c)))
+        .collect()
+}
+
+/// Convert every value in a row to the canonical type of its target column.
+///
+/// Columns without an entry in `column_types` are passed through unchanged.
+/// All failing columns are reported, not just the first one.
+pub fn convert_row(
+    row: &Row,
+    column_types: &HashMap<String, CanonicalType>,
+) -> Result<Row, Vec<ConversionError>> {
+    let mut converted = Row::with_capacity(row.len());
+    let mut errors = Vec::new();
+
+    for (name, value) in row {
+        match column_types.get(name) {
+            Some(target) => match convert_value(value, target) {
+                Ok(v) => {
+                    converted.insert(name.clone(), v);
+                }
+                Err(e) => errors.push(e.for_column(name)),
+            },
+            None => {
+                converted.insert(name.clone(), value.clone());
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(converted)
+    } else {
+        errors.sort_by(|a, b| a.column.cmp(&b.column));
+        Err(errors)
+    }
+}
+
+/// Coerce a single value from its source representation to `target`.
+///
+/// Tagged inputs (see `typed_value`) are unwrapped first. The result uses
+/// the representation the rest of the pipeline expects:
+/// - integers and floats become JSON numbers, booleans JSON booleans
+/// - decimals become `$decimal` values rounded to the target scale, never
+///   passing through `f64`
+/// - dates, times, date-times and timestamps become the matching tagged
+///   values; offsets are converted to UTC for naive date-time targets and
+///   kept for timestamp targets
+/// - UUIDs and binary become `$uuid` / `$binary` values
+///
+/// String length is not enforced here; oversized strings are truncated by
+/// `SqlGenerator::prepare_row_for_insert`.
+pub fn convert_value(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    if let CanonicalType::Unknown(_) = target {
+        // Nothing to coerce to; keep tags such as `$binary` intact
+        return Ok(value.clone());
+    }
+    if let Some(typed) = TypedValue::from_json(value) {
+        return convert_value(&Value::String(typed.to_text()), target);
+    }
+
+    match target {
+        CanonicalType::Boolean => to_boolean(value, target),
+        CanonicalType::TinyInt => to_integer(value, target, i8::MIN as i64, i8::MAX as i64),
+        CanonicalType::UnsignedTinyInt => to_integer(value, target, 0, u8::MAX as i64),
+        CanonicalType::SmallInt => to_integer(value, target, i16::MIN as i64, i16::MAX as i64),
+        CanonicalType::Int => to_integer(value, target, i32::MIN as i64, i32::MAX as i64),
+        CanonicalType::BigInt => to_integer(value, target, i64::MIN, i64::MAX),
+        CanonicalType::Float => to_float(value, target, true),
+        CanonicalType::Double => to_float(value, target, false),
+        CanonicalType::Decimal { precision, scale } => {
+            let text = decimal_source_text(value, target)?;
+            normalize_decimal(&text, *precision, *scale)
+                .map(typed_value::decimal)
+                .map_err(|reason| ConversionError::new(value, target, reason))
+        }
+        CanonicalType::Char(_)
+        | CanonicalType::Varchar(_)
+        | CanonicalType::Text
+        | CanonicalType::NChar(_)
+        | CanonicalType::NVarchar(_)
+        | CanonicalType::NText
+        | CanonicalType::Xml => Ok(Value::String(value_to_text(value))),
+        CanonicalType::Binary(n) | CanonicalType::Varbinary(n) => {
+            let bytes = to_bytes(value, target)?;
+            if *n > 0 && *n != u32::MAX && bytes.len() > *n as usize {
+                return Err(ConversionError::new(
+                    value,
+                    target,
+                    format!("{} bytes exceed column length {}", bytes.len(), n),
+                ));
+            }
+            Ok(typed_value::bytes(&bytes))
+        }
+        CanonicalType::Blob => to_bytes(value, target).map(|b| typed_value::bytes(&b)),
+        CanonicalType::Date => to_date(value, target),
+        CanonicalType::Time => to_time(value, target),
+        CanonicalType::DateTime => to_datetime(value, target),
+        CanonicalType::Timestamp => to_timestamp(value, target),
+        CanonicalType::Uuid => to_uuid(value, target),
+        CanonicalType::Json => to_json(value, target),
+        CanonicalType::Array(inner) => to_array(value, target, inner),
+        CanonicalType::Unknown(_) => Ok(value.clone()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Column type resolution helpers
+// ---------------------------------------------------------------------------
+
+fn sqlite_declared_type(declared: &str) -> Option<CanonicalType> {
+    let base = declared.split('(').next().unwrap_or("").trim();
+    match base {
+        "date" => Some(CanonicalType::Date),
+        "time" => Some(CanonicalType::Time),
+        "datetime" | "timestamp" => Some(CanonicalType::DateTime),
+        "timestamptz" => Some(CanonicalType::Timestamp),
+        "bool" | "boolean" => Some(CanonicalType::Boolean),
+        "uuid" | "guid" | "uniqueidentifier" => Some(CanonicalType::Uuid),
+        "json" | "jsonb" => Some(CanonicalType::Json),
+        "decimal" | "numeric" => {
+            let (_, p, s) = super::type_mapper::parse_native_type(declared);
+            Some(CanonicalType::Decimal {
+                precision: p.unwrap_or(0) as u8,
+                scale: s.unwrap_or(0) as u8,
+            })
+        }
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scalar conversions
+// ---------------------------------------------------------------------------
+
+fn to_boolean(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    match value {
+        Value::Bool(_) => Ok(value.clone()),
+        Value::Number(n) => match n.as_f64() {
+            Some(0.0) => Ok(Value::Bool(false)),
+            Some(1.0) => Ok(Value::Bool(true)),
+            _ => Err(ConversionError::new(
+                value,
+                target,
+                "only 0 and 1 map to a boolean",
+            )),
+        },
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "on" | "1" => Ok(Value::Bool(true)),
+            "false" | "f" | "no" | "n" | "off" | "0" => Ok(Value::Bool(false)),
+            _ => Err(ConversionError::new(
+                value,
+                target,
+                "not a recognised boolean literal",
+            )),
+        },
+        _ => Err(ConversionError::new(
+            value,
+            target,
+            "structured value cannot be a boolean",
+        )),
+    }
+}
+
+fn to_integer(
+    value: &Value,
+    target: &CanonicalType,
+    min: i64,
+    max: i64,
+) -> Result<Value, ConversionError> {
+    let parsed: i128 = match value {
+        Value::Bool(b) => *b as i128,
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i as i128
+            } else if let Some(u) = n.as_u64() {
+                u as i128
+            } else {
+                integer_from_decimal_text(&n.to_string())
+                    .map_err(|reason| ConversionError::new(value, target, reason))?
+            }
+        }
+        Value::String(s) => integer_from_decimal_text(s)
+            .map_err(|reason| ConversionError::new(value, target, reason))?,
+        _ => {
+            return Err(ConversionError::new(
+                value,
+                target,
+                "structured value cannot be an integer",
+            ))
+        }
+    };
+
+    if parsed < min as i128 || parsed > max as i128 {
+        return Err(ConversionError::new(
+            value,
+            target,
+            format!("out of range [{}, {}]", min, max),
+        ));
+    }
+    Ok(Value::Number(serde_json::Number::from(parsed as i64)))
+}
+
+/// Parse decimal text into an integer, rejecting non-zero fractional digits.
+fn integer_from_decimal_text(text: &str) -> Result<i128, String> {
+    let parts = parse_decimal(text)?;
+    if parts.frac.bytes().any(|b| b != b'0') {
+        return Err("fractional part would be lost".to_string());
+    }
+    if parts.int.len() > 38 {
+        return Err("integer is too large".to_string());
+    }
+    let magnitude: i128 = if parts.int.is_empty() {
+        0
+    } else {
+        parts
+            .int
+            .parse()
+            .map_err(|_| "integer is too large".to_string())?
+    };
+    Ok(if parts.negative {
+        -magnitude
+    } else {
+        magnitude
+    })
+}
+
+fn to_float(value: &Value, target: &CanonicalType, single: bool) -> Result<Value, ConversionError> {
+    let f = match value {
+        Value::Bool(b) => {
+            if *b {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        Value::Number(n) => n
+            .as_f64()
+            .ok_or_else(|| ConversionError::new(value, target, "not representable as f64"))?,
+        Value::String(s) => s
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ConversionError::new(value, target, "not a number"))?,
+        _ => {
+            return Err(ConversionError::new(
+                value,
+                target,
+                "structured value cannot be a number",
+            ))
+        }
+    };
+
+    if !f.is_finite() {
+        return Err(ConversionError::new(
+            value,
+            target,
+            "NaN and infinity are not supported",
+        ));
+    }
+    if single && f.abs() > f32::MAX as f64 {
+        return Err(ConversionError::new(value, target, "out of range for REAL"));
+    }
+    serde_json::Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| ConversionError::new(value, target, "not representable as a number"))
+}
+
+fn decimal_source_text(value: &Value, target: &CanonicalType) -> Result<String, ConversionError> {
+    match value {
+        // serde_json prints the shortest text that round-trips, so an f64
+        // source contributes no extra digits of its own.
+        Value::Number(n) => Ok(n.to_string()),
+        Value::String(s) => Ok(s.clone()),
+        Value::Bool(b) => Ok(if *b { "1" } else { "0" }.to_string()),
+        _ => Err(ConversionError::new(
+            value,
+            target,
+            "structured value cannot be a decimal",
+        )),
+    }
+}
+
+/// Sign, integer digits (no leading zeros) and fractional digits of a
+/// decimal literal.
+struct DecimalParts {
+    negative: bool,
+    int: String,
+    frac: String,
+}
+
+/// Parse a decimal literal such as `-0012.3400` or `1.5e3` into its digits.
+fn parse_decimal(text: &str) -> Result<DecimalParts, String> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err("empty string is not a number".to_string());
+    }
+    let lower = trimmed.to_lowercase();
+    if matches!(
+        lower.as_str(),
+        "nan" | "inf" | "-inf" | "infinity" | "-infinity"
+    ) {
+        return Err("NaN and infinity are not supported".to_string());
+    }
+
+    let (negative, unsigned) = match trimmed.as_bytes()[0] {
+        b'-' => (true, &trimmed[1..]),
+        b'+' => (false, &trimmed[1..]),
+        _ => (false, trimmed),
+    };
+
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(pos) => {
+            let exp: i32 = unsigned[pos + 1..]
+                .parse()
+                .map_err(|_| format!("invalid exponent in '{}'", trimmed))?;
+            (&unsigned[..pos], exp)
+        }
+        None => (unsigned, 0),
+    };
+
+    let (int_part, frac_part) = match mantissa.find('.') {
+        Some(pos) => (&mantissa[..pos], &mantissa[pos + 1..]),
+        None => (mantissa, ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(format!("'{}' is not a number", trimmed));
+    }
+    if !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(format!("'{}' is not a number", trimmed));
+    }
+    if exponent.unsigned_abs() > 4096 {
+        return Err("exponent is out of range".to_string());
+    }
+
+    // Shift the decimal point by the exponent.
+    let mut digits = format!("{}{}", int_part, frac_part);
+    let mut point = int_part.len() as i64 + exponent as i64;
+    if point < 0 {
+        digits = format!("{}{}", "0".repeat((-point) as usize), digits);
+        point = 0;
+    }
+    if point as usize > digits.len() {
+        digits.push_str(&"0".repeat(point as usize - digits.len()));
+    }
+    let (int_digits, frac_digits) = digits.split_at(point as usize);
+
+    Ok(DecimalParts {
+        negative,
+        int: int_digits.trim_start_matches('0').to_string(),
+        frac: frac_digits.to_string(),
+    })
+}
+
+/// Render decimal text with exactly `scale` fractional digits, rounding
+/// half away from zero like SQL Server, PostgreSQL and MySQL do on insert.
+///
+/// A `precision` of 0 means unconstrained (e.g. a bare PostgreSQL `NUMERIC`).
+fn normalize_decimal(text: &str, precision: u8, scale: u8) -> Result<String, String> {
+    let parts = parse_decimal(text)?;
+    let unconstrained = precision == 0;
+    let scale = if unconstrained {
+        parts.frac.trim_end_matches('0').len()
+    } else {
+        scale as usize
+    };
+
+    let mut digits: Vec<u8> = parts.int.bytes().map(|b| b - b'0').collect();
+    let int_len = digits.len();
+    let mut frac: Vec<u8> = parts.frac.bytes().map(|b| b - b'0').collect();
+    let round_up = frac.len() > scale && frac[scale] >= 5;
+    frac.resize(scale, 0);
+    digits.extend(frac);
+
+    let mut int_len = int_len;
+    if round_up {
+        let mut i = digits.len();
+        loop {
+            if i == 0 {
+                digits.insert(0, 1);
+                int_len += 1;
+                break;
+            }
+            i -= 1;
+            if digits[i] == 9 {
+                digits[i] = 0;
+            } else {
+                digits[i] += 1;
+                break;
+            }
+        }
+    }
+
+    if !unconstrained && int_len > (precision as usize).saturating_sub(scale) {
+        return Err(format!(
+            "{} integer digits exceed DECIMAL({},{})",
+            int_len, precision, scale
+        ));
+    }
+
+    let int_str: String = if int_len == 0 {
+        "0".to_string()
+    } else {
+        digits[..int_len]
+            .iter()
+            .map(|d| (d + b'0') as char)
+            .collect()
+    };
+    let frac_str: String = digits[int_len..]
+        .iter()
+        .map(|d| (d + b'0') as char)
+        .collect();
+    let is_zero = digits.iter().all(|d| *d == 0);
+
+    let mut out = String::new();
+    if parts.negative && !is_zero {
+        out.push('-');
+    }
+    out.push_str(&int_str);
+    if !frac_str.is_empty() {
+        out.push('.');
+        out.push_str(&frac_str);
+    }
+    Ok(out)
+}
+
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        other => other.to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Binary, UUID, JSON and arrays
+// ---------------------------------------------------------------------------
+
+/// Decode bytes from `0x`/`\x` prefixed or bare hex text, or a JSON array
+/// of byte values.
+fn to_bytes(value: &Value, target: &CanonicalType) -> Result<Vec<u8>, ConversionError> {
+    match value {
+        Value::String(s) => {
+            let t = s.trim();
+            let hex_part = t
+                .strip_prefix("0x")
+                .or_else(|| t.strip_prefix("0X"))
+                .or_else(|| t.strip_prefix("\\x"))
+                .unwrap_or(t);
+            hex::decode(hex_part)
+                .map_err(|_| ConversionError::new(value, target, "expected hex-encoded bytes"))
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_u64()
+                    .filter(|b| *b <= 255)
+                    .map(|b| b as u8)
+                    .ok_or_else(|| {
+                        ConversionError::new(value, target, "array elements must be bytes (0-255)")
+                    })
+            })
+            .collect(),
+        _ => Err(ConversionError::new(value, target, "not a binary value")),
+    }
+}
+
+fn to_uuid(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        _ => return Err(ConversionError::new(value, target, "UUIDs must be strings")),
+    };
+    let hex_form = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("\\x"))
+        .unwrap_or(&text);
+    uuid::Uuid::parse_str(hex_form)
+        .map(typed_value::uuid)
+        .map_err(|e| ConversionError::new(value, target, e.to_string()))
+}
+
+/// Parse JSON text. Text that does not open an object or array and does
+/// not parse is a JSON string scalar, such as a jsonb `"hello"`.
+fn to_json(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    match value {
+        Value::String(s) => match serde_json::from_str::<Value>(s) {
+            Ok(parsed) => Ok(parsed),
+            Err(_) if !s.trim_start().starts_with(['{', '[']) => Ok(value.clone()),
+            Err(e) => Err(ConversionError::new(
+                value,
+                target,
+                format!("invalid JSON: {}", e),
+            )),
+        },
+        other => Ok(other.clone()),
+    }
+}
+
+/// Accept a JSON array, JSON array text or a PostgreSQL `{a,b}` literal
+/// and convert each element to `inner`.
+fn to_array(
+    value: &Value,
+    target: &CanonicalType,
+    inner: &CanonicalType,
+) -> Result<Value, ConversionError> {
+    let items: Vec<Value> = match value {
+        Value::Array(items) => items.clone(),
+        Value::String(s) => {
+            let t = s.trim();
+            if t.starts_with('[') {
+                match serde_json::from_str::<Value>(t) {
+                    Ok(Value::Array(items)) => items,
+                    _ => return Err(ConversionError::new(value, target, "invalid JSON array")),
+                }
+            } else if let Some(body) = t.strip_prefix('{').and_then(|b| b.strip_suffix('}')) {
+                parse_pg_array_literal(body)
+            } else {
+                return Err(ConversionError::new(value, target, "not an array"));
+            }
+        }
+        _ => return Err(ConversionError::new(value, target, "not an array")),
+    };
+
+    items
+        .iter()
+        .map(|item| convert_value(item, inner))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+/// Split the body of a one-dimensional PostgreSQL array literal.
+fn parse_pg_array_literal(body: &str) -> Vec<Value> {
+    if body.trim().is_empty() {
+        return Vec::new();
+    }
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            '\\' if quoted => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ',' if !quoted => {
+                items.push(pg_array_element(&current, was_quoted));
+                current.clear();
+                was_quoted = false;
+            }
+            _ => current.push(c),
+        }
+    }
+    items.push(pg_array_element(&current, was_quoted));
+    items
+}
+
+fn pg_array_element(raw: &str, was_quoted: bool) -> Value {
+    if !was_quoted && raw.trim().eq_ignore_ascii_case("null") {
+        Value::Null
+    } else if was_quoted {
+        Value::String(raw.to_string())
+    } else {
+        Value::String(raw.trim().to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Temporal conversions
+// ---------------------------------------------------------------------------
+
+/// A parsed temporal value, keeping whatever precision the source had.
+enum Temporal {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Naive(NaiveDateTime),
+    Zoned(DateTime<FixedOffset>),
+}
+
+const ZONED_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f %:z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%.f%#z",
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+];
+
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S%.f",
+];
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d-%b-%Y", "%d-%b-%y"];
+
+const TIME_FORMATS: &[&str] = &["%H:%M:%S%.f", "%H:%M"];
+
+fn parse_temporal(text: &str) -> Option<Temporal> {
+    let t = text.trim();
+
+    // Normalise a trailing " UTC" / "Z" suffix to an explicit offset.
+    let owned;
+    let t = if let Some(stripped) = t.strip_suffix(" UTC") {
+        owned = format!("{}+00:00", stripped);
+        owned.as_str()
+    } else {
+        t
+    };
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(t) {
+        return Some(Temporal::Zoned(dt));
+    }
+    for fmt in ZONED_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(t, fmt) {
+            return Some(Temporal::Zoned(dt));
+        }
+    }
+    for fmt in NAIVE_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(t, fmt) {
+            return Some(Temporal::Naive(dt));
+        }
+    }
+    for fmt in DATE_FORMATS {
+        if let Ok(d) = NaiveDate::parse_from_str(t, fmt) {
+            return Some(Temporal::Date(d));
+        }
+    }
+    for fmt in TIME_FORMATS {
+        if let Ok(tm) = NaiveTime::parse_from_str(t, fmt) {
+            return Some(Temporal::Time(tm));
+        }
+    }
+    None
+}
+
+fn temporal_from_value(value: &Value, target: &CanonicalType) -> Result<Temporal, ConversionError> {
+    match value {
+        Value::String(s) => parse_temporal(s)
+            .ok_or_else(|| ConversionError::new(value, target, "unrecognised date/time format")),
+        _ => Err(ConversionError::new(
+            value,
+            target,
+            "date/time values must be strings",
+        )),
+    }
+}
+
+fn is_midnight(t: &NaiveTime) -> bool {
+    t.num_seconds_from_midnight() == 0 && t.nanosecond() == 0
+}
+
+fn to_date(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    let date = match temporal_from_value(value, target)? {
+        Temporal::Date(d) => d,
+        Temporal::Naive(dt) if is_midnight(&dt.time()) => dt.date(),
+        Temporal::Zoned(dt) if is_midnight(&dt.time()) => dt.date_naive(),
+        Temporal::Naive(_) | Temporal::Zoned(_) => {
+            return Err(ConversionError::new(
+                value,
+                target,
+                "time component would be lost",
+            ))
+        }
+        Temporal::Time(_) => return Err(ConversionError::new(value, target, "a time has no date")),
+    };
+    Ok(typed_value::date(date))
+}
+
+fn to_time(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    match temporal_from_value(value, target)? {
+        Temporal::Time(t) => Ok(typed_value::time(t)),
+        _ => Err(ConversionError::new(
+            value,
+            target,
+            "date component would be lost",
+        )),
+    }
+}
+
+fn to_datetime(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    let naive = match temporal_from_value(value, target)? {
+        Temporal::Date(d) => d.and_time(NaiveTime::MIN),
+        Temporal::Naive(dt) => dt,
+        Temporal::Zoned(dt) => dt.naive_utc(),
+        Temporal::Time(_) => return Err(ConversionError::new(value, target, "a time has no date")),
+    };
+    Ok(typed_value::datetime(naive))
+}
+
+/// Naive inputs are assumed to be UTC.
+fn to_timestamp(value: &Value, target: &CanonicalType) -> Result<Value, ConversionError> {
+    let utc = FixedOffset::east_opt(0).expect("zero offset is valid");
+    let zoned = match temporal_from_value(value, target)? {
+        Temporal::Date(d) => d.and_time(NaiveTime::MIN).and_utc().with_timezone(&utc),
+        Temporal::Naive(dt) => dt.and_utc().with_timezone(&utc),
+        Temporal::Zoned(dt) => dt,
+        Temporal::Time(_) => return Err(ConversionError::new(value, target, "a time has no date")),
+    };
+    Ok(typed_value::timestamp(zoned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn col(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            is_primary_key: false,
+            max_length: None,
+            precision: None,
+            scale: None,
+            default_value: None,
+            ordinal_position: 1,
+        }
+    }
+
+    fn dec(precision: u8, scale: u8) -> CanonicalType {
+        CanonicalType::Decimal { precision, scale }
+    }
+
+    #[test]
+    fn test_null_passes_through_every_type() {
+        for t in [
+            CanonicalType::Int,
+            CanonicalType::Uuid,
+            CanonicalType::Date,
+            dec(10, 2),
+        ] {
+            assert_eq!(convert_value(&Value::Null, &t).unwrap(), Value::Null);
+        }
+    }
+
+    #[test]
+    fn test_boolean_from_strings_and_numbers() {
+        let t = CanonicalType::Boolean;
+        assert_eq!(convert_value(&json!("Yes"), &t).unwrap(), json!(true));
+        assert_eq!(convert_value(&json!("f"), &t).unwrap(), json!(false));
+        assert_eq!(convert_value(&json!(1), &t).unwrap(), json!(true));
+        assert_eq!(convert_value(&json!(0), &t).unwrap(), json!(false));
+        assert!(convert_value(&json!(2), &t).is_err());
+        assert!(convert_value(&json!("maybe"), &t).is_err());
+    }
+
+    #[test]
+    fn test_integer_from_string_and_whole_float() {
+        assert_eq!(
+            convert_value(&json!("42"), &CanonicalType::Int).unwrap(),
+            json!(42)
+        );
+        assert_eq!(
+            convert_value(&json!(7.0), &CanonicalType::Int).unwrap(),
+            json!(7)
+        );
+        assert_eq!(
+            convert_value(&json!("12.000"), &CanonicalType::BigInt).unwrap(),
+            json!(12)
+        );
+        assert_eq!(
+            convert_value(&json!(true), &CanonicalType::TinyInt).unwrap(),
+            json!(1)
+        );
+    }
+
+    #[test]
+    fn test_integer_rejects_fraction_and_overflow() {
+        assert!(convert_value(&json!("12.5"), &CanonicalType::Int).is_err());
+        assert!(convert_value(&json!(40000), &CanonicalType::SmallInt).is_err());
+        assert!(convert_value(&json!(300), &CanonicalType::TinyInt).is_err());
+        assert!(convert_value(&json!(200), &CanonicalType::TinyInt).is_err());
+        assert!(convert_value(&json!(-1), &CanonicalType::UnsignedTinyInt).is_err());
+        assert_eq!(
+            convert_value(&json!(-128), &CanonicalType::TinyInt).unwrap(),
+            json!(-128)
+        );
+        assert_eq!(
+            convert_value(&json!(255), &CanonicalType::UnsignedTinyInt).unwrap(),
+            json!(255)
+        );
+        let err = convert_value(&json!("3000000000"), &CanonicalType::Int).unwrap_err();
+        assert!(err.reason.contains("out of range"));
+    }
+
+    #[test]
+    fn test_float_from_string() {
+        assert_eq!(
+            convert_value(&json!("1.5"), &CanonicalType::Double).unwrap(),
+            json!(1.5)
+        );
+        assert!(convert_value(&json!("abc"), &CanonicalType::Double).is_err());
+        assert!(convert_value(&json!("NaN"), &CanonicalType::Double).is_err());
+        assert!(convert_value(&json!(1e300), &CanonicalType::Float).is_err());
+    }
+
+    #[test]
+    fn test_decimal_keeps_digits_beyond_f64() {
+        let v = convert_value(&json!("12345678901234567890.123456789"), &dec(38, 9)).unwrap();
+        assert_eq!(v, json!({"$decimal": "12345678901234567890.123456789"}));
+    }
+
+    #[test]
+    fn test_decimal_pads_and_rounds_to_scale() {
+        assert_eq!(
+            convert_value(&json!("1.5"), &dec(10, 2)).unwrap(),
+            json!({"$decimal": "1.50"})
+        );
+        assert_eq!(
+            convert_value(&json!("1.005"), &dec(10, 2)).unwrap(),
+            json!({"$decimal": "1.01"})
+        );
+        assert_eq!(
+            convert_value(&json!("-1.005"), &dec(10, 2)).unwrap(),
+            json!({"$decimal": "-1.01"})
+        );
+        assert_eq!(
+            convert_value(&json!("9.999"), &dec(10, 2)).unwrap(),
+            json!({"$decimal": "10.00"})
+        );
+        assert_eq!(
+            convert_value(&json!(42), &dec(10, 0)).unwrap(),
+            json!({"$decimal": "42"})
+        );
+    }
+
+    #[test]
+    fn test_decimal_from_float_number_uses_shortest_text() {
+        assert_eq!(
+            convert_value(&json!(0.1), &dec(10, 4)).unwrap(),
+            json!({"$decimal": "0.1000"})
+        );
+    }
+
+    #[test]
+    fn test_decimal_exponent_notation() {
+        assert_eq!(
+            convert_value(&json!("1.5e3"), &dec(10, 1)).unwrap(),
+            json!({"$decimal": "1500.0"})
+        );
+        assert_eq!(
+            convert_value(&json!("2E-3"), &dec(10, 3)).unwrap(),
+            json!({"$decimal": "0.002"})
+        );
+    }
+
+    #[test]
+    fn test_decimal_overflow_is_error() {
+        let err = convert_value(&json!("123456.7"), &dec(5, 2)).unwrap_err();
+        assert!(err.reason.contains("DECIMAL(5,2)"));
+        assert!(convert_value(&json!("99.999"), &dec(4, 2)).is_err());
+    }
+
+    #[test]
+    fn test_decimal_unconstrained_keeps_scale() {
+        assert_eq!(
+            convert_value(&json!("007.2500"), &dec(0, 0)).unwrap(),
+            json!({"$decimal": "7.25"})
+        );
+    }
+
+    #[test]
+    fn test_decimal_negative_zero_drops_sign() {
+        assert_eq!(
+            convert_value(&json!("-0.001"), &dec(5, 2)).unwrap(),
+            json!({"$decimal": "0.00"})
+        );
+    }
+
+    #[test]
+    fn test_decimal_rejects_garbage() {
+        assert!(convert_value(&json!("12a"), &dec(10, 2)).is_err());
+        assert!(convert_value(&json!(""), &dec(10, 2)).is_err());
+        assert!(convert_value(&json!({"a": 1}), &dec(10, 2)).is_err());
+    }
+
+    #[test]
+    fn test_text_from_scalars_and_objects() {
+        assert_eq!(
+            convert_value(&json!(12), &CanonicalType::Text).unwrap(),
+            json!("12")
+        );
+        assert_eq!(
+            convert_value(&json!(true), &CanonicalType::Varchar(5)).unwrap(),
+            json!("true")
+        );
+        assert_eq!(
+            convert_value(&json!({"a": 1}), &CanonicalType::NText).unwrap(),
+            json!("{\"a\":1}")
+        );
+    }
+
+    #[test]
+    fn test_binary_accepts_prefixed_hex() {
+        let t = CanonicalType::Blob;
+        assert_eq!(
+            convert_value(&json!("0xDEADBEEF"), &t).unwrap(),
+            json!({"$binary": "deadbeef"})
+        );
+        assert_eq!(
+            convert_value(&json!("\\x0102"), &t).unwrap(),
+            json!({"$binary": "0102"})
+        );
+        assert_eq!(
+            convert_value(&json!([1, 255]), &t).unwrap(),
+            json!({"$binary": "01ff"})
+        );
+        assert!(convert_value(&json!("xyz"), &t).is_err());
+    }
+
+    #[test]
+    fn test_binary_length_is_enforced() {
+        assert!(convert_value(&json!("010203"), &CanonicalType::Varbinary(2)).is_err());
+        assert!(convert_value(&json!("0102"), &CanonicalType::Binary(2)).is_ok());
+    }
+
+    #[test]
+    fn test_uuid_normalised() {
+        let t = CanonicalType::Uuid;
+        assert_eq!(
+            convert_value(&json!("{A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A11}"), &t).unwrap(),
+            json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"})
+        );
+        assert_eq!(
+            convert_value(&json!("a0eebc999c0b4ef8bb6d6bb9bd380a11"), &t).unwrap(),
+            json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"})
+        );
+        assert!(convert_value(&json!("not-a-uuid"), &t).is_err());
+        assert!(convert_value(&json!(5), &t).is_err());
+    }
+
+    #[test]
+    fn test_json_parses_text() {
+        let t = CanonicalType::Json;
+        assert_eq!(
+            convert_value(&json!("{\"a\":[1,2]}"), &t).unwrap(),
+            json!({"a": [1, 2]})
+        );
+        assert_eq!(
+            convert_value(&json!({"k": "v"}), &t).unwrap(),
+            json!({"k": "v"})
+        );
+        assert!(convert_value(&json!("{broken"), &t).is_err());
+        assert_eq!(convert_value(&json!("hello"), &t).unwrap(), json!("hello"));
+    }
+
+    #[test]
+    fn test_array_from_pg_literal() {
+        let t = CanonicalType::Array(Box::new(CanonicalType::Int));
+        assert_eq!(
+            convert_value(&json!("{1,2,3}"), &t).unwrap(),
+            json!([1, 2, 3])
+        );
+        assert_eq!(convert_value(&json!("[4, 5]"), &t).unwrap(), json!([4, 5]));
+        let texts = CanonicalType::Array(Box::new(CanonicalType::Text));
+        assert_eq!(
+            convert_value(&json!("{\"a,b\",NULL,c}"), &texts).unwrap(),
+            json!(["a,b", null, "c"])
+        );
+    }
+
+    #[test]
+    fn test_date_from_various_formats() {
+        let t = CanonicalType::Date;
+        assert_eq!(
+            convert_value(&json!("2024-03-05"), &t).unwrap(),
+            json!({"$date": "2024-03-05"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024/03/05"), &t).unwrap(),
+            json!({"$date": "2024-03-05"})
+        );
+        assert_eq!(
+            convert_value(&json!("05-MAR-2024"), &t).unwrap(),
+            json!({"$date": "2024-03-05"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024-03-05T00:00:00Z"), &t).unwrap(),
+            json!({"$date": "2024-03-05"})
+        );
+    }
+
+    #[test]
+    fn test_date_rejects_time_component() {
+        let err = convert_value(&json!("2024-03-05 10:30:00"), &CanonicalType::Date).unwrap_err();
+        assert!(err.reason.contains("time component"));
+    }
+
+    #[test]
+    fn test_time_formats() {
+        let t = CanonicalType::Time;
+        assert_eq!(
+            convert_value(&json!("10:30"), &t).unwrap(),
+            json!({"$time": "10:30:00"})
+        );
+        assert_eq!(
+            convert_value(&json!("10:30:15.250"), &t).unwrap(),
+            json!({"$time": "10:30:15.250"})
+        );
+        assert!(convert_value(&json!("2024-03-05 10:30:00"), &t).is_err());
+    }
+
+    #[test]
+    fn test_datetime_converts_offsets_to_utc() {
+        let t = CanonicalType::DateTime;
+        assert_eq!(
+            convert_value(&json!("2024-03-05T10:30:00+02:00"), &t).unwrap(),
+            json!({"$datetime": "2024-03-05T08:30:00"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024-03-05 10:30:00.123456"), &t).unwrap(),
+            json!({"$datetime": "2024-03-05T10:30:00.123456"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024-03-05"), &t).unwrap(),
+            json!({"$datetime": "2024-03-05T00:00:00"})
+        );
+    }
+
+    #[test]
+    fn test_timestamp_keeps_offset_and_assumes_utc() {
+        let t = CanonicalType::Timestamp;
+        assert_eq!(
+            convert_value(&json!("2024-03-05 10:30:00 -0500"), &t).unwrap(),
+            json!({"$timestamp": "2024-03-05T10:30:00-05:00"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024-03-05 10:30:00+05"), &t).unwrap(),
+            json!({"$timestamp": "2024-03-05T10:30:00+05:00"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024-03-05 10:30:00"), &t).unwrap(),
+            json!({"$timestamp": "2024-03-05T10:30:00+00:00"})
+        );
+        assert_eq!(
+            convert_value(&json!("2024-03-05 10:30:00 UTC"), &t).unwrap(),
+            json!({"$timestamp": "2024-03-05T10:30:00+00:00"})
+        );
+    }
+
+    #[test]
+    fn test_temporal_rejects_numbers_and_garbage() {
+        assert!(convert_value(&json!(1700000000), &CanonicalType::DateTime).is_err());
+        assert!(convert_value(&json!("yesterday"), &CanonicalType::Date).is_err());
+    }
+
+    #[test]
+    fn test_unknown_passes_through() {
+        let v = json!({"type": "Point"});
+        assert_eq!(
+            convert_value(&v, &CanonicalType::Unknown("geometry".into())).unwrap(),
+            v
+        );
+        let tagged = json!({"$binary": "00ff"});
+        assert_eq!(
+            convert_value(&tagged, &CanonicalType::Unknown("sql_variant".into())).unwrap(),
+            tagged
+        );
+    }
+
+    #[test]
+    fn test_convert_row_reports_all_failures() {
+        let mut types = HashMap::new();
+        types.insert("a".to_string(), CanonicalType::Int);
+        types.insert("b".to_string(), CanonicalType::Uuid);
+        let row: Row = [
+            ("a".to_string(), json!("x")),
+            ("b".to_string(), json!("y")),
+            ("c".to_string(), json!("untouched")),
+        ]
+        .into_iter()
+        .collect();
+        let errors = convert_row(&row, &types).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].column.as_deref(), Some("a"));
+        assert_eq!(errors[1].column.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_convert_row_success() {
+        let mut types = HashMap::new();
+        types.insert("price".to_string(), dec(10, 2));
+        let row: Row = [
+            ("price".to_string(), json!(19.9)),
+            ("name".to_string(), json!("Widget")),
+        ]
+        .into_iter()
+        .collect();
+        let out = convert_row(&row, &types).unwrap();
+        assert_eq!(out["price"], json!({"$decimal": "19.90"}));
+        assert_eq!(out["name"], json!("Widget"));
+    }
+
+    #[test]
+    fn test_column_type_uses_precision_and_scale() {
+        let mut c = col("amount", "numeric");
+        c.precision = Some(12);
+        c.scale = Some(4);
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::PostgreSql, &c),
+            dec(12, 4)
+        );
+    }
+
+    #[test]
+    fn test_column_type_uses_max_length() {
+        let mut c = col("data", "varbinary");
+        c.max_length = Some(-1);
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::SqlServer, &c),
+            CanonicalType::Blob
+        );
+        c.max_length = Some(16);
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::SqlServer, &c),
+            CanonicalType::Varbinary(16)
+        );
+    }
+
+    #[test]
+    fn test_column_type_sqlite_declared_names() {
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::Sqlite, &col("d", "DATETIME")),
+            CanonicalType::DateTime
+        );
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::Sqlite, &col("p", "DECIMAL(10,2)")),
+            dec(10, 2)
+        );
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::Sqlite, &col("x", "")),
+            CanonicalType::Unknown(String::new())
+        );
+        assert_eq!(
+            column_canonical_type(&DatabaseEngine::Sqlite, &col("n", "INTEGER")),
+            CanonicalType::BigInt
+        );
+    }
+
+    #[test]
+    fn test_tagged_input_is_unwrapped() {
+        let v = typed_value::decimal("12.345");
+        assert_eq!(
+            convert_value(&v, &dec(10, 2)).unwrap(),
+            json!({"$decimal": "12.35"})
+        );
+        assert_eq!(
+            convert_value(&v, &CanonicalType::Varchar(20)).unwrap(),
+            json!("12.345")
+        );
+        let ts = json!({"$timestamp": "2024-03-05T10:30:00+02:00"});
+        assert_eq!(
+            convert_value(&ts, &CanonicalType::DateTime).unwrap(),
+            json!({"$datetime": "2024-03-05T08:30:00"})
+        );
+    }
+}
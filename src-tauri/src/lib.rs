@@ -76,12 +76,26 @@ pub fn run() {
             commands::connection::test_connection,
             commands::connection::connect_database,
             commands::connection::disconnect_database,
+            commands::connection::connection_pool_status,
             commands::schema::get_tables,
             commands::schema::get_table_info,
             commands::schema::get_row_count,
+            commands::schema::type_compatibility_report,
+            commands::schema::export_type_compatibility_report,
+            commands::schema::plan_document_flattening,
+            commands::schema::compare_schemas,
+            commands::schema::confirm_schema_rename,
+            commands::schema::reject_schema_rename,
             commands::migration::dry_run,
             commands::migration::execute_migration,
+            commands::migration::resume_migration,
             commands::migration::cancel_migration,
+            commands::migration::restore_backup,
+            commands::migration::undo_migration,
+            commands::cdc::start_cdc_sync,
+            commands::cdc::stop_cdc_sync,
+            commands::cdc::get_cdc_positions,
+            commands::cdc::reset_cdc_position,
             commands::chat::check_ollama_status,
             commands::chat::list_ollama_models,
             commands::chat::send_chat_message,
@@ -96,6 +110,9 @@ pub fn run() {
             commands::appdb::set_setting,
             commands::appdb::get_all_settings,
             commands::appdb::get_migration_history,
+            commands::appdb::get_migration_backups,
+            commands::appdb::get_sync_watermarks,
+            commands::appdb::reset_sync_watermarks,
             commands::appdb::save_chat_message,
             commands::appdb::load_chat_messages,
             commands::appdb::clear_chat_messages,
@@ -158,6 +158,9 @@ pub fn build_connection_config(
         file_path: profile.file_path.clone(),
         read_only: profile.read_only,
         connection_timeout_secs: 30,
+        pool: Default::default(),
+        schema_sample_size: None,
+        schema_filter: Default::default(),
     })
 }
 
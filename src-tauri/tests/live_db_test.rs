@@ -3,15 +3,27 @@
 //! Prerequisites:
 //!   - SQL Server Express on localhost:1433 with sa/YourPassword123, database UpsertTestSource seeded
 //!   - PostgreSQL on localhost:5432 with user postgres, database upsert_test_target seeded
+//!   - MySQL from docker/docker-compose.yml (typed round-trip tests only)
+//!   - Oracle (`gvenzl/oracle-free`) from docker/docker-compose.yml, plus Oracle Instant Client;
+//!     the Oracle tests only build with `--features oracle`
+//!   - Linux Cosmos DB emulator from docker/docker-compose.yml on https://localhost:8081
+//!   - MongoDB from docker/docker-compose.yml: the standalone `mongodb` service and the
+//!     single-node replica set `mongodb-rs` on localhost:27018 (transaction tests)
 //!
 //! These tests are `#[ignore]`d by default. Run them explicitly:
 //!   cargo test --test live_db_test -- --ignored
 
+#[cfg(feature = "oracle")]
+use upsert_lib::db::connectors::oracle::OracleConnector;
 use upsert_lib::db::connectors::{
-    postgres::PostgresConnector, sqlserver::SqlServerConnector, ConnectionConfig, DatabaseConnector,
-    DatabaseEngine,
+    cosmosdb::CosmosDbConnector, mongodb_connector::MongoDbConnector, mysql::MySqlConnector,
+    postgres::PostgresConnector, sqlserver::SqlServerConnector, ConnectionConfig,
+    DatabaseConnector, DatabaseEngine,
 };
-use upsert_lib::db::schema::ConstraintType;
+use upsert_lib::db::document::{DocumentQuery, DocumentWrite, DocumentWriteOptions};
+use upsert_lib::db::schema::{ConstraintType, Row};
+use upsert_lib::db::sql_generator::SqlGenerator;
+use upsert_lib::db::typed_value;
 
 // ─── helpers ───────────────────────────────────────────────────────────────
 
@@ -39,6 +51,104 @@ fn postgres_config() -> ConnectionConfig {
     }
 }
 
+fn mysql_config() -> ConnectionConfig {
+    ConnectionConfig {
+        engine: DatabaseEngine::MySql,
+        host: Some("localhost".to_string()),
+        port: Some(3306),
+        database: Some("upsert_test".to_string()),
+        username: Some("upsert".to_string()),
+        password: Some("upsert_test".to_string()),
+        read_only: false,
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "oracle")]
+fn oracle_config() -> ConnectionConfig {
+    ConnectionConfig {
+        engine: DatabaseEngine::Oracle,
+        host: Some("localhost".to_string()),
+        port: Some(1521),
+        database: Some("FREEPDB1".to_string()),
+        username: Some("upsert".to_string()),
+        password: Some("upsert_test".to_string()),
+        read_only: false,
+        ..Default::default()
+    }
+}
+
+fn mongo_standalone_config() -> ConnectionConfig {
+    ConnectionConfig {
+        engine: DatabaseEngine::MongoDb,
+        host: Some("localhost".to_string()),
+        port: Some(27017),
+        database: Some("upsert_test".to_string()),
+        username: Some("upsert".to_string()),
+        password: Some("upsert_test".to_string()),
+        read_only: false,
+        ..Default::default()
+    }
+}
+
+fn mongo_replica_set_config() -> ConnectionConfig {
+    ConnectionConfig {
+        engine: DatabaseEngine::MongoDb,
+        connection_string: Some("mongodb://localhost:27018/?directConnection=true".to_string()),
+        database: Some("upsert_test".to_string()),
+        read_only: false,
+        ..Default::default()
+    }
+}
+
+/// Well-known key every Cosmos DB emulator accepts.
+const COSMOS_EMULATOR_KEY: &str =
+    "C2y6yDjf5/R+ob0N8A7Cgv30VRDJIWEHLM+4QDU9DE2nQ9nDuVTqobD4b8mGGyPMbIZnqyMsEcaGQy67XIw/Jw==";
+
+fn cosmos_config() -> ConnectionConfig {
+    ConnectionConfig {
+        engine: DatabaseEngine::CosmosDb,
+        connection_string: Some(format!(
+            "AccountEndpoint=https://localhost:8081/;AccountKey={};",
+            COSMOS_EMULATOR_KEY
+        )),
+        database: Some("upsert_test".to_string()),
+        read_only: false,
+        ..Default::default()
+    }
+}
+
+/// Insert `row` into a scratch table through `SqlGenerator`, read it back
+/// and require every column to come back identical.
+async fn assert_typed_round_trip(
+    conn: &mut dyn DatabaseConnector,
+    engine: DatabaseEngine,
+    create_sql: &str,
+    drop_sql: &str,
+    row: Row,
+) {
+    conn.execute_query(drop_sql).await.ok();
+    conn.execute_query(create_sql).await.expect("create scratch table");
+
+    let sql = SqlGenerator::new(engine).generate_insert("upsert_typed_roundtrip", &row);
+    conn.execute_query(&sql).await.expect("insert typed row");
+
+    let rows = conn
+        .get_rows("upsert_typed_roundtrip", None, None)
+        .await
+        .expect("read typed row");
+    conn.execute_query(drop_sql).await.ok();
+
+    assert_eq!(rows.len(), 1);
+    for (col, expected) in &row {
+        assert_eq!(&rows[0][col], expected, "column '{}' changed on round trip", col);
+    }
+}
+
+fn typed_row(pairs: Vec<(&str, serde_json::Value)>) -> Row {
+    pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 //  SQL SERVER - CONNECTION TESTS
 // ═══════════════════════════════════════════════════════════════════════════
@@ -848,3 +958,582 @@ async fn postgres_unicode_data() {
 
     conn.disconnect().await.ok();
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  TYPED VALUE ROUND TRIPS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+#[ignore]
+async fn sqlserver_typed_round_trip() {
+    let mut conn = SqlServerConnector::new(ConnectionConfig {
+        read_only: false,
+        ..sqlserver_config()
+    });
+    conn.connect().await.expect("connect");
+
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let row = typed_row(vec![
+        ("id", serde_json::json!(1)),
+        ("amount", typed_value::decimal("12345678901234567890123456.123456789012")),
+        ("money_col", typed_value::decimal("-12345.6789")),
+        ("data", typed_value::bytes(&all_bytes)),
+        ("d", serde_json::json!({"$date": "2024-02-29"})),
+        ("t", serde_json::json!({"$time": "23:59:59.123456700"})),
+        ("dt", serde_json::json!({"$datetime": "2024-02-29T23:59:59.123456700"})),
+        ("ts", serde_json::json!({"$timestamp": "2024-02-29T23:59:59.123456700+05:30"})),
+        ("u", serde_json::json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"})),
+    ]);
+    assert_typed_round_trip(
+        &mut conn,
+        DatabaseEngine::SqlServer,
+        "CREATE TABLE upsert_typed_roundtrip (id INT PRIMARY KEY, amount DECIMAL(38,12), \
+         money_col MONEY, data VARBINARY(MAX), d DATE, t TIME(7), dt DATETIME2(7), \
+         ts DATETIMEOFFSET(7), u UNIQUEIDENTIFIER)",
+        "IF OBJECT_ID('upsert_typed_roundtrip') IS NOT NULL DROP TABLE upsert_typed_roundtrip",
+        row,
+    )
+    .await;
+
+    conn.disconnect().await.ok();
+}
+
+#[tokio::test]
+#[ignore]
+async fn postgres_typed_round_trip() {
+    let mut conn = PostgresConnector::new(ConnectionConfig {
+        read_only: false,
+        ..postgres_config()
+    });
+    conn.connect().await.expect("connect");
+
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let row = typed_row(vec![
+        ("id", serde_json::json!(1)),
+        ("amount", typed_value::decimal("123456789012345678901234567890.000000000001")),
+        ("small", typed_value::decimal("-0.00012")),
+        ("data", typed_value::bytes(&all_bytes)),
+        ("d", serde_json::json!({"$date": "2024-02-29"})),
+        ("t", serde_json::json!({"$time": "23:59:59.123456"})),
+        ("dt", serde_json::json!({"$datetime": "2024-02-29T23:59:59.123456"})),
+        // timestamptz is normalised to UTC by the server
+        ("ts", serde_json::json!({"$timestamp": "2024-02-29T18:29:59.123456+00:00"})),
+        ("u", serde_json::json!({"$uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"})),
+    ]);
+    assert_typed_round_trip(
+        &mut conn,
+        DatabaseEngine::PostgreSql,
+        "CREATE TABLE upsert_typed_roundtrip (id INT PRIMARY KEY, amount NUMERIC(42,12), \
+         small NUMERIC, data BYTEA, d DATE, t TIME, dt TIMESTAMP, ts TIMESTAMPTZ, u UUID)",
+        "DROP TABLE IF EXISTS upsert_typed_roundtrip",
+        row,
+    )
+    .await;
+
+    conn.disconnect().await.ok();
+}
+
+#[tokio::test]
+#[ignore]
+async fn mysql_typed_round_trip() {
+    let mut conn = MySqlConnector::new(mysql_config());
+    conn.connect().await.expect("connect");
+
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let row = typed_row(vec![
+        ("id", serde_json::json!(1)),
+        ("amount", typed_value::decimal("12345678901234567890123456789.123456789")),
+        ("data", typed_value::bytes(&all_bytes)),
+        ("d", serde_json::json!({"$date": "2024-02-29"})),
+        ("t", serde_json::json!({"$time": "23:59:59.123456"})),
+        ("dt", serde_json::json!({"$datetime": "2024-02-29T23:59:59.123456"})),
+    ]);
+    assert_typed_round_trip(
+        &mut conn,
+        DatabaseEngine::MySql,
+        "CREATE TABLE upsert_typed_roundtrip (id INT PRIMARY KEY, amount DECIMAL(38,9), \
+         data LONGBLOB, d DATE, t TIME(6), dt DATETIME(6))",
+        "DROP TABLE IF EXISTS upsert_typed_roundtrip",
+        row,
+    )
+    .await;
+
+    conn.disconnect().await.ok();
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  READ-ONLY CONNECTIONS
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn read_only_error(err: anyhow::Error) -> bool {
+    err.to_string().contains("read-only")
+}
+
+#[tokio::test]
+#[ignore]
+async fn postgres_read_only_session() {
+    let mut conn = PostgresConnector::new(postgres_config());
+    conn.connect().await.expect("connect");
+
+    let rows = conn
+        .execute_query("SHOW default_transaction_read_only")
+        .await
+        .unwrap();
+    assert_eq!(rows[0]["default_transaction_read_only"], serde_json::json!("on"));
+    assert!(read_only_error(
+        conn.execute_query("DELETE FROM customers").await.unwrap_err()
+    ));
+    // Reads still work
+    assert!(conn.get_row_count("customers").await.unwrap() > 0);
+
+    conn.disconnect().await.ok();
+}
+
+#[tokio::test]
+#[ignore]
+async fn mysql_read_only_session() {
+    let mut conn = MySqlConnector::new(ConnectionConfig {
+        read_only: true,
+        ..mysql_config()
+    });
+    conn.connect().await.expect("connect");
+
+    let rows = conn
+        .execute_query("SELECT @@session.transaction_read_only AS ro")
+        .await
+        .unwrap();
+    assert_eq!(rows[0]["ro"], serde_json::json!(1));
+    assert!(read_only_error(
+        conn.execute_query("CREATE TABLE upsert_ro_test (id INT)")
+            .await
+            .unwrap_err()
+    ));
+
+    conn.disconnect().await.ok();
+}
+
+#[tokio::test]
+#[ignore]
+async fn sqlserver_read_only_rejects_writes() {
+    let mut conn = SqlServerConnector::new(sqlserver_config());
+    conn.connect().await.expect("connect with ApplicationIntent=ReadOnly");
+
+    assert!(read_only_error(
+        conn.execute_query("UPDATE customers SET loyalty_points = 0")
+            .await
+            .unwrap_err()
+    ));
+    let rows = conn.execute_query("SELECT COUNT(*) AS n FROM customers").await.unwrap();
+    assert!(rows[0]["n"].as_i64().unwrap() > 0);
+
+    conn.disconnect().await.ok();
+}
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_read_only_rejects_writes() {
+    let mut conn = OracleConnector::new(ConnectionConfig {
+        read_only: true,
+        ..oracle_config()
+    });
+    conn.connect().await.expect("connect");
+
+    assert!(read_only_error(
+        conn.execute_query("DELETE FROM orders").await.unwrap_err()
+    ));
+    assert!(conn.get_row_count("ORDERS").await.unwrap() > 0);
+
+    conn.disconnect().await.ok();
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  ORACLE
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_connect_disconnect() {
+    let mut conn = OracleConnector::new(oracle_config());
+    assert!(!conn.is_connected().await);
+
+    conn.connect().await.expect("Oracle connect failed");
+    assert!(conn.is_connected().await);
+
+    conn.disconnect().await.expect("Oracle disconnect failed");
+    assert!(!conn.is_connected().await);
+}
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_connect_wrong_password() {
+    let mut cfg = oracle_config();
+    cfg.password = Some("WrongPassword999".to_string());
+    let mut conn = OracleConnector::new(cfg);
+
+    assert!(conn.connect().await.is_err(), "Should fail with wrong credentials");
+}
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_schema_introspection() {
+    let mut conn = OracleConnector::new(oracle_config());
+    conn.connect().await.expect("connect");
+
+    let tables = conn.get_tables().await.expect("get_tables");
+    assert!(tables.contains(&"CUSTOMERS".to_string()));
+    assert!(tables.contains(&"ORDERS".to_string()));
+
+    let customers = conn.get_table_info("CUSTOMERS").await.expect("customers");
+    assert_eq!(customers.schema_name, "UPSERT");
+    let id = customers.columns.iter().find(|c| c.name == "ID").unwrap();
+    assert!(id.is_primary_key);
+    assert!(!id.is_nullable);
+    let email = customers.columns.iter().find(|c| c.name == "EMAIL").unwrap();
+    assert_eq!(email.data_type, "VARCHAR2");
+    assert_eq!(email.max_length, Some(255));
+    let balance = customers.columns.iter().find(|c| c.name == "BALANCE").unwrap();
+    assert_eq!((balance.precision, balance.scale), (Some(18), Some(2)));
+
+    let orders = conn.get_table_info("ORDERS").await.expect("orders");
+    let fk = orders
+        .constraints
+        .iter()
+        .find(|c| c.constraint_type == ConstraintType::ForeignKey)
+        .expect("FK on orders");
+    assert_eq!(fk.columns, vec!["CUSTOMER_ID".to_string()]);
+    assert_eq!(fk.referenced_table.as_deref(), Some("CUSTOMERS"));
+    assert_eq!(fk.referenced_columns, Some(vec!["ID".to_string()]));
+    assert!(orders
+        .constraints
+        .iter()
+        .any(|c| c.constraint_type == ConstraintType::Check && c.name == "CHK_ORDERS_TOTAL"));
+    // NOT NULL columns must not show up as check constraints
+    assert_eq!(
+        orders
+            .constraints
+            .iter()
+            .filter(|c| c.constraint_type == ConstraintType::Check)
+            .count(),
+        1
+    );
+    let idx = orders
+        .indexes
+        .iter()
+        .find(|i| i.name == "IDX_ORDERS_CUSTOMER")
+        .expect("composite index");
+    assert_eq!(idx.columns, vec!["CUSTOMER_ID".to_string(), "ORDER_DATE".to_string()]);
+
+    conn.disconnect().await.ok();
+}
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_get_rows_pages() {
+    let mut conn = OracleConnector::new(oracle_config());
+    conn.connect().await.expect("connect");
+
+    let total = conn.get_row_count("CUSTOMERS").await.expect("count");
+    assert!(total >= 2);
+    let first = conn.get_rows("CUSTOMERS", Some(1), Some(0)).await.expect("page 1");
+    let second = conn.get_rows("CUSTOMERS", Some(1), Some(1)).await.expect("page 2");
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.len(), 1);
+    assert_ne!(first[0]["ID"], second[0]["ID"]);
+    assert_eq!(
+        conn.get_rows("CUSTOMERS", None, Some(total as u64))
+            .await
+            .expect("past end")
+            .len(),
+        0
+    );
+
+    conn.disconnect().await.ok();
+}
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_transaction_rollback_and_commit() {
+    let mut conn = OracleConnector::new(oracle_config());
+    conn.connect().await.expect("connect");
+    conn.execute_query("DROP TABLE upsert_tx_test").await.ok();
+    conn.execute_query("CREATE TABLE upsert_tx_test (id NUMBER(10) PRIMARY KEY)")
+        .await
+        .expect("create");
+
+    conn.begin_transaction().await.expect("begin");
+    conn.execute_query("INSERT INTO upsert_tx_test (id) VALUES (1);")
+        .await
+        .expect("insert");
+    conn.rollback_transaction().await.expect("rollback");
+    assert_eq!(conn.get_row_count("UPSERT_TX_TEST").await.unwrap(), 0);
+
+    conn.begin_transaction().await.expect("begin");
+    conn.execute_query("INSERT INTO upsert_tx_test (id) VALUES (2)")
+        .await
+        .expect("insert");
+    conn.commit_transaction().await.expect("commit");
+    assert_eq!(conn.get_row_count("UPSERT_TX_TEST").await.unwrap(), 1);
+
+    conn.execute_query("DROP TABLE upsert_tx_test").await.ok();
+    conn.disconnect().await.ok();
+}
+
+#[cfg(feature = "oracle")]
+#[tokio::test]
+#[ignore]
+async fn oracle_typed_round_trip() {
+    let mut conn = OracleConnector::new(oracle_config());
+    conn.connect().await.expect("connect");
+
+    let all_bytes: Vec<u8> = (0..=255).collect();
+    let row = typed_row(vec![
+        ("id", serde_json::json!(1)),
+        ("amount", typed_value::decimal("12345678901234567890123456.000000000001")),
+        ("small", typed_value::decimal("-0.00012")),
+        ("data", typed_value::bytes(&all_bytes)),
+        ("dt", serde_json::json!({"$datetime": "2024-02-29T23:59:59.123456"})),
+        ("ts", serde_json::json!({"$timestamp": "2024-02-29T23:59:59.123456+05:30"})),
+    ]);
+    assert_typed_round_trip(
+        &mut conn,
+        DatabaseEngine::Oracle,
+        "CREATE TABLE \"upsert_typed_roundtrip\" (\"id\" NUMBER(10) PRIMARY KEY, \
+         \"amount\" NUMBER(38,12), \"small\" NUMBER, \"data\" BLOB, \
+         \"dt\" TIMESTAMP(6), \"ts\" TIMESTAMP(6) WITH TIME ZONE)",
+        "DROP TABLE \"upsert_typed_roundtrip\"",
+        row,
+    )
+    .await;
+
+    conn.disconnect().await.ok();
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  COSMOS DB (emulator)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Connect and (re)create a scratch container partitioned on `/region`.
+async fn cosmos_scratch(container: &str) -> CosmosDbConnector {
+    let mut conn = CosmosDbConnector::new(cosmos_config());
+    conn.connect().await.expect("connect to emulator");
+    conn.ensure_container(container, "/region")
+        .await
+        .expect("create container");
+    conn
+}
+
+fn cosmos_doc(id: i64, region: &str) -> Row {
+    typed_row(vec![
+        ("id", serde_json::json!(id)),
+        ("region", serde_json::json!(region)),
+        ("name", serde_json::json!(format!("item {}", id))),
+        ("price", typed_value::decimal("19.99")),
+        ("tags", serde_json::json!(["a", "b"])),
+    ])
+}
+
+#[tokio::test]
+#[ignore]
+async fn cosmos_connect_disconnect() {
+    let mut conn = CosmosDbConnector::new(cosmos_config());
+    conn.connect().await.expect("connect");
+    assert!(conn.is_connected().await);
+    conn.disconnect().await.expect("disconnect");
+    assert!(!conn.is_connected().await);
+}
+
+#[tokio::test]
+#[ignore]
+async fn cosmos_connect_wrong_key() {
+    let mut cfg = cosmos_config();
+    cfg.connection_string = Some(
+        "AccountEndpoint=https://localhost:8081/;AccountKey=d3Jvbmcta2V5;".to_string(),
+    );
+    let mut conn = CosmosDbConnector::new(cfg);
+    assert!(conn.connect().await.is_err());
+}
+
+#[tokio::test]
+#[ignore]
+async fn cosmos_upsert_and_schema_inference() {
+    let conn = cosmos_scratch("upsert_schema").await;
+    let docs = vec![cosmos_doc(1, "eu"), cosmos_doc(2, "us")];
+    assert_eq!(conn.upsert_documents("upsert_schema", &docs).await.unwrap(), 2);
+    // Upserting the same ids again must not duplicate
+    conn.upsert_documents("upsert_schema", &docs).await.unwrap();
+
+    let tables = conn.get_tables().await.unwrap();
+    assert!(tables.contains(&"upsert_schema".to_string()));
+
+    let info = conn.get_table_info("upsert_schema").await.unwrap();
+    let col = |n: &str| info.columns.iter().find(|c| c.name == n).unwrap();
+    assert!(col("id").is_primary_key);
+    assert_eq!(col("region").data_type, "string");
+    assert_eq!(col("tags").data_type, "array");
+    assert!(info.columns.iter().all(|c| !c.name.starts_with('_')));
+    assert_eq!(info.indexes[0].index_type, "HASH");
+    assert_eq!(info.indexes[0].columns, vec!["region"]);
+    assert_eq!(conn.get_row_count("upsert_schema").await.unwrap(), 2);
+}
+
+#[tokio::test]
+#[ignore]
+async fn cosmos_get_rows_pages_with_continuations() {
+    let conn = cosmos_scratch("upsert_paging").await;
+    let docs: Vec<Row> = (1..=25)
+        .map(|i| cosmos_doc(i, if i % 2 == 0 { "eu" } else { "us" }))
+        .collect();
+    conn.upsert_documents("upsert_paging", &docs).await.unwrap();
+
+    let mut seen = std::collections::HashSet::new();
+    for page in 0..3 {
+        let rows = conn
+            .get_rows("upsert_paging", Some(10), Some(page * 10))
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), if page < 2 { 10 } else { 5 });
+        for row in rows {
+            assert!(!row.contains_key("_etag"));
+            assert!(seen.insert(row["id"].as_str().unwrap().to_string()));
+        }
+    }
+    assert_eq!(seen.len(), 25);
+    assert!(conn
+        .get_rows("upsert_paging", Some(10), Some(30))
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+#[ignore]
+async fn cosmos_query_and_delete_commands() {
+    let conn = cosmos_scratch("upsert_commands").await;
+    let upserted = conn
+        .execute_query(
+            r#"{"upsert": "upsert_commands", "documents": [
+                {"id": "q1", "region": "eu", "qty": 3},
+                {"id": "q2", "region": "us", "qty": 7}
+            ]}"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(upserted[0]["upserted"], serde_json::json!(2));
+
+    let rows = conn
+        .execute_query(
+            r#"{"container": "upsert_commands",
+                "query": "SELECT * FROM c WHERE c.qty > @min",
+                "parameters": [{"name": "@min", "value": 5}]}"#,
+        )
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["id"], serde_json::json!("q2"));
+
+    conn.execute_query(r#"{"delete": "upsert_commands", "id": "q2", "partitionKey": "us"}"#)
+        .await
+        .unwrap();
+    let rows = conn
+        .execute_query(r#"{"container": "upsert_commands", "query": "SELECT * FROM c"}"#)
+        .await
+        .unwrap();
+    assert!(rows.iter().all(|r| r["id"] != serde_json::json!("q2")));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+//  MONGODB - TRANSACTIONS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Replica set connection with `collection` emptied.
+async fn mongo_scratch(collection: &str) -> MongoDbConnector {
+    let mut conn = MongoDbConnector::new(mongo_replica_set_config());
+    conn.connect().await.expect("connect to the mongodb-rs replica set");
+    let clear = format!(
+        r#"{{"delete": "{}", "deletes": [{{"q": {{}}, "limit": 0}}]}}"#,
+        collection
+    );
+    conn.execute_query(&clear).await.unwrap();
+    conn
+}
+
+fn mongo_inserts(ids: &[i64]) -> Vec<DocumentWrite> {
+    ids.iter()
+        .map(|id| DocumentWrite::Insert {
+            document: typed_row(vec![
+                ("_id", serde_json::json!(id)),
+                ("qty", serde_json::json!(id * 10)),
+            ]),
+        })
+        .collect()
+}
+
+const MONGO_WRITE_OPTIONS: DocumentWriteOptions = DocumentWriteOptions {
+    batch_size: 100,
+    ordered: true,
+};
+
+#[tokio::test]
+#[ignore]
+async fn mongo_transaction_commit() {
+    let mut conn = mongo_scratch("upsert_tx_commit").await;
+    assert!(conn.supports_transactions().await.unwrap());
+    conn.begin_transaction().await.unwrap();
+    let result = conn
+        .write_documents("upsert_tx_commit", &mongo_inserts(&[1, 2]), &MONGO_WRITE_OPTIONS)
+        .await
+        .unwrap();
+    assert_eq!(result.inserted, 2);
+    // Reads inside the transaction see its writes; other sessions do not
+    let inside = conn
+        .find_documents("upsert_tx_commit", &DocumentQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(inside.len(), 2);
+    let mut other = MongoDbConnector::new(mongo_replica_set_config());
+    other.connect().await.unwrap();
+    assert!(other.get_rows("upsert_tx_commit", None, None).await.unwrap().is_empty());
+
+    conn.commit_transaction().await.unwrap();
+    let rows = other.get_rows("upsert_tx_commit", None, None).await.unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1]["qty"], serde_json::json!(20));
+}
+
+#[tokio::test]
+#[ignore]
+async fn mongo_transaction_rollback() {
+    let mut conn = mongo_scratch("upsert_tx_rollback").await;
+    conn.begin_transaction().await.unwrap();
+    assert!(conn.begin_transaction().await.is_err());
+    let writes = mongo_inserts(&[1, 2, 3]);
+    conn.write_documents("upsert_tx_rollback", &writes, &MONGO_WRITE_OPTIONS)
+        .await
+        .unwrap();
+    conn.execute_query(
+        r#"{"update": "upsert_tx_rollback",
+            "updates": [{"q": {"_id": 1}, "u": {"$set": {"qty": 0}}}]}"#,
+    )
+    .await
+    .unwrap();
+    conn.rollback_transaction().await.unwrap();
+
+    assert!(conn.get_rows("upsert_tx_rollback", None, None).await.unwrap().is_empty());
+    assert!(conn.commit_transaction().await.is_err());
+}
+
+#[tokio::test]
+#[ignore]
+async fn mongo_standalone_refuses_transactions() {
+    let mut conn = MongoDbConnector::new(mongo_standalone_config());
+    conn.connect().await.expect("connect to the standalone mongodb service");
+    assert!(!conn.supports_transactions().await.unwrap());
+    let err = conn.begin_transaction().await.unwrap_err();
+    assert!(format!("{:#}", err).contains("standalone"), "{:#}", err);
+}
@@ -26,6 +26,7 @@ use upsert_lib::db::migrator::{
     execute_migration, plan_migration, CancellationToken, ConflictResolution, MigrationConfig,
     MigrationMode, MigrationStatus,
 };
+use upsert_lib::db::scheduler::Schedule;
 use upsert_lib::db::schema::{ConstraintType, Row};
 use upsert_lib::db::sql_generator::SqlGenerator;
 
@@ -476,11 +477,16 @@ async fn fk_ordering_parents_before_children() {
         "order_items should depend on orders"
     );
 
-    // Verify that in a correct topological order:
+    // The scheduler must order parents first:
     //   categories < products < order_items
     //   customers < orders < order_items
-    // This is tested indirectly by verifying that the dependency graph is correct.
-    // The sort_tables_by_fk function in commands/migration.rs uses Kahn's algorithm.
+    let tables: Vec<String> = SHARED_TABLES.iter().map(|t| t.to_string()).collect();
+    let order = Schedule::new(&tables, &fk_deps).linear_order();
+    let pos = |t: &str| order.iter().position(|o| o == t).unwrap();
+    assert!(pos("categories") < pos("products"));
+    assert!(pos("products") < pos("order_items"));
+    assert!(pos("customers") < pos("orders"));
+    assert!(pos("orders") < pos("order_items"));
 
     pg.disconnect().await.ok();
 }
@@ -7,6 +7,7 @@ import type {
   ChangeTypeFilter,
   ObjectTypeFilter,
 } from "../../types/comparison";
+import { useComparisonStore } from "../../stores/comparisonStore";
 import DiffSummaryBar from "./DiffSummaryBar";
 
 // -- Helpers ------------------------------------------------------------------
@@ -16,6 +17,7 @@ const changeColor: Record<ChangeType, string> = {
   Removed: "#ef4444",
   Modified: "#f59e0b",
   Unchanged: "#9ca3af",
+  Renamed: "#3b82f6",
 };
 
 const changeTailwind: Record<ChangeType, string> = {
@@ -23,6 +25,7 @@ const changeTailwind: Record<ChangeType, string> = {
   Removed: "text-red-600 dark:text-red-400",
   Modified: "text-amber-600 dark:text-amber-400",
   Unchanged: "text-neutral-500 dark:text-neutral-500",
+  Renamed: "text-blue-600 dark:text-blue-400",
 };
 
 const changeBg: Record<ChangeType, string> = {
@@ -30,6 +33,7 @@ const changeBg: Record<ChangeType, string> = {
   Removed: "bg-red-50 dark:bg-red-950/30",
   Modified: "bg-amber-50 dark:bg-amber-950/30",
   Unchanged: "",
+  Renamed: "bg-blue-50 dark:bg-blue-950/30",
 };
 
 const changeSymbol: Record<ChangeType, string> = {
@@ -37,6 +41,7 @@ const changeSymbol: Record<ChangeType, string> = {
   Removed: "-",
   Modified: "~",
   Unchanged: "=",
+  Renamed: ">",
 };
 
 interface TreeNode {
@@ -204,6 +209,43 @@ function TreeNodeRow({
   );
 }
 
+function RenamePanel({ change }: { change: SchemaChange }) {
+  const confirmRename = useComparisonStore((s) => s.confirmRename);
+  const rejectRename = useComparisonStore((s) => s.rejectRename);
+  const rename = change.rename;
+  if (!rename) return null;
+
+  return (
+    <div className="flex items-center gap-2 rounded border border-blue-200 px-2 py-1.5 text-xs dark:border-blue-900">
+      <span className="text-neutral-600 dark:text-neutral-400">
+        Renamed from <span className="font-mono">{rename.from}</span> (
+        {Math.round(rename.confidence * 100)}% confidence)
+      </span>
+      {rename.confirmed ? (
+        <span className="ml-auto text-[10px] font-semibold uppercase text-blue-600 dark:text-blue-400">
+          Confirmed
+        </span>
+      ) : (
+        <div className="ml-auto flex gap-1">
+          <button
+            onClick={() => confirmRename(change.objectName)}
+            className="rounded bg-blue-600 px-2 py-0.5 text-white hover:bg-blue-700"
+          >
+            Confirm
+          </button>
+          <button
+            onClick={() => rejectRename(change.objectName)}
+            className="rounded border border-neutral-300 px-2 py-0.5 text-neutral-600 hover:bg-neutral-100 dark:border-neutral-600 dark:text-neutral-300 dark:hover:bg-neutral-800"
+            title="Treat as a removal and an addition"
+          >
+            Reject
+          </button>
+        </div>
+      )}
+    </div>
+  );
+}
+
 function DetailPanel({ change }: { change: SchemaChange }) {
   return (
     <div className="flex flex-col gap-2 p-3">
@@ -218,6 +260,8 @@ function DetailPanel({ change }: { change: SchemaChange }) {
         </span>
       </div>
 
+      <RenamePanel change={change} />
+
       {change.details.length > 0 && (
         <table className="w-full text-xs">
           <thead>
@@ -399,6 +443,13 @@ export default function SchemaDiffViewer({ diff }: Props) {
             active: changeFilter === "Modified",
             onClick: () => setChangeFilter(changeFilter === "Modified" ? "all" : "Modified"),
           },
+          {
+            label: "Renamed",
+            count: diff.summary.renames ?? 0,
+            color: changeColor.Renamed,
+            active: changeFilter === "Renamed",
+            onClick: () => setChangeFilter(changeFilter === "Renamed" ? "all" : "Renamed"),
+          },
           {
             label: "Unchanged",
             count: diff.summary.unchanged,
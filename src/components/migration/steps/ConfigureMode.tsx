@@ -2,6 +2,7 @@ import {
   useMigrationStore,
   type MigrationMode,
   type ConflictResolution,
+  type ConstraintMode,
 } from "../../../stores/migrationStore";
 
 const modeDescriptions: Record<MigrationMode, string> = {
@@ -24,6 +25,12 @@ const conflictOptions: Array<{ value: ConflictResolution; label: string }> = [
   { value: "ManualReview", label: "Flag for Manual Review" },
 ];
 
+const constraintOptions: Array<{ value: ConstraintMode; label: string }> = [
+  { value: "Auto", label: "Enforce (relax only for circular references)" },
+  { value: "Defer", label: "Defer until commit" },
+  { value: "Disable", label: "Disable while loading, validate after" },
+];
+
 const transactionModes: Array<{
   value: "PerBatch" | "WholeMigration" | "None";
   label: string;
@@ -121,6 +128,28 @@ export default function ConfigureMode() {
         </select>
       </section>
 
+      {/* Foreign Key Constraints */}
+      <section className="space-y-2">
+        <label className="block text-xs font-medium text-neutral-600 dark:text-neutral-400">
+          Foreign Key Constraints
+        </label>
+        <select
+          value={config.constraintMode}
+          onChange={(e) =>
+            setConfig({
+              constraintMode: e.target.value as ConstraintMode,
+            })
+          }
+          className="input-field"
+        >
+          {constraintOptions.map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+      </section>
+
       {/* Batch Size */}
       <section className="space-y-2">
         <label className="block text-xs font-medium text-neutral-600 dark:text-neutral-400">
@@ -74,6 +74,16 @@ export default function DryRun() {
         (r.warnings ?? []).map((w) => `[${r.targetTable}] ${w}`),
       );
 
+      // Report which user type override fired for each column
+      const overrideNotes: string[] = results.flatMap((r) =>
+        (r.typeMappings ?? [])
+          .filter((m) => m.appliedOverride)
+          .map(
+            (m) =>
+              `[${r.sourceTable}.${m.column}] ${m.sourceType} -> ${m.targetType} via override ${m.appliedOverride}`,
+          ),
+      );
+
       const result: DryRunResult = {
         tableSummaries: results.map((r) => {
           const mapping = includedTables.find(
@@ -91,6 +101,7 @@ export default function DryRun() {
         }),
         warnings: [
           ...backendWarnings,
+          ...overrideNotes,
           includedTables.length > 5
             ? `Large migration: ${includedTables.length} tables selected`
             : "",
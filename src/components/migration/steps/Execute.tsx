@@ -125,6 +125,8 @@ export default function Execute() {
         mode: config.mode,
         conflictResolution: config.conflictResolution,
         batchSize: config.batchSize,
+        constraintMode: config.constraintMode,
+        backupBeforeMigrate: config.backupBeforeMigrate,
       },
     };
 
@@ -155,6 +157,15 @@ export default function Execute() {
         elapsedMs: result.durationMs,
       });
 
+      for (const warning of result.warnings) {
+        appendLog(`Warning: ${warning}`);
+      }
+      for (const v of result.constraintViolations) {
+        appendLog(
+          `Constraint violation: ${v.violatingRows} row(s) in ${v.table} violate ${v.constraint} (references ${v.referencedTable})`,
+        );
+      }
+
       if (result.status === "cancelled") {
         setStatus("cancelled");
         appendLog("Migration cancelled.");
@@ -143,12 +143,13 @@ export async function buildChatContext(userQuery: string): Promise<string> {
     parts.push("\n## Schema Comparison Results");
     parts.push(`  Source: ${sd.sourceDatabase} → Target: ${sd.targetDatabase}`);
     parts.push(
-      `  Summary: +${sd.summary.additions} added, -${sd.summary.removals} removed, ~${sd.summary.modifications} modified, ${sd.summary.unchanged} unchanged`,
+      `  Summary: +${sd.summary.additions} added, -${sd.summary.removals} removed, ~${sd.summary.modifications} modified, ${sd.summary.renames ?? 0} renamed, ${sd.summary.unchanged} unchanged`,
     );
     if (sd.changes.length > 0) {
       parts.push("  Changes:");
       for (const c of sd.changes.slice(0, 30)) {
-        parts.push(`    [${c.changeType}] ${c.objectType}: ${c.objectName}`);
+        const from = c.rename ? ` (from ${c.rename.from})` : "";
+        parts.push(`    [${c.changeType}] ${c.objectType}: ${c.objectName}${from}`);
         for (const d of c.details) {
           parts.push(
             `      ${d.property}: ${d.sourceValue ?? "(none)"} → ${d.targetValue ?? "(none)"}`,
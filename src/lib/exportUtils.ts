@@ -68,9 +68,25 @@ function changeIcon(changeType: SchemaChange["changeType"]): string {
       return "~";
     case "Unchanged":
       return " ";
+    case "Renamed":
+      return ">";
   }
 }
 
+/** Object name of a change, showing the old name of a detected rename. */
+function changeLabel(change: SchemaChange): string {
+  if (!change.rename) return change.objectName;
+  return `${change.rename.from} -> ${change.objectName}`;
+}
+
+/** Change type of a change, with a rename's confidence and whether it was confirmed. */
+function changeTypeLabel(change: SchemaChange): string {
+  if (!change.rename) return change.changeType;
+  const confidence = Math.round(change.rename.confidence * 100);
+  const state = change.rename.confirmed ? "confirmed" : "unconfirmed";
+  return `${change.changeType}, ${confidence}% confidence, ${state}`;
+}
+
 /** Escape a value for safe inclusion in a CSV field (RFC 4180). */
 function escapeCsvField(value: unknown): string {
   const str = value === null || value === undefined ? "" : String(value);
@@ -108,6 +124,7 @@ export function exportSchemaDiffAsMarkdown(diff: SchemaDiffResult): string {
   lines.push(`| Removals | ${diff.summary.removals} |`);
   lines.push(`| Modifications | ${diff.summary.modifications} |`);
   lines.push(`| Unchanged | ${diff.summary.unchanged} |`);
+  lines.push(`| Renames | ${diff.summary.renames ?? 0} |`);
   lines.push(`| **Total** | **${diff.changes.length}** |`);
   lines.push("");
 
@@ -135,7 +152,9 @@ export function exportSchemaDiffAsMarkdown(diff: SchemaDiffResult): string {
     lines.push("");
 
     for (const change of changes) {
-      lines.push(`- \`[${changeIcon(change.changeType)}]\` **${change.objectName}** (${change.changeType})`);
+      lines.push(
+        `- \`[${changeIcon(change.changeType)}]\` **${changeLabel(change)}** (${changeTypeLabel(change)})`,
+      );
 
       if (change.details.length > 0) {
         for (const detail of change.details) {
@@ -1,5 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { SchemaDiffResult } from "../stores/comparisonStore";
 import type { DatabaseEngine } from "../stores/connectionStore";
+import type { ConstraintMode } from "../stores/migrationStore";
 
 // ── Request / Response types ─────────────────────────────────────────
 
@@ -13,18 +15,66 @@ export interface ConnectionConfigDto {
   connectionString?: string;
   filePath?: string;
   readOnly: boolean;
+  pool?: Partial<PoolConfig>;
+  schemaSampleSize?: number;
+  schemaFilter?: Partial<SchemaFilter>;
+}
+
+export interface SchemaFilter {
+  includeSchemas: string[];
+  excludeSchemas: string[];
+  includeTables: string[];
+  excludeTables: string[];
+}
+
+export interface PoolConfig {
+  minSize: number;
+  maxSize: number;
+  idleTimeoutSecs: number;
+  healthCheckIntervalSecs: number;
+  acquireTimeoutSecs: number;
+}
+
+export interface PoolStatus {
+  size: number;
+  idle: number;
+  inUse: number;
+  maxSize: number;
 }
 
 export interface TableMappingDto {
   sourceTable: string;
   targetTable: string;
   keyColumns: string[];
+  watermarkColumn?: string;
+  softDeleteColumn?: string;
+  sourceFilter?: Record<string, unknown>;
+  sourceProjection?: Record<string, unknown>;
+  embeddings?: EmbeddingDto[];
+  flatten?: boolean;
+  flattenArray?: string;
+}
+
+export interface EmbeddingDto {
+  sourceTable: string;
+  field: string;
+  embedAs?: "Array" | "Object";
+  parentColumns?: string[];
+  childColumns?: string[];
+  embeddings?: EmbeddingDto[];
 }
 
 export interface MigrationConfigDto {
   mode: string;
   conflictResolution: string;
   batchSize: number;
+  maxParallelTables?: number;
+  constraintMode?: ConstraintMode;
+  backupBeforeMigrate?: boolean;
+  backupRetention?: number;
+  incremental?: boolean;
+  orderedWrites?: boolean;
+  schemaMap?: Record<string, string>;
 }
 
 export interface DryRunRequest {
@@ -32,6 +82,17 @@ export interface DryRunRequest {
   targetConnectionId: string;
   tables: TableMappingDto[];
   config: MigrationConfigDto;
+  typeOverrideFile?: string;
+  jobId?: string;
+}
+
+export interface ColumnTypeMappingDto {
+  column: string;
+  sourceType: string;
+  targetType: string;
+  appliedOverride: string | null;
+  warnings: string[];
+  isLossy: boolean;
 }
 
 export interface DryRunTableResult {
@@ -44,6 +105,7 @@ export interface DryRunTableResult {
   deletes: number;
   skips: number;
   warnings: string[];
+  typeMappings: ColumnTypeMappingDto[];
 }
 
 export interface ColumnInfo {
@@ -65,8 +127,58 @@ export interface TableInfo {
   indexes: unknown[];
   constraints: unknown[];
   rowCount: number | null;
+  fieldStats?: FieldStats[];
+}
+
+export interface FieldStats {
+  path: string;
+  typeCounts: Record<string, number>;
+  nullRatio: number;
+  missingRatio: number;
+  maxStringLength: number | null;
+}
+
+export interface FlatTable {
+  arrayPath: string | null;
+  parentTable: string | null;
+  keyColumns: string[];
+  info: TableInfo;
+  ddl: string | null;
+}
+
+export interface TypeOverrideDto {
+  sourceEngine: DatabaseEngine;
+  sourceType: string;
+  targetEngine: DatabaseEngine;
+  targetType: string;
+  table?: string | null;
+  column?: string | null;
+}
+
+export type MappingSeverity = "Ok" | "Warning" | "Lossy";
+
+export interface TypeCompatibilityEntry {
+  table: string;
+  column: string;
+  sourceType: string;
+  canonicalType: string;
+  targetType: string;
+  isLossy: boolean;
+  severity: MappingSeverity;
+  warnings: string[];
+  suggestedOverride: TypeOverrideDto | null;
 }
 
+export interface TypeCompatibilityReport {
+  sourceEngine: DatabaseEngine;
+  targetEngine: DatabaseEngine;
+  entries: TypeCompatibilityEntry[];
+  summary: { ok: number; warning: number; lossy: number };
+  suggestedOverrides: TypeOverrideDto[];
+}
+
+export type ReportFormat = "Csv" | "Markdown" | "Json";
+
 export interface MigrationProgressEvent {
   migrationId: string;
   table: string;
@@ -88,6 +200,15 @@ export interface MigrationResultDto {
   errorCount: number;
   durationMs: number;
   status: string;
+  warnings: string[];
+  constraintViolations: ConstraintViolationDto[];
+}
+
+export interface ConstraintViolationDto {
+  table: string;
+  constraint: string;
+  referencedTable: string;
+  violatingRows: number;
 }
 
 // ── Typed invoke wrappers ────────────────────────────────────────────
@@ -107,6 +228,10 @@ export function disconnectDatabase(id: string): Promise<void> {
   return invoke<void>("disconnect_database", { id });
 }
 
+export function connectionPoolStatus(id: string): Promise<PoolStatus> {
+  return invoke<PoolStatus>("connection_pool_status", { id });
+}
+
 export function getTables(connectionId: string): Promise<string[]> {
   return invoke<string[]>("get_tables", { connectionId });
 }
@@ -125,6 +250,76 @@ export function getRowCount(
   return invoke<number>("get_row_count", { connectionId, tableName });
 }
 
+export function typeCompatibilityReport(
+  connectionId: string,
+  targetEngine: DatabaseEngine,
+  tables?: string[],
+): Promise<TypeCompatibilityReport> {
+  return invoke<TypeCompatibilityReport>("type_compatibility_report", {
+    connectionId,
+    targetEngine,
+    tables: tables ?? null,
+  });
+}
+
+export function planDocumentFlattening(
+  connectionId: string,
+  collection: string,
+  targetEngine: DatabaseEngine,
+  rootTable?: string,
+  sampleSize?: number,
+): Promise<FlatTable[]> {
+  return invoke<FlatTable[]>("plan_document_flattening", {
+    connectionId,
+    collection,
+    targetEngine,
+    rootTable: rootTable ?? null,
+    sampleSize: sampleSize ?? null,
+  });
+}
+
+export function exportTypeCompatibilityReport(
+  report: TypeCompatibilityReport,
+  format: ReportFormat,
+  path: string,
+): Promise<void> {
+  return invoke<void>("export_type_compatibility_report", {
+    report,
+    format,
+    path,
+  });
+}
+
+/** How column types, defaults and index kinds are compared. */
+export type SchemaCompareMode = "Native" | "CrossEngine";
+
+/** Without a mode, different engines compare cross-engine, one engine natively. */
+export function compareSchemas(
+  sourceConnectionId: string,
+  targetConnectionId: string,
+  mode?: SchemaCompareMode,
+): Promise<SchemaDiffResult> {
+  return invoke<SchemaDiffResult>("compare_schemas", {
+    sourceConnectionId,
+    targetConnectionId,
+    mode: mode ?? null,
+  });
+}
+
+export function confirmSchemaRename(
+  diff: SchemaDiffResult,
+  objectName: string,
+): Promise<SchemaDiffResult> {
+  return invoke<SchemaDiffResult>("confirm_schema_rename", { diff, objectName });
+}
+
+export function rejectSchemaRename(
+  diff: SchemaDiffResult,
+  objectName: string,
+): Promise<SchemaDiffResult> {
+  return invoke<SchemaDiffResult>("reject_schema_rename", { diff, objectName });
+}
+
 export function dryRun(
   request: DryRunRequest,
 ): Promise<DryRunTableResult[]> {
@@ -141,10 +336,85 @@ export function executeMigration(
   });
 }
 
+export function resumeMigration(
+  migrationId: string,
+): Promise<MigrationResultDto> {
+  return invoke<MigrationResultDto>("resume_migration", { migrationId });
+}
+
+export function restoreBackup(
+  migrationId: string,
+): Promise<MigrationBackupDto[]> {
+  return invoke<MigrationBackupDto[]>("restore_backup", { migrationId });
+}
+
+export interface UndoConflictDto {
+  table: string;
+  operation: "insert" | "update" | "delete";
+  key: Record<string, unknown>;
+  reason: string;
+}
+
+export interface UndoResultDto {
+  applied: boolean;
+  rowsReverted: number;
+  conflicts: UndoConflictDto[];
+  warnings: string[];
+}
+
+export function undoMigration(migrationId: string): Promise<UndoResultDto> {
+  return invoke<UndoResultDto>("undo_migration", { migrationId });
+}
+
 export function cancelMigration(migrationId: string): Promise<boolean> {
   return invoke<boolean>("cancel_migration", { migrationId });
 }
 
+// ── Change Data Capture ─────────────────────────────────────────────────
+
+export interface CdcSyncRequest {
+  streamId: string;
+  sourceConnectionId: string;
+  targetConnectionId: string;
+  tables: TableMappingDto[];
+  batchSize?: number;
+  pollIntervalMs?: number;
+}
+
+/** Payload of the `cdc:progress` event. */
+export interface CdcProgressEvent {
+  streamId: string;
+  status: "running" | "stopped" | "failed";
+  eventsApplied: number;
+  position: string | null;
+  error: string | null;
+}
+
+export interface CdcPositionDto {
+  streamId: string;
+  sourceConnectionId: string;
+  targetConnectionId: string;
+  position: string;
+  eventsApplied: number;
+  updatedAt: string;
+}
+
+export function startCdcSync(request: CdcSyncRequest): Promise<void> {
+  return invoke<void>("start_cdc_sync", { request });
+}
+
+export function stopCdcSync(streamId: string): Promise<boolean> {
+  return invoke<boolean>("stop_cdc_sync", { streamId });
+}
+
+export function getCdcPositions(): Promise<CdcPositionDto[]> {
+  return invoke<CdcPositionDto[]>("get_cdc_positions");
+}
+
+export function resetCdcPosition(streamId: string): Promise<void> {
+  return invoke<void>("reset_cdc_position", { streamId });
+}
+
 // ── Chat / Ollama ────────────────────────────────────────────────────
 
 export interface OllamaModel {
@@ -226,6 +496,16 @@ export interface MigrationHistoryDto {
   durationMs: number;
 }
 
+export interface MigrationBackupDto {
+  migrationId: string;
+  targetConnectionId: string;
+  targetTable: string;
+  backupTable: string;
+  rowCount: number | null;
+  status: "available" | "restored" | "expired";
+  createdAt: string;
+}
+
 export interface ChatMessagePersistDto {
   id: string;
   role: string;
@@ -264,6 +544,32 @@ export function getMigrationHistory(): Promise<MigrationHistoryDto[]> {
   return invoke<MigrationHistoryDto[]>("get_migration_history");
 }
 
+export function getMigrationBackups(
+  migrationId?: string,
+): Promise<MigrationBackupDto[]> {
+  return invoke<MigrationBackupDto[]>("get_migration_backups", {
+    migrationId: migrationId ?? null,
+  });
+}
+
+export interface SyncWatermarkDto {
+  scope: string;
+  sourceTable: string;
+  targetTable: string;
+  watermarkColumn: string;
+  watermarkJson: string;
+  updatedAt: string;
+}
+
+/** `scope` is a job ID, or "{sourceId}->{targetId}" for runs outside a job. */
+export function getSyncWatermarks(scope: string): Promise<SyncWatermarkDto[]> {
+  return invoke<SyncWatermarkDto[]>("get_sync_watermarks", { scope });
+}
+
+export function resetSyncWatermarks(scope: string): Promise<void> {
+  return invoke<void>("reset_sync_watermarks", { scope });
+}
+
 export function saveChatMessageToDb(
   message: ChatMessagePersistDto,
 ): Promise<void> {
@@ -1,5 +1,11 @@
-import { describe, it, expect, beforeEach } from 'vitest';
+import { describe, it, expect, beforeEach, vi } from 'vitest';
 import { useComparisonStore } from '../comparisonStore';
+import { confirmSchemaRename, rejectSchemaRename } from '../../lib/tauriCommands';
+
+vi.mock('../../lib/tauriCommands', () => ({
+  confirmSchemaRename: vi.fn(),
+  rejectSchemaRename: vi.fn(),
+}));
 
 describe('comparisonStore', () => {
   beforeEach(() => {
@@ -118,6 +124,40 @@ describe('comparisonStore', () => {
     });
   });
 
+  describe('renames', () => {
+    const renamed = {
+      sourceDatabase: 'a', targetDatabase: 'b',
+      changes: [
+        {
+          objectType: 'Table',
+          objectName: 'clients',
+          changeType: 'Renamed' as const,
+          details: [],
+          rename: { from: 'customers', confidence: 0.9, confirmed: false },
+        },
+      ],
+      summary: { additions: 0, removals: 0, modifications: 0, unchanged: 0, renames: 1 },
+    };
+
+    it('should store the diff with the rename confirmed', async () => {
+      const rename = { ...renamed.changes[0].rename, confirmed: true };
+      const confirmed = { ...renamed, changes: [{ ...renamed.changes[0], rename }] };
+      vi.mocked(confirmSchemaRename).mockResolvedValueOnce(confirmed);
+      useComparisonStore.getState().setSchemaDiff(renamed);
+      await useComparisonStore.getState().confirmRename('clients');
+      expect(confirmSchemaRename).toHaveBeenCalledWith(renamed, 'clients');
+      expect(useComparisonStore.getState().schemaDiff).toEqual(confirmed);
+    });
+
+    it('should keep the diff and report the error when rejecting fails', async () => {
+      vi.mocked(rejectSchemaRename).mockRejectedValueOnce("No rename to 'x' was detected");
+      useComparisonStore.getState().setSchemaDiff(renamed);
+      await useComparisonStore.getState().rejectRename('x');
+      expect(useComparisonStore.getState().schemaDiff).toEqual(renamed);
+      expect(useComparisonStore.getState().error).toBe("No rename to 'x' was detected");
+    });
+  });
+
   describe('reset', () => {
     it('should reset all state to defaults', () => {
       useComparisonStore.getState().setSchemaDiff({
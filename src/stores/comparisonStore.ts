@@ -1,6 +1,7 @@
 import { create } from "zustand";
+import { confirmSchemaRename, rejectSchemaRename } from "../lib/tauriCommands";
 
-export type ChangeType = "Added" | "Removed" | "Modified" | "Unchanged";
+export type ChangeType = "Added" | "Removed" | "Modified" | "Unchanged" | "Renamed";
 
 export interface SchemaChange {
   objectType: string;
@@ -11,6 +12,7 @@ export interface SchemaChange {
     sourceValue?: string;
     targetValue?: string;
   }>;
+  rename?: { from: string; confidence: number; confirmed: boolean } | null;
 }
 
 export interface SchemaDiffResult {
@@ -22,6 +24,7 @@ export interface SchemaDiffResult {
     removals: number;
     modifications: number;
     unchanged: number;
+    renames?: number;
   };
 }
 
@@ -47,10 +50,14 @@ interface ComparisonState {
   setComparing: (comparing: boolean) => void;
   setProgress: (progress: number) => void;
   setError: (error: string | null) => void;
+  /** Accept the rename detected to `objectName` in the schema diff. */
+  confirmRename: (objectName: string) => Promise<void>;
+  /** Split the rename detected to `objectName` into a removal and an addition. */
+  rejectRename: (objectName: string) => Promise<void>;
   reset: () => void;
 }
 
-export const useComparisonStore = create<ComparisonState>()((set) => ({
+export const useComparisonStore = create<ComparisonState>()((set, get) => ({
   schemaDiff: null,
   dataDiff: null,
   isComparing: false,
@@ -62,6 +69,24 @@ export const useComparisonStore = create<ComparisonState>()((set) => ({
   setComparing: (isComparing) => set({ isComparing }),
   setProgress: (progress) => set({ progress }),
   setError: (error) => set({ error }),
+  confirmRename: async (objectName) => {
+    const diff = get().schemaDiff;
+    if (!diff) return;
+    try {
+      set({ schemaDiff: await confirmSchemaRename(diff, objectName), error: null });
+    } catch (e) {
+      set({ error: String(e) });
+    }
+  },
+  rejectRename: async (objectName) => {
+    const diff = get().schemaDiff;
+    if (!diff) return;
+    try {
+      set({ schemaDiff: await rejectSchemaRename(diff, objectName), error: null });
+    } catch (e) {
+      set({ error: String(e) });
+    }
+  },
   reset: () =>
     set({
       schemaDiff: null,
@@ -13,6 +13,8 @@ export type ConflictResolution =
   | "NewestWins"
   | "ManualReview";
 
+export type ConstraintMode = "Auto" | "Defer" | "Disable";
+
 export type MigrationStatus =
   | "idle"
   | "configuring"
@@ -27,6 +29,7 @@ export interface MigrationConfig {
   mode: MigrationMode;
   conflictResolution: ConflictResolution;
   batchSize: number;
+  constraintMode: ConstraintMode;
   transactionMode: "PerBatch" | "WholeMigration" | "None";
   retryCount: number;
   autoRollback: boolean;
@@ -145,6 +148,7 @@ const defaultConfig: MigrationConfig = {
   mode: "Upsert",
   conflictResolution: "SourceWins",
   batchSize: 1000,
+  constraintMode: "Auto",
   transactionMode: "PerBatch",
   retryCount: 3,
   autoRollback: true,
@@ -1,5 +1,14 @@
 export type ObjectType = "Table" | "Column" | "Index" | "Constraint";
-export type ChangeType = "Added" | "Removed" | "Modified" | "Unchanged";
+export type ChangeType = "Added" | "Removed" | "Modified" | "Unchanged" | "Renamed";
+
+/** A rename detected from structural similarity, pending until confirmed. */
+export interface RenameInfo {
+  /** Name in the source; the change's objectName is the new one. */
+  from: string;
+  /** Similarity of the two objects, up to 1. */
+  confidence: number;
+  confirmed: boolean;
+}
 
 export interface SchemaChange {
   objectType: ObjectType;
@@ -10,6 +19,7 @@ export interface SchemaChange {
     sourceValue?: string;
     targetValue?: string;
   }>;
+  rename?: RenameInfo | null;
 }
 
 export interface SchemaDiffResult {
@@ -21,6 +31,7 @@ export interface SchemaDiffResult {
     removals: number;
     modifications: number;
     unchanged: number;
+    renames?: number;
   };
 }
 